@@ -1,60 +1,679 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
 use std::env;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::emulator::block::DEFAULT_BLOCK_GAS_LIMIT;
+use crate::stellar::breaker::{DEFAULT_COOLDOWN, DEFAULT_FAILURE_THRESHOLD};
+use crate::stellar::client::{DEFAULT_POOL_IDLE_TIMEOUT, DEFAULT_POOL_MAX_IDLE_PER_HOST};
+use crate::translator::abi::{parse_selector, validate_symbol};
+use crate::translator::gas::GasModel;
+use crate::translator::tx::{decode_strkey_checked, STRKEY_VERSION_SEED};
+
+/// Stellar networks with well-known endpoints, selected by `STELLAR_NETWORK`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StellarNetwork {
+    Testnet,
+    Mainnet,
+    Futurenet,
+    /// A local quickstart container
+    Local,
+}
+
+impl StellarNetwork {
+    /// Default Soroban RPC endpoint.
+    pub fn rpc_url(&self) -> &'static str {
+        match self {
+            StellarNetwork::Testnet => "https://soroban-testnet.stellar.org",
+            StellarNetwork::Mainnet => "https://mainnet.sorobanrpc.com",
+            StellarNetwork::Futurenet => "https://rpc-futurenet.stellar.org",
+            StellarNetwork::Local => "http://localhost:8000/rpc",
+        }
+    }
+
+    /// Default Horizon endpoint.
+    pub fn horizon_url(&self) -> &'static str {
+        match self {
+            StellarNetwork::Testnet => "https://horizon-testnet.stellar.org",
+            StellarNetwork::Mainnet => "https://horizon.stellar.org",
+            StellarNetwork::Futurenet => "https://horizon-futurenet.stellar.org",
+            StellarNetwork::Local => "http://localhost:8000",
+        }
+    }
+
+    /// Network passphrase.
+    pub fn passphrase(&self) -> &'static str {
+        match self {
+            StellarNetwork::Testnet => "Test SDF Network ; September 2015",
+            StellarNetwork::Mainnet => "Public Global Stellar Network ; September 2015",
+            StellarNetwork::Futurenet => "Test SDF Future Network ; October 2022",
+            StellarNetwork::Local => "Standalone Network ; February 2017",
+        }
+    }
+}
+
+impl FromStr for StellarNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "testnet" => Ok(StellarNetwork::Testnet),
+            "mainnet" | "pubnet" | "public" => Ok(StellarNetwork::Mainnet),
+            "futurenet" => Ok(StellarNetwork::Futurenet),
+            "local" | "standalone" => Ok(StellarNetwork::Local),
+            other => Err(anyhow!(
+                "Unknown STELLAR_NETWORK '{}' (expected testnet, mainnet, futurenet or local)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StellarNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            StellarNetwork::Testnet => "testnet",
+            StellarNetwork::Mainnet => "mainnet",
+            StellarNetwork::Futurenet => "futurenet",
+            StellarNetwork::Local => "local",
+        };
+        f.write_str(name)
+    }
+}
 
 /// TVA RPC Server configuration loaded from environment variables.
 #[derive(Debug, Clone)]
 pub struct Config {
+    /// Stellar network preset the endpoints default to
+    pub stellar_network: StellarNetwork,
     /// Soroban RPC endpoint URL
     pub stellar_rpc_url: String,
+    /// Soroban RPC endpoint transactions are submitted to (`stellar_rpc_url`
+    /// unless set)
+    pub stellar_write_rpc_url: String,
+    /// Horizon endpoint URL
+    pub stellar_horizon_url: String,
     /// Stellar network passphrase
     pub stellar_network_passphrase: String,
     /// Stellar secret key for signing transactions
     pub stellar_secret_key: String,
     /// TVA chain ID (decimal)
     pub tva_chain_id: u64,
+    /// Address the RPC server binds to
+    pub tva_bind_address: IpAddr,
     /// RPC server port
     pub tva_rpc_port: u16,
     /// Log level
     pub log_level: String,
+    /// Seconds between background Soroban RPC health checks
+    pub health_poll_interval_secs: u64,
+    /// Idle upstream connections kept open per host
+    pub upstream_pool_max_idle_per_host: usize,
+    /// Seconds an idle upstream connection is kept open
+    pub upstream_pool_idle_timeout_secs: u64,
+    /// Consecutive failed Soroban RPC calls that open the circuit (0 disables it)
+    pub upstream_circuit_failure_threshold: u32,
+    /// Seconds an open circuit fails calls fast before probing the upstream
+    pub upstream_circuit_cooldown_secs: u64,
+    /// Accept raw transactions without an EIP-155 chain ID (replayable across chains)
+    pub allow_unprotected_txs: bool,
+    /// Sign eth_sendTransaction calls with `stellar_secret_key` (custodial)
+    pub enable_managed_signing: bool,
+    /// Widest block range eth_getLogs will scan
+    pub max_block_range: u64,
+    /// Most logs eth_getLogs will return
+    pub max_log_results: usize,
+    /// Largest JSON-RPC request body the server accepts, in bytes
+    pub max_request_bytes: u32,
+    /// Wei-equivalent of one stroop (1 XLM = 10^7 stroops)
+    pub xlm_wei_factor: u128,
+    /// Price of one gas unit in wei
+    pub gas_price_wei: u128,
+    /// Soroban CPU instructions per gas unit in estimates
+    pub gas_cpu_insns_per_gas: u64,
+    /// Soroban memory bytes per gas unit in estimates
+    pub gas_mem_bytes_per_gas: u64,
+    /// Gas limit reported for blocks; gas estimates never exceed it
+    pub block_gas_limit: u64,
+    /// Ledgers the `safe` and `finalized` block tags lag behind `latest`
+    pub finality_depth: u64,
+    /// Report events of failed contract calls from eth_getLogs, marked `removed`
+    pub include_failed_call_events: bool,
+    /// Selector -> Soroban function invoked in place of the ABI name
+    pub function_aliases: Vec<([u8; 4], String)>,
+    /// Let RPC clients change function aliases with tva_setFunctionAlias
+    pub enable_function_alias_rpc: bool,
+    /// File persisting address and block-hash mappings across restarts
+    /// (in memory only when unset)
+    pub state_db_path: Option<String>,
+    /// Methods the server answers (every method when unset)
+    pub rpc_method_allowlist: Option<HashSet<String>>,
+    /// Methods the server refuses, even if allowlisted
+    pub rpc_method_denylist: HashSet<String>,
 }
 
 impl Config {
     /// Load configuration from environment variables.
     /// Call dotenvy::dotenv() before calling this.
     pub fn from_env() -> Result<Self> {
-        let stellar_rpc_url = env::var("STELLAR_RPC_URL")
-            .unwrap_or_else(|_| "https://soroban-testnet.stellar.org".to_string());
+        Self::from_lookup(|key| env::var(key).ok())
+    }
+
+    /// Load configuration from a variable lookup (the environment in `from_env`).
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        // The network preset supplies endpoint defaults; explicit variables win
+        let stellar_network: StellarNetwork = match lookup("STELLAR_NETWORK") {
+            Some(name) => name.parse()?,
+            None => StellarNetwork::Testnet,
+        };
 
-        let stellar_network_passphrase = env::var("STELLAR_NETWORK_PASSPHRASE")
-            .unwrap_or_else(|_| "Test SDF Network ; September 2015".to_string());
+        let stellar_rpc_url = lookup("STELLAR_RPC_URL")
+            .unwrap_or_else(|| stellar_network.rpc_url().to_string());
 
-        let stellar_secret_key = env::var("STELLAR_SECRET_KEY")
+        let stellar_write_rpc_url = lookup("STELLAR_WRITE_RPC_URL")
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or_else(|| stellar_rpc_url.clone());
+
+        let stellar_horizon_url = lookup("STELLAR_HORIZON_URL")
+            .unwrap_or_else(|| stellar_network.horizon_url().to_string());
+
+        let stellar_network_passphrase = lookup("STELLAR_NETWORK_PASSPHRASE")
+            .unwrap_or_else(|| stellar_network.passphrase().to_string());
+
+        let stellar_secret_key = lookup("STELLAR_SECRET_KEY")
             .context("STELLAR_SECRET_KEY must be set in environment or .env file")?;
 
-        let tva_chain_id: u64 = env::var("TVA_CHAIN_ID")
-            .unwrap_or_else(|_| "1414676736".to_string())
+        let tva_chain_id: u64 = lookup("TVA_CHAIN_ID")
+            .unwrap_or_else(|| "1414676736".to_string())
             .parse()
             .context("TVA_CHAIN_ID must be a valid u64")?;
 
-        let tva_rpc_port: u16 = env::var("TVA_RPC_PORT")
-            .unwrap_or_else(|_| "8545".to_string())
+        let tva_bind_address: IpAddr = lookup("TVA_BIND_ADDRESS")
+            .unwrap_or_else(|| "0.0.0.0".to_string())
+            .trim()
+            .parse()
+            .context("TVA_BIND_ADDRESS must be an IP address, e.g. 0.0.0.0 or 127.0.0.1")?;
+
+        let tva_rpc_port: u16 = lookup("TVA_RPC_PORT")
+            .unwrap_or_else(|| "8545".to_string())
             .parse()
             .context("TVA_RPC_PORT must be a valid u16")?;
 
-        let log_level = env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
+        let log_level = lookup("RUST_LOG").unwrap_or_else(|| "info".to_string());
+
+        let health_poll_interval_secs: u64 = lookup("TVA_HEALTH_POLL_INTERVAL_SECS")
+            .unwrap_or_else(|| "15".to_string())
+            .parse()
+            .context("TVA_HEALTH_POLL_INTERVAL_SECS must be a valid u64")?;
+        if health_poll_interval_secs == 0 {
+            anyhow::bail!("TVA_HEALTH_POLL_INTERVAL_SECS must be greater than zero");
+        }
+
+        let upstream_pool_max_idle_per_host: usize = match lookup("UPSTREAM_POOL_MAX_IDLE_PER_HOST") {
+            Some(v) => v.parse().context("UPSTREAM_POOL_MAX_IDLE_PER_HOST must be a valid integer")?,
+            None => DEFAULT_POOL_MAX_IDLE_PER_HOST,
+        };
+        let upstream_pool_idle_timeout_secs: u64 = match lookup("UPSTREAM_POOL_IDLE_TIMEOUT_SECS") {
+            Some(v) => v.parse().context("UPSTREAM_POOL_IDLE_TIMEOUT_SECS must be a valid u64")?,
+            None => DEFAULT_POOL_IDLE_TIMEOUT.as_secs(),
+        };
+        let upstream_circuit_failure_threshold: u32 = match lookup("UPSTREAM_CIRCUIT_FAILURE_THRESHOLD") {
+            Some(v) => v.parse().context("UPSTREAM_CIRCUIT_FAILURE_THRESHOLD must be a valid u32")?,
+            None => DEFAULT_FAILURE_THRESHOLD,
+        };
+        let upstream_circuit_cooldown_secs: u64 = match lookup("UPSTREAM_CIRCUIT_COOLDOWN_SECS") {
+            Some(v) => v.parse().context("UPSTREAM_CIRCUIT_COOLDOWN_SECS must be a valid u64")?,
+            None => DEFAULT_COOLDOWN.as_secs(),
+        };
+        if upstream_circuit_cooldown_secs == 0 {
+            anyhow::bail!("UPSTREAM_CIRCUIT_COOLDOWN_SECS must be greater than zero");
+        }
+
+        let allow_unprotected_txs: bool = lookup("TVA_ALLOW_UNPROTECTED_TXS")
+            .unwrap_or_else(|| "false".to_string())
+            .parse()
+            .context("TVA_ALLOW_UNPROTECTED_TXS must be true or false")?;
+
+        let enable_managed_signing: bool = lookup("ENABLE_MANAGED_SIGNING")
+            .unwrap_or_else(|| "false".to_string())
+            .parse()
+            .context("ENABLE_MANAGED_SIGNING must be true or false")?;
+
+        let max_block_range: u64 = lookup("MAX_BLOCK_RANGE")
+            .unwrap_or_else(|| "10000".to_string())
+            .parse()
+            .context("MAX_BLOCK_RANGE must be a valid u64")?;
+
+        let max_log_results: usize = lookup("MAX_LOG_RESULTS")
+            .unwrap_or_else(|| "10000".to_string())
+            .parse()
+            .context("MAX_LOG_RESULTS must be a valid integer")?;
+
+        let max_request_bytes: u32 = lookup("MAX_REQUEST_BYTES")
+            .unwrap_or_else(|| "1048576".to_string())
+            .parse()
+            .context("MAX_REQUEST_BYTES must be a valid u32")?;
+
+        let gas_defaults = GasModel::default();
+        let xlm_wei_factor: u128 = match lookup("XLM_WEI_FACTOR") {
+            Some(v) => v.parse().context("XLM_WEI_FACTOR must be a valid integer")?,
+            None => gas_defaults.wei_per_stroop,
+        };
+        let gas_price_wei: u128 = match lookup("GAS_PRICE_WEI") {
+            Some(v) => v.parse().context("GAS_PRICE_WEI must be a valid integer")?,
+            None => gas_defaults.wei_per_gas,
+        };
+        let gas_cpu_insns_per_gas: u64 = match lookup("GAS_CPU_INSNS_PER_GAS") {
+            Some(v) => v.parse().context("GAS_CPU_INSNS_PER_GAS must be a valid u64")?,
+            None => gas_defaults.cpu_insns_per_gas,
+        };
+        let gas_mem_bytes_per_gas: u64 = match lookup("GAS_MEM_BYTES_PER_GAS") {
+            Some(v) => v.parse().context("GAS_MEM_BYTES_PER_GAS must be a valid u64")?,
+            None => gas_defaults.mem_bytes_per_gas,
+        };
+
+        let block_gas_limit: u64 = match lookup("BLOCK_GAS_LIMIT") {
+            Some(v) => v.parse().context("BLOCK_GAS_LIMIT must be a valid u64")?,
+            None => DEFAULT_BLOCK_GAS_LIMIT,
+        };
+
+        let finality_depth: u64 = lookup("FINALITY_DEPTH")
+            .unwrap_or_else(|| "0".to_string())
+            .parse()
+            .context("FINALITY_DEPTH must be a valid u64")?;
+
+        let include_failed_call_events: bool = lookup("INCLUDE_FAILED_CALL_EVENTS")
+            .unwrap_or_else(|| "false".to_string())
+            .parse()
+            .context("INCLUDE_FAILED_CALL_EVENTS must be true or false")?;
+
+        // Comma-separated selector=symbol pairs, e.g. 0xa9059cbb=xfer
+        let function_aliases = lookup("FUNCTION_ALIASES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (selector, symbol) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("FUNCTION_ALIASES entry '{}' must be selector=symbol", pair))?;
+                let selector = parse_selector(selector.trim()).context("FUNCTION_ALIASES")?;
+                validate_symbol(symbol.trim()).context("FUNCTION_ALIASES")?;
+                Ok((selector, symbol.trim().to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let enable_function_alias_rpc: bool = lookup("ENABLE_FUNCTION_ALIAS_RPC")
+            .unwrap_or_else(|| "false".to_string())
+            .parse()
+            .context("ENABLE_FUNCTION_ALIAS_RPC must be true or false")?;
+
+        let state_db_path = lookup("STATE_DB_PATH").filter(|path| !path.trim().is_empty());
+
+        // Comma-separated method names, e.g. eth_sendRawTransaction,eth_getLogs
+        let method_set = |value: String| -> HashSet<String> {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|method| !method.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let rpc_method_allowlist = lookup("RPC_METHOD_ALLOWLIST")
+            .filter(|list| !list.trim().is_empty())
+            .map(method_set);
+        let rpc_method_denylist = lookup("RPC_METHOD_DENYLIST").map(method_set).unwrap_or_default();
 
         Ok(Config {
+            stellar_network,
             stellar_rpc_url,
+            stellar_write_rpc_url,
+            stellar_horizon_url,
             stellar_network_passphrase,
             stellar_secret_key,
             tva_chain_id,
+            tva_bind_address,
             tva_rpc_port,
             log_level,
+            health_poll_interval_secs,
+            upstream_pool_max_idle_per_host,
+            upstream_pool_idle_timeout_secs,
+            upstream_circuit_failure_threshold,
+            upstream_circuit_cooldown_secs,
+            allow_unprotected_txs,
+            enable_managed_signing,
+            max_block_range,
+            max_log_results,
+            max_request_bytes,
+            xlm_wei_factor,
+            gas_price_wei,
+            gas_cpu_insns_per_gas,
+            gas_mem_bytes_per_gas,
+            block_gas_limit,
+            finality_depth,
+            include_failed_call_events,
+            function_aliases,
+            enable_function_alias_rpc,
+            state_db_path,
+            rpc_method_allowlist,
+            rpc_method_denylist,
         })
     }
 
+    /// Whether the method allowlist and denylist let `method` through.
+    pub fn method_enabled(&self, method: &str) -> bool {
+        let allowed = match &self.rpc_method_allowlist {
+            Some(allowlist) => allowlist.contains(method),
+            None => true,
+        };
+        allowed && !self.rpc_method_denylist.contains(method)
+    }
+
+    /// Check the loaded values, reporting every problem at once so a
+    /// misconfigured deployment fails at startup rather than mid-request.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+
+        // The write endpoint defaults to the read one; report a bad URL once
+        let write_rpc_url = Some(("STELLAR_WRITE_RPC_URL", &self.stellar_write_rpc_url))
+            .filter(|(_, url)| **url != self.stellar_rpc_url);
+        for (name, url) in [
+            ("STELLAR_RPC_URL", &self.stellar_rpc_url),
+            ("STELLAR_HORIZON_URL", &self.stellar_horizon_url),
+        ]
+        .into_iter()
+        .chain(write_rpc_url)
+        {
+            match reqwest::Url::parse(url) {
+                Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+                Ok(parsed) => problems.push(format!(
+                    "{} must be an http(s) URL, got scheme '{}'",
+                    name,
+                    parsed.scheme()
+                )),
+                Err(e) => problems.push(format!("{} '{}' is not a valid URL: {}", name, url, e)),
+            }
+        }
+
+        if !self.stellar_secret_key.starts_with('S') {
+            problems.push("STELLAR_SECRET_KEY must be a Stellar secret seed starting with 'S'".to_string());
+        } else if let Err(e) = decode_strkey_checked(STRKEY_VERSION_SEED, &self.stellar_secret_key) {
+            problems.push(format!("STELLAR_SECRET_KEY is not a valid secret seed: {}", e));
+        }
+
+        if self.tva_rpc_port == 0 {
+            problems.push("TVA_RPC_PORT must be nonzero".to_string());
+        }
+
+        if self.max_block_range == 0 {
+            problems.push("MAX_BLOCK_RANGE must be nonzero".to_string());
+        }
+
+        if self.max_log_results == 0 {
+            problems.push("MAX_LOG_RESULTS must be nonzero".to_string());
+        }
+
+        if self.max_request_bytes == 0 {
+            problems.push("MAX_REQUEST_BYTES must be nonzero".to_string());
+        }
+
+        problems.extend(self.gas_model().problems());
+
+        if self.block_gas_limit == 0 {
+            problems.push("BLOCK_GAS_LIMIT must be nonzero".to_string());
+        }
+
+        if self.stellar_network_passphrase.trim().is_empty() {
+            problems.push("STELLAR_NETWORK_PASSPHRASE must not be empty".to_string());
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Invalid configuration:\n  - {}", problems.join("\n  - "))
+        }
+    }
+
+    /// Socket address the RPC server listens on
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.tva_bind_address, self.tva_rpc_port)
+    }
+
+    /// Gas conversion for estimates, eth_gasPrice and receipts
+    pub fn gas_model(&self) -> GasModel {
+        GasModel {
+            wei_per_gas: self.gas_price_wei,
+            wei_per_stroop: self.xlm_wei_factor,
+            cpu_insns_per_gas: self.gas_cpu_insns_per_gas,
+            mem_bytes_per_gas: self.gas_mem_bytes_per_gas,
+        }
+    }
+
+    /// Interval between background Soroban RPC health checks
+    pub fn health_poll_interval(&self) -> Duration {
+        Duration::from_secs(self.health_poll_interval_secs)
+    }
+
+    /// How long an idle upstream connection is kept open
+    pub fn upstream_pool_idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.upstream_pool_idle_timeout_secs)
+    }
+
+    /// How long an open upstream circuit fails calls fast
+    pub fn upstream_circuit_cooldown(&self) -> Duration {
+        Duration::from_secs(self.upstream_circuit_cooldown_secs)
+    }
+
     /// Return the chain ID as a hex string with 0x prefix
     pub fn chain_id_hex(&self) -> String {
         format!("0x{:x}", self.tva_chain_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_with(vars: &[(&str, &str)]) -> Result<Config> {
+        let mut vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        vars.entry("STELLAR_SECRET_KEY".to_string())
+            .or_insert_with(valid_seed);
+        Config::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    fn valid_seed() -> String {
+        crate::translator::tx::encode_strkey(STRKEY_VERSION_SEED, &[7; 32])
+    }
+
+    #[test]
+    fn test_network_presets() {
+        let cases = [
+            ("testnet", "Test SDF Network ; September 2015", "https://horizon-testnet.stellar.org"),
+            ("mainnet", "Public Global Stellar Network ; September 2015", "https://horizon.stellar.org"),
+            ("futurenet", "Test SDF Future Network ; October 2022", "https://horizon-futurenet.stellar.org"),
+            ("local", "Standalone Network ; February 2017", "http://localhost:8000"),
+        ];
+        for (name, passphrase, horizon) in cases {
+            let config = config_with(&[("STELLAR_NETWORK", name)]).unwrap();
+            assert_eq!(config.stellar_network.to_string(), name);
+            assert_eq!(config.stellar_network_passphrase, passphrase);
+            assert_eq!(config.stellar_horizon_url, horizon);
+            assert_eq!(config.stellar_rpc_url, config.stellar_network.rpc_url());
+        }
+
+        // Testnet is the default
+        let config = config_with(&[]).unwrap();
+        assert_eq!(config.stellar_network, StellarNetwork::Testnet);
+    }
+
+    #[test]
+    fn test_explicit_values_override_preset() {
+        let config = config_with(&[
+            ("STELLAR_NETWORK", "Futurenet"),
+            ("STELLAR_RPC_URL", "http://rpc.internal:8000"),
+            ("STELLAR_NETWORK_PASSPHRASE", "Private Network"),
+        ])
+        .unwrap();
+        assert_eq!(config.stellar_network, StellarNetwork::Futurenet);
+        assert_eq!(config.stellar_rpc_url, "http://rpc.internal:8000");
+        assert_eq!(config.stellar_network_passphrase, "Private Network");
+        assert_eq!(config.stellar_horizon_url, "https://horizon-futurenet.stellar.org");
+        assert_eq!(config.stellar_write_rpc_url, "http://rpc.internal:8000");
+
+        let config = config_with(&[("STELLAR_WRITE_RPC_URL", "https://submit.internal")]).unwrap();
+        assert_eq!(config.stellar_write_rpc_url, "https://submit.internal");
+        assert_eq!(config.stellar_rpc_url, StellarNetwork::Testnet.rpc_url());
+    }
+
+    #[test]
+    fn test_unknown_network_is_rejected() {
+        let err = config_with(&[("STELLAR_NETWORK", "devnet")]).unwrap_err();
+        assert!(err.to_string().contains("Unknown STELLAR_NETWORK 'devnet'"));
+    }
+
+    #[test]
+    fn test_bind_address() {
+        let config = config_with(&[]).unwrap();
+        assert_eq!(config.socket_addr().to_string(), "0.0.0.0:8545");
+
+        let config = config_with(&[("TVA_BIND_ADDRESS", "127.0.0.1"), ("TVA_RPC_PORT", "9000")]).unwrap();
+        assert_eq!(config.socket_addr().to_string(), "127.0.0.1:9000");
+
+        let config = config_with(&[("TVA_BIND_ADDRESS", "::1")]).unwrap();
+        assert_eq!(config.socket_addr().to_string(), "[::1]:8545");
+
+        let err = config_with(&[("TVA_BIND_ADDRESS", "localhost")]).unwrap_err();
+        assert!(err.to_string().contains("TVA_BIND_ADDRESS"));
+    }
+
+    #[test]
+    fn test_validate_accepts_defaults() {
+        for network in ["testnet", "mainnet", "futurenet", "local"] {
+            config_with(&[("STELLAR_NETWORK", network)]).unwrap().validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_rpc_url() {
+        let config = config_with(&[("STELLAR_RPC_URL", "soroban-testnet.stellar.org")]).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("STELLAR_RPC_URL"), "{}", err);
+
+        let config = config_with(&[("STELLAR_RPC_URL", "ftp://example.com")]).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("http(s)"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_secret_key() {
+        // Account ID instead of a seed
+        let account = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+        let err = config_with(&[("STELLAR_SECRET_KEY", account)]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("starting with 'S'"));
+
+        // Wrong length
+        let err = config_with(&[("STELLAR_SECRET_KEY", "SABC")]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("56 characters"));
+
+        // Corrupted checksum
+        let mut seed = valid_seed();
+        let last = if seed.ends_with('A') { "B" } else { "A" };
+        seed.replace_range(55.., last);
+        let err = config_with(&[("STELLAR_SECRET_KEY", &seed)]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_port() {
+        let err = config_with(&[("TVA_RPC_PORT", "0")]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("TVA_RPC_PORT must be nonzero"));
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_passphrase() {
+        let err = config_with(&[("STELLAR_NETWORK_PASSPHRASE", "  ")]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("STELLAR_NETWORK_PASSPHRASE must not be empty"));
+    }
+
+    #[test]
+    fn test_xlm_wei_factor() {
+        let config = config_with(&[("XLM_WEI_FACTOR", "1"), ("GAS_PRICE_WEI", "1")]).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.gas_model().stroops_to_wei(10_000_000), 10_000_000);
+
+        // The default gas price (1 gwei) does not divide a 1 wei stroop
+        let err = config_with(&[("XLM_WEI_FACTOR", "1")]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("GAS_PRICE_WEI"), "{}", err);
+        let err = config_with(&[("XLM_WEI_FACTOR", "0")]).unwrap().validate().unwrap_err();
+        assert!(err.to_string().contains("XLM_WEI_FACTOR must be nonzero"), "{}", err);
+    }
+
+    #[test]
+    fn test_function_aliases() {
+        assert!(config_with(&[]).unwrap().function_aliases.is_empty());
+
+        let config = config_with(&[("FUNCTION_ALIASES", "0xa9059cbb=xfer, balanceOf(address)=balance")]).unwrap();
+        assert_eq!(
+            config.function_aliases,
+            vec![
+                ([0xa9, 0x05, 0x9c, 0xbb], "xfer".to_string()),
+                ([0x70, 0xa0, 0x82, 0x31], "balance".to_string()),
+            ]
+        );
+
+        for bad in ["0xa9059cbb", "0xa905=xfer", "0xa9059cbb=not-a-symbol"] {
+            assert!(config_with(&[("FUNCTION_ALIASES", bad)]).is_err(), "{}", bad);
+        }
+
+        // Changing aliases over RPC is opt-in
+        assert!(!config.enable_function_alias_rpc);
+        assert!(config_with(&[("ENABLE_FUNCTION_ALIAS_RPC", "true")]).unwrap().enable_function_alias_rpc);
+        assert!(config_with(&[("ENABLE_FUNCTION_ALIAS_RPC", "yes")]).is_err());
+    }
+
+    #[test]
+    fn test_method_allowlist_and_denylist() {
+        let config = config_with(&[]).unwrap();
+        assert!(config.rpc_method_allowlist.is_none());
+        assert!(config.method_enabled("eth_sendRawTransaction"));
+
+        let config = config_with(&[("RPC_METHOD_DENYLIST", "eth_sendRawTransaction, eth_getLogs,")]).unwrap();
+        assert!(!config.method_enabled("eth_sendRawTransaction"));
+        assert!(!config.method_enabled("eth_getLogs"));
+        assert!(config.method_enabled("eth_call"));
+
+        // The denylist wins over the allowlist
+        let config = config_with(&[
+            ("RPC_METHOD_ALLOWLIST", "eth_chainId,eth_call"),
+            ("RPC_METHOD_DENYLIST", "eth_call"),
+        ])
+        .unwrap();
+        assert!(config.method_enabled("eth_chainId"));
+        assert!(!config.method_enabled("eth_call"));
+        assert!(!config.method_enabled("eth_blockNumber"));
+
+        // An empty allowlist is unset rather than blocking everything
+        let config = config_with(&[("RPC_METHOD_ALLOWLIST", " ")]).unwrap();
+        assert!(config.method_enabled("eth_blockNumber"));
+    }
+
+    #[test]
+    fn test_validate_aggregates_problems() {
+        let config = config_with(&[
+            ("STELLAR_RPC_URL", "not a url"),
+            ("STELLAR_SECRET_KEY", "nope"),
+            ("TVA_RPC_PORT", "0"),
+        ])
+        .unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.starts_with("Invalid configuration:"));
+        assert_eq!(err.matches("\n  - ").count(), 3, "{}", err);
+    }
+}
@@ -1,6 +1,20 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
 use tracing::debug;
 
+use crate::error::TvaError;
+use crate::state_store::{Namespace, StateStore};
+use crate::translator::tx::parse_quantity;
+
+/// Block gas limit reported unless `BLOCK_GAS_LIMIT` says otherwise: 30M,
+/// Ethereum mainnet's, since Soroban limits resources rather than gas.
+pub const DEFAULT_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
 /// EVM-formatted block object.
 /// Maps Stellar ledger data to EVM block format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,13 +60,14 @@ pub struct EvmBlock {
     pub uncles: Vec<String>,
     /// Base fee per gas (from Stellar fee model)
     pub base_fee_per_gas: String,
-    /// Mix hash (not applicable)
+    /// Mix hash: the Stellar ledger hash when known, zero otherwise
     pub mix_hash: String,
 }
 
 impl EvmBlock {
     /// Create an EVM block from Stellar ledger data.
     pub fn from_ledger(
+        network_passphrase: &str,
         ledger_sequence: u64,
         close_time: u64,
         tx_count: u32,
@@ -60,9 +75,9 @@ impl EvmBlock {
         include_txs: bool,
     ) -> Self {
         let number = format!("0x{:x}", ledger_sequence);
-        let hash = ledger_to_block_hash(ledger_sequence);
+        let hash = ledger_to_block_hash(network_passphrase, ledger_sequence);
         let parent_hash = if ledger_sequence > 0 {
-            ledger_to_block_hash(ledger_sequence - 1)
+            ledger_to_block_hash(network_passphrase, ledger_sequence - 1)
         } else {
             format!("0x{}", "0".repeat(64))
         };
@@ -71,7 +86,7 @@ impl EvmBlock {
 
         // Estimate gas from transaction count
         let gas_used = format!("0x{:x}", tx_count as u64 * 21000);
-        let gas_limit = "0x1c9c380".to_string(); // 30M gas limit
+        let gas_limit = format!("0x{:x}", DEFAULT_BLOCK_GAS_LIMIT);
 
         let transactions = if include_txs {
             // Full transaction objects would go here
@@ -118,40 +133,158 @@ impl EvmBlock {
     }
 
     /// Create a block representing the "latest" state.
-    pub fn latest(ledger_sequence: u64, close_time: u64, base_fee: u64) -> Self {
-        Self::from_ledger(ledger_sequence, close_time, 0, base_fee, false)
+    pub fn latest(network_passphrase: &str, ledger_sequence: u64, close_time: u64, base_fee: u64) -> Self {
+        Self::from_ledger(network_passphrase, ledger_sequence, close_time, 0, base_fee, false)
     }
 
     /// Create a "pending" block.
-    pub fn pending(ledger_sequence: u64) -> Self {
+    pub fn pending(network_passphrase: &str, ledger_sequence: u64) -> Self {
         let now = chrono::Utc::now().timestamp() as u64;
-        Self::from_ledger(ledger_sequence + 1, now, 0, 100, false)
+        Self::from_ledger(network_passphrase, ledger_sequence + 1, now, 0, 100, false)
+    }
+
+    /// Report `gas_limit` as the block gas limit.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = format!("0x{:x}", gas_limit);
+        self
+    }
+
+    /// Report the Stellar ledger hash (hex, as in getLatestLedger's `id`) as
+    /// the block's mix hash. Anything but a 32-byte hex hash is ignored.
+    pub fn with_ledger_hash(mut self, ledger_hash: &str) -> Self {
+        let digits = ledger_hash.strip_prefix("0x").unwrap_or(ledger_hash);
+        if digits.len() == 64 && digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            self.mix_hash = format!("0x{}", digits.to_lowercase());
+        }
+        self
     }
 }
 
-/// Generate a deterministic block hash from a ledger sequence number.
-/// Uses a simple hash derivation to produce a consistent 32-byte hash.
-pub fn ledger_to_block_hash(ledger_sequence: u64) -> String {
-    use sha3::{Digest, Keccak256};
+/// Generate a deterministic block hash for a ledger:
+/// keccak256("TVA_BLOCK_" || network ID || sequence), where the network ID is
+/// the SHA-256 of the network passphrase (as in Stellar transaction
+/// signatures), so the same sequence hashes differently on each network.
+///
+/// The real Stellar ledger hash is deliberately not folded in: a block must
+/// hash the same whether it is reached as "latest", by number, as a parent,
+/// from a log or from a receipt, and only the block handlers fetch the ledger
+/// header. Blocks report the real hash as `mixHash` instead.
+pub fn ledger_to_block_hash(network_passphrase: &str, ledger_sequence: u64) -> String {
+    block_hash(&network_id(network_passphrase), ledger_sequence)
+}
+
+fn network_id(network_passphrase: &str) -> [u8; 32] {
+    Sha256::digest(network_passphrase.as_bytes()).into()
+}
 
+fn block_hash(network_id: &[u8; 32], ledger_sequence: u64) -> String {
     let mut hasher = Keccak256::new();
     hasher.update(b"TVA_BLOCK_");
+    hasher.update(network_id);
     hasher.update(ledger_sequence.to_be_bytes());
-    let hash = hasher.finalize();
-    format!("0x{}", hex::encode(hash))
+    format!("0x{}", hex::encode(hasher.finalize()))
+}
+
+/// Ledgers searched back from the latest when resolving a block hash;
+/// one day of ledgers, matching Soroban RPC's default retention window.
+pub const BLOCK_HASH_SEARCH_DEPTH: u64 = 17_280;
+
+/// Resolves block hashes to ledger sequences. Hashes of the last
+/// `BLOCK_HASH_SEARCH_DEPTH` ledgers are kept in memory, extended as the
+/// chain advances; older blocks resolve if the state store recorded their
+/// hash when they were served.
+pub struct BlockHashIndex {
+    network_id: [u8; 32],
+    store: Option<Arc<StateStore>>,
+    window: Mutex<HashWindow>,
+}
+
+/// Block hashes of a contiguous run of ledgers, oldest first.
+#[derive(Default)]
+struct HashWindow {
+    hashes: VecDeque<(u64, String)>,
+    ledgers: HashMap<String, u64>,
+}
+
+impl HashWindow {
+    /// Cover `[latest_ledger - BLOCK_HASH_SEARCH_DEPTH, latest_ledger]`,
+    /// hashing only ledgers not already covered.
+    fn advance(&mut self, network_id: &[u8; 32], latest_ledger: u64) {
+        let oldest = latest_ledger.saturating_sub(BLOCK_HASH_SEARCH_DEPTH);
+        let next = self.hashes.back().map_or(oldest, |(seq, _)| seq + 1).max(oldest);
+        for seq in next..=latest_ledger {
+            let hash = block_hash(network_id, seq);
+            self.ledgers.insert(hash.clone(), seq);
+            self.hashes.push_back((seq, hash));
+        }
+        while self.hashes.front().is_some_and(|(seq, _)| *seq < oldest) {
+            if let Some((_, hash)) = self.hashes.pop_front() {
+                self.ledgers.remove(&hash);
+            }
+        }
+    }
+}
+
+impl BlockHashIndex {
+    pub fn new(network_passphrase: &str, store: Option<Arc<StateStore>>) -> Self {
+        Self {
+            network_id: network_id(network_passphrase),
+            store,
+            window: Mutex::new(HashWindow::default()),
+        }
+    }
+
+    /// Record the hash of a block being served, so it still resolves once it
+    /// is older than the search depth or the server restarts.
+    pub fn record(&self, block: &EvmBlock) {
+        let (Some(store), Some(seq)) = (&self.store, block.number.strip_prefix("0x")) else {
+            return;
+        };
+        if let Ok(seq) = u64::from_str_radix(seq, 16) {
+            store.record(Namespace::BlockHash, &block.hash, &seq.to_string());
+        }
+    }
+
+    /// The ledger whose derived block hash is `hash`, if it is at most
+    /// `latest_ledger`.
+    pub fn find(&self, hash: &str, latest_ledger: u64) -> Option<u64> {
+        let target = hash.to_lowercase();
+        let recent = {
+            let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+            window.advance(&self.network_id, latest_ledger);
+            window.ledgers.get(&target).copied()
+        };
+        recent.filter(|seq| *seq <= latest_ledger).or_else(|| {
+            let seq: u64 = self.store.as_ref()?.get(Namespace::BlockHash, &target)?.parse().ok()?;
+            (seq <= latest_ledger && block_hash(&self.network_id, seq) == target).then_some(seq)
+        })
+    }
+}
+
+/// A block parameter as the string `parse_block_number` takes: tags and hex
+/// strings as given, and JSON integers (sent by some clients) as hex.
+/// None for anything else.
+pub fn block_param(value: Option<&serde_json::Value>) -> Option<String> {
+    match value? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => n.as_u64().map(|n| format!("0x{:x}", n)),
+        _ => None,
+    }
 }
 
 /// Parse an EVM block number parameter.
 /// Handles "latest", "earliest", "pending", "safe", "finalized", and hex numbers.
-pub fn parse_block_number(block_param: &str, latest_ledger: u64) -> u64 {
+/// "safe" and "finalized" lag `finality_depth` ledgers behind the latest.
+/// Anything else is an invalid-params error.
+pub fn parse_block_number(block_param: &str, latest_ledger: u64, finality_depth: u64) -> Result<u64> {
     match block_param {
-        "latest" | "safe" | "finalized" => latest_ledger,
-        "earliest" => 0,
-        "pending" => latest_ledger + 1,
-        hex_str => {
-            let stripped = hex_str.strip_prefix("0x").unwrap_or(hex_str);
-            u64::from_str_radix(stripped, 16).unwrap_or(latest_ledger)
-        }
+        "latest" => Ok(latest_ledger),
+        "safe" | "finalized" => Ok(latest_ledger.saturating_sub(finality_depth)),
+        "earliest" => Ok(0),
+        "pending" => Ok(latest_ledger + 1),
+        quantity => parse_quantity(quantity)
+            .and_then(|number| u64::try_from(number).ok())
+            .ok_or_else(|| TvaError::InvalidParams(format!("invalid block number '{}'", block_param)).into()),
     }
 }
 
@@ -159,32 +292,123 @@ pub fn parse_block_number(block_param: &str, latest_ledger: u64) -> u64 {
 mod tests {
     use super::*;
 
+    const TESTNET: &str = "Test SDF Network ; September 2015";
+    const MAINNET: &str = "Public Global Stellar Network ; September 2015";
+
     #[test]
     fn test_ledger_to_block_hash() {
-        let hash1 = ledger_to_block_hash(100);
-        let hash2 = ledger_to_block_hash(101);
-        let hash1_again = ledger_to_block_hash(100);
+        let hash1 = ledger_to_block_hash(TESTNET, 100);
+        let hash2 = ledger_to_block_hash(TESTNET, 101);
+        let hash1_again = ledger_to_block_hash(TESTNET, 100);
 
         assert_ne!(hash1, hash2);
         assert_eq!(hash1, hash1_again);
         assert!(hash1.starts_with("0x"));
         assert_eq!(hash1.len(), 66); // 0x + 64 hex chars
+
+        // The same sequence on another network is a different block
+        assert_ne!(ledger_to_block_hash(MAINNET, 100), hash1);
+
+        // keccak256("TVA_BLOCK_" || sha256(passphrase) || sequence)
+        let mut preimage = b"TVA_BLOCK_".to_vec();
+        preimage.extend_from_slice(&Sha256::digest(TESTNET.as_bytes()));
+        preimage.extend_from_slice(&100u64.to_be_bytes());
+        assert_eq!(hash1, format!("0x{}", hex::encode(Keccak256::digest(&preimage))));
+    }
+
+    #[test]
+    fn test_block_hash_index() {
+        let index = BlockHashIndex::new(TESTNET, None);
+        let hash = ledger_to_block_hash(TESTNET, 990).to_uppercase().replacen("0X", "0x", 1);
+        assert_eq!(index.find(&hash, 1000), Some(990));
+        assert_eq!(BlockHashIndex::new(MAINNET, None).find(&hash, 1000), None);
+        assert_eq!(index.find(&ledger_to_block_hash(TESTNET, 1001), 1000), None);
+        assert_eq!(index.find(&format!("0x{}", "00".repeat(32)), 1000), None);
+
+        // The window follows the chain, dropping ledgers past the search depth
+        let latest = BLOCK_HASH_SEARCH_DEPTH + 1000;
+        let too_old = latest - BLOCK_HASH_SEARCH_DEPTH - 1;
+        let old_hash = ledger_to_block_hash(TESTNET, too_old);
+        assert_eq!(index.find(&ledger_to_block_hash(TESTNET, latest), latest), Some(latest));
+        assert_eq!(index.find(&old_hash, latest), None);
+        assert_eq!(index.find(&ledger_to_block_hash(TESTNET, too_old + 1), latest), Some(too_old + 1));
+        {
+            let window = index.window.lock().unwrap();
+            assert_eq!(window.hashes.len() as u64, BLOCK_HASH_SEARCH_DEPTH + 1);
+            assert_eq!(window.ledgers.len() as u64, BLOCK_HASH_SEARCH_DEPTH + 1);
+        }
+        // An upstream node lagging behind does not see blocks past its head
+        assert_eq!(index.find(&ledger_to_block_hash(TESTNET, latest), latest - 1), None);
+
+        // A block recorded when it was served resolves past the search depth
+        let path = std::env::temp_dir().join(format!("tva-state-block-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = Arc::new(StateStore::open(&path).unwrap());
+        let index = BlockHashIndex::new(TESTNET, Some(store.clone()));
+        index.record(&EvmBlock::from_ledger(TESTNET, too_old, 0, 0, 100, false));
+        assert_eq!(index.find(&old_hash, latest), Some(too_old));
+        assert_eq!(BlockHashIndex::new(MAINNET, Some(store)).find(&old_hash, latest), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_block_param_accepts_numbers() {
+        use serde_json::json;
+        assert_eq!(block_param(Some(&json!("latest"))).as_deref(), Some("latest"));
+        assert_eq!(block_param(Some(&json!("0xa"))).as_deref(), Some("0xa"));
+        assert_eq!(block_param(Some(&json!(10))).as_deref(), Some("0xa"));
+        assert_eq!(parse_block_number(&block_param(Some(&json!(255))).unwrap(), 1000, 0).unwrap(), 255);
+        assert_eq!(block_param(Some(&json!(-1))), None);
+        assert_eq!(block_param(Some(&json!(true))), None);
+        assert_eq!(block_param(None), None);
     }
 
     #[test]
     fn test_parse_block_number() {
-        assert_eq!(parse_block_number("latest", 1000), 1000);
-        assert_eq!(parse_block_number("earliest", 1000), 0);
-        assert_eq!(parse_block_number("pending", 1000), 1001);
-        assert_eq!(parse_block_number("0xa", 1000), 10);
-        assert_eq!(parse_block_number("0xff", 1000), 255);
+        assert_eq!(parse_block_number("latest", 1000, 0).unwrap(), 1000);
+        assert_eq!(parse_block_number("earliest", 1000, 0).unwrap(), 0);
+        assert_eq!(parse_block_number("pending", 1000, 0).unwrap(), 1001);
+        assert_eq!(parse_block_number("0xa", 1000, 0).unwrap(), 10);
+        assert_eq!(parse_block_number("0xff", 1000, 0).unwrap(), 255);
+        assert_eq!(parse_block_number("safe", 1000, 0).unwrap(), 1000);
+        assert_eq!(parse_block_number("finalized", 1000, 0).unwrap(), 1000);
+
+        // Malformed numbers are rejected rather than read as the latest ledger
+        for bad in ["0xzz", "foo", "0x", "10", "", "0x+1", "0x10000000000000000"] {
+            let err = parse_block_number(bad, 1000, 0).unwrap_err();
+            let error = crate::error::to_error_object(&err);
+            assert_eq!(error.code(), crate::error::INVALID_PARAMS_CODE, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_safe_and_finalized_lag_by_finality_depth() {
+        assert_eq!(parse_block_number("latest", 1000, 2).unwrap(), 1000);
+        assert_eq!(parse_block_number("safe", 1000, 2).unwrap(), 998);
+        assert_eq!(parse_block_number("finalized", 1000, 2).unwrap(), 998);
+        assert_eq!(parse_block_number("pending", 1000, 2).unwrap(), 1001);
+        assert_eq!(parse_block_number("0xa", 1000, 2).unwrap(), 10);
+
+        // Clamped at the genesis ledger
+        assert_eq!(parse_block_number("finalized", 1, 2).unwrap(), 0);
+        assert_eq!(parse_block_number("safe", 1, 2).unwrap(), 0);
     }
 
     #[test]
     fn test_evm_block_creation() {
-        let block = EvmBlock::from_ledger(42, 1700000000, 5, 100, false);
+        let block = EvmBlock::from_ledger(TESTNET, 42, 1700000000, 5, 100, false);
         assert_eq!(block.number, "0x2a");
         assert_eq!(block.timestamp, "0x6553f100");
-        assert!(block.hash.starts_with("0x"));
+        assert_eq!(block.hash, ledger_to_block_hash(TESTNET, 42));
+        assert_eq!(block.parent_hash, ledger_to_block_hash(TESTNET, 41));
+        assert_eq!(block.mix_hash, format!("0x{}", "0".repeat(64)));
+        assert_eq!(block.gas_limit, "0x1c9c380");
+        let block = block.with_gas_limit(50_000_000);
+        assert_eq!(block.gas_limit, "0x2faf080");
+
+        let ledger_hash = "AB".repeat(32);
+        let block = block.with_ledger_hash(&ledger_hash);
+        assert_eq!(block.mix_hash, format!("0x{}", "ab".repeat(32)));
+        assert_eq!(block.hash, ledger_to_block_hash(TESTNET, 42));
     }
 }
@@ -1,33 +1,54 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
 use sha3::{Digest, Keccak256};
 use tracing::debug;
 
 use crate::stellar::types::SorobanEvent;
+use crate::translator::abi::{encode_abi_values, is_dynamic_type, AbiParam, AbiRegistry};
+use crate::translator::address_map::AddressMapper;
 use crate::translator::receipt::EvmLog;
+use crate::translator::scval::{
+    decode_scval_xdr_to_abi, parse_scval_from_xdr, scval_to_abi_bytes, ScVal,
+};
+use crate::translator::tx::{decode_strkey_checked, STRKEY_VERSION_CONTRACT};
 use super::block::ledger_to_block_hash;
 
 /// Convert a Soroban contract event to an EVM log entry.
+///
+/// If the contract's ABI is registered and the first Soroban topic names one of its
+/// events, topics and data are ABI-encoded so they match what EVM tooling filters on.
+/// Otherwise each topic is converted to a raw 32-byte value.
 pub fn soroban_event_to_evm_log(
+    network_passphrase: &str,
+    mapper: &dyn AddressMapper,
     event: &SorobanEvent,
     log_index: u64,
     tx_hash: &str,
     tx_index: u64,
+    abi_registry: Option<&AbiRegistry>,
 ) -> Result<EvmLog> {
-    // Convert contract_id to EVM address format (take last 20 bytes)
-    let contract_address = contract_id_to_evm_address(&event.contract_id);
+    let contract_address = contract_id_to_evm_address(mapper, &event.contract_id)?;
 
-    // Convert Soroban topics to EVM topics (32-byte hex strings)
-    let topics: Vec<String> = event
-        .topic
-        .iter()
-        .map(|t| xdr_topic_to_evm_topic(t))
-        .collect();
+    let abi_encoded = abi_registry
+        .and_then(|registry| abi_encode_event(mapper, event, &contract_address, registry));
 
-    // Convert the event value to EVM log data
-    let data = xdr_value_to_log_data(&event.value);
+    let (topics, data) = match abi_encoded {
+        Some(encoded) => encoded,
+        None => {
+            // Convert Soroban topics to EVM topics (32-byte hex strings)
+            let topics: Vec<String> = event
+                .topic
+                .iter()
+                .map(|t| xdr_topic_to_evm_topic(t))
+                .collect();
+
+            // Convert the event value to EVM log data
+            (topics, xdr_value_to_log_data(mapper, &event.value))
+        }
+    };
 
     let block_number = format!("0x{:x}", event.ledger);
-    let block_hash = ledger_to_block_hash(event.ledger);
+    let block_hash = ledger_to_block_hash(network_passphrase, event.ledger);
 
     Ok(EvmLog {
         address: contract_address,
@@ -38,20 +59,49 @@ pub fn soroban_event_to_evm_log(
         transaction_index: format!("0x{:x}", tx_index),
         block_hash,
         log_index: format!("0x{:x}", log_index),
-        removed: false,
+        // Events of a reverted contract call never took effect
+        removed: event.in_successful_contract_call == Some(false),
     })
 }
 
-/// Convert a list of Soroban events to EVM logs.
+/// Convert a list of Soroban events (in ledger order, as getEvents returns
+/// them) to EVM logs. Each log's transaction index comes from its event ID,
+/// and log indexes count up within each ledger.
+/// Events from failed contract calls are skipped unless `include_failed` is
+/// set, in which case they are kept as `removed` logs.
 pub fn soroban_events_to_evm_logs(
+    network_passphrase: &str,
+    mapper: &dyn AddressMapper,
     events: &[SorobanEvent],
     tx_hash: &str,
+    abi_registry: Option<&AbiRegistry>,
+    include_failed: bool,
 ) -> Vec<EvmLog> {
     let mut logs = Vec::new();
+    let mut current_ledger = None;
+    let mut log_index = 0;
 
-    for (i, event) in events.iter().enumerate() {
-        match soroban_event_to_evm_log(event, i as u64, tx_hash, 0) {
-            Ok(log) => logs.push(log),
+    for event in events {
+        if event.in_successful_contract_call == Some(false) && !include_failed {
+            debug!("Skipping event {} from a failed contract call", event.id);
+            continue;
+        }
+        if current_ledger != Some(event.ledger) {
+            current_ledger = Some(event.ledger);
+            log_index = 0;
+        }
+        let tx_index = match parse_event_id(&event.id) {
+            Ok(ordinal) => ordinal.tx_index,
+            Err(e) => {
+                debug!("{}; using transaction index 0", e);
+                0
+            }
+        };
+        match soroban_event_to_evm_log(network_passphrase, mapper, event, log_index, tx_hash, tx_index, abi_registry) {
+            Ok(log) => {
+                logs.push(log);
+                log_index += 1;
+            }
             Err(e) => {
                 debug!("Failed to convert Soroban event to EVM log: {}", e);
             }
@@ -61,11 +111,148 @@ pub fn soroban_events_to_evm_logs(
     logs
 }
 
-/// Convert a Stellar contract ID to an EVM-style address (20 bytes, 0x-prefixed).
-fn contract_id_to_evm_address(contract_id: &str) -> String {
-    // Hash the contract ID and take the last 20 bytes
-    let hash = Keccak256::digest(contract_id.as_bytes());
-    format!("0x{}", hex::encode(&hash[12..32]))
+/// Position of a Soroban event, decoded from its event ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EventOrdinal {
+    /// Ledger sequence
+    pub ledger: u64,
+    /// 0-based index of the transaction in the ledger's application order
+    pub tx_index: u64,
+    /// 0-based index of the operation in the transaction
+    pub op_index: u64,
+    /// 0-based index of the event among the operation's events
+    pub event_index: u64,
+}
+
+/// Decode a Soroban event ID, `<TOID>-<event index>` with both parts
+/// zero-padded decimals (e.g. `0005302424889741312-0000000002`).
+///
+/// The TOID packs the ledger sequence (high 32 bits), the 1-based application
+/// order of the transaction (next 20 bits) and the operation index (low 12 bits).
+pub fn parse_event_id(id: &str) -> Result<EventOrdinal> {
+    let (toid, event_index) = id
+        .split_once('-')
+        .ok_or_else(|| anyhow!("Invalid event ID '{}': expected <toid>-<event index>", id))?;
+    let toid: u64 = toid.parse().map_err(|e| anyhow!("Invalid event ID '{}': {}", id, e))?;
+    let event_index: u64 = event_index.parse().map_err(|e| anyhow!("Invalid event ID '{}': {}", id, e))?;
+
+    let tx_order = (toid >> 12) & 0xfffff;
+    if tx_order == 0 {
+        return Err(anyhow!("Invalid event ID '{}': transaction order is 0", id));
+    }
+
+    Ok(EventOrdinal {
+        ledger: toid >> 32,
+        tx_index: tx_order - 1,
+        op_index: toid & 0xfff,
+        event_index,
+    })
+}
+
+/// Convert a Stellar contract ID (C... strkey or 32-byte hex) to an EVM-style
+/// address (20 bytes, 0x-prefixed) through the address mapper.
+pub(crate) fn contract_id_to_evm_address(mapper: &dyn AddressMapper, contract_id: &str) -> Result<String> {
+    let contract = if contract_id.starts_with('C') {
+        decode_strkey_checked(STRKEY_VERSION_CONTRACT, contract_id).ok()
+    } else {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(contract_id.strip_prefix("0x").unwrap_or(contract_id), &mut bytes)
+            .ok()
+            .map(|_| bytes)
+    };
+    contract
+        .map(|contract| mapper.contract_to_evm(&contract))
+        .ok_or_else(|| anyhow!("Invalid contract ID '{}'", contract_id))
+}
+
+/// ABI-encode a Soroban event using the contract's registered event definition.
+///
+/// Soroban events carry the event name as a Symbol in topic[0], followed by the
+/// indexed parameters; the event value holds the non-indexed parameters. Returns
+/// None if the event cannot be matched to the ABI.
+fn abi_encode_event(
+    mapper: &dyn AddressMapper,
+    event: &SorobanEvent,
+    contract_address: &str,
+    registry: &AbiRegistry,
+) -> Option<(Vec<String>, String)> {
+    let name = match decode_topic_scval(event.topic.first()?)? {
+        ScVal::Symbol(name) | ScVal::Str(name) => name,
+        _ => return None,
+    };
+    let info = registry.lookup_event_by_name(contract_address, &name)?;
+
+    let (indexed, non_indexed): (Vec<AbiParam>, Vec<AbiParam>) =
+        info.inputs.iter().cloned().partition(|p| p.indexed);
+    if indexed.len() != event.topic.len() - 1 {
+        debug!(
+            "Event {} has {} indexed params but {} topics",
+            info.signature,
+            indexed.len(),
+            event.topic.len() - 1
+        );
+        return None;
+    }
+
+    let mut topics = vec![format!("0x{}", hex::encode(info.topic))];
+    for (param, topic_xdr) in indexed.iter().zip(&event.topic[1..]) {
+        let scval = decode_topic_scval(topic_xdr)?;
+        let encoded = scval_to_abi_bytes(mapper, &scval, param).ok()?;
+        // Indexed dynamic values are stored as the keccak256 of their encoding
+        let word = if is_dynamic_type(&param.param_type) || param.param_type.starts_with("tuple") {
+            Keccak256::digest(&encoded).to_vec()
+        } else {
+            encoded
+        };
+        topics.push(format!("0x{}", hex::encode(word)));
+    }
+
+    let data = if non_indexed.is_empty() {
+        Vec::new()
+    } else {
+        decode_scval_xdr_to_abi(mapper, &event.value, &non_indexed).ok()?
+    };
+
+    Some((topics, format!("0x{}", hex::encode(data))))
+}
+
+/// Decode a base64 XDR topic into a ScVal.
+fn decode_topic_scval(xdr_base64: &str) -> Option<ScVal> {
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, xdr_base64).ok()?;
+    parse_scval_from_xdr(&bytes).ok()
+}
+
+/// Check whether a log matches an `eth_getLogs` topics filter.
+///
+/// Each position is either null (wildcard), a single topic, or an array of
+/// alternatives. Positions beyond the filter's length match anything.
+pub fn log_matches_topics(log: &EvmLog, filter_topics: &Value) -> bool {
+    let positions = match filter_topics.as_array() {
+        Some(positions) => positions,
+        None => return true,
+    };
+
+    positions.iter().enumerate().all(|(i, wanted)| {
+        let alternatives: Vec<&str> = match wanted {
+            Value::Null => return true,
+            Value::String(topic) => vec![topic.as_str()],
+            Value::Array(options) => {
+                if options.iter().any(|o| o.is_null()) {
+                    return true;
+                }
+                options.iter().filter_map(|o| o.as_str()).collect()
+            }
+            _ => return false,
+        };
+        if alternatives.is_empty() {
+            return true;
+        }
+
+        match log.topics.get(i) {
+            Some(actual) => alternatives.iter().any(|t| t.eq_ignore_ascii_case(actual)),
+            None => false,
+        }
+    })
 }
 
 /// Convert a Soroban XDR topic to a 32-byte EVM topic.
@@ -94,22 +281,75 @@ fn xdr_topic_to_evm_topic(xdr_base64: &str) -> String {
     }
 }
 
-/// Convert a Soroban XDR value to EVM log data (hex-encoded bytes).
-fn xdr_value_to_log_data(xdr_base64: &str) -> String {
-    match base64::Engine::decode(&base64::engine::general_purpose::STANDARD, xdr_base64) {
-        Ok(bytes) => {
-            if bytes.is_empty() {
-                "0x".to_string()
-            } else {
-                // Pad to 32-byte alignment for ABI compatibility
-                let padded_len = bytes.len().div_ceil(32) * 32;
-                let mut padded = bytes.clone();
-                padded.resize(padded_len, 0);
-                format!("0x{}", hex::encode(&padded))
-            }
-        }
-        Err(_) => "0x".to_string(),
+/// Convert a Soroban event value to EVM log data for a contract without a
+/// registered ABI.
+///
+/// The value (or each item of a Vec, or each value of a Map) is ABI-encoded as
+/// the Solidity type its ScVal type corresponds to, so a dapp that knows the
+/// event's parameter types decodes it as it would an EVM log. A value with no
+/// Solidity counterpart (such as a nested collection) is passed through as
+/// its raw XDR, padded to 32-byte alignment.
+fn xdr_value_to_log_data(mapper: &dyn AddressMapper, xdr_base64: &str) -> String {
+    let bytes = match base64::Engine::decode(&base64::engine::general_purpose::STANDARD, xdr_base64) {
+        Ok(bytes) if !bytes.is_empty() => bytes,
+        _ => return "0x".to_string(),
+    };
+
+    if let Some(data) = parse_scval_from_xdr(&bytes).ok().and_then(|scval| encode_untyped_scval(mapper, &scval)) {
+        return format!("0x{}", hex::encode(data));
     }
+
+    // Pad to 32-byte alignment for ABI compatibility
+    let padded_len = bytes.len().div_ceil(32) * 32;
+    let mut padded = bytes;
+    padded.resize(padded_len, 0);
+    format!("0x{}", hex::encode(&padded))
+}
+
+/// ABI-encode an event value by the Solidity types its ScVal types map to.
+/// Returns None if any item has no Solidity counterpart.
+fn encode_untyped_scval(mapper: &dyn AddressMapper, scval: &ScVal) -> Option<Vec<u8>> {
+    let items: Vec<&ScVal> = match scval {
+        ScVal::Void => return Some(Vec::new()),
+        ScVal::Vec(items) => items.iter().collect(),
+        ScVal::Map(entries) => entries.iter().map(|(_, value)| value).collect(),
+        other => vec![other],
+    };
+
+    let mut values = Vec::with_capacity(items.len());
+    let mut params = Vec::with_capacity(items.len());
+    for item in items {
+        let param_type = match item {
+            ScVal::Bool(_) => "bool",
+            ScVal::U32(_) => "uint32",
+            ScVal::I32(_) => "int32",
+            ScVal::U64(_) => "uint64",
+            ScVal::I64(_) => "int64",
+            ScVal::U128(_) => "uint128",
+            ScVal::I128(_) => "int128",
+            ScVal::U256(_) => "uint256",
+            ScVal::I256(_) => "int256",
+            ScVal::Address(_) => "address",
+            ScVal::Bytes(_) => "bytes",
+            ScVal::Str(_) | ScVal::Symbol(_) => "string",
+            ScVal::Void | ScVal::Vec(_) | ScVal::Map(_) => return None,
+        };
+        let param = AbiParam {
+            name: String::new(),
+            param_type: param_type.to_string(),
+            indexed: false,
+            components: None,
+            internal_type: None,
+        };
+        let value = match item {
+            ScVal::Symbol(symbol) => symbol.as_bytes().to_vec(),
+            other => scval_to_abi_bytes(mapper, other, &param).ok()?,
+        };
+        values.push(value);
+        params.push(param);
+    }
+
+    Some(encode_abi_values(&values, &params))
 }
 
 /// Ensure a string has the 0x prefix.
@@ -131,6 +371,7 @@ pub fn event_signature_to_topic(signature: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::translator::address_map::DefaultAddressMapper;
 
     #[test]
     fn test_event_signature_to_topic() {
@@ -142,10 +383,294 @@ mod tests {
         );
     }
 
+    fn param(name: &str, param_type: &str, indexed: bool) -> AbiParam {
+        AbiParam {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            indexed,
+            components: None,
+            internal_type: None,
+        }
+    }
+
+    fn to_base64(scval: &ScVal) -> String {
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, scval.to_xdr())
+    }
+
+    fn evm_address_key(byte: u8) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key[12..].copy_from_slice(&[byte; 20]);
+        key
+    }
+
+    #[test]
+    fn test_transfer_event_topics_and_filter() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::stellar::types::SorobanEvent;
+        use crate::translator::abi::AbiEntry;
+        use crate::translator::scval::StellarAddress;
+
+        let token = "0x1234567890abcdef1234567890abcdef12345678";
+        let registry = AbiRegistry::new();
+        registry
+            .register_contract(
+                token,
+                &[AbiEntry {
+                    entry_type: "event".to_string(),
+                    name: Some("Transfer".to_string()),
+                    inputs: vec![
+                        param("from", "address", true),
+                        param("to", "address", true),
+                        param("value", "uint256", false),
+                    ],
+                    outputs: vec![],
+                    state_mutability: None,
+                }],
+            )
+            .unwrap();
+
+        let event = SorobanEvent {
+            event_type: "contract".to_string(),
+            ledger: 100,
+            ledger_closed_at: None,
+            contract_id: format!("{:0>64}", &token[2..]),
+            id: "1".to_string(),
+            paging_token: None,
+            topic: vec![
+                to_base64(&ScVal::Symbol("transfer".to_string())),
+                to_base64(&ScVal::Address(StellarAddress::Account(evm_address_key(0xaa)))),
+                to_base64(&ScVal::Address(StellarAddress::Account(evm_address_key(0xbb)))),
+            ],
+            value: to_base64(&ScVal::I128(1000)),
+            in_successful_contract_call: Some(true),
+        };
+
+        let log = soroban_event_to_evm_log("Test SDF Network ; September 2015", &mapper, &event, 0, "0x00", 0, Some(&registry)).unwrap();
+        let transfer_topic = event_signature_to_topic("Transfer(address,address,uint256)");
+        let from_topic = format!("0x{}{}", "0".repeat(24), "aa".repeat(20));
+        let to_topic = format!("0x{}{}", "0".repeat(24), "bb".repeat(20));
+
+        assert_eq!(log.address, token);
+        assert_eq!(log.topics, vec![transfer_topic.clone(), from_topic.clone(), to_topic.clone()]);
+        assert_eq!(log.data, format!("0x{:064x}", 1000));
+
+        // Filter on the signature and recipient, any sender
+        let filter = serde_json::json!([transfer_topic, null, [to_topic, from_topic]]);
+        assert!(log_matches_topics(&log, &filter));
+        let approval = event_signature_to_topic("Approval(address,address,uint256)");
+        assert!(!log_matches_topics(&log, &serde_json::json!([approval])));
+        assert!(!log_matches_topics(&log, &serde_json::json!([null, null, null, transfer_topic])));
+        assert!(log_matches_topics(&log, &Value::Null));
+
+        // Without a registered ABI the raw topic conversion is used
+        let raw = soroban_event_to_evm_log("Test SDF Network ; September 2015", &mapper, &event, 0, "0x00", 0, None).unwrap();
+        assert_ne!(raw.topics[0], event_signature_to_topic("Transfer(address,address,uint256)"));
+    }
+
+    #[test]
+    fn test_transfer_uint256_value_is_abi_encoded() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::stellar::types::SorobanEvent;
+        use crate::translator::abi::AbiEntry;
+        use crate::translator::scval::StellarAddress;
+
+        let token = "0x2234567890abcdef1234567890abcdef12345678";
+        let registry = AbiRegistry::new();
+        registry
+            .register_contract(
+                token,
+                &[AbiEntry {
+                    entry_type: "event".to_string(),
+                    name: Some("Transfer".to_string()),
+                    inputs: vec![
+                        param("from", "address", true),
+                        param("to", "address", true),
+                        param("value", "uint256", false),
+                    ],
+                    outputs: vec![],
+                    state_mutability: None,
+                }],
+            )
+            .unwrap();
+
+        // 2^128 + 5: above u128, so all four limbs matter
+        let value = ScVal::U256([0, 1, 0, 5]);
+        let event = SorobanEvent {
+            event_type: "contract".to_string(),
+            ledger: 100,
+            ledger_closed_at: None,
+            contract_id: format!("{:0>64}", &token[2..]),
+            id: "1".to_string(),
+            paging_token: None,
+            topic: vec![
+                to_base64(&ScVal::Symbol("transfer".to_string())),
+                to_base64(&ScVal::Address(StellarAddress::Account(evm_address_key(0xaa)))),
+                to_base64(&ScVal::Address(StellarAddress::Account(evm_address_key(0xbb)))),
+            ],
+            value: to_base64(&value),
+            in_successful_contract_call: Some(true),
+        };
+        let expected = format!("0x{:032x}{:032x}", 1, 5);
+
+        let log = soroban_event_to_evm_log("Test SDF Network ; September 2015", &mapper, &event, 0, "0x00", 0, Some(&registry)).unwrap();
+        assert_eq!(log.data, expected);
+
+        // Without the ABI the value is still one uint256 word, not raw XDR
+        let raw = soroban_event_to_evm_log("Test SDF Network ; September 2015", &mapper, &event, 0, "0x00", 0, None).unwrap();
+        assert_eq!(raw.data, expected);
+    }
+
+    #[test]
+    fn test_failed_call_events_are_skipped_or_removed() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::stellar::types::SorobanEvent;
+
+        let event = |id: &str, successful: Option<bool>| SorobanEvent {
+            event_type: "contract".to_string(),
+            ledger: 100,
+            ledger_closed_at: None,
+            contract_id: "22".repeat(32),
+            id: id.to_string(),
+            paging_token: None,
+            topic: vec![to_base64(&ScVal::Symbol("ping".to_string()))],
+            value: to_base64(&ScVal::U32(1)),
+            in_successful_contract_call: successful,
+        };
+        let events = [
+            event("ok", Some(true)),
+            event("failed", Some(false)),
+            event("unknown", None),
+            event("ok-again", Some(true)),
+        ];
+        let network = "Test SDF Network ; September 2015";
+
+        let logs = soroban_events_to_evm_logs(network, &mapper, &events, "0x00", None, false);
+        assert_eq!(logs.len(), 3);
+        assert!(logs.iter().all(|log| !log.removed));
+        let indexes: Vec<&str> = logs.iter().map(|log| log.log_index.as_str()).collect();
+        assert_eq!(indexes, ["0x0", "0x1", "0x2"]);
+
+        let logs = soroban_events_to_evm_logs(network, &mapper, &events, "0x00", None, true);
+        let removed: Vec<bool> = logs.iter().map(|log| log.removed).collect();
+        assert_eq!(removed, [false, true, false, false]);
+    }
+
+    #[test]
+    fn test_logs_carry_transaction_and_block_order() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::stellar::types::SorobanEvent;
+
+        // TOID of (ledger, 1-based transaction order, operation index)
+        let id = |ledger: u64, tx: u64, op: u64, event: u32| {
+            format!("{:019}-{:010}", (ledger << 32) | (tx << 12) | op, event)
+        };
+        let event = |ledger: u64, id: String| SorobanEvent {
+            event_type: "contract".to_string(),
+            ledger,
+            ledger_closed_at: None,
+            contract_id: "22".repeat(32),
+            id,
+            paging_token: None,
+            topic: vec![to_base64(&ScVal::Symbol("ping".to_string()))],
+            value: to_base64(&ScVal::U32(1)),
+            in_successful_contract_call: Some(true),
+        };
+        let events = [
+            event(100, id(100, 1, 0, 0)),
+            event(100, id(100, 1, 0, 1)),
+            event(100, id(100, 3, 1, 0)),
+            event(101, id(101, 2, 0, 0)),
+        ];
+
+        let logs = soroban_events_to_evm_logs("Test SDF Network ; September 2015", &mapper, &events, "0x00", None, false);
+        let order: Vec<(&str, &str, &str)> = logs
+            .iter()
+            .map(|log| (log.block_number.as_str(), log.transaction_index.as_str(), log.log_index.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            [("0x64", "0x0", "0x0"), ("0x64", "0x0", "0x1"), ("0x64", "0x2", "0x2"), ("0x65", "0x1", "0x0")]
+        );
+
+    }
+
+    #[test]
+    fn test_parse_event_id() {
+        // Ledger 1234567, 5th transaction, first operation, third event
+        assert_eq!(
+            parse_event_id("0005302424889741312-0000000002").unwrap(),
+            EventOrdinal { ledger: 1_234_567, tx_index: 4, op_index: 0, event_index: 2 }
+        );
+        // Ledger 1234567, 1st transaction, second operation
+        assert_eq!(
+            parse_event_id("0005302424889724929-0000000000").unwrap(),
+            EventOrdinal { ledger: 1_234_567, tx_index: 0, op_index: 1, event_index: 0 }
+        );
+
+        // Ordinals order the way the IDs do
+        assert!(
+            parse_event_id("0005302424889724929-0000000000").unwrap()
+                < parse_event_id("0005302424889741312-0000000002").unwrap()
+        );
+
+        assert!(parse_event_id("1").is_err());
+        assert!(parse_event_id("abc-0000000000").is_err());
+        assert!(parse_event_id("0005302424889720832-0000000000").is_err()); // transaction order 0
+    }
+
+    #[test]
+    fn test_untyped_event_values() {
+        let mapper = DefaultAddressMapper::new();
+        assert_eq!(xdr_value_to_log_data(&mapper, &to_base64(&ScVal::Void)), "0x");
+        assert_eq!(xdr_value_to_log_data(&mapper, &to_base64(&ScVal::I128(-1))), format!("0x{}", "f".repeat(64)));
+
+        // A Vec is encoded as a tuple: a static word, then a string by offset
+        let value = ScVal::Vec(vec![ScVal::U32(7), ScVal::Symbol("hi".to_string())]);
+        let expected = [
+            format!("{:064x}", 7),
+            format!("{:064x}", 0x40),
+            format!("{:064x}", 2),
+            format!("{:0<64}", hex::encode("hi")),
+        ]
+        .concat();
+        assert_eq!(xdr_value_to_log_data(&mapper, &to_base64(&value)), format!("0x{}", expected));
+
+        // Nested collections have no flat encoding and pass through as XDR
+        let nested = ScVal::Vec(vec![ScVal::Vec(vec![ScVal::U32(1)])]);
+        let xdr = nested.to_xdr();
+        let data = xdr_value_to_log_data(&mapper, &to_base64(&nested));
+        assert!(data.starts_with(&format!("0x{}", hex::encode(&xdr))));
+        assert_eq!((data.len() - 2) % 64, 0);
+    }
+
     #[test]
     fn test_contract_id_to_evm_address() {
-        let addr = contract_id_to_evm_address("CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHWHYF");
+        let mapper = DefaultAddressMapper::new();
+        let addr = contract_id_to_evm_address(&mapper, &hex::encode([0x5c; 32])).unwrap();
         assert!(addr.starts_with("0x"));
         assert_eq!(addr.len(), 42); // 0x + 40 hex chars
+
+        // An ID that is not a contract has no address, rather than a made-up one
+        assert!(contract_id_to_evm_address(&mapper, "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHWHYF").is_err());
+        assert!(contract_id_to_evm_address(&mapper, "not-a-contract").is_err());
+    }
+
+    #[test]
+    fn test_log_address_maps_back_to_contract() {
+        use crate::translator::tx::encode_strkey;
+
+        // A native Soroban contract: its log address resolves to the same contract
+        let mapper = DefaultAddressMapper::new();
+        let contract = [0x5cu8; 32];
+        let strkey = encode_strkey(STRKEY_VERSION_CONTRACT, &contract);
+        let address = contract_id_to_evm_address(&mapper, &strkey).unwrap();
+        assert_eq!(mapper.evm_to_contract(&address), Some(contract));
+        assert_eq!(contract_id_to_evm_address(&mapper, &hex::encode(contract)).unwrap(), address);
+
+        // A contract derived from an EVM address shows as that address
+        let mut derived = [0u8; 32];
+        derived[12..].copy_from_slice(&[0x33; 20]);
+        let strkey = encode_strkey(STRKEY_VERSION_CONTRACT, &derived);
+        assert_eq!(contract_id_to_evm_address(&mapper, &strkey).unwrap(), format!("0x{}", "33".repeat(20)));
     }
 }
@@ -1,5 +1,10 @@
 pub mod block;
 pub mod logs;
+pub mod pending;
 
-pub use block::{EvmBlock, ledger_to_block_hash, parse_block_number};
-pub use logs::{soroban_event_to_evm_log, soroban_events_to_evm_logs, event_signature_to_topic};
+pub use block::{block_param, EvmBlock, ledger_to_block_hash, parse_block_number};
+pub use logs::{
+    event_signature_to_topic, log_matches_topics, parse_event_id, soroban_event_to_evm_log,
+    soroban_events_to_evm_logs, EventOrdinal,
+};
+pub use pending::{is_pending_tag, spawn_confirmation_watch, PendingTx, PendingTxStore, TxStatus};
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::stellar::types::GetTransactionResponse;
+use crate::stellar::SorobanClient;
+
+/// How long a submission is considered in flight if the account sequence never
+/// catches up with it (e.g. the transaction was dropped or expired).
+pub const PENDING_TX_TTL: Duration = Duration::from_secs(120);
+
+/// How long a submission is remembered, so its receipt can be served even if
+/// the upstream node has not caught up with it.
+pub const TRACKED_TX_RETENTION: Duration = Duration::from_secs(600);
+
+/// Interval between getTransaction polls while watching a submission.
+pub const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Polls before a watcher gives up; the transaction then stays pending until
+/// a receipt lookup finds it or it ages out.
+const CONFIRMATION_POLL_ATTEMPTS: u32 = 30;
+
+/// Where a tracked submission stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Submitted, not yet in a ledger
+    Pending,
+    /// Applied successfully
+    Success,
+    /// Applied but failed
+    Failed,
+}
+
+impl TxStatus {
+    /// Map a getTransaction status; anything but SUCCESS/FAILED is still pending.
+    pub fn from_stellar(status: &str) -> Self {
+        match status {
+            "SUCCESS" => TxStatus::Success,
+            "FAILED" => TxStatus::Failed,
+            _ => TxStatus::Pending,
+        }
+    }
+}
+
+/// A transaction submitted through this server, and what became of it.
+#[derive(Debug, Clone)]
+pub struct PendingTx {
+    /// Stellar transaction hash (hex)
+    pub stellar_hash: String,
+    /// Stellar account the transaction was submitted from
+    pub source_account: String,
+    /// Sequence number the transaction consumes
+    pub sequence: u64,
+    /// Maximum fee the transaction can be charged, in stroops
+    pub fee_stroops: u64,
+    /// When the transaction was submitted
+    pub submitted_at: Instant,
+    /// Current status
+    pub status: TxStatus,
+    /// The final getTransaction response, once applied
+    pub result: Option<GetTransactionResponse>,
+}
+
+impl PendingTx {
+    /// Track a freshly submitted transaction.
+    pub fn submitted(stellar_hash: &str, source_account: &str, sequence: u64, fee_stroops: u64) -> Self {
+        Self {
+            stellar_hash: stellar_hash.to_string(),
+            source_account: source_account.to_string(),
+            sequence,
+            fee_stroops,
+            submitted_at: Instant::now(),
+            status: TxStatus::Pending,
+            result: None,
+        }
+    }
+}
+
+/// In-flight state of one account, on top of its confirmed ledger state.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PendingSummary {
+    /// Number of in-flight transactions
+    pub count: u64,
+    /// Highest sequence number consumed by an in-flight transaction
+    pub highest_sequence: Option<u64>,
+    /// Total fees the in-flight transactions can be charged, in stroops
+    pub fee_stroops: u64,
+}
+
+impl PendingSummary {
+    /// The sequence number the account will have once its in-flight transactions apply.
+    pub fn sequence(&self, confirmed_sequence: u64) -> u64 {
+        self.highest_sequence.map_or(confirmed_sequence, |s| s.max(confirmed_sequence))
+    }
+
+    /// The balance the account will have once its in-flight transactions are charged.
+    pub fn balance_stroops(&self, confirmed_balance: u64) -> u64 {
+        confirmed_balance.saturating_sub(self.fee_stroops)
+    }
+}
+
+/// Transactions submitted through this server, keyed by EVM transaction hash.
+/// Backs the "pending" block tag and receipts for freshly sent transactions.
+#[derive(Debug, Default)]
+pub struct PendingTxStore {
+    txs: Mutex<HashMap<String, PendingTx>>,
+}
+
+impl PendingTxStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a submitted transaction under its EVM hash.
+    pub fn insert(&self, evm_hash: &str, tx: PendingTx) {
+        let mut txs = self.txs.lock().unwrap_or_else(|e| e.into_inner());
+        txs.insert(evm_hash.to_lowercase(), tx);
+    }
+
+    /// Look up a tracked transaction by EVM hash.
+    pub fn get(&self, evm_hash: &str) -> Option<PendingTx> {
+        let txs = self.txs.lock().unwrap_or_else(|e| e.into_inner());
+        txs.get(&evm_hash.to_lowercase()).cloned()
+    }
+
+    /// Record the getTransaction response of a tracked transaction. Returns
+    /// the new status, or None if the hash is not tracked.
+    pub fn resolve(&self, evm_hash: &str, response: &GetTransactionResponse) -> Option<TxStatus> {
+        let mut txs = self.txs.lock().unwrap_or_else(|e| e.into_inner());
+        let tx = txs.get_mut(&evm_hash.to_lowercase())?;
+        tx.status = TxStatus::from_stellar(&response.status);
+        if tx.status != TxStatus::Pending {
+            tx.result = Some(response.clone());
+        }
+        Some(tx.status)
+    }
+
+    /// Summarize the in-flight transactions of `account`. A transaction stops
+    /// counting once it is resolved, the account's sequence reaches it, or it
+    /// outlives `PENDING_TX_TTL`.
+    pub fn pending_for(&self, account: &str, confirmed_sequence: u64) -> PendingSummary {
+        let mut txs = self.txs.lock().unwrap_or_else(|e| e.into_inner());
+        txs.retain(|_, tx| tx.submitted_at.elapsed() < TRACKED_TX_RETENTION);
+
+        txs.values()
+            .filter(|tx| {
+                tx.source_account == account
+                    && tx.status == TxStatus::Pending
+                    && tx.sequence > confirmed_sequence
+                    && tx.submitted_at.elapsed() < PENDING_TX_TTL
+            })
+            .fold(PendingSummary::default(), |mut summary, tx| {
+                summary.count += 1;
+                summary.highest_sequence = Some(summary.highest_sequence.unwrap_or(0).max(tx.sequence));
+                summary.fee_stroops += tx.fee_stroops;
+                summary
+            })
+    }
+
+    /// Number of tracked transactions.
+    pub fn len(&self) -> usize {
+        self.txs.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether no transactions are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Watch a tracked submission in the background until getTransaction reports
+/// it applied, recording the result in `store`.
+pub fn spawn_confirmation_watch(
+    client: SorobanClient,
+    store: Arc<PendingTxStore>,
+    evm_hash: String,
+    poll_interval: Duration,
+) {
+    let Some(stellar_hash) = store.get(&evm_hash).map(|tx| tx.stellar_hash) else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        for attempt in 0..CONFIRMATION_POLL_ATTEMPTS {
+            match client.get_transaction(&stellar_hash).await {
+                Ok(response) => match store.resolve(&evm_hash, &response) {
+                    Some(TxStatus::Pending) => {}
+                    Some(status) => {
+                        info!("Transaction {} applied: {:?}", evm_hash, status);
+                        return;
+                    }
+                    None => return,
+                },
+                Err(e) => debug!("Polling transaction {} (attempt {}): {}", evm_hash, attempt, e),
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+        debug!("Stopped watching transaction {}; still pending", evm_hash);
+    });
+}
+
+/// Whether a block parameter selects the pending state. Every other tag (and
+/// any block number) is served from the latest confirmed state, since Soroban
+/// RPC cannot read historical state.
+pub fn is_pending_tag(block_param: Option<&Value>) -> bool {
+    block_param.and_then(|v| v.as_str()) == Some("pending")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_tx(account: &str, sequence: u64, fee_stroops: u64) -> PendingTx {
+        PendingTx::submitted(&format!("{:064x}", sequence), account, sequence, fee_stroops)
+    }
+
+    fn tx_response(status: &str) -> GetTransactionResponse {
+        serde_json::from_value(serde_json::json!({ "status": status, "ledger": 900 })).unwrap()
+    }
+
+    #[test]
+    fn test_pending_summary_prunes_applied_transactions() {
+        let store = PendingTxStore::new();
+        store.insert("0xAA", pending_tx("GA", 11, 500));
+        store.insert("0xbb", pending_tx("GA", 12, 700));
+        store.insert("0xcc", pending_tx("GB", 5, 100));
+
+        let summary = store.pending_for("GA", 10);
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.sequence(10), 12);
+        assert_eq!(summary.balance_stroops(10_000), 8_800);
+
+        // Once the account reaches sequence 11 the first transaction applied
+        let summary = store.pending_for("GA", 11);
+        assert_eq!(summary.count, 1);
+        assert_eq!(summary.fee_stroops, 700);
+
+        // Resolved transactions stop counting but stay tracked for receipts
+        assert_eq!(store.resolve("0xbb", &tx_response("FAILED")), Some(TxStatus::Failed));
+        assert_eq!(store.pending_for("GA", 11).count, 0);
+        assert_eq!(store.len(), 3);
+
+        assert_eq!(store.pending_for("GC", 0), PendingSummary::default());
+        assert_eq!(PendingSummary::default().sequence(42), 42);
+    }
+
+    #[test]
+    fn test_resolve_records_final_result() {
+        let store = PendingTxStore::new();
+        store.insert("0xAB", pending_tx("GA", 1, 100));
+
+        assert_eq!(store.resolve("0xab", &tx_response("NOT_FOUND")), Some(TxStatus::Pending));
+        assert!(store.get("0xAB").unwrap().result.is_none());
+        assert_eq!(store.resolve("0xab", &tx_response("SUCCESS")), Some(TxStatus::Success));
+        assert_eq!(store.get("0xab").unwrap().result.unwrap().ledger, Some(900));
+        assert_eq!(store.resolve("0xcd", &tx_response("SUCCESS")), None);
+    }
+
+    #[test]
+    fn test_is_pending_tag() {
+        assert!(is_pending_tag(Some(&Value::String("pending".into()))));
+        assert!(!is_pending_tag(Some(&Value::String("latest".into()))));
+        assert!(!is_pending_tag(Some(&Value::String("0x10".into()))));
+        assert!(!is_pending_tag(None));
+    }
+}
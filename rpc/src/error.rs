@@ -0,0 +1,177 @@
+use jsonrpsee::types::ErrorObjectOwned;
+
+use crate::translator::abi::{encode_abi_values, AbiParam};
+
+/// JSON-RPC code for internal server faults.
+pub const INTERNAL_ERROR_CODE: i32 = -32603;
+
+/// JSON-RPC code for missing or malformed method parameters.
+pub const INVALID_PARAMS_CODE: i32 = -32602;
+
+/// JSON-RPC code geth uses for execution reverts and rejected transactions.
+pub const SERVER_ERROR_CODE: i32 = -32000;
+
+/// JSON-RPC code for methods the server does not offer.
+pub const METHOD_NOT_FOUND_CODE: i32 = -32601;
+
+/// EIP-1474 code for standard methods the server recognizes but cannot support.
+pub const METHOD_NOT_SUPPORTED_CODE: i32 = -32004;
+
+/// EIP-1474 code for requests exceeding a server limit.
+pub const LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+/// Selector of the Solidity `Error(string)` revert encoding.
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// The failure classes a handler reports, each with its JSON-RPC error code.
+///
+/// Handlers return `anyhow::Result`; wrap one of these in the `anyhow::Error`
+/// and `to_error_object` recovers it by downcasting. Any other error is
+/// reported as an internal error.
+#[derive(Debug, thiserror::Error)]
+pub enum TvaError {
+    /// A required parameter is missing or malformed.
+    #[error("{0}")]
+    InvalidParams(String),
+    /// No ABI entry is registered for the contract function being called.
+    #[error("{0}")]
+    AbiNotFound(String),
+    /// The Soroban RPC or Horizon request failed or timed out.
+    #[error("{0}")]
+    Upstream(String),
+    /// Contract execution (or its simulation) reverted.
+    #[error("execution reverted: {reason}")]
+    Reverted { reason: String },
+    /// The network rejected a submitted transaction.
+    #[error("transaction rejected: {0}")]
+    Rejected(String),
+    /// A submitted transaction failed validation before translation.
+    #[error("{0}")]
+    InvalidTransaction(String),
+    /// The method exists but is disabled by configuration.
+    #[error("{0}")]
+    MethodUnavailable(String),
+    /// A standard method with no Stellar equivalent (e.g. mining).
+    #[error("{0}")]
+    Unsupported(String),
+    /// The request exceeds a configured limit (block range, result count).
+    #[error("{0}")]
+    LimitExceeded(String),
+}
+
+impl TvaError {
+    /// Create a revert error from a Soroban failure message.
+    pub fn reverted(reason: impl Into<String>) -> Self {
+        TvaError::Reverted { reason: reason.into() }
+    }
+
+    /// The JSON-RPC error code for this error.
+    pub fn code(&self) -> i32 {
+        match self {
+            TvaError::InvalidParams(_) => INVALID_PARAMS_CODE,
+            TvaError::Upstream(_) => INTERNAL_ERROR_CODE,
+            TvaError::Reverted { .. }
+            | TvaError::Rejected(_)
+            | TvaError::InvalidTransaction(_)
+            | TvaError::AbiNotFound(_) => SERVER_ERROR_CODE,
+            TvaError::MethodUnavailable(_) => METHOD_NOT_FOUND_CODE,
+            TvaError::Unsupported(_) => METHOD_NOT_SUPPORTED_CODE,
+            TvaError::LimitExceeded(_) => LIMIT_EXCEEDED_CODE,
+        }
+    }
+
+    /// ABI-encoded revert data, if any: `Error(string)` with the revert reason.
+    pub fn revert_data(&self) -> Option<Vec<u8>> {
+        match self {
+            TvaError::Reverted { reason } => Some(encode_revert_reason(reason)),
+            TvaError::InvalidParams(_)
+            | TvaError::AbiNotFound(_)
+            | TvaError::Upstream(_)
+            | TvaError::Rejected(_)
+            | TvaError::InvalidTransaction(_)
+            | TvaError::MethodUnavailable(_)
+            | TvaError::Unsupported(_)
+            | TvaError::LimitExceeded(_) => None,
+        }
+    }
+}
+
+/// Encode a reason as Solidity `Error(string)` revert data.
+pub fn encode_revert_reason(reason: &str) -> Vec<u8> {
+    let param = AbiParam {
+        name: String::new(),
+        param_type: "string".to_string(),
+        indexed: false,
+        components: None,
+        internal_type: None,
+    };
+    let mut data = ERROR_STRING_SELECTOR.to_vec();
+    data.extend(encode_abi_values(&[reason.as_bytes().to_vec()], &[param]));
+    data
+}
+
+/// Convert a handler error into a JSON-RPC error object, using the code
+/// carried by a `TvaError` or -32603 for anything else.
+pub fn to_error_object(err: &anyhow::Error) -> ErrorObjectOwned {
+    match err.downcast_ref::<TvaError>() {
+        Some(rpc_err) => ErrorObjectOwned::owned(
+            rpc_err.code(),
+            rpc_err.to_string(),
+            rpc_err
+                .revert_data()
+                .map(|data| format!("0x{}", hex::encode(data))),
+        ),
+        None => ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_revert_maps_to_server_error_with_data() {
+        let err = anyhow::Error::new(TvaError::reverted("not owner"));
+        let obj = to_error_object(&err);
+        assert_eq!(obj.code(), SERVER_ERROR_CODE);
+        assert_eq!(obj.message(), "execution reverted: not owner");
+
+        let data: String = serde_json::from_str(obj.data().unwrap().get()).unwrap();
+        let bytes = hex::decode(data.strip_prefix("0x").unwrap()).unwrap();
+        assert_eq!(&bytes[..4], &ERROR_STRING_SELECTOR);
+        assert_eq!(bytes.len(), 4 + 32 + 32 + 32);
+        assert_eq!(bytes[4 + 31], 0x20); // offset
+        assert_eq!(bytes[4 + 63], 9); // length of "not owner"
+        assert_eq!(&bytes[4 + 64..4 + 73], b"not owner");
+    }
+
+    #[test]
+    fn test_other_errors_stay_internal() {
+        let err = anyhow::anyhow!("Soroban RPC HTTP error: 502");
+        let obj = to_error_object(&err);
+        assert_eq!(obj.code(), INTERNAL_ERROR_CODE);
+        assert!(obj.data().is_none());
+
+        let rejected = anyhow::Error::new(TvaError::Rejected("txBadSeq".to_string()));
+        let obj = to_error_object(&rejected);
+        assert_eq!(obj.code(), SERVER_ERROR_CODE);
+        assert!(obj.data().is_none());
+    }
+
+    #[test]
+    fn test_error_class_codes() {
+        let codes = [
+            (TvaError::InvalidParams("eth_call: 'to' field is required".to_string()), INVALID_PARAMS_CODE),
+            (TvaError::AbiNotFound("no fallback".to_string()), SERVER_ERROR_CODE),
+            (TvaError::Upstream("Request to Soroban RPC timed out".to_string()), INTERNAL_ERROR_CODE),
+            (TvaError::reverted("not owner"), SERVER_ERROR_CODE),
+            (TvaError::Unsupported("mining is not supported".to_string()), METHOD_NOT_SUPPORTED_CODE),
+        ];
+        for (err, code) in codes {
+            let message = err.to_string();
+            let obj = to_error_object(&anyhow::Error::new(err));
+            assert_eq!(obj.code(), code);
+            assert_eq!(obj.message(), message);
+        }
+    }
+}
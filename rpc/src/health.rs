@@ -0,0 +1,370 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use http::{Method, StatusCode};
+use jsonrpsee::server::{HttpBody, HttpRequest, HttpResponse};
+use serde_json::json;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tower::{Layer, Service};
+use tracing::{debug, info, warn};
+
+use crate::stellar::SorobanClient;
+
+/// Path of the plain HTTP readiness endpoint.
+pub const HEALTH_PATH: &str = "/health";
+
+/// Default interval between background upstream health checks.
+pub const DEFAULT_HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Ledgers the upstream may trail the network head before eth_syncing reports syncing.
+pub const SYNC_LAG_THRESHOLD: u64 = 10;
+
+/// Cached view of the Soroban RPC upstream, refreshed by a background task.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UpstreamStatus {
+    /// Whether the last health check reached Soroban RPC and it reported healthy
+    pub healthy: bool,
+    /// Status string reported by getHealth, if it answered
+    pub status: Option<String>,
+    /// Why the last health check failed, if it did
+    pub error: Option<String>,
+    /// Latest ledger known to Soroban RPC
+    pub latest_ledger: Option<u64>,
+    /// Protocol version of the latest ledger
+    pub protocol_version: Option<u32>,
+    /// Latest ledger closed by the network, as reported by Horizon's stellar-core
+    pub network_head: Option<u64>,
+    /// Ledger at which the upstream was first seen lagging, while it still is
+    pub sync_started_at: Option<u64>,
+    /// Unix time (seconds) of the last health check
+    pub checked_at: Option<u64>,
+    /// Whether the client's circuit breaker was failing calls fast
+    pub circuit_open: bool,
+}
+
+impl UpstreamStatus {
+    /// Peer count for net_peerCount: the single upstream, if it is reachable.
+    pub fn peer_count(&self) -> u64 {
+        if self.healthy {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Whether the upstream trails the network head by more than `SYNC_LAG_THRESHOLD`.
+    pub fn is_lagging(&self) -> bool {
+        match (self.latest_ledger, self.network_head) {
+            (Some(latest), Some(head)) => head > latest + SYNC_LAG_THRESHOLD,
+            _ => false,
+        }
+    }
+}
+
+/// Shared handle to the cached upstream status.
+pub type SharedUpstreamStatus = Arc<RwLock<UpstreamStatus>>;
+
+/// Query Soroban RPC and Horizon for a fresh upstream status.
+/// `previous` carries over the ledger at which a lag started.
+pub async fn refresh_upstream_status(
+    client: &SorobanClient,
+    previous: &UpstreamStatus,
+) -> UpstreamStatus {
+    let mut status = UpstreamStatus {
+        checked_at: Some(chrono::Utc::now().timestamp().max(0) as u64),
+        ..UpstreamStatus::default()
+    };
+
+    match client.get_health().await {
+        Ok(health) => {
+            status.healthy = health.status == "healthy";
+            if !status.healthy {
+                status.error = Some(format!("Soroban RPC reports status '{}'", health.status));
+            }
+            status.latest_ledger = health.latest_ledger;
+            status.status = Some(health.status);
+        }
+        Err(e) => {
+            debug!("Upstream health check failed: {}", e);
+            status.error = Some(e.to_string());
+        }
+    }
+
+    if let Ok(latest) = client.get_latest_ledger().await {
+        status.latest_ledger = Some(latest.sequence);
+        status.protocol_version = Some(latest.protocol_version);
+    }
+    status.network_head = client.get_network_head().await.ok();
+    status.circuit_open = client.circuit_open();
+
+    if status.is_lagging() {
+        status.sync_started_at = previous.sync_started_at.or(status.latest_ledger);
+    }
+    status
+}
+
+/// Spawn a background task that refreshes `status` every `interval`.
+/// The first refresh happens one interval from now; abort the returned
+/// handle to stop polling on shutdown.
+pub fn spawn_upstream_monitor(
+    client: SorobanClient,
+    status: SharedUpstreamStatus,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let previous = status.read().unwrap_or_else(|e| e.into_inner()).clone();
+            let current = refresh_upstream_status(&client, &previous).await;
+            if current.healthy != previous.healthy {
+                if current.healthy {
+                    info!("Soroban RPC upstream is reachable again");
+                } else {
+                    warn!(
+                        "Soroban RPC upstream is unreachable or unhealthy: {}",
+                        current.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+            }
+            *status.write().unwrap_or_else(|e| e.into_inner()) = current;
+        }
+    })
+}
+
+/// Tower layer that answers `GET /health` directly from the cached upstream
+/// status and passes everything else through to the JSON-RPC service.
+#[derive(Clone)]
+pub struct HealthLayer {
+    upstream: SharedUpstreamStatus,
+}
+
+impl HealthLayer {
+    /// Create a health layer reporting the given cached upstream status.
+    pub fn new(upstream: SharedUpstreamStatus) -> Self {
+        Self { upstream }
+    }
+}
+
+impl<S> Layer<S> for HealthLayer {
+    type Service = HealthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HealthService {
+            inner,
+            upstream: self.upstream.clone(),
+        }
+    }
+}
+
+/// Service produced by `HealthLayer`.
+#[derive(Clone)]
+pub struct HealthService<S> {
+    inner: S,
+    upstream: SharedUpstreamStatus,
+}
+
+impl<S, B> Service<HttpRequest<B>> for HealthService<S>
+where
+    S: Service<HttpRequest<B>, Response = HttpResponse>,
+    S::Future: Send + 'static,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        if req.method() == Method::GET && req.uri().path() == HEALTH_PATH {
+            let status = self.upstream.read().unwrap_or_else(|e| e.into_inner()).clone();
+            let response = health_response(&status);
+            return Box::pin(async move { Ok(response) });
+        }
+
+        Box::pin(self.inner.call(req))
+    }
+}
+
+/// Build the `/health` response: 200 with the latest ledger when the last
+/// check found Soroban RPC reachable and healthy, 503 otherwise. Both report
+/// whether the circuit breaker was open.
+pub fn health_response(upstream: &UpstreamStatus) -> HttpResponse {
+    let (status, body) = if upstream.healthy {
+        (
+            StatusCode::OK,
+            json!({
+                "status": "ok",
+                "upstream": upstream.status,
+                "latestLedger": upstream.latest_ledger,
+                "protocolVersion": upstream.protocol_version,
+                "circuitOpen": upstream.circuit_open,
+                "checkedAt": upstream.checked_at,
+            }),
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            json!({
+                "status": "unavailable",
+                "error": upstream.error.as_deref().unwrap_or("Upstream not checked yet"),
+                "circuitOpen": upstream.circuit_open,
+                "checkedAt": upstream.checked_at,
+            }),
+        )
+    };
+
+    HttpResponse::builder()
+        .status(status)
+        .header("content-type", "application/json; charset=utf-8")
+        .body(HttpBody::from(body.to_string()))
+        .expect("Static status and header are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{latest_ledger_response, spawn_mock_server};
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+
+    async fn body_json(response: HttpResponse) -> Value {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_ok_reports_latest_ledger() {
+        let url = spawn_mock_server(vec![
+            (
+                "rpc:getHealth",
+                200,
+                r#"{"jsonrpc":"2.0","id":1,"result":{"status":"healthy"}}"#.to_string(),
+            ),
+            ("rpc:getLatestLedger", 200, latest_ledger_response(4242)),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let status = refresh_upstream_status(&client, &UpstreamStatus::default()).await;
+        assert!(status.checked_at.is_some());
+
+        let response = health_response(&status);
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["latestLedger"], 4242);
+    }
+
+    #[test]
+    fn test_upstream_status_lag_and_peers() {
+        let mut status = UpstreamStatus::default();
+        assert_eq!(status.peer_count(), 0);
+        assert!(!status.is_lagging());
+
+        status.healthy = true;
+        status.latest_ledger = Some(100);
+        status.network_head = Some(100 + SYNC_LAG_THRESHOLD);
+        assert_eq!(status.peer_count(), 1);
+        assert!(!status.is_lagging());
+
+        status.network_head = Some(100 + SYNC_LAG_THRESHOLD + 1);
+        assert!(status.is_lagging());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_upstream_status_detects_lag() {
+        let url = spawn_mock_server(vec![
+            (
+                "rpc:getHealth",
+                200,
+                r#"{"jsonrpc":"2.0","id":1,"result":{"status":"healthy"}}"#.to_string(),
+            ),
+            ("rpc:getLatestLedger", 200, latest_ledger_response(500)),
+            ("GET / ", 200, r#"{"core_latest_ledger": 600}"#.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url(&url);
+
+        let first = refresh_upstream_status(&client, &UpstreamStatus::default()).await;
+        assert!(first.healthy);
+        assert_eq!(first.latest_ledger, Some(500));
+        assert_eq!(first.network_head, Some(600));
+        assert_eq!(first.sync_started_at, Some(500));
+
+        // The starting ledger is kept while the lag persists
+        let previous = UpstreamStatus { sync_started_at: Some(450), ..first };
+        let second = refresh_upstream_status(&client, &previous).await;
+        assert_eq!(second.sync_started_at, Some(450));
+    }
+
+    #[tokio::test]
+    async fn test_health_unreachable_is_503() {
+        // Bind and drop a listener to get a port nothing is listening on
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let status = refresh_upstream_status(&client, &UpstreamStatus::default()).await;
+        assert!(!status.healthy);
+        assert!(status.error.is_some());
+
+        let response = health_response(&status);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body_json(response).await["status"], "unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_open_circuit() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url(&url)
+            .with_circuit_breaker(1, Duration::from_secs(60));
+
+        let status = refresh_upstream_status(&client, &UpstreamStatus::default()).await;
+        assert!(status.circuit_open);
+        assert!(status.error.is_some());
+
+        let response = health_response(&status);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body_json(response).await["circuitOpen"], true);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_updates_shared_status() {
+        let url = spawn_mock_server(vec![
+            (
+                "rpc:getHealth",
+                200,
+                r#"{"jsonrpc":"2.0","id":1,"result":{"status":"healthy"}}"#.to_string(),
+            ),
+            ("rpc:getLatestLedger", 200, latest_ledger_response(77)),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let shared: SharedUpstreamStatus = Arc::default();
+
+        let monitor = spawn_upstream_monitor(client, shared.clone(), Duration::from_millis(20));
+        for _ in 0..100 {
+            if shared.read().unwrap().healthy {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        monitor.abort();
+
+        let status = shared.read().unwrap().clone();
+        assert!(status.healthy);
+        assert_eq!(status.latest_ledger, Some(77));
+        assert!(monitor.await.unwrap_err().is_cancelled());
+    }
+}
@@ -23,7 +23,13 @@
 //! # Modules
 //!
 //! - `config` - Environment and configuration management
+//! - `error` - JSON-RPC error codes for reverts and server faults
+//! - `health` - Plain HTTP `GET /health` readiness endpoint
+//! - `method_filter` - Allowlist/denylist enforcement for RPC methods
+//! - `metrics` - Prometheus metrics for RPC methods and upstream failures
+//! - `request_log` - Per-request tracing spans with a request ID
 //! - `server` - JSON-RPC server setup and method registration
+//! - `state_store` - Optional on-disk persistence of address and block-hash mappings
 //! - `methods` - Individual RPC method implementations (eth, net, web3)
 //! - `translator` - EVM-to-Stellar transaction translation logic
 //! - `stellar` - Soroban/Horizon RPC client wrapper
@@ -31,7 +37,16 @@
 
 pub mod config;
 pub mod emulator;
+pub mod error;
+pub mod health;
+pub mod method_filter;
 pub mod methods;
+pub mod metrics;
+pub mod request_log;
 pub mod server;
+pub mod state_store;
 pub mod stellar;
 pub mod translator;
+
+#[cfg(test)]
+mod test_utils;
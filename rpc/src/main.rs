@@ -34,12 +34,15 @@ async fn main() -> Result<()> {
 
     // Load configuration
     let config = Config::from_env()?;
+    config.validate()?;
 
     info!("Configuration:");
     info!("  Chain ID: {} ({})", config.tva_chain_id, config.chain_id_hex());
-    info!("  RPC Port: {}", config.tva_rpc_port);
+    info!("  Bind address: {}", config.socket_addr());
+    info!("  Stellar network: {}", config.stellar_network);
     info!("  Stellar RPC: {}", config.stellar_rpc_url);
-    info!("  Network: {}", config.stellar_network_passphrase);
+    info!("  Horizon: {}", config.stellar_horizon_url);
+    info!("  Passphrase: {}", config.stellar_network_passphrase);
     info!("");
 
     // Start the RPC server
@@ -0,0 +1,127 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::server::MethodResponse;
+use jsonrpsee::types::Request;
+use tower::Layer;
+use tracing::debug;
+
+use crate::config::Config;
+use crate::error::{to_error_object, TvaError};
+
+/// JSON-RPC middleware layer that refuses methods disabled by
+/// `RPC_METHOD_ALLOWLIST` and `RPC_METHOD_DENYLIST` before their handler runs.
+///
+/// Disabled methods are answered with a method-not-found error, the same
+/// code geth uses for namespaces it does not expose.
+#[derive(Clone)]
+pub struct MethodFilterLayer {
+    config: Arc<Config>,
+}
+
+impl MethodFilterLayer {
+    /// Create a layer enforcing the method lists of `config`.
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: Arc::new(config.clone()),
+        }
+    }
+}
+
+impl<S> Layer<S> for MethodFilterLayer {
+    type Service = MethodFilterService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodFilterService {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// Service produced by `MethodFilterLayer`.
+#[derive(Clone)]
+pub struct MethodFilterService<S> {
+    inner: S,
+    config: Arc<Config>,
+}
+
+impl<'a, S> RpcServiceT<'a> for MethodFilterService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let method = request.method_name();
+        if self.config.method_enabled(method) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        debug!("Refusing disabled method {}", method);
+        let err = TvaError::MethodUnavailable(format!("{} is disabled on this server", method));
+        let response = MethodResponse::error(request.id, to_error_object(&err.into()));
+        Box::pin(std::future::ready(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::tx::{encode_strkey, STRKEY_VERSION_SEED};
+    use jsonrpsee::server::{RpcModule, RpcServiceBuilder, Server};
+
+    #[tokio::test]
+    async fn test_denylisted_method_is_refused() {
+        let config = Config::from_lookup(|key| match key {
+            "STELLAR_SECRET_KEY" => Some(encode_strkey(STRKEY_VERSION_SEED, &[5; 32])),
+            "RPC_METHOD_DENYLIST" => Some("eth_sendRawTransaction".to_string()),
+            _ => None,
+        })
+        .unwrap();
+
+        let mut module = RpcModule::new(());
+        for method in ["eth_chainId", "eth_sendRawTransaction"] {
+            module
+                .register_method(method, |_, _, _| Ok::<_, jsonrpsee::types::ErrorObjectOwned>("ran"))
+                .unwrap();
+        }
+
+        let server = Server::builder()
+            .set_rpc_middleware(RpcServiceBuilder::new().layer(MethodFilterLayer::new(&config)))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.start(module);
+
+        let call = |method: &'static str| {
+            let url = format!("http://{}", addr);
+            async move {
+                reqwest::Client::new()
+                    .post(url)
+                    .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method }))
+                    .send()
+                    .await
+                    .unwrap()
+                    .json::<serde_json::Value>()
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let allowed = call("eth_chainId").await;
+        assert_eq!(allowed["result"], "ran");
+
+        let refused = call("eth_sendRawTransaction").await;
+        assert!(refused.get("result").is_none());
+        assert_eq!(refused["error"]["code"], crate::error::METHOD_NOT_FOUND_CODE);
+        assert_eq!(refused["error"]["message"], "eth_sendRawTransaction is disabled on this server");
+        assert_eq!(refused["id"], 1);
+
+        handle.stop().unwrap();
+    }
+}
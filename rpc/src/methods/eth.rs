@@ -1,18 +1,40 @@
-use anyhow::{anyhow, Result};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use anyhow::Result;
 use serde_json::Value;
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
-use crate::emulator::block::{EvmBlock, parse_block_number};
-use crate::stellar::SorobanClient;
-use crate::stellar::types::{EventFilter, EventPagination, GetEventsParams};
+use crate::emulator::block::{
+    block_param, ledger_to_block_hash, parse_block_number, BlockHashIndex, EvmBlock,
+};
+use crate::emulator::logs::{log_matches_topics, parse_event_id, soroban_events_to_evm_logs};
+use crate::emulator::pending::{
+    is_pending_tag, spawn_confirmation_watch, PendingTx, PendingTxStore, TxStatus,
+    CONFIRMATION_POLL_INTERVAL,
+};
+use crate::error::TvaError;
+use crate::health::UpstreamStatus;
+use crate::stellar::{SorobanClient, StellarSigner};
+use crate::stellar::types::{
+    EventFilter, EventPagination, GetEventsParams, GetTransactionResponse, LatestLedgerResponse,
+    SimulateTransactionResponse, SorobanEvent,
+};
+use crate::translator::address_map::AddressMapper;
+use crate::translator::abi::encode_abi_values;
+use crate::translator::contract_data::{contract_data_key, decode_contract_data, instance_wasm_hash, StorageDurability};
+use crate::translator::envelope::{decode_envelope_summary, decode_footprint_keys, EnvelopeSummary};
 use crate::translator::receipt::{
-    build_receipt_from_stellar, build_transaction_from_stellar,
+    build_receipt_from_stellar, build_transaction_from_stellar, EvmLog, EvmTransaction, EvmTransactionReceipt,
 };
+use crate::translator::scval::{scval_from_json, scval_to_abi_bytes, ScVal};
 use crate::translator::tx::{
-    decode_calldata, decode_raw_transaction, stroops_to_wei,
+    decode_calldata, decode_raw_transaction, decode_raw_transaction_hex, decode_strkey_checked, encode_strkey,
+    gas_covering_fee, intrinsic_gas, invokes_contract, parse_call_object, parse_quantity, stroops_to_wei,
+    STRKEY_VERSION_ACCOUNT, STRKEY_VERSION_CONTRACT,
 };
-use crate::translator::AbiRegistry;
+use crate::translator::{AbiRegistry, TranslationStats};
 
 /// Handler for eth_chainId
 pub async fn chain_id(config: &Config) -> Result<Value> {
@@ -32,12 +54,11 @@ pub async fn block_number(client: &SorobanClient) -> Result<Value> {
 /// Handler for eth_getBlockByNumber
 pub async fn get_block_by_number(
     client: &SorobanClient,
+    config: &Config,
+    block_hashes: &BlockHashIndex,
     params: &[Value],
 ) -> Result<Value> {
-    let block_param = params
-        .first()
-        .and_then(|v| v.as_str())
-        .unwrap_or("latest");
+    let block_param = block_param(params.first()).unwrap_or_else(|| "latest".to_string());
 
     let include_txs = params
         .get(1)
@@ -45,82 +66,159 @@ pub async fn get_block_by_number(
         .unwrap_or(false);
 
     let latest_ledger = client.get_latest_ledger().await?;
-    let target_ledger = parse_block_number(block_param, latest_ledger.sequence);
+    let target_ledger = parse_block_number(&block_param, latest_ledger.sequence, config.finality_depth)?;
 
     debug!(
         "eth_getBlockByNumber: param={}, target_ledger={}",
         block_param, target_ledger
     );
 
-    // Get base fee for the block
-    let base_fee = client.get_base_fee().await.unwrap_or(100);
-
-    // Estimate close time (Stellar ~5 second blocks)
-    let time_diff = (latest_ledger.sequence - target_ledger) * 5;
-    let now = chrono::Utc::now().timestamp() as u64;
-    let close_time = if target_ledger == latest_ledger.sequence {
-        now
-    } else {
-        now.saturating_sub(time_diff)
-    };
-
-    let block = EvmBlock::from_ledger(target_ledger, close_time, 0, base_fee, include_txs);
+    let block = block_at_ledger(client, config, &latest_ledger, target_ledger, include_txs).await;
+    block_hashes.record(&block);
 
     Ok(serde_json::to_value(&block)?)
 }
 
 /// Handler for eth_getBlockByHash
+/// Resolves the hash to its ledger; an unknown hash is null.
 pub async fn get_block_by_hash(
     client: &SorobanClient,
+    config: &Config,
+    block_hashes: &BlockHashIndex,
     params: &[Value],
 ) -> Result<Value> {
-    // Since we generate block hashes deterministically, we cannot reverse them.
-    // Return the latest block as a fallback.
+    let block_hash = params
+        .first()
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TvaError::InvalidParams("eth_getBlockByHash requires block hash".to_string()))?;
+
     let include_txs = params
         .get(1)
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
-    let latest = client.get_latest_ledger().await?;
-    let base_fee = client.get_base_fee().await.unwrap_or(100);
-    let now = chrono::Utc::now().timestamp() as u64;
+    let latest_ledger = client.get_latest_ledger().await?;
+    let Some(ledger) = block_hashes.find(block_hash, latest_ledger.sequence) else {
+        debug!("eth_getBlockByHash: unknown block {}", block_hash);
+        return Ok(Value::Null);
+    };
+
+    let block = block_at_ledger(client, config, &latest_ledger, ledger, include_txs).await;
+    block_hashes.record(&block);
 
-    let block = EvmBlock::from_ledger(latest.sequence, now, 0, base_fee, include_txs);
     Ok(serde_json::to_value(&block)?)
 }
 
+/// Build the block for `ledger`, with its close time and ledger hash (as
+/// `mixHash`) from Horizon when Horizon has the ledger.
+async fn block_at_ledger(
+    client: &SorobanClient,
+    config: &Config,
+    latest_ledger: &LatestLedgerResponse,
+    ledger: u64,
+    include_txs: bool,
+) -> EvmBlock {
+    // Get base fee for the block
+    let base_fee = client.get_base_fee().await.unwrap_or(100);
+
+    let header = match client.get_ledger_header(ledger).await {
+        Ok(header) => header,
+        Err(e) => {
+            warn!("Could not fetch header for ledger {}: {}", ledger, e);
+            None
+        }
+    };
+    let close_time = header.as_ref().map(|h| h.close_time).unwrap_or_else(|| {
+        // Estimate close time (Stellar ~5 second blocks)
+        let time_diff = latest_ledger.sequence.saturating_sub(ledger) * 5;
+        let now = chrono::Utc::now().timestamp() as u64;
+        now.saturating_sub(time_diff)
+    });
+
+    let block = EvmBlock::from_ledger(client.network_passphrase(), ledger, close_time, 0, base_fee, include_txs)
+        .with_gas_limit(config.block_gas_limit);
+    match header {
+        Some(header) => block.with_ledger_hash(&header.hash),
+        None if ledger == latest_ledger.sequence => block.with_ledger_hash(&latest_ledger.id),
+        None => block,
+    }
+}
+
 /// Handler for eth_call (read-only contract invocation)
+/// Simulates against the latest ledger state; a past block number is served
+/// from that simulation only if the state it read is unchanged since (see
+/// `check_state_unchanged_since`). For the "pending" tag the
+/// simulation is sequenced after this server's in-flight submissions, since
+/// Soroban cannot simulate on top of unapplied transactions. State overrides
+/// (third param) are limited to what simulation can honour, plus redirecting
+/// the called contract to another implementation; see `check_state_overrides`.
 pub async fn call(
     client: &SorobanClient,
     config: &Config,
+    mapper: &dyn AddressMapper,
     abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &PendingTxStore,
     params: &[Value],
 ) -> Result<Value> {
     let call_obj = params
         .first()
-        .ok_or_else(|| anyhow!("eth_call requires call object parameter"))?;
-
-    let to = call_obj["to"]
-        .as_str()
-        .ok_or_else(|| anyhow!("eth_call requires 'to' field"))?;
+        .ok_or_else(|| TvaError::InvalidParams("eth_call: call object is required".to_string()))?;
+
+    let call_obj = parse_call_object(call_obj)
+        .map_err(|e| TvaError::InvalidParams(format!("eth_call: {}", e)))?;
+    let to = call_obj
+        .to
+        .as_deref()
+        .filter(|to| is_hex_address(to))
+        .ok_or_else(|| {
+            TvaError::InvalidParams("eth_call: 'to' field is required and must be a hex address".to_string())
+        })?;
+    let data_bytes = &call_obj.data;
+
+    let implementation = check_state_overrides(config, mapper, params.get(2), call_obj.from.as_deref(), to)?;
+    check_block_overrides(params.get(3))?;
+
+    let use_pending = is_pending_tag(params.get(1));
+    let historical = historical_ledger(client, params.get(1)).await?;
+    debug!(
+        "eth_call: to={}, data_len={}, pending={}, ledger={:?}",
+        to,
+        data_bytes.len(),
+        use_pending,
+        historical
+    );
 
-    let data = call_obj["data"]
-        .as_str()
-        .or_else(|| call_obj["input"].as_str())
-        .unwrap_or("0x");
+    if !invokes_contract(data_bytes, to, abi_registry) {
+        // No function selector and no receive/fallback to run - return empty
+        return Ok(Value::String("0x".to_string()));
+    }
 
-    let data_bytes = hex::decode(data.strip_prefix("0x").unwrap_or(data))
-        .map_err(|e| anyhow!("Invalid calldata hex: {}", e))?;
+    // A code override simulates the call against another implementation
+    let contract_id = implementation.unwrap_or_else(|| evm_address_to_contract_id(mapper, to));
 
-    debug!("eth_call: to={}, data_len={}", to, data_bytes.len());
+    // Like a call to an EOA, a call to an address with no contract returns
+    // empty data. If the lookup fails, simulation reports the problem instead.
+    match contract_deployed(client, &contract_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            debug!("eth_call: no contract deployed at {}", to);
+            return Ok(Value::String("0x".to_string()));
+        }
+        Err(e) => warn!("eth_call: could not check for a contract at {}: {}", to, e),
+    }
 
-    if data_bytes.len() < 4 {
-        // No function selector - return empty
-        return Ok(Value::String("0x".to_string()));
+    // Soroban invocations carry no native value; payments are token transfers
+    if call_obj.value != 0 {
+        return Err(TvaError::InvalidTransaction(
+            "eth_call: value transfers to payable functions are not supported".to_string(),
+        )
+        .into());
     }
 
     // Decode the calldata
-    let decoded = decode_calldata(&data_bytes, to, abi_registry)?;
+    let decoded = decode_calldata(data_bytes, to, abi_registry)?;
+    stats.record_decoded(decoded.abi_resolved);
 
     let function_name = decoded
         .function_name
@@ -129,11 +227,10 @@ pub async fn call(
     info!("eth_call: invoking {} on {}", function_name, to);
 
     // For simulation, we need to build a transaction XDR
-    // Use the admin key as the source for simulation (does not require signature)
-    let source_account = get_source_account_id(config)?;
-    let sequence = client.get_account_sequence(&source_account).await.unwrap_or(0);
-
-    let contract_id = evm_address_to_contract_id(to);
+    // The caller's account is the source (simulation does not require a signature)
+    let source_account = simulation_source_account(config, mapper, call_obj.from.as_deref())?;
+    let confirmed_sequence = client.get_account_sequence(&source_account).await.unwrap_or(0);
+    let sequence = state_sequence(pending, &source_account, confirmed_sequence, use_pending);
 
     // Build the invoke transaction for simulation
     let tx_xdr = crate::translator::tx::build_soroban_invoke_tx(
@@ -148,10 +245,28 @@ pub async fn call(
 
     // Simulate the transaction
     let sim_result = client.simulate_transaction(&tx_xdr).await?;
+    stats.record_simulation(sim_result.error.is_some());
+    check_archived_state(&sim_result)?;
+    if let Some(ledger) = historical {
+        check_state_unchanged_since(client, &sim_result, ledger).await?;
+    }
 
     if let Some(error) = &sim_result.error {
         error!("eth_call simulation error: {}", error);
-        return Err(anyhow!("Contract call reverted: {}", error));
+        return Err(TvaError::reverted(error.as_str()).into());
+    }
+
+    // A gas limit must cover the simulated work, priced as eth_estimateGas does
+    if let Some(gas_limit) = call_obj.gas {
+        let (cpu_insns, mem_bytes) = simulated_resources(&sim_result);
+        let gas_needed = config.gas_model().estimate(intrinsic_gas(data_bytes, false), cpu_insns, mem_bytes, 0);
+        if gas_needed > gas_limit {
+            return Err(TvaError::InvalidTransaction(format!(
+                "out of gas: gas required exceeds allowance ({}), needs {}",
+                gas_limit, gas_needed
+            ))
+            .into());
+        }
     }
 
     // Extract the return value
@@ -162,6 +277,7 @@ pub async fn call(
                 let func_info = abi_registry.lookup_function(to, &decoded.selector);
                 if let Some(info) = func_info {
                     let abi_bytes = crate::translator::scval::decode_scval_xdr_to_abi(
+                        mapper,
                         xdr_result,
                         &info.outputs,
                     )?;
@@ -185,16 +301,19 @@ pub async fn call(
 pub async fn send_raw_transaction(
     client: &SorobanClient,
     config: &Config,
+    mapper: &dyn AddressMapper,
     abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &Arc<PendingTxStore>,
     params: &[Value],
 ) -> Result<Value> {
     let raw_tx_hex = params
         .first()
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("eth_sendRawTransaction requires raw tx hex"))?;
+        .ok_or_else(|| TvaError::InvalidParams("eth_sendRawTransaction: raw transaction hex is required".to_string()))?;
 
-    let raw_tx_bytes = hex::decode(raw_tx_hex.strip_prefix("0x").unwrap_or(raw_tx_hex))
-        .map_err(|e| anyhow!("Invalid raw transaction hex: {}", e))?;
+    let raw_tx_bytes = decode_raw_transaction_hex(raw_tx_hex)
+        .map_err(|e| TvaError::InvalidParams(format!("eth_sendRawTransaction: {}", e)))?;
 
     info!(
         "eth_sendRawTransaction: received {} bytes",
@@ -202,7 +321,8 @@ pub async fn send_raw_transaction(
     );
 
     // Step 1: RLP-decode the EVM transaction
-    let decoded_tx = decode_raw_transaction(&raw_tx_bytes)?;
+    let decoded_tx = decode_raw_transaction(&raw_tx_bytes)
+        .map_err(|e| TvaError::InvalidParams(format!("eth_sendRawTransaction: {}", e)))?;
 
     debug!(
         "Decoded EVM tx: nonce={}, to={:?}, value={}, data_len={}",
@@ -212,11 +332,15 @@ pub async fn send_raw_transaction(
         decoded_tx.data.len()
     );
 
+    // Reject transactions signed for another chain before translating them
+    check_chain_id(decoded_tx.chain_id, config)?;
+
     // Step 2: Determine if this is a contract deployment or invocation
     let is_deployment = decoded_tx.to.is_none();
 
     if is_deployment {
         info!("Contract deployment detected - translating to Soroban deploy");
+        stats.record_deployment();
         // Contract deployment: the data field contains the contract bytecode/initcode
         // For TVA, this would be WASM bytecode compiled by Solang
         // Return the tx hash immediately (deployment handled asynchronously)
@@ -228,116 +352,296 @@ pub async fn send_raw_transaction(
     let to_address = decoded_tx.to.unwrap(); // Safe: checked above
     let to_hex = format!("0x{}", hex::encode(to_address));
 
-    if decoded_tx.data.len() >= 4 {
-        let decoded = decode_calldata(&decoded_tx.data, &to_hex, abi_registry)?;
+    if invokes_contract(&decoded_tx.data, &to_hex, abi_registry) {
+        let fallback_hash = format!("0x{}", hex::encode(decoded_tx.tx_hash));
+        submit_contract_call(
+            client,
+            config,
+            mapper,
+            abi_registry,
+            stats,
+            pending,
+            &to_hex,
+            &decoded_tx.data,
+            Some(decoded_tx.nonce),
+            &fallback_hash,
+        )
+        .await
+    } else {
+        // No calldata (simple value transfer)
+        info!("Simple value transfer: {} wei to {}", decoded_tx.value, to_hex);
+        let tx_hash = format!("0x{}", hex::encode(decoded_tx.tx_hash));
+        Ok(Value::String(tx_hash))
+    }
+}
 
-        let function_name = decoded
-            .function_name
-            .unwrap_or_else(|| format!("fn_{}", hex::encode(decoded.selector)));
+/// A contract call translated into a Soroban invocation and simulated.
+pub(crate) struct SimulatedCall {
+    pub function_name: String,
+    pub selector: [u8; 4],
+    pub scval_params: Vec<ScVal>,
+    pub contract_id: String,
+    /// Sequence number the invocation was built with
+    pub sequence: u64,
+    pub simulation: SimulateTransactionResponse,
+}
 
-        info!(
-            "Translating call to {} on contract {}",
-            function_name, to_hex
-        );
+/// Decode calldata for `to_hex`, translate it into a Soroban invocation from
+/// `source_account` and simulate it. A failed simulation is reported in
+/// `simulation.error`, not as an error.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn simulate_contract_call(
+    client: &SorobanClient,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &PendingTxStore,
+    source_account: &str,
+    to_hex: &str,
+    data: &[u8],
+) -> Result<SimulatedCall> {
+    let decoded = decode_calldata(data, to_hex, abi_registry)?;
+    stats.record_decoded(decoded.abi_resolved);
 
-        // Build the Soroban transaction
-        let source_account = get_source_account_id(config)?;
-        let sequence = client.get_account_sequence(&source_account).await?;
-        let contract_id = evm_address_to_contract_id(&to_hex);
-
-        // First simulate to get resource estimates
-        let sim_tx_xdr = crate::translator::tx::build_soroban_invoke_tx(
-            &source_account,
-            sequence + 1,
-            &contract_id,
-            &function_name,
-            &decoded.scval_params,
-            client.network_passphrase(),
-            100,
-        )?;
+    let function_name = decoded
+        .function_name
+        .unwrap_or_else(|| format!("fn_{}", hex::encode(decoded.selector)));
 
-        let sim_result = client.simulate_transaction(&sim_tx_xdr).await?;
+    info!(
+        "Translating call to {} on contract {}",
+        function_name, to_hex
+    );
 
-        if let Some(error) = &sim_result.error {
-            error!("Transaction simulation failed: {}", error);
-            return Err(anyhow!("Transaction would revert: {}", error));
-        }
+    // Sequence after any of our own submissions that are still in flight
+    let confirmed_sequence = client.get_account_sequence(source_account).await?;
+    let sequence = state_sequence(pending, source_account, confirmed_sequence, true) + 1;
+    let contract_id = evm_address_to_contract_id(mapper, to_hex);
 
-        // Get the resource fee from simulation
-        let resource_fee: u32 = sim_result
-            .min_resource_fee
-            .as_ref()
-            .and_then(|f| f.parse::<u32>().ok())
-            .unwrap_or(10000);
+    let sim_tx_xdr = crate::translator::tx::build_soroban_invoke_tx(
+        source_account,
+        sequence,
+        &contract_id,
+        &function_name,
+        &decoded.scval_params,
+        client.network_passphrase(),
+        100,
+    )?;
+    let simulation = client.simulate_transaction(&sim_tx_xdr).await?;
+    stats.record_simulation(simulation.error.is_some());
+
+    Ok(SimulatedCall {
+        function_name,
+        selector: decoded.selector,
+        scval_params: decoded.scval_params,
+        contract_id,
+        sequence,
+        simulation,
+    })
+}
 
-        // Build the actual transaction with proper fee
-        let tx_xdr = crate::translator::tx::build_soroban_invoke_tx(
-            &source_account,
-            sequence + 1,
-            &contract_id,
-            &function_name,
-            &decoded.scval_params,
-            client.network_passphrase(),
-            resource_fee + 1000, // Add buffer
-        )?;
+/// Translate a contract call into a Soroban invocation, sign it with the
+/// server's key, submit it and track it in the pending store. Returns the EVM
+/// transaction hash; `fallback_hash` is returned when the submission status is
+/// neither accepted nor rejected.
+///
+/// The invocation is sequenced after the server account's in-flight
+/// submissions. A given `nonce` must be the one that sequence stands for (see
+/// `check_nonce`); the transaction is never renumbered.
+#[allow(clippy::too_many_arguments)]
+async fn submit_contract_call(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &Arc<PendingTxStore>,
+    to_hex: &str,
+    data: &[u8],
+    nonce: Option<u64>,
+    fallback_hash: &str,
+) -> Result<Value> {
+    let signer = StellarSigner::from_secret(&config.stellar_secret_key)?;
+    let source_account = signer.account_id();
+
+    // First simulate to get resource estimates
+    let SimulatedCall {
+        function_name,
+        scval_params,
+        contract_id,
+        sequence,
+        simulation: sim_result,
+        ..
+    } = simulate_contract_call(client, mapper, abi_registry, stats, pending, &source_account, to_hex, data).await?;
+
+    if let Some(nonce) = nonce {
+        check_nonce(nonce, sequence)?;
+    }
+
+    check_archived_state(&sim_result)?;
+    if let Some(error) = &sim_result.error {
+        error!("Transaction simulation failed: {}", error);
+        return Err(TvaError::reverted(error.as_str()).into());
+    }
 
-        // Submit to Stellar network
-        let send_result = client.send_transaction(&tx_xdr).await?;
+    // Get the resource fee from simulation
+    let resource_fee: u32 = sim_result
+        .min_resource_fee
+        .as_ref()
+        .and_then(|f| f.parse::<u32>().ok())
+        .unwrap_or(10000);
 
-        match send_result.status.as_str() {
-            "PENDING" | "SUCCESS" => {
-                let stellar_hash = send_result.hash.unwrap_or_default();
-                let tx_hash = stellar_hash_to_evm_hash(&stellar_hash);
-                info!("Transaction submitted: stellar_hash={}, evm_hash={}", stellar_hash, tx_hash);
-                Ok(Value::String(tx_hash))
-            }
-            "ERROR" | "FAILED" => {
-                let error_msg = send_result
-                    .error_result_xdr
-                    .unwrap_or_else(|| "Unknown error".to_string());
-                error!("Transaction submission failed: {}", error_msg);
-                Err(anyhow!("Transaction failed: {}", error_msg))
-            }
-            status => {
-                warn!("Unexpected transaction status: {}", status);
-                let tx_hash = format!("0x{}", hex::encode(decoded_tx.tx_hash));
-                Ok(Value::String(tx_hash))
-            }
+    // Build the actual transaction with proper fee
+    let fee = resource_fee + 1000; // Add buffer
+    let unsigned_xdr = crate::translator::tx::build_soroban_invoke_tx(
+        &source_account,
+        sequence,
+        &contract_id,
+        &function_name,
+        &scval_params,
+        client.network_passphrase(),
+        fee,
+    )?;
+    let (tx_xdr, _) = signer.sign_envelope(&unsigned_xdr, client.network_passphrase())?;
+
+    // Submit to Stellar network
+    let send_result = client.send_transaction(&tx_xdr).await?;
+
+    match send_result.status.as_str() {
+        "PENDING" | "SUCCESS" => {
+            let stellar_hash = send_result.hash.unwrap_or_default();
+            let tx_hash = stellar_hash_to_evm_hash(&stellar_hash);
+            info!("Transaction submitted: stellar_hash={}, evm_hash={}", stellar_hash, tx_hash);
+            // Track it so receipts and the pending state reflect it right away
+            pending.insert(
+                &tx_hash,
+                PendingTx::submitted(&stellar_hash, &source_account, sequence, u64::from(fee)),
+            );
+            spawn_confirmation_watch(
+                client.clone(),
+                pending.clone(),
+                tx_hash.clone(),
+                CONFIRMATION_POLL_INTERVAL,
+            );
+            Ok(Value::String(tx_hash))
         }
-    } else {
-        // No calldata (simple value transfer)
-        info!("Simple value transfer: {} wei to {}", decoded_tx.value, to_hex);
-        let tx_hash = format!("0x{}", hex::encode(decoded_tx.tx_hash));
-        Ok(Value::String(tx_hash))
+        "ERROR" | "FAILED" => {
+            let error_msg = send_result
+                .error_result_xdr
+                .unwrap_or_else(|| "Unknown error".to_string());
+            error!("Transaction submission failed: {}", error_msg);
+            Err(TvaError::Rejected(error_msg).into())
+        }
+        status => {
+            warn!("Unexpected transaction status: {}", status);
+            Ok(Value::String(fallback_hash.to_string()))
+        }
+    }
+}
+
+/// Handler for eth_sendTransaction
+/// Only available with `ENABLE_MANAGED_SIGNING`: the server signs the call with
+/// its own Stellar key, so `from` must be that key's EVM address if given.
+pub async fn send_transaction(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &Arc<PendingTxStore>,
+    params: &[Value],
+) -> Result<Value> {
+    if !config.enable_managed_signing {
+        return Err(TvaError::MethodUnavailable(
+            "eth_sendTransaction is disabled; sign locally and use eth_sendRawTransaction, \
+             or set ENABLE_MANAGED_SIGNING=true to sign with the server's key"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let call_obj = params
+        .first()
+        .ok_or_else(|| TvaError::InvalidParams("eth_sendTransaction: transaction object is required".to_string()))?;
+    let call_obj = parse_call_object(call_obj)
+        .map_err(|e| TvaError::InvalidParams(format!("eth_sendTransaction: {}", e)))?;
+
+    let signer = StellarSigner::from_secret(&config.stellar_secret_key)?;
+    let managed_address = signer.evm_address(mapper);
+    if let Some(from) = &call_obj.from {
+        if !from.eq_ignore_ascii_case(&managed_address) {
+            return Err(TvaError::InvalidTransaction(format!("unknown account {}", from)).into());
+        }
+    }
+
+    let to = call_obj
+        .to
+        .as_deref()
+        .ok_or_else(|| TvaError::InvalidTransaction("contract deployment is not supported".to_string()))?;
+    if !invokes_contract(&call_obj.data, to, abi_registry) {
+        return Err(TvaError::InvalidTransaction(
+            "eth_sendTransaction requires contract calldata; value transfers are not supported".to_string(),
+        )
+        .into());
     }
+
+    info!("eth_sendTransaction: {} -> {}", managed_address, to);
+    submit_contract_call(client, config, mapper, abi_registry, stats, pending, to, &call_obj.data, None, "0x").await
 }
 
 /// Handler for eth_getTransactionReceipt
+/// Transactions sent through this server are answered from the pending store:
+/// null while still pending, then the recorded result once applied, even if
+/// the upstream node has not caught up yet.
+/// Logs are the transaction's contract events, indexed within its block.
 pub async fn get_transaction_receipt(
     client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    pending: &PendingTxStore,
     params: &[Value],
 ) -> Result<Value> {
     let tx_hash = params
         .first()
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("eth_getTransactionReceipt requires tx hash"))?;
+        .ok_or_else(|| TvaError::InvalidParams("eth_getTransactionReceipt requires tx hash".to_string()))?;
 
     debug!("eth_getTransactionReceipt: hash={}", tx_hash);
 
-    // Convert EVM hash format to Stellar hash for lookup
-    let stellar_hash = evm_hash_to_stellar_hash(tx_hash);
-
-    let tx_response = client.get_transaction(&stellar_hash).await?;
+    let tx_response = match pending.get(tx_hash) {
+        Some(tracked) => match tracked.result {
+            Some(result) => result,
+            None => {
+                // The watcher may not have polled since it applied
+                let response = client.get_transaction(&tracked.stellar_hash).await?;
+                if pending.resolve(tx_hash, &response) != Some(TxStatus::Pending) {
+                    debug!("eth_getTransactionReceipt: {} applied", tx_hash);
+                }
+                response
+            }
+        },
+        None => {
+            // Convert EVM hash format to Stellar hash for lookup
+            let stellar_hash = evm_hash_to_stellar_hash(tx_hash);
+            client.get_transaction(&stellar_hash).await?
+        }
+    };
 
     match tx_response.status.as_str() {
         "SUCCESS" | "FAILED" => {
-            let receipt = build_receipt_from_stellar(
-                &tx_response,
-                tx_hash,
-                &format!("0x{}", "0".repeat(40)), // from (would need to decode envelope)
-                Some(&format!("0x{}", "0".repeat(40))), // to
-                None,
-            )?;
+            let (from, to) = envelope_addresses(mapper, &tx_response);
+            let mut receipt =
+                build_receipt_from_stellar(&tx_response, tx_hash, &from, to.as_deref(), None, &config.gas_model())?;
+            // application_order is 1-based; without it the events cannot be attributed
+            if let (Some(ledger), Some(order @ 1..)) = (tx_response.ledger, tx_response.application_order) {
+                let tx_index = u64::from(order - 1);
+                receipt.block_hash = ledger_to_block_hash(client.network_passphrase(), ledger);
+                receipt.transaction_index = format!("0x{:x}", tx_index);
+                match ledger_logs(client, config, mapper, abi_registry, ledger, Some(tx_index)).await {
+                    Ok(logs) => attach_logs(&mut receipt, &logs),
+                    Err(e) => warn!("eth_getTransactionReceipt: cannot read events of {}: {:#}", tx_hash, e),
+                }
+            }
             Ok(serde_json::to_value(&receipt)?)
         }
         "NOT_FOUND" => {
@@ -348,15 +652,157 @@ pub async fn get_transaction_receipt(
     }
 }
 
+/// Handler for eth_getBlockReceipts
+/// Receipts of every transaction applied in the block, in application order.
+/// The block is a number, tag or block hash; an unknown block is null.
+pub async fn get_block_receipts(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    block_hashes: &BlockHashIndex,
+    abi_registry: &AbiRegistry,
+    params: &[Value],
+) -> Result<Value> {
+    let block_param = block_param(params.first())
+        .ok_or_else(|| TvaError::InvalidParams("eth_getBlockReceipts requires block number or hash".to_string()))?;
+
+    let latest = client.get_latest_ledger().await?;
+    let ledger = if block_param.len() == 66 && block_param.starts_with("0x") {
+        match block_hashes.find(&block_param, latest.sequence) {
+            Some(ledger) => ledger,
+            None => {
+                debug!("eth_getBlockReceipts: unknown block {}", block_param);
+                return Ok(Value::Null);
+            }
+        }
+    } else {
+        parse_block_number(&block_param, latest.sequence, config.finality_depth)?
+    };
+    if ledger > latest.sequence {
+        return Ok(Value::Null);
+    }
+
+    debug!("eth_getBlockReceipts: ledger={}", ledger);
+
+    let block_hash = ledger_to_block_hash(client.network_passphrase(), ledger);
+    let gas_model = config.gas_model();
+    let logs = match ledger_logs(client, config, mapper, abi_registry, ledger, None).await {
+        Ok(logs) => logs,
+        Err(e) => {
+            warn!("eth_getBlockReceipts: cannot read events of ledger {}: {:#}", ledger, e);
+            Vec::new()
+        }
+    };
+    let mut cumulative_gas: u64 = 0;
+    let mut receipts = Vec::new();
+    for (index, info) in client.get_ledger_transactions(ledger).await?.iter().enumerate() {
+        let tx_response = info.to_transaction_response();
+        let (from, to) = envelope_addresses(mapper, &tx_response);
+        let mut receipt = build_receipt_from_stellar(
+            &tx_response,
+            &stellar_hash_to_evm_hash(&info.tx_hash),
+            &from,
+            to.as_deref(),
+            None,
+            &gas_model,
+        )?;
+        let gas_used = u64::from_str_radix(receipt.gas_used.trim_start_matches("0x"), 16)?;
+        cumulative_gas = cumulative_gas.saturating_add(gas_used);
+        receipt.block_hash = block_hash.clone();
+        receipt.transaction_index = format!("0x{:x}", index);
+        receipt.cumulative_gas_used = format!("0x{:x}", cumulative_gas);
+        attach_logs(&mut receipt, &logs);
+        receipts.push(receipt);
+    }
+
+    Ok(serde_json::to_value(&receipts)?)
+}
+
+/// Logs of the contract events in `ledger`, in block order with log indexes
+/// counted across the block. Only transactions up to application index
+/// `last_tx_index` are read if it is set. Transaction hashes are left for
+/// `attach_logs` to fill in.
+async fn ledger_logs(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    ledger: u64,
+    last_tx_index: Option<u64>,
+) -> Result<Vec<EvmLog>> {
+    let mut events: Vec<SorobanEvent> = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let events_params = GetEventsParams {
+            start_ledger: if cursor.is_none() { Some(ledger) } else { None },
+            end_ledger: Some(ledger + 1),
+            filters: None,
+            pagination: Some(EventPagination {
+                limit: EVENTS_PAGE_LIMIT,
+                cursor: cursor.take(),
+            }),
+        };
+        let events_response = client.get_events(events_params).await?;
+        let page: Vec<SorobanEvent> = events_response.events.unwrap_or_default();
+        let page_len = page.len();
+
+        // Events come in ledger order, so the first one past the
+        // transactions wanted ends the scan
+        let wanted = |event: &SorobanEvent| {
+            event.ledger == ledger
+                && match (last_tx_index, parse_event_id(&event.id)) {
+                    (Some(last), Ok(ordinal)) => ordinal.tx_index <= last,
+                    _ => true,
+                }
+        };
+        let before = events.len();
+        events.extend(page.into_iter().take_while(wanted));
+        let past_wanted = events.len() - before < page_len;
+
+        if page_len < EVENTS_PAGE_LIMIT as usize || past_wanted {
+            break;
+        }
+        cursor = events_response.cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    Ok(soroban_events_to_evm_logs(
+        client.network_passphrase(),
+        mapper,
+        &events,
+        &format!("0x{}", "0".repeat(64)),
+        Some(abi_registry),
+        config.include_failed_call_events,
+    ))
+}
+
+/// Give `receipt` the logs of `block_logs` emitted by its transaction.
+fn attach_logs(receipt: &mut EvmTransactionReceipt, block_logs: &[EvmLog]) {
+    let logs = block_logs
+        .iter()
+        .filter(|log| log.transaction_index == receipt.transaction_index)
+        .map(|log| EvmLog {
+            transaction_hash: receipt.transaction_hash.clone(),
+            ..log.clone()
+        })
+        .collect();
+    receipt.set_logs(logs);
+}
+
 /// Handler for eth_getTransactionByHash
 pub async fn get_transaction_by_hash(
     client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
     params: &[Value],
 ) -> Result<Value> {
     let tx_hash = params
         .first()
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("eth_getTransactionByHash requires tx hash"))?;
+        .ok_or_else(|| TvaError::InvalidParams("eth_getTransactionByHash requires tx hash".to_string()))?;
 
     debug!("eth_getTransactionByHash: hash={}", tx_hash);
 
@@ -365,12 +811,7 @@ pub async fn get_transaction_by_hash(
 
     match tx_response.status.as_str() {
         "SUCCESS" | "FAILED" => {
-            let tx = build_transaction_from_stellar(
-                &tx_response,
-                tx_hash,
-                &format!("0x{}", "0".repeat(40)),
-                Some(&format!("0x{}", "0".repeat(40))),
-            )?;
+            let tx = transaction_object(client, config, mapper, abi_registry, &tx_response, tx_hash)?;
             Ok(serde_json::to_value(&tx)?)
         }
         "NOT_FOUND" => Ok(Value::Null),
@@ -378,60 +819,199 @@ pub async fn get_transaction_by_hash(
     }
 }
 
-/// Handler for eth_getCode
-pub async fn get_code(
+/// Handler for eth_getTransactionByBlockNumberAndIndex
+pub async fn get_transaction_by_block_number_and_index(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    params: &[Value],
+) -> Result<Value> {
+    let block_param = block_param(params.first())
+        .ok_or_else(|| TvaError::InvalidParams("eth_getTransactionByBlockNumberAndIndex requires block number".to_string()))?;
+    let index = parse_index_param(params.get(1))?;
+
+    let latest = client.get_latest_ledger().await?;
+    let ledger = parse_block_number(&block_param, latest.sequence, config.finality_depth)?;
+    debug!(
+        "eth_getTransactionByBlockNumberAndIndex: ledger={}, index={}",
+        ledger, index
+    );
+
+    transaction_in_ledger(client, config, mapper, abi_registry, ledger, index).await
+}
+
+/// Handler for eth_getTransactionByBlockHashAndIndex
+pub async fn get_transaction_by_block_hash_and_index(
     client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    block_hashes: &BlockHashIndex,
+    abi_registry: &AbiRegistry,
     params: &[Value],
 ) -> Result<Value> {
-    let address = params
+    let block_hash = params
         .first()
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("eth_getCode requires address"))?;
+        .ok_or_else(|| TvaError::InvalidParams("eth_getTransactionByBlockHashAndIndex requires block hash".to_string()))?;
+    let index = parse_index_param(params.get(1))?;
+
+    let latest = client.get_latest_ledger().await?;
+    let Some(ledger) = block_hashes.find(block_hash, latest.sequence) else {
+        debug!("eth_getTransactionByBlockHashAndIndex: unknown block {}", block_hash);
+        return Ok(Value::Null);
+    };
+
+    transaction_in_ledger(client, config, mapper, abi_registry, ledger, index).await
+}
+
+/// Translate the `index`-th transaction applied in `ledger`, or null if out of range.
+async fn transaction_in_ledger(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    ledger: u64,
+    index: usize,
+) -> Result<Value> {
+    let transactions = client.get_ledger_transactions(ledger).await?;
+    let Some(info) = transactions.get(index) else {
+        return Ok(Value::Null);
+    };
+
+    let tx_response = info.to_transaction_response();
+    let tx_hash = stellar_hash_to_evm_hash(&info.tx_hash);
+    let mut tx = transaction_object(client, config, mapper, abi_registry, &tx_response, &tx_hash)?;
+    tx.transaction_index = format!("0x{:x}", index);
+    Ok(serde_json::to_value(&tx)?)
+}
+
+/// The EVM transaction object for an applied Stellar transaction, in the
+/// block `eth_getBlockByNumber` reports for its ledger.
+fn transaction_object(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    tx_response: &GetTransactionResponse,
+    tx_hash: &str,
+) -> Result<EvmTransaction> {
+    let summary = tx_response
+        .envelope_xdr
+        .as_deref()
+        .map(decode_envelope_summary)
+        .and_then(|decoded| decoded.map_err(|e| warn!("Could not decode transaction envelope: {}", e)).ok());
+    let input = summary
+        .as_ref()
+        .and_then(|summary| invocation_calldata(mapper, summary, abi_registry))
+        .unwrap_or_default();
+
+    let mut tx =
+        build_transaction_from_stellar(tx_response, tx_hash, summary.as_ref(), &input, &config.gas_model(), mapper)?;
+    if let Some(ledger) = tx_response.ledger {
+        tx.block_hash = ledger_to_block_hash(client.network_passphrase(), ledger);
+    }
+    Ok(tx)
+}
+
+/// Re-encode a contract invocation as the calldata that would make it: the
+/// selector and ABI-encoded arguments of the registered function it names.
+/// None when the contract has no registered function taking those arguments.
+fn invocation_calldata(
+    mapper: &dyn AddressMapper,
+    summary: &EnvelopeSummary,
+    abi_registry: &AbiRegistry,
+) -> Option<String> {
+    let to = summary.to_address(mapper)?;
+    let function = summary.invoked_function.as_deref()?;
+    let info = abi_registry.lookup_function_by_name(&to, function)?;
+    if info.inputs.len() != summary.invoke_args.len() {
+        debug!("{} on {} takes {} arguments, invoked with {}", function, to, info.inputs.len(), summary.invoke_args.len());
+        return None;
+    }
+
+    let values = summary
+        .invoke_args
+        .iter()
+        .zip(&info.inputs)
+        .map(|(arg, param)| scval_to_abi_bytes(mapper, arg, param))
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| warn!("Could not re-encode arguments of {}: {}", function, e))
+        .ok()?;
+    let mut calldata = info.selector.to_vec();
+    calldata.extend(encode_abi_values(&values, &info.inputs));
+    Some(format!("0x{}", hex::encode(calldata)))
+}
+
+/// Parse a hex transaction index parameter.
+fn parse_index_param(param: Option<&Value>) -> Result<usize> {
+    let hex_index = param
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TvaError::InvalidParams("Transaction index is required".to_string()))?;
+    let index = usize::from_str_radix(hex_index.strip_prefix("0x").unwrap_or(hex_index), 16)
+        .map_err(|e| TvaError::InvalidParams(format!("Invalid transaction index '{}': {}", hex_index, e)))?;
+    Ok(index)
+}
+
+/// Handler for eth_getCode
+pub async fn get_code(
+    client: &SorobanClient,
+    mapper: &dyn AddressMapper,
+    params: &[Value],
+) -> Result<Value> {
+    let address = address_param("eth_getCode", params.first())?;
 
     debug!("eth_getCode: address={}", address);
 
     // For Soroban contracts, we check if a contract exists at this address
     // by attempting to get its WASM code hash from ledger entries
-    let contract_id = evm_address_to_contract_id(address);
+    let contract_id = evm_address_to_contract_id(mapper, address);
 
-    // Build the ledger key for the contract instance
-    let ledger_key = build_contract_instance_key(&contract_id);
-
-    let entries = client.get_ledger_entries(vec![ledger_key]).await?;
-
-    if let Some(entries_list) = entries.entries {
-        if !entries_list.is_empty() {
-            // Contract exists - return a non-empty code indicator
-            // In a full implementation, we would decode the WASM from the ledger entry
-            let code_hash = format!("0x{}", hex::encode(contract_id.as_bytes()));
-            return Ok(Value::String(code_hash));
-        }
+    if contract_deployed(client, &contract_id).await? {
+        // Contract exists - return a non-empty code indicator
+        // In a full implementation, we would decode the WASM from the ledger entry
+        let code_hash = format!("0x{}", hex::encode(contract_id.as_bytes()));
+        return Ok(Value::String(code_hash));
     }
 
     // No contract found at this address
     Ok(Value::String("0x".to_string()))
 }
 
+/// Whether a contract instance ledger entry exists for `contract_id`.
+async fn contract_deployed(client: &SorobanClient, contract_id: &str) -> Result<bool> {
+    let ledger_key = build_contract_instance_key(contract_id);
+    let entries = client.get_ledger_entries(vec![ledger_key]).await?;
+    Ok(entries.entries.is_some_and(|list| !list.is_empty()))
+}
+
 /// Handler for eth_getBalance
+/// For the "pending" tag, fees of this server's in-flight submissions from the
+/// account are deducted from the confirmed balance.
 pub async fn get_balance(
     client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    pending: &PendingTxStore,
     params: &[Value],
 ) -> Result<Value> {
-    let address = params
-        .first()
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("eth_getBalance requires address"))?;
+    let address = address_param("eth_getBalance", params.first())?;
 
     debug!("eth_getBalance: address={}", address);
 
     // Map EVM address to Stellar account and query XLM balance
-    // For now, use the mapped Stellar account
-    let stellar_account = evm_address_to_stellar_account(address);
-
-    let balance_stroops = client.get_xlm_balance(&stellar_account).await.unwrap_or(0);
+    let stellar_account = evm_address_to_stellar_account(mapper, address)?;
+
+    let mut balance_stroops = client.get_xlm_balance(&stellar_account).await.unwrap_or(0);
+    if is_pending_tag(params.get(1)) {
+        let sequence = client.get_account_sequence(&stellar_account).await.unwrap_or(0);
+        balance_stroops = pending
+            .pending_for(&stellar_account, sequence)
+            .balance_stroops(balance_stroops);
+    }
 
     // Convert stroops to wei-equivalent
-    let balance_wei = stroops_to_wei(balance_stroops);
+    let balance_wei = stroops_to_wei(balance_stroops, config.xlm_wei_factor);
     let hex_balance = format!("0x{:x}", balance_wei);
 
     debug!(
@@ -443,50 +1023,158 @@ pub async fn get_balance(
 }
 
 /// Handler for eth_gasPrice
-pub async fn gas_price(client: &SorobanClient) -> Result<Value> {
-    let base_fee = client.get_base_fee().await.unwrap_or(100);
-
-    // Convert Stellar base fee (stroops) to a gas price in wei
-    // 100 stroops ~= 1 gwei for a reasonable comparison
-    let gas_price_wei = stroops_to_wei(base_fee);
-    let hex_price = format!("0x{:x}", gas_price_wei);
+pub async fn gas_price(config: &Config) -> Result<Value> {
+    // A fixed price per gas; fees show up in the gas estimates instead
+    let hex_price = format!("0x{:x}", config.gas_model().gas_price());
 
-    debug!("eth_gasPrice: base_fee={} stroops -> {}", base_fee, hex_price);
+    debug!("eth_gasPrice -> {}", hex_price);
     Ok(Value::String(hex_price))
 }
 
+/// Blocks at most reported by one eth_feeHistory call, as geth caps it.
+const MAX_FEE_HISTORY_BLOCKS: u64 = 1024;
+
+/// Handler for eth_feeHistory
+/// Every block's base fee is the fixed eth_gasPrice and there is no tip
+/// market, so rewards are zero. Blocks are reported half full, the EIP-1559
+/// target, so wallets project the base fee unchanged.
+pub async fn fee_history(client: &SorobanClient, config: &Config, params: &[Value]) -> Result<Value> {
+    let block_count = match params.first() {
+        Some(Value::Number(n)) => n.as_u64(),
+        Some(Value::String(s)) => parse_quantity(s).and_then(|n| u64::try_from(n).ok()),
+        _ => None,
+    }
+    .ok_or_else(|| TvaError::InvalidParams("eth_feeHistory: blockCount must be a quantity".to_string()))?;
+    let newest_block = block_param(params.get(1))
+        .ok_or_else(|| TvaError::InvalidParams("eth_feeHistory: newestBlock is required".to_string()))?;
+    let percentiles = match params.get(2) {
+        None | Some(Value::Null) => None,
+        Some(Value::Array(values)) => {
+            let percentiles: Vec<f64> = values.iter().filter_map(|v| v.as_f64()).collect();
+            let valid = percentiles.len() == values.len()
+                && percentiles.iter().all(|p| (0.0..=100.0).contains(p))
+                && percentiles.windows(2).all(|pair| pair[0] <= pair[1]);
+            if !valid {
+                return Err(TvaError::InvalidParams(format!(
+                    "eth_feeHistory: rewardPercentiles must be increasing numbers between 0 and 100, got {}",
+                    Value::Array(values.clone())
+                ))
+                .into());
+            }
+            Some(percentiles.len())
+        }
+        Some(other) => {
+            return Err(TvaError::InvalidParams(format!("eth_feeHistory: invalid rewardPercentiles {}", other)).into())
+        }
+    };
+
+    let latest = client.get_latest_ledger().await?;
+    let newest = parse_block_number(&newest_block, latest.sequence, config.finality_depth)?;
+    if newest > latest.sequence + 1 {
+        return Err(TvaError::InvalidParams(format!(
+            "eth_feeHistory: block {} is beyond the latest ledger {}",
+            newest, latest.sequence
+        ))
+        .into());
+    }
+
+    let count = block_count.min(MAX_FEE_HISTORY_BLOCKS).min(newest + 1) as usize;
+    if count == 0 {
+        return Ok(serde_json::json!({ "oldestBlock": "0x0", "baseFeePerGas": [], "gasUsedRatio": [] }));
+    }
+    let oldest = newest + 1 - count as u64;
+    let base_fee = format!("0x{:x}", config.gas_model().gas_price());
+
+    debug!("eth_feeHistory: {} blocks from {}", count, oldest);
+
+    let mut history = serde_json::json!({
+        "oldestBlock": format!("0x{:x}", oldest),
+        // One entry per block plus the block after the newest
+        "baseFeePerGas": vec![base_fee; count + 1],
+        "gasUsedRatio": vec![0.5; count],
+    });
+    if let Some(percentiles) = percentiles {
+        history["reward"] = serde_json::json!(vec![vec!["0x0"; percentiles]; count]);
+    }
+    Ok(history)
+}
+
+/// Handler for eth_maxPriorityFeePerGas
+/// Tips buy nothing: gas is priced at the fixed eth_gasPrice.
+pub async fn max_priority_fee_per_gas() -> Result<Value> {
+    Ok(Value::String("0x0".to_string()))
+}
+
 /// Handler for eth_estimateGas
 pub async fn estimate_gas(
     client: &SorobanClient,
     config: &Config,
+    mapper: &dyn AddressMapper,
     abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
     params: &[Value],
 ) -> Result<Value> {
     let call_obj = params
         .first()
-        .ok_or_else(|| anyhow!("eth_estimateGas requires call object"))?;
+        .ok_or_else(|| TvaError::InvalidParams("eth_estimateGas: call object is required".to_string()))?;
+
+    let call_obj = parse_call_object(call_obj)
+        .map_err(|e| TvaError::InvalidParams(format!("eth_estimateGas: {}", e)))?;
+    let to = call_obj.to.as_deref();
+    if to.is_some_and(|to| !is_hex_address(to)) {
+        return Err(TvaError::InvalidParams("eth_estimateGas: 'to' field must be a hex address".to_string()).into());
+    }
+    let data_bytes = &call_obj.data;
 
-    let to = call_obj["to"].as_str();
-    let data = call_obj["data"]
-        .as_str()
-        .or_else(|| call_obj["input"].as_str())
-        .unwrap_or("0x");
+    let gas_model = config.gas_model();
+    let gas_price = call_obj.fees.effective_gas_price(gas_model.gas_price());
+    let base_fee = client.get_base_fee().await?;
 
-    debug!("eth_estimateGas: to={:?}, data_len={}", to, data.len());
+    debug!(
+        "eth_estimateGas: to={:?}, data_len={}, gas_price={:?}",
+        to,
+        data_bytes.len(),
+        gas_price
+    );
+
+    // With explicit fee fields, the estimate must also cover the Stellar fee at that price.
+    // Tools reject transactions whose gas exceeds the block gas limit, so never report more;
+    // a fee that only fits above the limit cannot be paid at the caller's price.
+    let fit_to_fees = |gas: u64, fee_stroops: u64| -> Result<Value> {
+        let mut gas = gas;
+        if let Some(price) = gas_price {
+            let covering = gas_covering_fee(0, gas_model.stroops_to_wei(fee_stroops), price);
+            if covering > config.block_gas_limit {
+                return Err(TvaError::InvalidTransaction(format!(
+                    "max fee per gas too low: {} wei per gas needs {} gas to pay the {} stroop fee, above the block gas limit {}",
+                    price, covering, fee_stroops, config.block_gas_limit
+                ))
+                .into());
+            }
+            gas = gas.max(covering);
+        }
+        if gas > config.block_gas_limit {
+            warn!(
+                "eth_estimateGas: estimate {} exceeds the block gas limit, clamping to {}",
+                gas, config.block_gas_limit
+            );
+            gas = config.block_gas_limit;
+        }
+        Ok(Value::String(format!("0x{:x}", gas)))
+    };
 
     // If we have calldata and a target, simulate the transaction
     if let Some(to_addr) = to {
-        let data_bytes = hex::decode(data.strip_prefix("0x").unwrap_or(data)).unwrap_or_default();
-
-        if data_bytes.len() >= 4 {
-            let decoded = decode_calldata(&data_bytes, to_addr, abi_registry)?;
+        if invokes_contract(data_bytes, to_addr, abi_registry) {
+            let decoded = decode_calldata(data_bytes, to_addr, abi_registry)?;
+            stats.record_decoded(decoded.abi_resolved);
             let function_name = decoded
                 .function_name
                 .unwrap_or_else(|| format!("fn_{}", hex::encode(decoded.selector)));
 
             let source_account = get_source_account_id(config)?;
             let sequence = client.get_account_sequence(&source_account).await.unwrap_or(0);
-            let contract_id = evm_address_to_contract_id(to_addr);
+            let contract_id = evm_address_to_contract_id(mapper, to_addr);
 
             let tx_xdr = crate::translator::tx::build_soroban_invoke_tx(
                 &source_account,
@@ -499,52 +1187,142 @@ pub async fn estimate_gas(
             )?;
 
             let sim_result = client.simulate_transaction(&tx_xdr).await?;
-
-            if let Some(cost) = &sim_result.cost {
-                // Convert Soroban CPU instructions to gas-equivalent
-                let cpu_insns: u64 = cost
-                    .cpu_insns
-                    .as_ref()
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(0);
-                let mem_bytes: u64 = cost
-                    .mem_bytes
-                    .as_ref()
-                    .and_then(|s| s.parse().ok())
-                    .unwrap_or(0);
-
-                // Rough conversion: 1000 CPU insns ~= 1 gas unit
-                let estimated_gas = (cpu_insns / 1000) + (mem_bytes / 100) + 21000;
-                return Ok(Value::String(format!("0x{:x}", estimated_gas)));
+            stats.record_simulation(sim_result.error.is_some());
+            check_archived_state(&sim_result)?;
+            if let Some(error) = &sim_result.error {
+                debug!("eth_estimateGas simulation error: {}", error);
+                return Err(TvaError::reverted(error.as_str()).into());
             }
 
-            // Use min_resource_fee as fallback
-            if let Some(fee_str) = &sim_result.min_resource_fee {
-                let fee: u64 = fee_str.parse().unwrap_or(21000);
-                let gas = fee * 100 + 21000; // Convert fee to gas units
-                return Ok(Value::String(format!("0x{:x}", gas)));
-            }
+            let resource_fee: u64 = sim_result
+                .min_resource_fee
+                .as_ref()
+                .and_then(|f| f.parse().ok())
+                .unwrap_or(0);
+            let total_fee = resource_fee + base_fee;
+
+            let (cpu_insns, mem_bytes) = simulated_resources(&sim_result);
+
+            let gas = gas_model.estimate(intrinsic_gas(data_bytes, false), cpu_insns, mem_bytes, total_fee);
+            return fit_to_fees(gas, total_fee);
         }
     }
 
-    // Default gas estimate (standard transfer)
-    Ok(Value::String("0x5208".to_string())) // 21000
+    // Value transfer or deployment: intrinsic gas, covering a single-operation base fee
+    let gas = gas_model.estimate(intrinsic_gas(data_bytes, to.is_none()), 0, 0, base_fee);
+    fit_to_fees(gas, base_fee)
+}
+
+/// CPU instructions and memory bytes a simulation reported (0 where missing).
+fn simulated_resources(simulation: &SimulateTransactionResponse) -> (u64, u64) {
+    let parse_cost = |value: Option<&String>| value.and_then(|s| s.parse().ok()).unwrap_or(0);
+    simulation.cost.as_ref().map_or((0, 0), |cost| {
+        (parse_cost(cost.cpu_insns.as_ref()), parse_cost(cost.mem_bytes.as_ref()))
+    })
+}
+
+/// Fail if the simulation came with a restore preamble: some ledger entries
+/// the call touches are archived, and it can only succeed once a
+/// RestoreFootprint transaction has restored them.
+fn check_archived_state(simulation: &SimulateTransactionResponse) -> Result<()> {
+    let Some(preamble) = &simulation.restore_preamble else {
+        return Ok(());
+    };
+    let restore_fee = preamble
+        .get("minResourceFee")
+        .and_then(|fee| fee.as_str())
+        .unwrap_or("unknown");
+    Err(TvaError::InvalidTransaction(format!(
+        "contract state is archived and must be restored: submit a RestoreFootprint transaction \
+         with the simulation's restore preamble (resource fee {} stroops), then retry",
+        restore_fee
+    ))
+    .into())
+}
+
+/// The past ledger an eth_call block parameter reads state at, or None for
+/// the latest state ("safe" and "finalized" included, as elsewhere). A ledger
+/// older than the node retains is an error.
+async fn historical_ledger(client: &SorobanClient, block: Option<&Value>) -> Result<Option<u64>> {
+    let Some(block) = block_param(block) else {
+        return Ok(None);
+    };
+    if matches!(block.as_str(), "latest" | "pending" | "safe" | "finalized") {
+        return Ok(None);
+    }
+    let latest = client.get_latest_ledger().await?.sequence;
+    let ledger = parse_block_number(&block, latest, 0)?;
+    if ledger >= latest {
+        return Ok(None);
+    }
+
+    let oldest = client.get_health().await?.oldest_ledger.unwrap_or(latest);
+    if ledger < oldest {
+        return Err(TvaError::InvalidParams(format!(
+            "eth_call: ledger {} is outside the node's retention window (oldest ledger {})",
+            ledger, oldest
+        ))
+        .into());
+    }
+    Ok(Some(ledger))
+}
+
+/// Soroban RPC only simulates against the latest ledger. That result holds
+/// at `ledger` too if every entry in the simulation's footprint still exists
+/// and none changed since. An entry missing now may have been deleted or
+/// evicted after `ledger`, so it counts as changed.
+async fn check_state_unchanged_since(
+    client: &SorobanClient,
+    simulation: &SimulateTransactionResponse,
+    ledger: u64,
+) -> Result<()> {
+    let unavailable = |reason: &str| -> anyhow::Error {
+        TvaError::Unsupported(format!("eth_call: state at ledger {} is unavailable: {}", ledger, reason)).into()
+    };
+    let Some(transaction_data) = &simulation.transaction_data else {
+        return Err(unavailable("the simulation reported no footprint"));
+    };
+    let keys = decode_footprint_keys(transaction_data)?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    let entries = client.get_ledger_entries(keys.clone()).await?.entries.unwrap_or_default();
+    let unchanged: HashSet<&str> = entries
+        .iter()
+        .filter(|entry| entry.last_modified_ledger_seq.is_some_and(|seq| seq <= ledger))
+        .map(|entry| entry.key.as_str())
+        .collect();
+    if keys.iter().any(|key| !unchanged.contains(key.as_str())) {
+        return Err(unavailable("contract state the call reads has changed or been removed since"));
+    }
+    Ok(())
 }
 
 /// Handler for eth_getTransactionCount (nonce)
+/// The sequence of the address's Stellar account; for the server's own
+/// address that is the sequence every EVM submission is checked against. For
+/// the "pending" tag, this server's in-flight submissions from the account are
+/// counted on top of its confirmed sequence. Horizon failures are reported
+/// rather than read as nonce 0.
 pub async fn get_transaction_count(
     client: &SorobanClient,
+    mapper: &dyn AddressMapper,
+    pending: &PendingTxStore,
     params: &[Value],
 ) -> Result<Value> {
-    let address = params
-        .first()
-        .and_then(|v| v.as_str())
-        .unwrap_or("0x0");
+    let address = address_param("eth_getTransactionCount", params.first())?;
 
     debug!("eth_getTransactionCount: address={}", address);
 
-    let stellar_account = evm_address_to_stellar_account(address);
-    let sequence = client.get_account_sequence(&stellar_account).await.unwrap_or(0);
+    let stellar_account = evm_address_to_stellar_account(mapper, address)?;
+    let confirmed_sequence = client.get_account_sequence(&stellar_account).await?;
+    let sequence = state_sequence(
+        pending,
+        &stellar_account,
+        confirmed_sequence,
+        is_pending_tag(params.get(1)),
+    );
 
     Ok(Value::String(format!("0x{:x}", sequence)))
 }
@@ -552,75 +1330,115 @@ pub async fn get_transaction_count(
 /// Handler for eth_getLogs
 pub async fn get_logs(
     client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
     params: &[Value],
 ) -> Result<Value> {
     let default_filter = Value::Object(Default::default());
     let filter = params.first().unwrap_or(&default_filter);
 
-    let from_block = filter["fromBlock"]
-        .as_str()
-        .unwrap_or("latest");
-    let to_block = filter["toBlock"]
-        .as_str()
-        .unwrap_or("latest");
+    let from_block = block_param(filter.get("fromBlock")).unwrap_or_else(|| "latest".to_string());
+    let to_block = block_param(filter.get("toBlock")).unwrap_or_else(|| "latest".to_string());
 
     let latest = client.get_latest_ledger().await?;
 
-    let start_ledger = parse_block_number(from_block, latest.sequence);
-    let end_ledger = parse_block_number(to_block, latest.sequence);
+    let start_ledger = parse_block_number(&from_block, latest.sequence, config.finality_depth)?;
+    let end_ledger = parse_block_number(&to_block, latest.sequence, config.finality_depth)?;
 
     debug!(
         "eth_getLogs: from_ledger={}, to_ledger={}",
         start_ledger, end_ledger
     );
 
-    // Build event filters
-    let mut event_filters = Vec::new();
-
-    if let Some(address) = filter["address"].as_str() {
-        let contract_id = evm_address_to_contract_id(address);
-        event_filters.push(EventFilter {
-            event_type: "contract".to_string(),
-            contract_ids: Some(vec![contract_id]),
-            topics: None,
-        });
+    if end_ledger < start_ledger {
+        return Err(TvaError::InvalidParams("invalid block range params".to_string()).into());
+    }
+    let range = end_ledger - start_ledger + 1;
+    if range > config.max_block_range {
+        return Err(TvaError::LimitExceeded(format!(
+            "block range too wide: {} blocks requested, maximum is {}",
+            range, config.max_block_range
+        ))
+        .into());
     }
 
-    let events_params = GetEventsParams {
-        start_ledger,
-        end_ledger: Some(end_ledger),
-        filters: if event_filters.is_empty() {
-            None
-        } else {
-            Some(event_filters)
-        },
-        pagination: Some(EventPagination {
-            limit: 100,
-            cursor: None,
-        }),
-    };
-
-    let events_response = client.get_events(events_params).await?;
+    // Build event filters
+    let event_filters = build_event_filters(mapper, &filter["address"], &filter["topics"], abi_registry)?;
+
+    // Page through getEvents; topic filtering happens after ABI encoding, since
+    // Soroban filters match raw ScVal topics rather than EVM topic hashes
+    let mut logs: Vec<Value> = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let events_params = GetEventsParams {
+            start_ledger: if cursor.is_none() { Some(start_ledger) } else { None },
+            end_ledger: Some(end_ledger + 1),
+            filters: if event_filters.is_empty() {
+                None
+            } else {
+                Some(event_filters.clone())
+            },
+            pagination: Some(EventPagination {
+                limit: EVENTS_PAGE_LIMIT,
+                cursor: cursor.take(),
+            }),
+        };
+
+        let events_response = client.get_events(events_params).await?;
+        let events: Vec<SorobanEvent> = events_response.events.unwrap_or_default();
+        let page_len = events.len();
+        let in_range: Vec<SorobanEvent> = events
+            .into_iter()
+            .filter(|e| e.ledger >= start_ledger && e.ledger <= end_ledger)
+            .collect();
 
-    let logs: Vec<Value> = if let Some(events) = events_response.events {
         let evm_logs = crate::emulator::logs::soroban_events_to_evm_logs(
-            &events,
+            client.network_passphrase(),
+            mapper,
+            &in_range,
             &format!("0x{}", "0".repeat(64)),
+            Some(abi_registry),
+            config.include_failed_call_events,
         );
-        evm_logs
-            .iter()
-            .map(|log| serde_json::to_value(log).unwrap_or(Value::Null))
-            .collect()
-    } else {
-        Vec::new()
-    };
+        logs.extend(
+            evm_logs
+                .iter()
+                .filter(|log| log_matches_topics(log, &filter["topics"]))
+                .map(|log| serde_json::to_value(log).unwrap_or(Value::Null)),
+        );
+
+        if logs.len() > config.max_log_results {
+            return Err(TvaError::LimitExceeded(format!(
+                "query returned more than {} results",
+                config.max_log_results
+            ))
+            .into());
+        }
+
+        // A short page, or one reaching past the range, is the last
+        let past_range = in_range.len() < page_len;
+        if page_len < EVENTS_PAGE_LIMIT as usize || past_range {
+            break;
+        }
+        cursor = events_response.cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
 
     Ok(Value::Array(logs))
 }
 
-/// Handler for eth_accounts (return empty - no managed accounts)
-pub async fn accounts() -> Result<Value> {
-    Ok(Value::Array(Vec::new()))
+/// Handler for eth_accounts
+/// With managed signing enabled, the EVM address of the server's key;
+/// otherwise empty, since clients sign their own transactions.
+pub async fn accounts(config: &Config, mapper: &dyn AddressMapper) -> Result<Value> {
+    if !config.enable_managed_signing {
+        return Ok(Value::Array(Vec::new()));
+    }
+    let signer = StellarSigner::from_secret(&config.stellar_secret_key)?;
+    Ok(Value::Array(vec![Value::String(signer.evm_address(mapper))]))
 }
 
 /// Handler for eth_mining (always false)
@@ -633,9 +1451,51 @@ pub async fn hashrate() -> Result<Value> {
     Ok(Value::String("0x0".to_string()))
 }
 
-/// Handler for eth_syncing (always false - Stellar has instant finality)
-pub async fn syncing() -> Result<Value> {
-    Ok(Value::Bool(false))
+/// Mining methods with no Stellar equivalent: eth_getWork, eth_submitWork
+/// and eth_submitHashrate.
+pub const MINING_METHODS: [&str; 3] = ["eth_getWork", "eth_submitWork", "eth_submitHashrate"];
+
+/// Handler for the `MINING_METHODS`: an explicit unsupported error, so
+/// mining tools see why rather than a generic method-not-found.
+pub async fn mining_unsupported(method: &str) -> Result<Value> {
+    Err(TvaError::Unsupported(format!(
+        "{}: mining is not supported on TVA; Stellar uses SCP consensus",
+        method
+    ))
+    .into())
+}
+
+/// Handler for eth_syncing (false while the upstream is at the network head)
+/// Reports a sync object while the Soroban RPC upstream trails the network
+/// head, and while the client's circuit breaker is failing calls fast.
+pub async fn syncing(upstream: &UpstreamStatus) -> Result<Value> {
+    // An open circuit means calls fail fast: report not ready, at the last known ledgers
+    if upstream.circuit_open {
+        let current = upstream.latest_ledger.unwrap_or_default();
+        let highest = upstream.network_head.unwrap_or_default().max(current);
+        debug!("eth_syncing: upstream circuit open at {}", current);
+        return Ok(serde_json::json!({
+            "startingBlock": format!("0x{:x}", upstream.sync_started_at.unwrap_or(current)),
+            "currentBlock": format!("0x{:x}", current),
+            "highestBlock": format!("0x{:x}", highest),
+            "circuitOpen": true,
+        }));
+    }
+    if !upstream.is_lagging() {
+        return Ok(Value::Bool(false));
+    }
+
+    // is_lagging() guarantees both ledgers are known
+    let current = upstream.latest_ledger.unwrap_or_default();
+    let highest = upstream.network_head.unwrap_or_default();
+    let starting = upstream.sync_started_at.unwrap_or(current);
+    debug!("eth_syncing: upstream at {} of {}", current, highest);
+
+    Ok(serde_json::json!({
+        "startingBlock": format!("0x{:x}", starting),
+        "currentBlock": format!("0x{:x}", current),
+        "highestBlock": format!("0x{:x}", highest),
+    }))
 }
 
 /// Handler for eth_coinbase
@@ -653,25 +1513,273 @@ pub async fn get_storage_at(params: &[Value]) -> Result<Value> {
     Ok(Value::String(format!("0x{}", "0".repeat(64))))
 }
 
+/// keccak256 of empty code, the codeHash of accounts without a contract.
+const EMPTY_CODE_HASH: &str = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+
+/// Root of an empty Merkle-Patricia trie, reported as every storageHash.
+const EMPTY_TRIE_ROOT: &str = "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421";
+
+/// Handler for eth_getProof
+/// Best effort: Stellar state is not kept in a Merkle-Patricia trie, so the
+/// account values are reported with empty proofs that cannot be verified
+/// against the synthetic block's state root. Only the latest and pending
+/// state can be read. EVM storage slots have no Soroban counterpart, so
+/// storage keys are tagged ScVals, as tva_getContractData takes them; each is
+/// read from the contract's persistent storage and its value rendered as JSON
+/// (null if nothing is stored).
+pub async fn get_proof(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    pending: &PendingTxStore,
+    params: &[Value],
+) -> Result<Value> {
+    let address = address_param("eth_getProof", params.first())?;
+    let storage_keys = match params.get(1) {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(keys)) => keys
+            .iter()
+            .map(|key| {
+                let scval = scval_from_json(key).map_err(|e| {
+                    TvaError::InvalidParams(format!(
+                        "eth_getProof: storage key {} must be a tagged ScVal such as {{\"symbol\": \"Admin\"}}: {}",
+                        key, e
+                    ))
+                })?;
+                Ok((key.clone(), scval))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Some(other) => return Err(TvaError::InvalidParams(format!("eth_getProof storage keys must be an array, got {}", other)).into()),
+    };
+    let block = block_param(params.get(2)).unwrap_or_else(|| "latest".to_string());
+
+    let latest = client.get_latest_ledger().await?;
+    let ledger = parse_block_number(&block, latest.sequence, config.finality_depth)?;
+    let tag = if is_pending_tag(params.get(2)) {
+        "pending"
+    } else if ledger == latest.sequence {
+        "latest"
+    } else {
+        return Err(TvaError::Unsupported(format!(
+            "eth_getProof: state at ledger {} is unavailable; only the latest and pending state are served",
+            ledger
+        ))
+        .into());
+    };
+
+    debug!("eth_getProof: address={}, block={}", address, tag);
+
+    let account_params = [Value::String(address.to_string()), Value::String(tag.to_string())];
+    let balance = get_balance(client, config, mapper, pending, &account_params).await?;
+    let nonce = get_transaction_count(client, mapper, pending, &account_params).await?;
+
+    // The contract instance and the requested storage entries, in one request
+    let contract = mapper
+        .evm_to_contract(address)
+        .ok_or_else(|| TvaError::InvalidParams(format!("Invalid address: {}", address)))?;
+    let instance_key = build_contract_instance_key(&hex::encode(contract));
+    let data_keys: Vec<String> = storage_keys
+        .iter()
+        .map(|(_, key)| contract_data_key(&contract, key, StorageDurability::Persistent))
+        .collect();
+    let response = client
+        .get_ledger_entries(std::iter::once(instance_key.clone()).chain(data_keys.iter().cloned()).collect())
+        .await?;
+    let entries: HashMap<String, String> = response
+        .entries
+        .unwrap_or_default()
+        .into_iter()
+        .map(|entry| (entry.key, entry.xdr))
+        .collect();
+
+    // The hash of the WASM the contract instance runs; built-in executables
+    // (Stellar Asset Contracts) have none and report the zero hash
+    let code_hash = match entries.get(&instance_key) {
+        Some(xdr) => format!("0x{}", hex::encode(instance_wasm_hash(xdr)?.unwrap_or_default())),
+        None => EMPTY_CODE_HASH.to_string(),
+    };
+
+    let storage_proof = storage_keys
+        .into_iter()
+        .zip(&data_keys)
+        .map(|((key, _), data_key)| {
+            let value = match entries.get(data_key) {
+                Some(xdr) => decode_contract_data(xdr)?.to_json(),
+                None => Value::Null,
+            };
+            Ok(serde_json::json!({ "key": key, "value": value, "proof": [] }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(serde_json::json!({
+        "address": address.to_lowercase(),
+        "balance": balance,
+        "nonce": nonce,
+        "codeHash": code_hash,
+        "storageHash": EMPTY_TRIE_ROOT,
+        "accountProof": [],
+        "storageProof": storage_proof,
+    }))
+}
+
 // --- Helper functions ---
 
-/// Convert EVM address to Stellar account ID (G... format).
-/// In production this would use the AccountRegistry contract.
-fn evm_address_to_stellar_account(evm_address: &str) -> String {
-    // For now, return a placeholder. In production, query the AccountRegistry.
-    let _addr_bytes = hex::decode(
-        evm_address.strip_prefix("0x").unwrap_or(evm_address)
-    ).unwrap_or_default();
+/// Whether `s` is a 0x-prefixed 20-byte hex address.
+fn is_hex_address(s: &str) -> bool {
+    s.strip_prefix("0x")
+        .is_some_and(|digits| digits.len() == 40 && digits.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Read a required address parameter, rejecting anything but a hex address.
+pub(crate) fn address_param<'a>(method: &str, param: Option<&'a Value>) -> Result<&'a str, TvaError> {
+    param
+        .and_then(|v| v.as_str())
+        .filter(|address| is_hex_address(address))
+        .ok_or_else(|| TvaError::InvalidParams(format!("{}: address is required and must be a hex address", method)))
+}
+
+/// Convert EVM address to Stellar account ID (G... format) through the
+/// address mapper: addresses handed out for native accounts resolve to them,
+/// any other address to the account key derived from it.
+pub(crate) fn evm_address_to_stellar_account(mapper: &dyn AddressMapper, evm_address: &str) -> Result<String> {
+    let key = mapper
+        .evm_to_account(evm_address)
+        .ok_or_else(|| TvaError::InvalidParams(format!("Invalid address: {}", evm_address)))?;
+    Ok(encode_strkey(STRKEY_VERSION_ACCOUNT, &key))
+}
+
+/// EVM `from`/`to` of a Stellar transaction, decoded from its envelope.
+/// Falls back to zero addresses when the envelope is missing or unreadable;
+/// `to` is None for contract creation.
+fn envelope_addresses(mapper: &dyn AddressMapper, tx_response: &GetTransactionResponse) -> (String, Option<String>) {
+    let zero = format!("0x{}", "0".repeat(40));
+    let Some(envelope_xdr) = tx_response.envelope_xdr.as_deref() else {
+        return (zero.clone(), Some(zero));
+    };
+
+    match decode_envelope_summary(envelope_xdr) {
+        Ok(summary) => (summary.from_address(mapper), summary.to_address(mapper)),
+        Err(e) => {
+            warn!("Could not decode transaction envelope: {}", e);
+            (zero.clone(), Some(zero))
+        }
+    }
+}
+
+/// Events requested per getEvents page.
+const EVENTS_PAGE_LIMIT: u64 = 1000;
+
+/// Maximum contract IDs Soroban RPC accepts in a single getEvents filter.
+const MAX_CONTRACT_IDS_PER_FILTER: usize = 5;
+
+/// Maximum topic patterns Soroban RPC accepts in a single getEvents filter.
+const MAX_TOPIC_PATTERNS_PER_FILTER: usize = 5;
+
+/// Soroban topic patterns of a getEvents filter (None matches any topics).
+type TopicPatterns = Option<Vec<Vec<String>>>;
+
+/// Build getEvents filters from an `eth_getLogs` address field, which may be
+/// absent, a single address, or an array of addresses, narrowed by the
+/// topics filter where it translates (see `soroban_topic_patterns`).
+fn build_event_filters(
+    mapper: &dyn AddressMapper,
+    address: &Value,
+    topics: &Value,
+    abi_registry: &AbiRegistry,
+) -> Result<Vec<EventFilter>> {
+    let addresses: Vec<&str> = match address {
+        Value::Null => Vec::new(),
+        Value::String(addr) => vec![addr.as_str()],
+        Value::Array(addrs) => addrs
+            .iter()
+            .map(|a| a.as_str().ok_or_else(|| TvaError::InvalidParams(format!("Invalid address in filter: {}", a))))
+            .collect::<Result<_, _>>()?,
+        other => return Err(TvaError::InvalidParams(format!("Invalid address filter: {}", other)).into()),
+    };
+
+    // Contracts whose topic patterns agree share filters
+    let mut groups: Vec<(TopicPatterns, Vec<String>)> = Vec::new();
+    for addr in addresses {
+        let patterns = soroban_topic_patterns(abi_registry, addr, topics);
+        let contract_id = evm_address_to_contract_id(mapper, addr);
+        match groups.iter_mut().find(|(group, _)| *group == patterns) {
+            Some((_, contract_ids)) => contract_ids.push(contract_id),
+            None => groups.push((patterns, vec![contract_id])),
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .flat_map(|(topics, contract_ids)| {
+            contract_ids
+                .chunks(MAX_CONTRACT_IDS_PER_FILTER)
+                .map(|ids| EventFilter {
+                    event_type: "contract".to_string(),
+                    contract_ids: Some(ids.to_vec()),
+                    topics: topics.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
+
+/// Soroban topic patterns selecting the events of the contract at `address`
+/// that an `eth_getLogs` topics filter can match, to narrow getEvents.
+///
+/// Only the first topic maps back reliably: an event signature of the
+/// contract's registered ABI, which Soroban emits as the event name Symbol
+/// (as declared, or lowercased by convention) followed by one topic per
+/// indexed parameter. Those positions become `*` wildcards, so the patterns
+/// select a superset and `log_matches_topics` still applies the exact filter.
+/// Returns None (no narrowing) when the first topic is a wildcard, names no
+/// event of the ABI, or needs more patterns than one filter allows.
+fn soroban_topic_patterns(abi_registry: &AbiRegistry, address: &str, topics: &Value) -> TopicPatterns {
+    let alternatives: Vec<&str> = match topics.as_array()?.first()? {
+        Value::String(topic) => vec![topic.as_str()],
+        Value::Array(options) if !options.is_empty() => {
+            options.iter().map(|o| o.as_str()).collect::<Option<_>>()?
+        }
+        _ => return None,
+    };
+
+    let mut patterns: Vec<Vec<String>> = Vec::new();
+    for topic in alternatives {
+        let mut hash = [0u8; 32];
+        hex::decode_to_slice(topic.strip_prefix("0x").unwrap_or(topic), &mut hash).ok()?;
+        let event = abi_registry.lookup_event_by_topic(address, &hash)?;
+        let indexed = event.inputs.iter().filter(|p| p.indexed).count();
+
+        let mut names = vec![event.name.clone()];
+        if event.name.to_lowercase() != event.name {
+            names.push(event.name.to_lowercase());
+        }
+        for name in names {
+            let symbol = base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                ScVal::Symbol(name).to_xdr(),
+            );
+            let mut pattern = vec![symbol];
+            pattern.extend(std::iter::repeat_n("*".to_string(), indexed));
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+    }
 
-    // Default to admin account if no mapping exists
-    "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN".to_string()
+    (patterns.len() <= MAX_TOPIC_PATTERNS_PER_FILTER).then_some(patterns)
 }
 
-/// Convert EVM address to Stellar contract ID string.
-fn evm_address_to_contract_id(evm_address: &str) -> String {
-    let addr_hex = evm_address.strip_prefix("0x").unwrap_or(evm_address);
-    // Pad to 64 hex chars (32 bytes) for contract ID
-    format!("{:0>64}", addr_hex)
+/// Convert EVM address to Stellar contract ID string (32-byte hex), resolving
+/// addresses handed out for native Soroban contracts to the real contract.
+fn evm_address_to_contract_id(mapper: &dyn AddressMapper, evm_address: &str) -> String {
+    match mapper.evm_to_contract(evm_address) {
+        Some(contract) => hex::encode(contract),
+        None => {
+            let addr_hex = evm_address.strip_prefix("0x").unwrap_or(evm_address);
+            // Pad to 64 hex chars (32 bytes) for contract ID
+            format!("{:0>64}", addr_hex)
+        }
+    }
 }
 
 /// Build a ledger key XDR for a contract instance (base64 encoded).
@@ -699,7 +1807,7 @@ fn build_contract_instance_key(contract_id: &str) -> String {
 }
 
 /// Convert a Stellar transaction hash to EVM format (0x-prefixed 32-byte hex).
-fn stellar_hash_to_evm_hash(stellar_hash: &str) -> String {
+pub(crate) fn stellar_hash_to_evm_hash(stellar_hash: &str) -> String {
     if stellar_hash.starts_with("0x") {
         return stellar_hash.to_string();
     }
@@ -716,15 +1824,2017 @@ fn evm_hash_to_stellar_hash(evm_hash: &str) -> String {
     evm_hash.strip_prefix("0x").unwrap_or(evm_hash).to_string()
 }
 
-/// Get the source account ID from the config (derive from secret key).
-fn get_source_account_id(config: &Config) -> Result<String> {
-    let secret = &config.stellar_secret_key;
-    if secret.starts_with('S') && secret.len() == 56 {
-        // In a full implementation, we would derive the public key from the secret.
-        // For now, return a placeholder account.
-        // The actual derivation requires Ed25519 key derivation from the Stellar seed.
-        Ok("GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN".to_string())
+/// Sequence of `account` to read or build on: the confirmed one, or for the
+/// pending state the one after this server's in-flight submissions.
+pub(crate) fn state_sequence(
+    pending: &PendingTxStore,
+    account: &str,
+    confirmed_sequence: u64,
+    use_pending: bool,
+) -> u64 {
+    if use_pending {
+        pending.pending_for(account, confirmed_sequence).sequence(confirmed_sequence)
     } else {
-        Err(anyhow!("Invalid Stellar secret key format"))
+        confirmed_sequence
+    }
+}
+
+/// Check a raw transaction's nonce against the Stellar sequence it would be
+/// submitted with. Transactions are submitted from the server account, and
+/// nonce N is its sequence N + 1, so the expected nonce is what
+/// eth_getTransactionCount reports for the server's address with the
+/// `pending` tag. Mismatches fail the way EVM nodes report them.
+pub(crate) fn check_nonce(nonce: u64, sequence: u64) -> Result<()> {
+    let expected = sequence.saturating_sub(1);
+    match nonce.cmp(&expected) {
+        std::cmp::Ordering::Equal => Ok(()),
+        std::cmp::Ordering::Less => Err(TvaError::InvalidTransaction(format!(
+            "nonce too low: next nonce {}, tx nonce {}",
+            expected, nonce
+        ))
+        .into()),
+        std::cmp::Ordering::Greater => Err(TvaError::InvalidTransaction(format!(
+            "nonce too high: next nonce {}, tx nonce {}",
+            expected, nonce
+        ))
+        .into()),
+    }
+}
+
+/// Check a raw transaction's chain ID against the node's. Transactions without
+/// one (pre-EIP-155) are only accepted when `allow_unprotected_txs` is set.
+pub(crate) fn check_chain_id(tx_chain_id: Option<u64>, config: &Config) -> Result<()> {
+    match tx_chain_id {
+        Some(id) if id == config.tva_chain_id => Ok(()),
+        Some(id) => Err(TvaError::InvalidTransaction(format!(
+            "transaction chain id {} does not match node chain id {}",
+            id, config.tva_chain_id
+        ))
+        .into()),
+        None if config.allow_unprotected_txs => Ok(()),
+        None => Err(TvaError::InvalidTransaction(
+            "only replay-protected (EIP-155) transactions allowed".to_string(),
+        )
+        .into()),
+    }
+}
+
+/// Source account for simulating a call: the account behind `from`, so
+/// caller-dependent reads see the right caller, or the server's own account
+/// when the call has no `from`.
+fn simulation_source_account(config: &Config, mapper: &dyn AddressMapper, from: Option<&str>) -> Result<String> {
+    let signer = StellarSigner::from_secret(&config.stellar_secret_key)?;
+    match from {
+        // The managed address may not have been handed out (and recorded) yet
+        Some(from) if !from.eq_ignore_ascii_case(&signer.evm_address(mapper)) => {
+            evm_address_to_stellar_account(mapper, from)
+        }
+        _ => Ok(signer.account_id()),
+    }
+}
+
+/// Validate an eth_call state override set (address -> account overrides),
+/// returning the contract to simulate in place of `to` when its code is
+/// overridden.
+///
+/// Soroban simulation runs against real ledger state and cannot substitute
+/// balances, code or storage. It does not charge the source account, though,
+/// so the call already runs as if its source were funded: a `balance`
+/// override of the simulated source account is accepted as a no-op. A `code`
+/// override of the called contract naming another deployed contract (a C...
+/// contract ID or its EVM address) redirects the invocation there, which is
+/// how a proxy is simulated against a given implementation. Any other
+/// override is rejected rather than ignored, so callers never get results for
+/// state they did not ask for.
+fn check_state_overrides(
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    overrides: Option<&Value>,
+    from: Option<&str>,
+    to: &str,
+) -> Result<Option<String>> {
+    let accounts = match overrides {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Object(accounts)) => accounts,
+        Some(other) => {
+            return Err(TvaError::InvalidParams(format!("eth_call: state overrides must be an object, got {}", other)).into())
+        }
+    };
+
+    let mut implementation = None;
+    for (address, account_override) in accounts {
+        if !is_hex_address(address) {
+            return Err(TvaError::InvalidParams(format!("eth_call: invalid state override address {}", address)).into());
+        }
+        let fields = account_override.as_object().ok_or_else(|| {
+            TvaError::InvalidParams(format!("eth_call: state override for {} must be an object", address))
+        })?;
+        let is_callee = address.eq_ignore_ascii_case(to);
+
+        for (field, value) in fields {
+            match field.as_str() {
+                "balance" => {}
+                "code" if is_callee => {
+                    implementation = Some(code_override_contract(mapper, address, value)?);
+                    continue;
+                }
+                "wasmHash" if is_callee => {
+                    return Err(TvaError::InvalidParams(format!(
+                        "eth_call: wasmHash override for {} is not supported; simulation cannot swap a contract's \
+                         executable, so override 'code' with the address of a contract running that WASM",
+                        address
+                    ))
+                    .into());
+                }
+                "code" | "wasmHash" => {
+                    return Err(TvaError::InvalidParams(format!(
+                        "eth_call: '{}' state overrides are not supported for {}; only the called contract's code can be overridden",
+                        field, address
+                    ))
+                    .into());
+                }
+                _ => {
+                    return Err(TvaError::InvalidParams(format!(
+                        "eth_call: '{}' state overrides are not supported; only a balance override of the from account is",
+                        field
+                    ))
+                    .into());
+                }
+            }
+            let is_quantity = value
+                .as_str()
+                .and_then(|v| v.strip_prefix("0x"))
+                .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit()));
+            if !is_quantity {
+                return Err(TvaError::InvalidParams(format!("eth_call: invalid balance override {} for {}", value, address)).into());
+            }
+            let source = simulation_source_account(config, mapper, from)?;
+            if simulation_source_account(config, mapper, Some(address))? != source {
+                return Err(TvaError::InvalidParams(format!(
+                    "eth_call: balance override for {} is not supported; only the from account's balance can be overridden",
+                    address
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(implementation)
+}
+
+/// The contract ID (32-byte hex) a `code` override of `address` redirects
+/// the call to. Soroban cannot run EVM bytecode, so the override must name a
+/// deployed contract: a C... contract ID or an EVM contract address.
+fn code_override_contract(mapper: &dyn AddressMapper, address: &str, value: &Value) -> Result<String> {
+    let code = value.as_str().unwrap_or_default();
+    if let Ok(contract) = decode_strkey_checked(STRKEY_VERSION_CONTRACT, code) {
+        return Ok(hex::encode(contract));
+    }
+    if is_hex_address(code) {
+        return Ok(evm_address_to_contract_id(mapper, code));
+    }
+    Err(TvaError::InvalidParams(format!(
+        "eth_call: code override for {} must be the address of a deployed contract (C... or 0x-prefixed 20 bytes), got {}",
+        address, value
+    ))
+    .into())
+}
+
+/// Reject eth_call block overrides (fourth param): simulation always runs in
+/// the context of the latest ledger.
+fn check_block_overrides(overrides: Option<&Value>) -> Result<()> {
+    match overrides {
+        None | Some(Value::Null) => Ok(()),
+        Some(Value::Object(fields)) if fields.is_empty() => Ok(()),
+        Some(_) => Err(TvaError::InvalidParams("eth_call: block overrides are not supported".to_string()).into()),
+    }
+}
+
+/// Get the source account ID from the config (derive from secret key).
+pub(crate) fn get_source_account_id(config: &Config) -> Result<String> {
+    Ok(StellarSigner::from_secret(&config.stellar_secret_key)?.account_id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::address_map::DefaultAddressMapper;
+    use crate::test_utils::{latest_ledger_response, spawn_mock_server};
+    use std::time::Duration;
+
+    fn test_config() -> Config {
+        Config {
+            stellar_network: crate::config::StellarNetwork::Testnet,
+            stellar_rpc_url: "http://127.0.0.1:1".to_string(),
+            stellar_write_rpc_url: "http://127.0.0.1:1".to_string(),
+            stellar_horizon_url: "http://127.0.0.1:1".to_string(),
+            stellar_network_passphrase: "Test SDF Network ; September 2015".to_string(),
+            stellar_secret_key: String::new(),
+            tva_chain_id: 1414676736,
+            tva_bind_address: std::net::IpAddr::from([127, 0, 0, 1]),
+            tva_rpc_port: 8545,
+            log_level: "info".to_string(),
+            health_poll_interval_secs: 15,
+            upstream_pool_max_idle_per_host: 32,
+            upstream_pool_idle_timeout_secs: 90,
+            upstream_circuit_failure_threshold: 5,
+            upstream_circuit_cooldown_secs: 30,
+            allow_unprotected_txs: false,
+            enable_managed_signing: false,
+            max_block_range: 10_000,
+            max_log_results: 10_000,
+            max_request_bytes: 1_048_576,
+            xlm_wei_factor: 100_000_000_000,
+            gas_price_wei: 1_000_000_000,
+            gas_cpu_insns_per_gas: 1000,
+            gas_mem_bytes_per_gas: 100,
+            block_gas_limit: crate::emulator::block::DEFAULT_BLOCK_GAS_LIMIT,
+            finality_depth: 0,
+            include_failed_call_events: false,
+            function_aliases: Vec::new(),
+            enable_function_alias_rpc: false,
+            state_db_path: None,
+            rpc_method_allowlist: None,
+            rpc_method_denylist: Default::default(),
+        }
+    }
+
+    /// RLP-encode a legacy transaction with the given signature `v`.
+    fn legacy_raw_tx(v: u64) -> String {
+        let mut stream = rlp::RlpStream::new_list(9);
+        stream.append(&0u64);
+        stream.append(&1u64);
+        stream.append(&21000u64);
+        stream.append(&vec![0x11u8; 20]);
+        stream.append(&0u64);
+        stream.append(&Vec::<u8>::new());
+        stream.append(&v);
+        stream.append(&vec![1u8; 32]);
+        stream.append(&vec![2u8; 32]);
+        format!("0x{}", hex::encode(stream.out()))
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_transaction_rejects_foreign_chain_id() {
+        let mapper = DefaultAddressMapper::new();
+        let config = test_config();
+        let client = SorobanClient::new(&config.stellar_rpc_url, &config.stellar_network_passphrase);
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+
+        // v = chain_id * 2 + 35 for EIP-155; chain 1 is Ethereum mainnet
+        let params = vec![Value::String(legacy_raw_tx(37))];
+        let pending = Arc::new(PendingTxStore::new());
+        let err = send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &params)
+            .await
+            .unwrap_err();
+        let obj = crate::error::to_error_object(&err);
+        assert_eq!(obj.code(), crate::error::SERVER_ERROR_CODE);
+        assert_eq!(
+            obj.message(),
+            "transaction chain id 1 does not match node chain id 1414676736"
+        );
+
+        // Unprotected (v = 27) is rejected unless explicitly allowed
+        let params = vec![Value::String(legacy_raw_tx(27))];
+        assert!(send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &params)
+            .await
+            .is_err());
+        assert!(check_chain_id(None, &Config { allow_unprotected_txs: true, ..config.clone() }).is_ok());
+        assert!(check_chain_id(Some(1414676736), &config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_translation_stats_count_calls() {
+        let mapper = DefaultAddressMapper::new();
+        let ok = spawn_mock_server(vec![
+            ("rpc:simulateTransaction", 200, r#"{"jsonrpc": "2.0", "id": 1, "result": {}}"#.to_string()),
+        ])
+        .await;
+        let reverting = spawn_mock_server(vec![(
+            "rpc:simulateTransaction",
+            200,
+            r#"{"jsonrpc": "2.0", "id": 1, "result": {"error": "HostError: Error(Contract, #1)"}}"#.to_string(),
+        )])
+        .await;
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let registry = AbiRegistry::new();
+        let pending = Arc::new(PendingTxStore::new());
+        let to = "0x2222222222222222222222222222222222222222";
+        let abi = serde_json::json!([{
+            "type": "function",
+            "name": "totalSupply",
+            "inputs": [],
+            "outputs": [{"name": "", "type": "uint256"}],
+            "stateMutability": "view",
+        }]);
+        registry.register_contract(to, &serde_json::from_value::<Vec<_>>(abi).unwrap()).unwrap();
+        let total_supply = format!("0x{}", hex::encode(AbiRegistry::compute_selector("totalSupply()")));
+
+        let stats = TranslationStats::default();
+        // Two calls resolved through the ABI, one passed on raw and reverting
+        let client = SorobanClient::new(&ok, "Test SDF Network ; September 2015").with_horizon_url(&ok);
+        for _ in 0..2 {
+            let params = vec![serde_json::json!({ "to": to, "input": total_supply })];
+            call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        }
+        let client = SorobanClient::new(&reverting, "Test SDF Network ; September 2015").with_horizon_url(&reverting);
+        let params = vec![serde_json::json!({ "to": to, "input": "0xdeadbeef" })];
+        assert!(call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.is_err());
+
+        // And a deployment
+        let mut stream = rlp::RlpStream::new_list(9);
+        stream.append(&0u64);
+        stream.append(&1u64);
+        stream.append(&21000u64);
+        stream.append(&Vec::<u8>::new());
+        stream.append(&0u64);
+        stream.append(&vec![0x00u8, 0x61, 0x73, 0x6d]);
+        stream.append(&(config.tva_chain_id * 2 + 35));
+        stream.append(&vec![1u8; 32]);
+        stream.append(&vec![2u8; 32]);
+        let params = vec![Value::String(format!("0x{}", hex::encode(stream.out())))];
+        send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+
+        let summary = crate::methods::tva::get_translation_stats(&stats).await.unwrap();
+        assert_eq!(
+            summary,
+            serde_json::json!({
+                "calldataResolvedByAbi": 2,
+                "calldataRawFallback": 1,
+                "simulations": 3,
+                "simulationsReverted": 1,
+                "deploymentsAttempted": 1,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_transaction_checks_nonce() {
+        let mapper = DefaultAddressMapper::new();
+        let seed = crate::translator::tx::encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let url = spawn_mock_server(vec![
+            ("GET /accounts/", 200, r#"{"sequence": "41"}"#.to_string()),
+            ("rpc:simulateTransaction", 200, r#"{"jsonrpc": "2.0", "id": 1, "result": {}}"#.to_string()),
+            (
+                "rpc:sendTransaction",
+                200,
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": { "status": "PENDING", "hash": "cd".repeat(32) },
+                })
+                .to_string(),
+            ),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, &config.stellar_network_passphrase).with_horizon_url(&url);
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+
+        // A contract call with the given nonce, signed for this chain
+        let raw_call = |nonce: u64| {
+            let mut stream = rlp::RlpStream::new_list(9);
+            stream.append(&nonce);
+            stream.append(&1u64);
+            stream.append(&100_000u64);
+            stream.append(&vec![0x22u8; 20]);
+            stream.append(&0u64);
+            stream.append(&vec![0xa9u8, 0x05, 0x9c, 0xbb]);
+            stream.append(&(config.tva_chain_id * 2 + 35));
+            stream.append(&vec![1u8; 32]);
+            stream.append(&vec![2u8; 32]);
+            vec![Value::String(format!("0x{}", hex::encode(stream.out())))]
+        };
+
+        // The server account is at sequence 41, so the next nonce is 41
+        for (nonce, message) in [
+            (40, "nonce too low: next nonce 41, tx nonce 40"),
+            (0, "nonce too low: next nonce 41, tx nonce 0"),
+            (45, "nonce too high: next nonce 41, tx nonce 45"),
+        ] {
+            let err = send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &raw_call(nonce))
+                .await
+                .unwrap_err();
+            let obj = crate::error::to_error_object(&err);
+            assert_eq!(obj.code(), crate::error::SERVER_ERROR_CODE);
+            assert_eq!(obj.message(), message);
+        }
+        assert!(pending.is_empty());
+
+        // In-flight submissions move the next nonce along
+        let source = get_source_account_id(&config).unwrap();
+        pending.insert("0x01", PendingTx::submitted(&"01".repeat(32), &source, 42, 100));
+        let err = send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &raw_call(41))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "nonce too low: next nonce 42, tx nonce 41");
+
+        // The pending count of the server's address is the nonce that is accepted
+        let server = StellarSigner::from_secret(&config.stellar_secret_key).unwrap().evm_address(&mapper);
+        let server = vec![Value::String(server), Value::String("pending".into())];
+        let count = get_transaction_count(&client, &mapper, &pending, &server).await.unwrap();
+        let nonce = u64::from_str_radix(count.as_str().unwrap().trim_start_matches("0x"), 16).unwrap();
+        let hash = send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &raw_call(nonce))
+            .await
+            .unwrap();
+        assert_eq!(hash, stellar_hash_to_evm_hash(&"cd".repeat(32)));
+        assert_eq!(get_transaction_count(&client, &mapper, &pending, &server).await.unwrap(), "0x2b");
+
+        assert!(check_nonce(41, 42).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_transaction_by_block_and_index() {
+        let mapper = DefaultAddressMapper::new();
+        let tx = |hash: &str, ledger: u64, order: u32| {
+            serde_json::json!({
+                "status": "SUCCESS",
+                "txHash": hash,
+                "ledger": ledger,
+                "applicationOrder": order,
+                "createdAt": 1700000000u64,
+            })
+        };
+        let page = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "transactions": [
+                    tx(&"b".repeat(64), 900, 2),
+                    tx(&"a".repeat(64), 900, 1),
+                    tx(&"c".repeat(64), 901, 1),
+                ],
+                "latestLedger": 1000,
+                "cursor": "42",
+            }
+        });
+        let url = spawn_mock_server(vec![
+            ("rpc:getLatestLedger", 200, latest_ledger_response(1000)),
+            ("rpc:getTransactions", 200, page.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+
+        let params = vec![Value::String("0x384".into()), Value::String("0x1".into())];
+        let result = get_transaction_by_block_number_and_index(&client, &test_config(), &mapper, &AbiRegistry::new(), &params)
+            .await
+            .unwrap();
+        assert_eq!(result["hash"], format!("0x{}", "b".repeat(64)));
+        assert_eq!(result["blockNumber"], "0x384");
+        assert_eq!(result["transactionIndex"], "0x1");
+        assert_eq!(result["blockHash"], ledger_to_block_hash(client.network_passphrase(), 900));
+
+        let params = vec![
+            Value::String(ledger_to_block_hash(client.network_passphrase(), 900)),
+            Value::String("0x0".into()),
+        ];
+        let (registry, block_hashes) = (AbiRegistry::new(), BlockHashIndex::new(client.network_passphrase(), None));
+        let result = get_transaction_by_block_hash_and_index(&client, &test_config(), &mapper, &block_hashes, &registry, &params)
+            .await
+            .unwrap();
+        assert_eq!(result["hash"], format!("0x{}", "a".repeat(64)));
+
+        // Out of range index and unknown block hash are null
+        let params = vec![Value::String("0x384".into()), Value::String("0x2".into())];
+        assert!(get_transaction_by_block_number_and_index(&client, &test_config(), &mapper, &AbiRegistry::new(), &params)
+            .await
+            .unwrap()
+            .is_null());
+        let params = vec![Value::String(format!("0x{}", "0".repeat(64))), Value::String("0x0".into())];
+        assert!(get_transaction_by_block_hash_and_index(&client, &test_config(), &mapper, &block_hashes, &registry, &params)
+            .await
+            .unwrap()
+            .is_null());
+    }
+
+    #[tokio::test]
+    async fn test_get_block_receipts() {
+        let mapper = DefaultAddressMapper::new();
+        use base64::Engine;
+
+        let result_xdr = |fee: i64, code: i32| {
+            let mut bytes = fee.to_be_bytes().to_vec();
+            bytes.extend(code.to_be_bytes());
+            bytes.extend(0u32.to_be_bytes());
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        };
+        let tx = |hash: &str, order: u32, status: &str, result: String| {
+            serde_json::json!({
+                "status": status,
+                "txHash": hash,
+                "ledger": 900,
+                "applicationOrder": order,
+                "createdAt": 1700000000u64,
+                "resultXdr": result,
+            })
+        };
+        let page = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "transactions": [
+                    tx(&"b".repeat(64), 2, "FAILED", result_xdr(300, -1)),
+                    tx(&"a".repeat(64), 1, "SUCCESS", result_xdr(100, 0)),
+                ],
+                "latestLedger": 1000,
+                "cursor": "42",
+            }
+        });
+        let url = spawn_mock_server(vec![
+            ("rpc:getLatestLedger", 200, latest_ledger_response(1000)),
+            ("rpc:getTransactions", 200, page.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let block_hash = ledger_to_block_hash(client.network_passphrase(), 900);
+
+        let (config, registry) = (test_config(), AbiRegistry::new());
+        let block_hashes = BlockHashIndex::new(client.network_passphrase(), None);
+
+        let params = vec![Value::String("0x384".into())];
+        let receipts = get_block_receipts(&client, &config, &mapper, &block_hashes, &registry, &params).await.unwrap();
+        let receipts = receipts.as_array().unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0]["transactionHash"], format!("0x{}", "a".repeat(64)));
+        assert_eq!(receipts[0]["transactionIndex"], "0x0");
+        assert_eq!(receipts[0]["status"], "0x1");
+        assert_eq!(receipts[1]["transactionHash"], format!("0x{}", "b".repeat(64)));
+        assert_eq!(receipts[1]["transactionIndex"], "0x1");
+        assert_eq!(receipts[1]["status"], "0x0");
+        for receipt in receipts {
+            assert_eq!(receipt["blockNumber"], "0x384");
+            assert_eq!(receipt["blockHash"], block_hash);
+        }
+
+        // Cumulative gas adds up in application order (1 gwei per gas: 100 gas per stroop)
+        assert_eq!(receipts[0]["gasUsed"], format!("0x{:x}", 10_000));
+        assert_eq!(receipts[0]["cumulativeGasUsed"], format!("0x{:x}", 10_000));
+        assert_eq!(receipts[1]["gasUsed"], format!("0x{:x}", 30_000));
+        assert_eq!(receipts[1]["cumulativeGasUsed"], format!("0x{:x}", 40_000));
+
+        // The same block by hash; unknown hashes and future blocks are null
+        let by_hash = get_block_receipts(&client, &config, &mapper, &block_hashes, &registry, &[Value::String(block_hash)])
+            .await
+            .unwrap();
+        assert_eq!(by_hash.as_array().unwrap().len(), 2);
+        let unknown = vec![Value::String(format!("0x{}", "0".repeat(64)))];
+        assert!(get_block_receipts(&client, &config, &mapper, &block_hashes, &registry, &unknown).await.unwrap().is_null());
+        let future = vec![Value::String("0x3e9".into())];
+        assert!(get_block_receipts(&client, &config, &mapper, &block_hashes, &registry, &future).await.unwrap().is_null());
+    }
+
+    /// Horizon account with sequence 100 and 1 XLM, plus two in-flight submissions.
+    async fn pending_fixture() -> (SorobanClient, PendingTxStore, String) {
+        let account = r#"{"sequence":"100","balances":[{"asset_type":"native","balance":"1.0000000"}]}"#;
+        let url = spawn_mock_server(vec![("GET /accounts/", 200, account.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url(&url);
+
+        let mapper = DefaultAddressMapper::new();
+        let source = evm_address_to_stellar_account(&mapper, "0x1111111111111111111111111111111111111111").unwrap();
+        let pending = PendingTxStore::new();
+        for sequence in [101, 102] {
+            pending.insert(
+                &format!("0x{:064x}", sequence),
+                PendingTx::submitted(&format!("{:064x}", sequence), &source, sequence, 5_000),
+            );
+        }
+        (client, pending, "0x1111111111111111111111111111111111111111".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_count_pending_includes_submissions() {
+        let mapper = DefaultAddressMapper::new();
+        let (client, pending, address) = pending_fixture().await;
+
+        let latest = vec![Value::String(address.clone()), Value::String("latest".into())];
+        let result = get_transaction_count(&client, &mapper, &pending, &latest).await.unwrap();
+        assert_eq!(result, "0x64");
+
+        // Only the address's own in-flight submissions are counted
+        let tagged = vec![Value::String(address), Value::String("pending".into())];
+        let result = get_transaction_count(&client, &mapper, &pending, &tagged).await.unwrap();
+        assert_eq!(result, "0x66");
+        let other = vec![Value::String(format!("0x{}", "ab".repeat(20))), Value::String("pending".into())];
+        assert_eq!(get_transaction_count(&client, &mapper, &pending, &other).await.unwrap(), "0x64");
+    }
+
+    #[tokio::test]
+    async fn test_get_transaction_count_reports_horizon_failures() {
+        let mapper = DefaultAddressMapper::new();
+        let url = spawn_mock_server(vec![("GET /accounts/", 503, "{}".to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+
+        let params = vec![Value::String(format!("0x{}", "11".repeat(20)))];
+        let err = get_transaction_count(&client, &mapper, &PendingTxStore::new(), &params).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INTERNAL_ERROR_CODE);
+        assert!(err.downcast_ref::<TvaError>().is_some_and(|e| matches!(e, TvaError::Upstream(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_balance_pending_deducts_in_flight_fees() {
+        let mapper = DefaultAddressMapper::new();
+        let (client, pending, address) = pending_fixture().await;
+
+        let latest = vec![Value::String(address.clone()), Value::String("latest".into())];
+        let result = get_balance(&client, &test_config(), &mapper, &pending, &latest).await.unwrap();
+        assert_eq!(result, format!("0x{:x}", stroops_to_wei(10_000_000, test_config().xlm_wei_factor)));
+
+        let tagged = vec![Value::String(address), Value::String("pending".into())];
+        let result = get_balance(&client, &test_config(), &mapper, &pending, &tagged).await.unwrap();
+        assert_eq!(result, format!("0x{:x}", stroops_to_wei(9_990_000, test_config().xlm_wei_factor)));
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_reports_account_state_without_proofs() {
+        let mapper = DefaultAddressMapper::new();
+        let account = r#"{"sequence":"100","balances":[{"asset_type":"native","balance":"1.0000000"}]}"#;
+        let no_entries = r#"{"jsonrpc":"2.0","id":1,"result":{"entries":[],"latestLedger":1000}}"#;
+        let url = spawn_mock_server(vec![
+            ("GET /accounts/", 200, account.to_string()),
+            ("rpc:getLatestLedger", 200, latest_ledger_response(1000)),
+            ("rpc:getLedgerEntries", 200, no_entries.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let pending = PendingTxStore::new();
+        let config = test_config();
+        let address = "0x1111111111111111111111111111111111111111".to_string();
+        let params = vec![Value::String(address.clone()), serde_json::json!([]), Value::String("latest".into())];
+
+        let proof = get_proof(&client, &config, &mapper, &pending, &params).await.unwrap();
+        assert_eq!(proof["address"], address);
+        assert_eq!(proof["balance"], format!("0x{:x}", stroops_to_wei(10_000_000, test_config().xlm_wei_factor)));
+        assert_eq!(proof["nonce"], "0x64");
+        // No contract instance entry: the empty code hash is reported
+        assert_eq!(proof["codeHash"], EMPTY_CODE_HASH);
+        assert_eq!(proof["storageHash"], EMPTY_TRIE_ROOT);
+        assert_eq!(proof["accountProof"], serde_json::json!([]));
+        assert_eq!(proof["storageProof"], serde_json::json!([]));
+
+        // The latest block by number and the pending state are served too
+        for block in ["0x3e8", "pending"] {
+            let params = vec![Value::String(address.clone()), Value::Null, Value::String(block.into())];
+            assert!(get_proof(&client, &config, &mapper, &pending, &params).await.is_ok(), "{}", block);
+        }
+
+        // Keys that are not tagged ScVals, such as EVM slots, are invalid
+        let with_slot = vec![Value::String(address.clone()), serde_json::json!(["0x0"])];
+        let err = get_proof(&client, &config, &mapper, &pending, &with_slot).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+
+        // Past state cannot be served
+        let historical = vec![Value::String(address.clone()), serde_json::json!([]), Value::String("0x3e7".into())];
+        let err = get_proof(&client, &config, &mapper, &pending, &historical).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::METHOD_NOT_SUPPORTED_CODE);
+
+        let bad_keys = vec![Value::String(address.clone()), Value::String("0x0".into())];
+        assert!(get_proof(&client, &config, &mapper, &pending, &bad_keys).await.is_err());
+        let bad_block = vec![Value::String(address), serde_json::json!([]), Value::String("soon".into())];
+        let err = get_proof(&client, &config, &mapper, &pending, &bad_block).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_reports_the_instance_wasm_hash() {
+        use base64::Engine;
+        let mapper = DefaultAddressMapper::new();
+        let contract = [0x33u8; 32];
+        // LedgerEntryData: CONTRACT_DATA, ext, contract, instance key, PERSISTENT, instance running WASM 0xaa..
+        let mut entry = 6u32.to_be_bytes().to_vec();
+        entry.extend_from_slice(&0u32.to_be_bytes());
+        entry.extend_from_slice(&1u32.to_be_bytes());
+        entry.extend_from_slice(&contract);
+        entry.extend_from_slice(&20u32.to_be_bytes());
+        entry.extend_from_slice(&1u32.to_be_bytes());
+        entry.extend_from_slice(&19u32.to_be_bytes());
+        entry.extend_from_slice(&0u32.to_be_bytes());
+        entry.extend_from_slice(&[0xaa; 32]);
+        entry.extend_from_slice(&0u32.to_be_bytes());
+        let entries = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "entries": [{
+                    "key": build_contract_instance_key(&hex::encode(contract)),
+                    "xdr": base64::engine::general_purpose::STANDARD.encode(&entry),
+                }],
+                "latestLedger": 1000,
+            }
+        });
+        let url = spawn_mock_server(vec![
+            ("GET /accounts/", 404, "{}".to_string()),
+            ("rpc:getLatestLedger", 200, latest_ledger_response(1000)),
+            ("rpc:getLedgerEntries", 200, entries.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let config = test_config();
+
+        let params = vec![Value::String(mapper.contract_to_evm(&contract))];
+        let proof = get_proof(&client, &config, &mapper, &PendingTxStore::new(), &params).await.unwrap();
+        assert_eq!(proof["codeHash"], format!("0x{}", "aa".repeat(32)));
+    }
+
+    #[tokio::test]
+    async fn test_get_proof_reports_storage_values() {
+        use base64::Engine;
+        let mapper = DefaultAddressMapper::new();
+        let contract = [0x33u8; 32];
+        let admin = ScVal::Symbol("Admin".into());
+        let supply = ScVal::Symbol("Supply".into());
+        // LedgerEntryData: CONTRACT_DATA, ext, contract, key, PERSISTENT, value
+        let mut entry = 6u32.to_be_bytes().to_vec();
+        entry.extend_from_slice(&0u32.to_be_bytes());
+        entry.extend_from_slice(&1u32.to_be_bytes());
+        entry.extend_from_slice(&contract);
+        entry.extend(supply.to_xdr());
+        entry.extend_from_slice(&1u32.to_be_bytes());
+        entry.extend(ScVal::I128(250).to_xdr());
+        // Only the supply is stored
+        let entries = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "entries": [{
+                    "key": contract_data_key(&contract, &supply, StorageDurability::Persistent),
+                    "xdr": base64::engine::general_purpose::STANDARD.encode(&entry),
+                }],
+                "latestLedger": 1000,
+            }
+        });
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![
+            ("GET /accounts/", 404, "{}".to_string()),
+            ("rpc:getLatestLedger", 200, latest_ledger_response(1000)),
+            ("rpc:getLedgerEntries", 200, entries.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+
+        let keys = serde_json::json!([{"symbol": "Supply"}, {"symbol": "Admin"}]);
+        let params = vec![Value::String(mapper.contract_to_evm(&contract)), keys];
+        let proof = get_proof(&client, &test_config(), &mapper, &PendingTxStore::new(), &params).await.unwrap();
+        assert_eq!(
+            proof["storageProof"],
+            serde_json::json!([
+                { "key": {"symbol": "Supply"}, "value": "250", "proof": [] },
+                { "key": {"symbol": "Admin"}, "value": null, "proof": [] },
+            ])
+        );
+        // No contract instance entry came back
+        assert_eq!(proof["codeHash"], EMPTY_CODE_HASH);
+
+        // The instance and both storage entries were read in one request
+        let requests = requests.lock().unwrap();
+        let ledger_reads: Vec<&String> = requests.iter().filter(|r| r.contains("\"getLedgerEntries\"")).collect();
+        assert_eq!(ledger_reads.len(), 1);
+        for key in [&supply, &admin] {
+            assert!(ledger_reads[0].contains(&contract_data_key(&contract, key, StorageDurability::Persistent)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_with_empty_calldata_runs_fallback() {
+        let mapper = DefaultAddressMapper::new();
+        use base64::Engine;
+        let xdr = base64::engine::general_purpose::STANDARD.encode(ScVal::U32(1).to_xdr());
+        let url = spawn_mock_server(vec![
+            ("GET /accounts/", 200, r#"{"sequence": "41"}"#.to_string()),
+            (
+                "rpc:simulateTransaction",
+                200,
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"results": [{"xdr": xdr}]}}).to_string(),
+            ),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let config = Config {
+            stellar_secret_key: crate::translator::tx::encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]),
+            ..test_config()
+        };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let to = "0x2222222222222222222222222222222222222222";
+        let params = vec![serde_json::json!({ "to": to })];
+
+        // Without a receive or fallback function there is nothing to run
+        assert_eq!(call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap(), "0x");
+
+        let abi: Vec<crate::translator::abi::AbiEntry> =
+            serde_json::from_value(serde_json::json!([{ "type": "fallback" }])).unwrap();
+        registry.register_contract(to, &abi).unwrap();
+        let result = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        assert_eq!(result, format!("0x{}", hex::encode(ScVal::U32(1).to_xdr())));
+    }
+
+    #[tokio::test]
+    async fn test_call_simulates_as_from_account() {
+        let mapper = DefaultAddressMapper::new();
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![
+            ("rpc:simulateTransaction", 200, r#"{"jsonrpc": "2.0", "id": 1, "result": {}}"#.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed.clone(), ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let to = "0x2222222222222222222222222222222222222222";
+
+        // Source account key of each simulated transaction
+        let simulated_source = || -> [u8; 32] {
+            use base64::Engine;
+            let requests = requests.lock().unwrap();
+            let body = requests.iter().rev().find(|r| r.contains("simulateTransaction")).unwrap();
+            let body: Value = serde_json::from_str(&body[body.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+            let envelope = base64::engine::general_purpose::STANDARD
+                .decode(body["params"]["transaction"].as_str().unwrap())
+                .unwrap();
+            envelope[8..40].try_into().unwrap()
+        };
+
+        // A native account seen through its hashed address
+        let native = [0x42u8; 32];
+        let native_address = mapper.account_to_evm(&native);
+        let params = vec![serde_json::json!({ "from": native_address, "to": to, "input": "0xa9059cbb" })];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        assert_eq!(simulated_source(), native);
+
+        // An address with no native account maps to the key derived from it
+        let from = "0x1111111111111111111111111111111111111111";
+        let params = vec![serde_json::json!({ "from": from, "to": to, "input": "0xa9059cbb" })];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        assert_eq!(simulated_source(), crate::translator::evm_address_to_stellar_contract(&[0x11; 20]));
+
+        // Without `from`, the server's own account
+        let params = vec![serde_json::json!({ "to": to, "input": "0xa9059cbb" })];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        assert_eq!(simulated_source(), StellarSigner::from_secret(&seed).unwrap().public_key());
+    }
+
+    #[tokio::test]
+    async fn test_call_value_and_gas_limit() {
+        let mapper = DefaultAddressMapper::new();
+        let simulation = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "cost": { "cpuInsns": "5000000", "memBytes": "200000" } },
+        });
+        let url = spawn_mock_server(vec![("rpc:simulateTransaction", 200, simulation.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let to = "0x2222222222222222222222222222222222222222";
+
+        // Nonzero value cannot be attached to a Soroban invocation
+        let params = vec![serde_json::json!({ "to": to, "input": "0xa9059cbb", "value": "0x1" })];
+        let err = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        let obj = crate::error::to_error_object(&err);
+        assert_eq!(obj.code(), crate::error::SERVER_ERROR_CODE);
+        assert_eq!(obj.message(), "eth_call: value transfers to payable functions are not supported");
+
+        // Zero value is fine
+        let params = vec![serde_json::json!({ "to": to, "input": "0xa9059cbb", "value": "0x0" })];
+        assert_eq!(call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap(), "0x");
+
+        // 21064 intrinsic plus 5000 CPU and 2000 memory gas
+        let needed = intrinsic_gas(&[0xa9, 0x05, 0x9c, 0xbb], false) + 7_000;
+        let params = vec![serde_json::json!({ "to": to, "input": "0xa9059cbb", "gas": format!("0x{:x}", needed - 1) })];
+        let err = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("out of gas: gas required exceeds allowance ({}), needs {}", needed - 1, needed)
+        );
+        let params = vec![serde_json::json!({ "to": to, "input": "0xa9059cbb", "gas": format!("0x{:x}", needed) })];
+        assert_eq!(call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap(), "0x");
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_clamps_to_block_gas_limit() {
+        let mapper = DefaultAddressMapper::new();
+        let simulation = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "cost": { "cpuInsns": "900000000000", "memBytes": "200000" } },
+        });
+        let url = spawn_mock_server(vec![("rpc:simulateTransaction", 200, simulation.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let params = vec![serde_json::json!({ "to": "0x2222222222222222222222222222222222222222", "input": "0xa9059cbb" })];
+
+        // 900B instructions is far beyond the default 30M limit
+        let config = Config { stellar_secret_key: seed.clone(), ..test_config() };
+        let gas = estimate_gas(&client, &config, &mapper, &registry, &stats, &params).await.unwrap();
+        assert_eq!(gas, "0x1c9c380");
+
+        let config = Config { stellar_secret_key: seed, block_gas_limit: 50_000_000, ..test_config() };
+        let gas = estimate_gas(&client, &config, &mapper, &registry, &stats, &params).await.unwrap();
+        assert_eq!(gas, format!("0x{:x}", 50_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_estimate_gas_with_eip1559_fees() {
+        let mapper = DefaultAddressMapper::new();
+        let simulation = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "cost": { "cpuInsns": "5000000", "memBytes": "200000" }, "minResourceFee": "50" },
+        });
+        let url = spawn_mock_server(vec![("rpc:simulateTransaction", 200, simulation.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let (registry, stats) = (AbiRegistry::new(), TranslationStats::default());
+        let to = "0x2222222222222222222222222222222222222222";
+
+        // 21064 intrinsic plus 5000 CPU and 2000 memory gas already pays the
+        // 150-stroop fee (1.5 * 10^13 wei) at eth_gasPrice or above; at lower
+        // prices the estimate grows until gas * price pays it
+        let simulated = format!("0x{:x}", intrinsic_gas(&[0xa9, 0x05, 0x9c, 0xbb], false) + 7_000);
+        for (fees, expected) in [
+            (serde_json::json!({}), simulated.clone()),
+            (serde_json::json!({ "maxFeePerGas": "0x3b9aca00", "maxPriorityFeePerGas": "0x0" }), simulated.clone()),
+            (serde_json::json!({ "maxFeePerGas": "0x2540be400", "maxPriorityFeePerGas": "0x3b9aca00" }), simulated),
+            (serde_json::json!({ "maxFeePerGas": "0xf4240" }), format!("0x{:x}", 15_000_000)),
+            (serde_json::json!({ "gasPrice": "0x7a120" }), format!("0x{:x}", 30_000_000)),
+        ] {
+            let mut call_obj = serde_json::json!({ "to": to, "input": "0xa9059cbb" });
+            call_obj.as_object_mut().unwrap().extend(fees.as_object().unwrap().clone());
+            let gas = estimate_gas(&client, &config, &mapper, &registry, &stats, &[call_obj]).await.unwrap();
+            assert_eq!(gas, expected, "{}", fees);
+        }
+
+        // A value transfer covers the 100-stroop base fee at the caller's price
+        let transfer = serde_json::json!({ "to": to, "value": "0x1", "maxFeePerGas": "0x77359400" });
+        let gas = estimate_gas(&client, &config, &mapper, &registry, &stats, &[transfer]).await.unwrap();
+        assert_eq!(gas, "0x5208");
+        let transfer = serde_json::json!({ "to": to, "value": "0x1", "maxFeePerGas": "0xf4240" });
+        let gas = estimate_gas(&client, &config, &mapper, &registry, &stats, &[transfer]).await.unwrap();
+        assert_eq!(gas, format!("0x{:x}", 10_000_000));
+
+        // A price that needs more than the block gas limit to pay the fee cannot be met
+        let underpriced = serde_json::json!({ "to": to, "input": "0xa9059cbb", "gasPrice": "0x7a11f" });
+        let err = estimate_gas(&client, &config, &mapper, &registry, &stats, &[underpriced]).await.unwrap_err();
+        let obj = crate::error::to_error_object(&err);
+        assert_eq!(obj.code(), crate::error::SERVER_ERROR_CODE);
+        assert!(obj.message().starts_with("max fee per gas too low"), "{}", obj.message());
+
+        // Failing to read the base fee is an error rather than a guess
+        let unreachable = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url("http://127.0.0.1:1");
+        let transfer = serde_json::json!({ "to": to, "value": "0x1" });
+        assert!(estimate_gas(&unreachable, &config, &mapper, &registry, &stats, &[transfer]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fee_history_reports_the_fixed_gas_price() {
+        let url = spawn_mock_server(vec![("rpc:getLatestLedger", 200, latest_ledger_response(1000))]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let config = test_config();
+        let price = format!("0x{:x}", config.gas_model().gas_price());
+
+        let params = [serde_json::json!("0x4"), serde_json::json!("latest"), serde_json::json!([25, 75])];
+        let history = fee_history(&client, &config, &params).await.unwrap();
+        assert_eq!(history["oldestBlock"], "0x3e5");
+        assert_eq!(history["baseFeePerGas"], serde_json::json!(vec![price.clone(); 5]));
+        assert_eq!(history["gasUsedRatio"], serde_json::json!([0.5, 0.5, 0.5, 0.5]));
+        assert_eq!(history["reward"], serde_json::json!(vec![vec!["0x0"; 2]; 4]));
+
+        // Counts as a JSON number, clipped at genesis; no percentiles, no rewards
+        let history = fee_history(&client, &config, &[serde_json::json!(10), serde_json::json!("0x2")]).await.unwrap();
+        assert_eq!(history["oldestBlock"], "0x0");
+        assert_eq!(history["gasUsedRatio"].as_array().unwrap().len(), 3);
+        assert!(history.get("reward").is_none());
+
+        for bad in [
+            vec![serde_json::json!("4"), serde_json::json!("latest")],
+            vec![serde_json::json!("0x4")],
+            vec![serde_json::json!("0x4"), serde_json::json!("0x3ea")],
+            vec![serde_json::json!("0x4"), serde_json::json!("latest"), serde_json::json!([75, 25])],
+            vec![serde_json::json!("0x4"), serde_json::json!("latest"), serde_json::json!([101])],
+        ] {
+            let err = fee_history(&client, &config, &bad).await.unwrap_err();
+            assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE, "{:?}", bad);
+        }
+
+        assert_eq!(max_priority_fee_per_gas().await.unwrap(), "0x0");
+    }
+
+    #[tokio::test]
+    async fn test_archived_state_needs_restore() {
+        let mapper = DefaultAddressMapper::new();
+        // Simulation succeeds on the assumption the archived entries get restored first
+        let simulation = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "results": [{ "xdr": "AAAAAQ==" }],
+                "minResourceFee": "90000",
+                "restorePreamble": { "transactionData": "AAAA", "minResourceFee": "12345" },
+            },
+        });
+        let url = spawn_mock_server(vec![("rpc:simulateTransaction", 200, simulation.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, enable_managed_signing: true, ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+        let call_obj = serde_json::json!({ "to": "0x2222222222222222222222222222222222222222", "input": "0xa9059cbb" });
+
+        let expected = "contract state is archived and must be restored: submit a RestoreFootprint transaction \
+                        with the simulation's restore preamble (resource fee 12345 stroops), then retry";
+        let err = call(&client, &config, &mapper, &registry, &stats, &pending, std::slice::from_ref(&call_obj)).await.unwrap_err();
+        let obj = crate::error::to_error_object(&err);
+        assert_eq!(obj.code(), crate::error::SERVER_ERROR_CODE);
+        assert_eq!(obj.message(), expected);
+
+        let err = estimate_gas(&client, &config, &mapper, &registry, &stats, std::slice::from_ref(&call_obj)).await.unwrap_err();
+        assert_eq!(err.to_string(), expected);
+
+        // Nothing is submitted
+        let err = send_transaction(&client, &config, &mapper, &registry, &stats, &pending, &[call_obj]).await.unwrap_err();
+        assert_eq!(err.to_string(), expected);
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_call_state_overrides() {
+        let mapper = DefaultAddressMapper::new();
+        let url = spawn_mock_server(vec![
+            ("rpc:simulateTransaction", 200, r#"{"jsonrpc": "2.0", "id": 1, "result": {}}"#.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed.clone(), ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let to = "0x2222222222222222222222222222222222222222";
+        let from = "0x1111111111111111111111111111111111111111";
+        let call_obj = serde_json::json!({ "from": from, "to": to, "input": "0xa9059cbb" });
+
+        // A balance override of the from account is honoured
+        let overrides = serde_json::json!({ from: { "balance": "0xde0b6b3a7640000" } });
+        let params = vec![call_obj.clone(), "latest".into(), overrides];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+
+        // So is one of the server account when the call has no from
+        let managed = StellarSigner::from_secret(&seed).unwrap().evm_address(&mapper);
+        let overrides = serde_json::json!({ managed: { "balance": "0x1" } });
+        let params = vec![serde_json::json!({ "to": to, "input": "0xa9059cbb" }), "latest".into(), overrides];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+
+        let rejected = [
+            (serde_json::json!({ to: { "balance": "0x1" } }), "balance override for"),
+            (serde_json::json!({ from: { "code": "0x00" } }), "'code' state overrides are not supported"),
+            (serde_json::json!({ from: { "stateDiff": {} } }), "'stateDiff' state overrides are not supported"),
+            (serde_json::json!({ from: { "balance": "lots" } }), "invalid balance override"),
+        ];
+        for (overrides, message) in rejected {
+            let params = vec![call_obj.clone(), "latest".into(), overrides];
+            let err = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+            let error = crate::error::to_error_object(&err);
+            assert_eq!(error.code(), crate::error::INVALID_PARAMS_CODE);
+            assert!(error.message().contains(message), "{}", error.message());
+        }
+
+        let params = vec![call_obj, "latest".into(), Value::Null, serde_json::json!({ "number": "0x1" })];
+        let err = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        assert_eq!(err.to_string(), "eth_call: block overrides are not supported");
+    }
+
+    #[tokio::test]
+    async fn test_call_code_override_selects_implementation() {
+        let mapper = DefaultAddressMapper::new();
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![
+            ("rpc:simulateTransaction", 200, r#"{"jsonrpc": "2.0", "id": 1, "result": {}}"#.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let proxy = "0x2222222222222222222222222222222222222222";
+        let call_obj = serde_json::json!({ "to": proxy, "input": "0xa9059cbb" });
+
+        // Whether the last simulated transaction invokes `contract`
+        let simulates = |contract: [u8; 32]| -> bool {
+            use base64::Engine;
+            let requests = requests.lock().unwrap();
+            let body = requests.iter().rev().find(|r| r.contains("simulateTransaction")).unwrap();
+            let body: Value = serde_json::from_str(&body[body.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+            let envelope = base64::engine::general_purpose::STANDARD
+                .decode(body["params"]["transaction"].as_str().unwrap())
+                .unwrap();
+            envelope.windows(32).any(|window| window == contract)
+        };
+        let mut proxy_contract = [0u8; 32];
+        proxy_contract[12..].copy_from_slice(&[0x22; 20]);
+
+        call(&client, &config, &mapper, &registry, &stats, &pending, std::slice::from_ref(&call_obj)).await.unwrap();
+        assert!(simulates(proxy_contract));
+
+        // A C... contract ID as the proxy's code
+        let implementation = [0x77u8; 32];
+        let overrides = serde_json::json!({ proxy: { "code": encode_strkey(STRKEY_VERSION_CONTRACT, &implementation) } });
+        let params = vec![call_obj.clone(), "latest".into(), overrides];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        assert!(simulates(implementation));
+        assert!(!simulates(proxy_contract));
+
+        // Or the implementation's EVM address
+        let overrides = serde_json::json!({ proxy: { "code": "0x3333333333333333333333333333333333333333" } });
+        let params = vec![call_obj.clone(), "latest".into(), overrides];
+        call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap();
+        let mut evm_implementation = [0u8; 32];
+        evm_implementation[12..].copy_from_slice(&[0x33; 20]);
+        assert!(simulates(evm_implementation));
+
+        let rejected = [
+            (serde_json::json!({ proxy: { "code": "0x6080604052" } }), "must be the address of a deployed contract"),
+            (serde_json::json!({ proxy: { "wasmHash": format!("0x{}", "ab".repeat(32)) } }), "wasmHash override"),
+            (
+                serde_json::json!({ "0x4444444444444444444444444444444444444444": { "code": proxy } }),
+                "only the called contract's code can be overridden",
+            ),
+        ];
+        for (overrides, message) in rejected {
+            let params = vec![call_obj.clone(), "latest".into(), overrides];
+            let err = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+            let error = crate::error::to_error_object(&err);
+            assert_eq!(error.code(), crate::error::INVALID_PARAMS_CODE);
+            assert!(error.message().contains(message), "{}", error.message());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_checks_contract_is_deployed() {
+        let mapper = DefaultAddressMapper::new();
+        let entries = |entries: Value| {
+            serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": { "entries": entries, "latestLedger": 1000 } })
+                .to_string()
+        };
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let call_obj = serde_json::json!({ "to": "0x2222222222222222222222222222222222222222", "input": "0xa9059cbb" });
+        let simulate = (
+            "rpc:simulateTransaction",
+            200,
+            r#"{"jsonrpc": "2.0", "id": 1, "result": {"results": [{"xdr": "AAAAAQ==", "auth": []}]}}"#.to_string(),
+        );
+
+        // Nothing deployed: empty data, as for a call to an EOA, without simulating
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![
+            ("rpc:getLedgerEntries", 200, entries(serde_json::json!([]))),
+            simulate.clone(),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let result = call(&client, &config, &mapper, &registry, &stats, &pending, std::slice::from_ref(&call_obj)).await.unwrap();
+        assert_eq!(result, "0x");
+        assert!(!requests.lock().unwrap().iter().any(|r| r.contains("simulateTransaction")));
+
+        // A deployed contract is simulated
+        let instance = serde_json::json!([{
+            "key": "",
+            "xdr": "AAAABg==",
+            "lastModifiedLedgerSeq": 900,
+            "liveUntilLedgerSeq": 2000,
+        }]);
+        let (url, requests) =
+            crate::test_utils::spawn_recording_mock_server(vec![("rpc:getLedgerEntries", 200, entries(instance)), simulate])
+                .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let result = call(&client, &config, &mapper, &registry, &stats, &pending, std::slice::from_ref(&call_obj)).await.unwrap();
+        assert_eq!(result, "0x00000001");
+        assert!(requests.lock().unwrap().iter().any(|r| r.contains("simulateTransaction")));
+    }
+
+    #[tokio::test]
+    async fn test_call_at_historical_ledger() {
+        let mapper = DefaultAddressMapper::new();
+        use base64::Engine;
+        let b64 = base64::engine::general_purpose::STANDARD;
+        // A footprint reading the contract instance and its code
+        let instance_key = build_contract_instance_key(&"22".repeat(32));
+        let mut code_key = 7u32.to_be_bytes().to_vec();
+        code_key.extend([0x55u8; 32]);
+        let code_key = b64.encode(&code_key);
+        let mut transaction_data = vec![0u8; 4];
+        transaction_data.extend(2u32.to_be_bytes());
+        transaction_data.extend(b64.decode(&instance_key).unwrap());
+        transaction_data.extend(b64.decode(&code_key).unwrap());
+        transaction_data.extend([0u8; 24]);
+        let simulation = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "results": [{ "xdr": "AAAAAQ==", "auth": [] }], "transactionData": b64.encode(&transaction_data) }
+        });
+        let health = r#"{"jsonrpc": "2.0", "id": 1, "result": {"status": "healthy", "latestLedger": 1000, "oldestLedger": 900}}"#;
+        // The instance was last modified at 940, the code at 920
+        let entry = |key: &str, modified: u64| {
+            serde_json::json!({ "key": key, "xdr": "AAAABg==", "lastModifiedLedgerSeq": modified, "liveUntilLedgerSeq": 2000 })
+        };
+        let serve = |entries: Vec<Value>| {
+            let routes = vec![
+                ("rpc:getLatestLedger", 200, crate::test_utils::latest_ledger_response(1000)),
+                ("rpc:getHealth", 200, health.to_string()),
+                (
+                    "rpc:getLedgerEntries",
+                    200,
+                    serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": { "entries": entries, "latestLedger": 1000 } })
+                        .to_string(),
+                ),
+                ("rpc:simulateTransaction", 200, simulation.to_string()),
+            ];
+            async move {
+                let url = crate::test_utils::spawn_mock_server(routes).await;
+                SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url)
+            }
+        };
+        let seed = encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config { stellar_secret_key: seed, ..test_config() };
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let call_obj = serde_json::json!({ "to": "0x2222222222222222222222222222222222222222", "input": "0xa9059cbb" });
+        let call_at = |client: &SorobanClient, block: &str| {
+            let params = vec![call_obj.clone(), block.into()];
+            let (client, config, mapper, registry, stats, pending) =
+                (client.clone(), &config, &mapper, &registry, &stats, &pending);
+            async move { call(&client, config, mapper, registry, stats, pending, &params).await }
+        };
+        let client = serve(vec![entry(&instance_key, 940), entry(&code_key, 920)]).await;
+
+        // Ledger 950: the state read has not changed since, so the latest simulation holds
+        assert_eq!(call_at(&client, "0x3b6").await.unwrap(), "0x00000001");
+
+        // Ledger 930: the contract instance was modified at 940
+        let error = crate::error::to_error_object(&call_at(&client, "0x3a2").await.unwrap_err());
+        assert_eq!(error.code(), crate::error::METHOD_NOT_SUPPORTED_CODE);
+        assert!(error.message().contains("state at ledger 930 is unavailable"), "{}", error.message());
+
+        // Ledger 950 again, but the code entry has since been removed
+        let evicted = serve(vec![entry(&instance_key, 940)]).await;
+        let error = crate::error::to_error_object(&call_at(&evicted, "0x3b6").await.unwrap_err());
+        assert_eq!(error.code(), crate::error::METHOD_NOT_SUPPORTED_CODE);
+        assert!(error.message().contains("changed or been removed since"), "{}", error.message());
+
+        // Ledger 800: older than the node retains
+        let error = crate::error::to_error_object(&call_at(&client, "0x320").await.unwrap_err());
+        assert_eq!(error.code(), crate::error::INVALID_PARAMS_CODE);
+        assert!(error.message().contains("outside the node's retention window (oldest ledger 900)"), "{}", error.message());
+
+        // A malformed block is rejected rather than read as the latest state
+        let error = crate::error::to_error_object(&call_at(&client, "0xzz").await.unwrap_err());
+        assert_eq!(error.code(), crate::error::INVALID_PARAMS_CODE);
+        assert!(error.message().contains("invalid block number '0xzz'"), "{}", error.message());
+    }
+
+    #[tokio::test]
+    async fn test_call_accepts_input_only_call_object() {
+        let mapper = DefaultAddressMapper::new();
+        let client = SorobanClient::new("http://127.0.0.1:1", "Test SDF Network ; September 2015");
+        let config = test_config();
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let to = "0x2222222222222222222222222222222222222222";
+
+        // Calldata is read from `input`: short calldata has no selector to invoke
+        let params = vec![serde_json::json!({ "to": to, "input": "0x01" })];
+        assert_eq!(call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap(), "0x");
+
+        let params = vec![serde_json::json!({ "to": to, "input": "0xzz" })];
+        let err = call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        assert!(err.to_string().contains("input"), "{}", err);
+
+        let params = vec![serde_json::json!({ "to": to, "input": "0x01", "data": "0x02" })];
+        assert!(call(&client, &config, &mapper, &registry, &stats, &pending, &params).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_transaction_disabled_by_default() {
+        let mapper = DefaultAddressMapper::new();
+        let client = SorobanClient::new("http://127.0.0.1:1", "Test SDF Network ; September 2015");
+        let config = test_config();
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+        let params = vec![serde_json::json!({
+            "to": "0x2222222222222222222222222222222222222222",
+            "input": "0xa9059cbb",
+        })];
+
+        let err = send_transaction(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        let error = crate::error::to_error_object(&err);
+        assert_eq!(error.code(), crate::error::METHOD_NOT_FOUND_CODE);
+        assert!(error.message().contains("ENABLE_MANAGED_SIGNING"));
+        assert_eq!(accounts(&config, &mapper).await.unwrap(), serde_json::json!([]));
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_managed_signing_exposes_server_account() {
+        let mapper = DefaultAddressMapper::new();
+        let client = SorobanClient::new("http://127.0.0.1:1", "Test SDF Network ; September 2015");
+        let seed = crate::translator::tx::encode_strkey(crate::translator::tx::STRKEY_VERSION_SEED, &[3; 32]);
+        let config = Config {
+            stellar_secret_key: seed.clone(),
+            enable_managed_signing: true,
+            ..test_config()
+        };
+        let managed = StellarSigner::from_secret(&seed).unwrap().evm_address(&mapper);
+        assert_eq!(accounts(&config, &mapper).await.unwrap(), serde_json::json!([managed]));
+
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+        let to = "0x2222222222222222222222222222222222222222";
+
+        // Only the server's own account can send
+        let params = vec![serde_json::json!({ "from": to, "to": to, "input": "0xa9059cbb" })];
+        let err = send_transaction(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        assert!(err.to_string().contains("unknown account"), "{}", err);
+
+        let params = vec![serde_json::json!({ "from": managed.to_uppercase().replace("0X", "0x"), "to": to })];
+        let err = send_transaction(&client, &config, &mapper, &registry, &stats, &pending, &params).await.unwrap_err();
+        assert!(err.to_string().contains("requires contract calldata"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_call_pending_sequences_after_submissions() {
+        let mapper = DefaultAddressMapper::new();
+        let (_client, pending, address) = pending_fixture().await;
+        let source = evm_address_to_stellar_account(&mapper, &address).unwrap();
+
+        // eth_call simulates at the confirmed sequence, or after in-flight ones for "pending"
+        assert_eq!(state_sequence(&pending, &source, 100, false), 100);
+        assert_eq!(state_sequence(&pending, &source, 100, true), 102);
+        assert!(is_pending_tag(Some(&Value::String("pending".into()))));
+    }
+
+    #[tokio::test]
+    async fn test_receipt_after_submit_then_poll() {
+        let mapper = DefaultAddressMapper::new();
+        let not_found = r#"{"jsonrpc":"2.0","id":1,"result":{"status":"NOT_FOUND","latestLedger":1000}}"#;
+        let success = r#"{"jsonrpc":"2.0","id":1,"result":{"status":"SUCCESS","latestLedger":1000,"ledger":999,"applicationOrder":1}}"#;
+        // A lagging upstream that has not seen the transaction, and one that has
+        let lagging_url = spawn_mock_server(vec![("rpc:getTransaction", 200, not_found.to_string())]).await;
+        let synced_url = spawn_mock_server(vec![("rpc:getTransaction", 200, success.to_string())]).await;
+        let lagging = SorobanClient::new(&lagging_url, "Test SDF Network ; September 2015");
+        let synced = SorobanClient::new(&synced_url, "Test SDF Network ; September 2015");
+
+        // What send_raw_transaction records after a successful submission
+        let stellar_hash = "ab".repeat(32);
+        let tx_hash = stellar_hash_to_evm_hash(&stellar_hash);
+        let pending = Arc::new(PendingTxStore::new());
+        pending.insert(&tx_hash, PendingTx::submitted(&stellar_hash, "GA", 101, 1_000));
+
+        // Polled before it lands: no receipt yet, clients keep waiting
+        let params = vec![Value::String(tx_hash.clone())];
+        let receipt = get_transaction_receipt(&lagging, &test_config(), &mapper, &AbiRegistry::new(), &pending, &params).await.unwrap();
+        assert!(receipt.is_null());
+
+        // The watcher records the result
+        spawn_confirmation_watch(synced, pending.clone(), tx_hash.clone(), Duration::from_millis(10));
+        for _ in 0..100 {
+            if pending.get(&tx_hash).unwrap().status != TxStatus::Pending {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // The receipt is served from the store even though this upstream still lags
+        let receipt = get_transaction_receipt(&lagging, &test_config(), &mapper, &AbiRegistry::new(), &pending, &params).await.unwrap();
+        assert_eq!(receipt["status"], "0x1");
+        assert_eq!(receipt["blockNumber"], "0x3e7");
+        assert_eq!(receipt["transactionHash"], tx_hash);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_by_hash_decodes_envelope_addresses() {
+        let mapper = DefaultAddressMapper::new();
+        let source = encode_strkey(STRKEY_VERSION_ACCOUNT, &[0x42; 32]);
+        let envelope = crate::translator::build_soroban_invoke_tx(
+            &source,
+            7,
+            &evm_address_to_contract_id(&mapper, "0x2222222222222222222222222222222222222222"),
+            "balanceOf",
+            &[],
+            "Test SDF Network ; September 2015",
+            100,
+        )
+        .unwrap();
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "status": "SUCCESS", "ledger": 10, "envelopeXdr": envelope }
+        });
+        let url = spawn_mock_server(vec![("rpc:getTransaction", 200, response.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let params = vec![Value::String(format!("0x{}", "cd".repeat(32)))];
+
+        let tx = get_transaction_by_hash(&client, &test_config(), &mapper, &AbiRegistry::new(), &params).await.unwrap();
+        let expected_from = mapper.account_to_evm(&[0x42; 32]);
+        assert_eq!(tx["from"], expected_from);
+        assert_eq!(tx["to"], "0x2222222222222222222222222222222222222222");
+
+        let receipt = get_transaction_receipt(&client, &test_config(), &mapper, &AbiRegistry::new(), &PendingTxStore::new(), &params)
+            .await
+            .unwrap();
+        assert_eq!(receipt["from"], expected_from);
+        assert_eq!(receipt["to"], "0x2222222222222222222222222222222222222222");
+    }
+
+    #[tokio::test]
+    async fn test_transaction_by_hash_is_complete() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::translator::scval::StellarAddress;
+
+        let token = "0x2222222222222222222222222222222222222222";
+        let registry = AbiRegistry::new();
+        let abi = serde_json::json!([{
+            "type": "function",
+            "name": "transfer",
+            "inputs": [{"name": "to", "type": "address"}, {"name": "amount", "type": "uint64"}],
+            "outputs": [],
+            "stateMutability": "nonpayable",
+        }]);
+        registry.register_contract(token, &serde_json::from_value::<Vec<_>>(abi).unwrap()).unwrap();
+
+        let mut recipient = [0u8; 32];
+        recipient[12..].copy_from_slice(&[0x33; 20]);
+        let source = encode_strkey(STRKEY_VERSION_ACCOUNT, &[0x42; 32]);
+        let envelope = crate::translator::build_soroban_invoke_tx(
+            &source,
+            7,
+            &evm_address_to_contract_id(&mapper, token),
+            "transfer",
+            &[ScVal::Address(StellarAddress::Contract(recipient)), ScVal::U64(500)],
+            "Test SDF Network ; September 2015",
+            2_000,
+        )
+        .unwrap();
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "status": "SUCCESS", "ledger": 10, "applicationOrder": 3, "envelopeXdr": envelope }
+        });
+        let url = spawn_mock_server(vec![("rpc:getTransaction", 200, response.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let config = test_config();
+        let params = vec![Value::String(format!("0x{}", "cd".repeat(32)))];
+
+        let tx = get_transaction_by_hash(&client, &config, &mapper, &registry, &params).await.unwrap();
+        let tx: crate::translator::EvmTransaction = serde_json::from_value(tx).unwrap();
+        assert_eq!(tx.block_number, "0xa");
+        assert_eq!(tx.block_hash, ledger_to_block_hash(client.network_passphrase(), 10));
+        assert_eq!(tx.transaction_index, "0x2");
+        assert_eq!(tx.nonce, "0x6");
+        assert_eq!(tx.to.as_deref(), Some(token));
+        assert_eq!(tx.gas_price, format!("0x{:x}", config.gas_model().gas_price()));
+        assert_eq!(tx.gas, format!("0x{:x}", config.gas_model().fee_to_gas(2_000)));
+
+        let mut calldata = AbiRegistry::compute_selector("transfer(address,uint64)").to_vec();
+        calldata.extend([0u8; 12]);
+        calldata.extend([0x33u8; 20]);
+        let mut amount = [0u8; 32];
+        amount[30..].copy_from_slice(&500u16.to_be_bytes());
+        calldata.extend(amount);
+        assert_eq!(tx.input, format!("0x{}", hex::encode(calldata)));
+
+        // Without a registered ABI the arguments cannot be re-encoded
+        let tx = get_transaction_by_hash(&client, &config, &mapper, &AbiRegistry::new(), &params).await.unwrap();
+        assert_eq!(tx["input"], "0x");
+        assert_eq!(tx["nonce"], "0x6");
+    }
+
+    #[tokio::test]
+    async fn test_receipt_logs_from_transaction_events() {
+        let mapper = DefaultAddressMapper::new();
+        use base64::Engine;
+        use crate::emulator::logs::event_signature_to_topic;
+        use crate::translator::scval::StellarAddress;
+
+        let token = "0x1234567890abcdef1234567890abcdef12345678";
+        let registry = AbiRegistry::new();
+        let abi = serde_json::json!([{
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                {"name": "from", "type": "address", "indexed": true},
+                {"name": "to", "type": "address", "indexed": true},
+                {"name": "value", "type": "uint256", "indexed": false},
+            ],
+        }]);
+        registry.register_contract(token, &serde_json::from_value::<Vec<_>>(abi).unwrap()).unwrap();
+
+        let encode = |v: &ScVal| base64::engine::general_purpose::STANDARD.encode(v.to_xdr());
+        let account = |byte: u8| {
+            let mut key = [0u8; 32];
+            key[12..].copy_from_slice(&[byte; 20]);
+            ScVal::Address(StellarAddress::Account(key))
+        };
+        // Events of ledger 900 for the transactions applied first, second and third
+        let event = |order: u64, index: u64, topic: Vec<ScVal>| {
+            serde_json::json!({
+                "type": "contract",
+                "ledger": 900,
+                "contractId": format!("{:0>64}", &token[2..]),
+                "id": format!("{:019}-{:010}", (900u64 << 32) | (order << 12), index),
+                "topic": topic.iter().map(encode).collect::<Vec<_>>(),
+                "value": encode(&ScVal::I128(1000)),
+                "inSuccessfulContractCall": true,
+            })
+        };
+        let transfer = || vec![ScVal::Symbol("transfer".into()), account(0xaa), account(0xbb)];
+        let events = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "events": [
+                    event(1, 0, vec![ScVal::Symbol("ping".into())]),
+                    event(2, 0, transfer()),
+                    event(2, 1, vec![ScVal::Symbol("ping".into())]),
+                    event(3, 0, transfer()),
+                ],
+                "latestLedger": 1000,
+            }
+        });
+        let tx = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {"status": "SUCCESS", "latestLedger": 1000, "ledger": 900, "applicationOrder": 2}
+        });
+        let url = spawn_mock_server(vec![
+            ("rpc:getTransaction", 200, tx.to_string()),
+            ("rpc:getEvents", 200, events.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let tx_hash = format!("0x{}", "cd".repeat(32));
+
+        let receipt = get_transaction_receipt(&client, &test_config(), &mapper, &registry, &PendingTxStore::new(), &[Value::String(tx_hash.clone())])
+            .await
+            .unwrap();
+        let block_hash = ledger_to_block_hash(client.network_passphrase(), 900);
+        assert_eq!(receipt["blockHash"], block_hash);
+        assert_eq!(receipt["transactionIndex"], "0x1");
+
+        // Only the second transaction's events, indexed within the block
+        let logs = receipt["logs"].as_array().unwrap();
+        assert_eq!(logs.len(), 2);
+        let transfer_log = &logs[0];
+        assert_eq!(transfer_log["address"], token);
+        assert_eq!(transfer_log["topics"][0], event_signature_to_topic("Transfer(address,address,uint256)"));
+        assert_eq!(transfer_log["topics"][2], format!("0x{}{}", "0".repeat(24), "bb".repeat(20)));
+        assert_eq!(transfer_log["data"], format!("0x{:064x}", 1000));
+        assert_eq!(transfer_log["logIndex"], "0x1");
+        assert_eq!(logs[1]["logIndex"], "0x2");
+        for log in logs {
+            assert_eq!(log["transactionHash"], tx_hash);
+            assert_eq!(log["transactionIndex"], "0x1");
+            assert_eq!(log["blockHash"], block_hash);
+        }
+
+        // The bloom covers the logs' addresses and topics
+        let logs: Vec<EvmLog> = serde_json::from_value(receipt["logs"].clone()).unwrap();
+        assert_eq!(receipt["logsBloom"], crate::translator::receipt::logs_bloom(&logs));
+        assert_ne!(receipt["logsBloom"], format!("0x{}", "0".repeat(512)));
+    }
+
+    #[tokio::test]
+    async fn test_syncing_reports_lagging_upstream() {
+        let mut upstream = UpstreamStatus {
+            healthy: true,
+            latest_ledger: Some(0x100),
+            network_head: Some(0x101),
+            ..UpstreamStatus::default()
+        };
+        assert_eq!(syncing(&upstream).await.unwrap(), Value::Bool(false));
+
+        upstream.network_head = Some(0x200);
+        upstream.sync_started_at = Some(0xf0);
+        let progress = syncing(&upstream).await.unwrap();
+        assert_eq!(progress["startingBlock"], "0xf0");
+        assert_eq!(progress["currentBlock"], "0x100");
+        assert_eq!(progress["highestBlock"], "0x200");
+
+        // An open circuit reports not ready even without a lag
+        let upstream = UpstreamStatus { latest_ledger: Some(0x100), circuit_open: true, ..UpstreamStatus::default() };
+        let progress = syncing(&upstream).await.unwrap();
+        assert_eq!(progress["currentBlock"], "0x100");
+        assert_eq!(progress["highestBlock"], "0x100");
+        assert_eq!(progress["circuitOpen"], true);
+    }
+
+    #[test]
+    fn test_contract_address_roundtrips_through_registry() {
+        let mapper = DefaultAddressMapper::new();
+
+        let contract = [0x6du8; 32];
+        let address = mapper.contract_to_evm(&contract);
+        assert_eq!(evm_address_to_contract_id(&mapper, &address), hex::encode(contract));
+
+        // Filtering logs by that address targets the real contract
+        let filters = build_event_filters(&mapper, &Value::String(address), &Value::Null, &AbiRegistry::new()).unwrap();
+        assert_eq!(filters[0].contract_ids.as_deref().unwrap(), &[hex::encode(contract)]);
+    }
+
+    #[test]
+    fn test_address_filter_accepts_array() {
+        let mapper = DefaultAddressMapper::new();
+        let registry = AbiRegistry::new();
+        let filters = build_event_filters(&mapper, 
+            &serde_json::json!([
+                "0x1111111111111111111111111111111111111111",
+                "0x2222222222222222222222222222222222222222",
+            ]),
+            &Value::Null,
+            &registry,
+        )
+        .unwrap();
+
+        assert_eq!(filters.len(), 1);
+        assert_eq!(
+            filters[0].contract_ids.as_deref().unwrap(),
+            &[
+                format!("{}{}", "0".repeat(24), "1".repeat(40)),
+                format!("{}{}", "0".repeat(24), "2".repeat(40)),
+            ]
+        );
+
+        let single = build_event_filters(&mapper, 
+            &serde_json::json!("0x1111111111111111111111111111111111111111"),
+            &Value::Null,
+            &registry,
+        )
+        .unwrap();
+        assert_eq!(single[0].contract_ids.as_ref().unwrap().len(), 1);
+
+        assert!(build_event_filters(&mapper, &Value::Null, &Value::Null, &registry).unwrap().is_empty());
+        assert!(build_event_filters(&mapper, &serde_json::json!(42), &Value::Null, &registry).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_block_by_number_uses_horizon_header() {
+        let ledger_hash = "ab".repeat(32);
+        let url = spawn_mock_server(vec![
+            ("POST /", 200, latest_ledger_response(1000)),
+            ("GET /fee_stats", 200, r#"{"last_ledger_base_fee": "100"}"#.to_string()),
+            (
+                "GET /ledgers/900 ",
+                200,
+                serde_json::json!({ "sequence": 900, "hash": ledger_hash, "closed_at": "2024-01-15T12:30:45Z" })
+                    .to_string(),
+            ),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url(&url);
+        let block_hashes = BlockHashIndex::new(client.network_passphrase(), None);
+
+        let params = [Value::String("0x384".to_string()), Value::Bool(false)];
+        let block = get_block_by_number(&client, &test_config(), &block_hashes, &params)
+            .await
+            .unwrap();
+
+        // 2024-01-15T12:30:45Z
+        assert_eq!(block["timestamp"], format!("0x{:x}", 1_705_321_845u64));
+        assert_eq!(block["number"], "0x384");
+        // A past block carries its real ledger hash too
+        assert_eq!(block["mixHash"], format!("0x{}", ledger_hash));
+        assert_eq!(block["hash"], ledger_to_block_hash(client.network_passphrase(), 900));
+
+        // Some clients send the block number as a JSON integer
+        let block = get_block_by_number(&client, &test_config(), &block_hashes, &[serde_json::json!(900), Value::Bool(false)])
+            .await
+            .unwrap();
+        assert_eq!(block["number"], "0x384");
+        assert_eq!(block["timestamp"], format!("0x{:x}", 1_705_321_845u64));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_by_hash_resolves_the_ledger() {
+        let ledger_hash = "cd".repeat(32);
+        let url = spawn_mock_server(vec![
+            ("POST /", 200, latest_ledger_response(1000)),
+            ("GET /fee_stats", 200, r#"{"last_ledger_base_fee": "100"}"#.to_string()),
+            (
+                "GET /ledgers/900 ",
+                200,
+                serde_json::json!({ "sequence": 900, "hash": ledger_hash, "closed_at": "2024-01-15T12:30:45Z" })
+                    .to_string(),
+            ),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url(&url);
+        let block_hashes = BlockHashIndex::new(client.network_passphrase(), None);
+
+        let hash = ledger_to_block_hash(client.network_passphrase(), 900);
+        let block = get_block_by_hash(&client, &test_config(), &block_hashes, &[Value::String(hash.clone())])
+            .await
+            .unwrap();
+        assert_eq!(block["number"], "0x384");
+        assert_eq!(block["hash"], hash);
+        assert_eq!(block["mixHash"], format!("0x{}", ledger_hash));
+        assert_eq!(block["timestamp"], format!("0x{:x}", 1_705_321_845u64));
+
+        // Unknown hashes and blocks past the latest ledger are null, not the latest block
+        for unknown in [format!("0x{}", "0".repeat(64)), ledger_to_block_hash(client.network_passphrase(), 1001)] {
+            let block = get_block_by_hash(&client, &test_config(), &block_hashes, &[Value::String(unknown)])
+                .await
+                .unwrap();
+            assert!(block.is_null());
+        }
+
+        let err = get_block_by_hash(&client, &test_config(), &block_hashes, &[]).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+    }
+
+    #[tokio::test]
+    async fn test_get_block_by_number_falls_back_to_estimate() {
+        let url = spawn_mock_server(vec![
+            ("POST /", 200, latest_ledger_response(1000)),
+            ("GET /ledgers/", 404, r#"{"status": 404}"#.to_string()),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_horizon_url(&url);
+
+        let block_hashes = BlockHashIndex::new(client.network_passphrase(), None);
+        let block = get_block_by_number(&client, &test_config(), &block_hashes, &[Value::String("0x3de".to_string())])
+            .await
+            .unwrap();
+
+        // 10 ledgers behind latest at ~5 seconds each
+        let timestamp = u64::from_str_radix(
+            block["timestamp"].as_str().unwrap().trim_start_matches("0x"),
+            16,
+        )
+        .unwrap();
+        let expected = chrono::Utc::now().timestamp() as u64 - 50;
+        assert!(timestamp.abs_diff(expected) <= 5);
+    }
+
+    fn events_response(count: usize) -> String {
+        use base64::Engine;
+        let encode = |v: &ScVal| {
+            base64::engine::general_purpose::STANDARD.encode(v.to_xdr())
+        };
+        let topic = encode(&ScVal::Symbol("ping".to_string()));
+        let value = encode(&ScVal::Void);
+        let events: Vec<Value> = (0..count)
+            .map(|i| {
+                serde_json::json!({
+                    "type": "contract",
+                    "ledger": 995,
+                    "contractId": format!("{:064x}", 0x11),
+                    "id": format!("0004273492060631040-{:010}", i),
+                    "topic": [topic],
+                    "value": value,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {"events": events, "latestLedger": 1000},
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_get_logs_rejects_over_wide_range() {
+        let mapper = DefaultAddressMapper::new();
+        let url = spawn_mock_server(vec![("POST /", 200, latest_ledger_response(1000))]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let config = Config { max_block_range: 100, ..test_config() };
+
+        let filter = serde_json::json!({"fromBlock": "0x1", "toBlock": "latest"});
+        let err = get_logs(&client, &config, &mapper, &AbiRegistry::new(), &[filter]).await.unwrap_err();
+        let error = crate::error::to_error_object(&err);
+        assert_eq!(error.code(), crate::error::LIMIT_EXCEEDED_CODE);
+        assert_eq!(error.message(), "block range too wide: 1000 blocks requested, maximum is 100");
+
+        let filter = serde_json::json!({"fromBlock": "0x385", "toBlock": "latest"});
+        assert!(get_logs(&client, &config, &mapper, &AbiRegistry::new(), &[filter]).await.is_ok());
+
+        // Integer block numbers are read as numbers, not as "latest"
+        let filter = serde_json::json!({"fromBlock": 1, "toBlock": 1000});
+        let err = get_logs(&client, &config, &mapper, &AbiRegistry::new(), &[filter]).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::LIMIT_EXCEEDED_CODE);
+        let filter = serde_json::json!({"fromBlock": 901, "toBlock": 1000});
+        assert!(get_logs(&client, &config, &mapper, &AbiRegistry::new(), &[filter]).await.is_ok());
+
+        let filter = serde_json::json!({"fromBlock": "0x3e8", "toBlock": "0x3e7"});
+        let err = get_logs(&client, &config, &mapper, &AbiRegistry::new(), &[filter]).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+    }
+
+    #[tokio::test]
+    async fn test_get_logs_rejects_over_large_result_set() {
+        let mapper = DefaultAddressMapper::new();
+        let url = spawn_mock_server(vec![
+            ("rpc:getEvents", 200, events_response(3)),
+            ("POST /", 200, latest_ledger_response(1000)),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let filter = serde_json::json!({"fromBlock": "0x3e0"});
+
+        let config = Config { max_log_results: 3, ..test_config() };
+        let logs = get_logs(&client, &config, &mapper, &AbiRegistry::new(), std::slice::from_ref(&filter)).await.unwrap();
+        assert_eq!(logs.as_array().unwrap().len(), 3);
+
+        let config = Config { max_log_results: 2, ..test_config() };
+        let err = get_logs(&client, &config, &mapper, &AbiRegistry::new(), &[filter]).await.unwrap_err();
+        let error = crate::error::to_error_object(&err);
+        assert_eq!(error.code(), crate::error::LIMIT_EXCEEDED_CODE);
+        assert_eq!(error.message(), "query returned more than 2 results");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_params_are_invalid_params() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::error::{to_error_object, INVALID_PARAMS_CODE};
+
+        let client = SorobanClient::new("http://127.0.0.1:1", "Test SDF Network ; September 2015");
+        let config = test_config();
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = PendingTxStore::new();
+        let to = "0x2222222222222222222222222222222222222222";
+
+        let cases = [
+            (serde_json::json!({ "input": "0xa9059cbb" }), "eth_call: 'to' field is required and must be a hex address"),
+            (serde_json::json!({ "to": "0x2222", "input": "0xa9059cbb" }), "eth_call: 'to' field is required and must be a hex address"),
+            (serde_json::json!({ "to": to, "input": "0xzz" }), "eth_call: Invalid calldata hex in input"),
+        ];
+        for (call_obj, message) in cases {
+            let err = call(&client, &config, &mapper, &registry, &stats, &pending, &[call_obj]).await.unwrap_err();
+            let error = to_error_object(&err);
+            assert_eq!(error.code(), INVALID_PARAMS_CODE);
+            assert!(error.message().starts_with(message), "{}", error.message());
+        }
+
+        let pending = Arc::new(PendingTxStore::new());
+        let err = send_raw_transaction(&client, &config, &mapper, &registry, &stats, &pending, &[Value::String("0xzz".into())])
+            .await
+            .unwrap_err();
+        assert_eq!(to_error_object(&err).code(), INVALID_PARAMS_CODE);
+
+        let err = get_balance(&client, &test_config(), &mapper, &pending, &[Value::String("not an address".into())]).await.unwrap_err();
+        let error = to_error_object(&err);
+        assert_eq!(error.code(), INVALID_PARAMS_CODE);
+        assert_eq!(error.message(), "eth_getBalance: address is required and must be a hex address");
+    }
+
+    #[tokio::test]
+    async fn test_error_classes_map_to_codes() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::error::{to_error_object, INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE};
+
+        let url = spawn_mock_server(vec![("POST /", 502, "bad gateway".to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+
+        // A missing parameter is the caller's fault
+        let err = get_code(&client, &mapper, &[]).await.unwrap_err();
+        let error = to_error_object(&err);
+        assert_eq!(error.code(), INVALID_PARAMS_CODE);
+        assert_eq!(error.message(), "eth_getCode: address is required and must be a hex address");
+
+        let (registry, stats) = (AbiRegistry::new(), TranslationStats::default());
+        let err = call(&client, &test_config(), &mapper, &registry, &stats, &PendingTxStore::new(), &[serde_json::json!({})])
+            .await
+            .unwrap_err();
+        assert_eq!(to_error_object(&err).code(), INVALID_PARAMS_CODE);
+
+        // An upstream failure is ours
+        let err = block_number(&client).await.unwrap_err();
+        let error = to_error_object(&err);
+        assert_eq!(error.code(), INTERNAL_ERROR_CODE);
+        assert!(error.message().starts_with("Soroban RPC HTTP error: 502"));
+    }
+
+    #[tokio::test]
+    async fn test_get_logs_topic_wildcards_and_alternatives() {
+        let mapper = DefaultAddressMapper::new();
+        use crate::emulator::event_signature_to_topic;
+        use crate::translator::abi::{AbiEntry, AbiParam};
+        use crate::translator::scval::StellarAddress;
+        use base64::Engine;
+
+        let token = "0x1111111111111111111111111111111111111111";
+        let registry = AbiRegistry::new();
+        let param = |name: &str, param_type: &str, indexed: bool| AbiParam {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            indexed,
+            components: None,
+            internal_type: None,
+        };
+        registry
+            .register_contract(
+                token,
+                &[AbiEntry {
+                    entry_type: "event".to_string(),
+                    name: Some("Transfer".to_string()),
+                    inputs: vec![param("from", "address", true), param("to", "address", true), param("value", "uint256", false)],
+                    outputs: vec![],
+                    state_mutability: None,
+                }],
+            )
+            .unwrap();
+
+        let encode = |v: &ScVal| base64::engine::general_purpose::STANDARD.encode(v.to_xdr());
+        let account = |byte: u8| {
+            let mut key = [0u8; 32];
+            key[12..].copy_from_slice(&[byte; 20]);
+            ScVal::Address(StellarAddress::Account(key))
+        };
+        let word = |byte: u8| format!("0x{}{}", "0".repeat(24), format!("{:02x}", byte).repeat(20));
+        let events: Vec<Value> = [0xaa, 0xcc, 0xdd]
+            .iter()
+            .enumerate()
+            .map(|(i, from)| {
+                serde_json::json!({
+                    "type": "contract",
+                    "ledger": 995,
+                    "contractId": format!("{:0>64}", &token[2..]),
+                    "id": format!("0004273492060631040-{:010}", i),
+                    "topic": [encode(&ScVal::Symbol("transfer".into())), encode(&account(*from)), encode(&account(0xbb))],
+                    "value": encode(&ScVal::U256([0, 0, 0, 1])),
+                })
+            })
+            .collect();
+        let response = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": {"events": events, "latestLedger": 1000}});
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![
+            ("rpc:getEvents", 200, response.to_string()),
+            ("POST /", 200, latest_ledger_response(1000)),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        let transfer = event_signature_to_topic("Transfer(address,address,uint256)");
+
+        // Any event, sent by either of two accounts
+        let filter = serde_json::json!({
+            "fromBlock": "0x3e0",
+            "address": token,
+            "topics": [null, [word(0xaa), word(0xcc)]],
+        });
+        let logs = get_logs(&client, &test_config(), &mapper, &registry, std::slice::from_ref(&filter)).await.unwrap();
+        let senders: Vec<&str> = logs.as_array().unwrap().iter().map(|l| l["topics"][1].as_str().unwrap()).collect();
+        assert_eq!(senders, [word(0xaa), word(0xcc)]);
+
+        // A wildcard first topic cannot narrow getEvents
+        let filters = build_event_filters(&mapper, &filter["address"], &filter["topics"], &registry).unwrap();
+        assert_eq!(filters[0].topics, None);
+
+        // A Transfer sent by either account: narrowed to the event's symbol
+        let filter = serde_json::json!({
+            "fromBlock": "0x3e0",
+            "address": token,
+            "topics": [transfer, [word(0xaa), word(0xcc)]],
+        });
+        let logs = get_logs(&client, &test_config(), &mapper, &registry, std::slice::from_ref(&filter)).await.unwrap();
+        assert_eq!(logs.as_array().unwrap().len(), 2);
+
+        let symbol = |name: &str| encode(&ScVal::Symbol(name.into()));
+        let expected = vec![
+            vec![symbol("Transfer"), "*".to_string(), "*".to_string()],
+            vec![symbol("transfer"), "*".to_string(), "*".to_string()],
+        ];
+        let filters = build_event_filters(&mapper, &filter["address"], &filter["topics"], &registry).unwrap();
+        assert_eq!(filters[0].topics.as_ref(), Some(&expected));
+
+        let requests = requests.lock().unwrap();
+        let body = requests.iter().rev().find(|r| r.contains("getEvents")).unwrap();
+        let body: Value = serde_json::from_str(&body[body.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+        assert_eq!(body["params"]["filters"][0]["topics"], serde_json::json!(expected));
+
+        // An unknown signature is left to the exact match after translation
+        let unknown = serde_json::json!([event_signature_to_topic("Approval(address,address,uint256)")]);
+        let filters = build_event_filters(&mapper, &filter["address"], &unknown, &registry).unwrap();
+        assert_eq!(filters[0].topics, None);
     }
 }
@@ -1,3 +1,4 @@
 pub mod eth;
 pub mod net;
+pub mod tva;
 pub mod web3;
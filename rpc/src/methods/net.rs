@@ -3,6 +3,7 @@ use serde_json::Value;
 use tracing::debug;
 
 use crate::config::Config;
+use crate::health::UpstreamStatus;
 
 /// Handler for net_version
 /// Returns the network version (chain ID as decimal string).
@@ -19,7 +20,8 @@ pub async fn listening() -> Result<Value> {
 }
 
 /// Handler for net_peerCount
-/// Returns the number of peers. TVA connects to Soroban RPC, so effectively 1.
-pub async fn peer_count() -> Result<Value> {
-    Ok(Value::String("0x1".to_string()))
+/// Returns the number of peers. TVA connects to Soroban RPC, so 1 while the
+/// upstream is healthy and 0 when the last health check failed.
+pub async fn peer_count(upstream: &UpstreamStatus) -> Result<Value> {
+    Ok(Value::String(format!("0x{:x}", upstream.peer_count())))
 }
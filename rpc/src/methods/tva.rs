@@ -0,0 +1,932 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use tracing::{debug, error, info, warn};
+
+use crate::config::Config;
+use crate::emulator::pending::CONFIRMATION_POLL_INTERVAL;
+use crate::emulator::{spawn_confirmation_watch, PendingTx, PendingTxStore};
+use crate::error::TvaError;
+use crate::methods::eth::{
+    address_param, check_chain_id, evm_address_to_stellar_account, get_source_account_id, simulate_contract_call,
+    state_sequence, stellar_hash_to_evm_hash,
+};
+use crate::stellar::{SorobanClient, StellarSigner};
+use crate::translator::abi::{parse_selector, AbiEntry};
+use crate::translator::address_map::AddressMapper;
+use crate::translator::contract_data::{
+    contract_data_key, decode_contract_data, instance_storage_value, StorageDurability,
+};
+use crate::translator::scval::{decode_scval_xdr_to_abi, json_to_scval, scval_from_json, ScVal};
+use crate::translator::tx::{
+    build_soroban_create_contract_tx, build_soroban_upload_wasm_tx, contract_id_from_address, decode_calldata,
+    decode_raw_transaction, decode_raw_transaction_hex, decode_strkey_checked, encode_strkey, invokes_contract,
+    stroops_to_wei, STRKEY_VERSION_CONTRACT,
+};
+use crate::translator::{AbiRegistry, TranslationStats};
+
+/// getTransaction polls to wait for a WASM upload before creating the contract.
+const UPLOAD_CONFIRMATION_ATTEMPTS: u32 = 30;
+
+/// Most addresses one tva_getBalances call may query.
+const MAX_BALANCE_BATCH: usize = 256;
+
+/// Horizon balance lookups tva_getBalances keeps in flight at once.
+const BALANCE_CONCURRENCY: usize = 8;
+
+/// Handler for tva_getTranslationStats
+/// How calls have fared in translation since startup: calldata resolved
+/// through a registered ABI or passed on raw, simulations and how many
+/// failed, and deployments attempted.
+pub async fn get_translation_stats(stats: &TranslationStats) -> Result<Value> {
+    Ok(stats.summary())
+}
+
+/// Handler for tva_translateCalldata
+/// Shows how calldata for a contract is decoded: the selector, the function
+/// it resolves to in the ABI registry (null if none) and the ScVal arguments
+/// it translates to.
+pub async fn translate_calldata(
+    abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    params: &[Value],
+) -> Result<Value> {
+    let address = params
+        .first()
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TvaError::InvalidParams("tva_translateCalldata: contract address is required".to_string()))?;
+    let calldata_hex = params
+        .get(1)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TvaError::InvalidParams("tva_translateCalldata: calldata hex is required".to_string()))?;
+
+    let calldata = hex::decode(calldata_hex.strip_prefix("0x").unwrap_or(calldata_hex))
+        .map_err(|e| TvaError::InvalidParams(format!("tva_translateCalldata: invalid calldata hex: {}", e)))?;
+
+    let decoded = decode_calldata(&calldata, address, abi_registry)?;
+    stats.record_decoded(decoded.abi_resolved);
+
+    Ok(json!({
+        "selector": format!("0x{}", hex::encode(decoded.selector)),
+        "functionName": decoded.function_name,
+        "args": decoded.scval_params.iter().map(|arg| arg.to_json()).collect::<Vec<_>>(),
+    }))
+}
+
+/// Handler for tva_setFunctionAlias
+/// Takes `[selector, symbol]`: calls with the selector (4 hex bytes or a
+/// signature) then invoke the Soroban function `symbol` instead of the ABI
+/// name. A null symbol removes the alias. Returns true. Requires
+/// `ENABLE_FUNCTION_ALIAS_RPC`, since an alias reroutes every caller's calls.
+pub async fn set_function_alias(config: &Config, abi_registry: &AbiRegistry, params: &[Value]) -> Result<Value> {
+    if !config.enable_function_alias_rpc {
+        return Err(TvaError::MethodUnavailable(
+            "tva_setFunctionAlias is disabled; configure aliases with FUNCTION_ALIASES, \
+             or set ENABLE_FUNCTION_ALIAS_RPC=true to change them over RPC"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let selector = params
+        .first()
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TvaError::InvalidParams("tva_setFunctionAlias: selector is required".to_string()))?;
+    let selector = parse_selector(selector).map_err(|e| TvaError::InvalidParams(e.to_string()))?;
+
+    match params.get(1) {
+        Some(Value::String(symbol)) => {
+            abi_registry
+                .set_function_alias(selector, symbol)
+                .map_err(|e| TvaError::InvalidParams(e.to_string()))?;
+            info!("tva_setFunctionAlias: 0x{} -> {}", hex::encode(selector), symbol);
+        }
+        Some(Value::Null) => {
+            abi_registry.remove_function_alias(&selector);
+            info!("tva_setFunctionAlias: 0x{} alias removed", hex::encode(selector));
+        }
+        _ => {
+            return Err(TvaError::InvalidParams(
+                "tva_setFunctionAlias: Soroban function name (or null) is required".to_string(),
+            )
+            .into())
+        }
+    }
+    Ok(Value::Bool(true))
+}
+
+/// Handler for tva_simulateRawTransaction
+/// Runs a signed transaction through the same decode, translate and simulate
+/// steps as eth_sendRawTransaction, but returns the simulation instead of
+/// submitting. A reverting simulation is reported with `success: false`.
+pub async fn simulate_raw_transaction(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &Arc<PendingTxStore>,
+    params: &[Value],
+) -> Result<Value> {
+    let raw_tx_hex = params
+        .first()
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| TvaError::InvalidParams("tva_simulateRawTransaction: raw transaction hex is required".to_string()))?;
+
+    let raw_tx_bytes = decode_raw_transaction_hex(raw_tx_hex)
+        .map_err(|e| TvaError::InvalidParams(format!("tva_simulateRawTransaction: {}", e)))?;
+
+    let decoded_tx = decode_raw_transaction(&raw_tx_bytes)
+        .map_err(|e| TvaError::InvalidParams(format!("tva_simulateRawTransaction: {}", e)))?;
+    check_chain_id(decoded_tx.chain_id, config)?;
+
+    let to = decoded_tx
+        .to
+        .ok_or_else(|| anyhow!("Only contract calls can be simulated, not deployments"))?;
+    let to_hex = format!("0x{}", hex::encode(to));
+    if !invokes_contract(&decoded_tx.data, &to_hex, abi_registry) {
+        return Err(anyhow!("Only contract calls can be simulated, not value transfers"));
+    }
+
+    let source_account = get_source_account_id(config)?;
+    let call = simulate_contract_call(
+        client,
+        mapper,
+        abi_registry,
+        stats,
+        pending,
+        &source_account,
+        &to_hex,
+        &decoded_tx.data,
+    )
+    .await?;
+    let simulation = &call.simulation;
+
+    info!(
+        "tva_simulateRawTransaction: {} on {} -> {}",
+        call.function_name,
+        to_hex,
+        simulation.error.as_deref().unwrap_or("success")
+    );
+
+    // The return value, ABI-encoded when the contract's ABI is registered
+    let return_xdr = simulation
+        .results
+        .as_ref()
+        .and_then(|results| results.first())
+        .and_then(|result| result.xdr.clone());
+    let return_value = match (&return_xdr, abi_registry.lookup_function(&to_hex, &call.selector)) {
+        (Some(xdr), Some(info)) => decode_scval_xdr_to_abi(mapper, xdr, &info.outputs)
+            .ok()
+            .map(|abi| format!("0x{}", hex::encode(abi))),
+        _ => None,
+    };
+
+    let args: Vec<Value> = call.scval_params.iter().map(|arg| arg.to_json()).collect();
+
+    Ok(json!({
+        "success": simulation.error.is_none(),
+        "error": simulation.error,
+        "hash": format!("0x{}", hex::encode(decoded_tx.tx_hash)),
+        "contractId": call.contract_id,
+        "functionName": call.function_name,
+        "args": args,
+        "minResourceFee": simulation.min_resource_fee,
+        "returnValue": return_value,
+        "returnValueXdr": return_xdr,
+        "latestLedger": simulation.latest_ledger,
+    }))
+}
+
+/// Handler for tva_deployContract
+/// Deploys a contract from the server account without going through EVM
+/// transaction encoding. Takes `{ wasm, constructorArgs, abi }`: uploads the
+/// WASM and waits for it to apply, then creates a contract from it, calling
+/// its constructor with `constructorArgs` converted by the ABI's
+/// `constructor` entry. The ABI is registered for the new contract's EVM
+/// address. Returns `{ txHash, contractAddress, contractId }`, where `txHash`
+/// is the creation, tracked like any other submission. Requires
+/// `ENABLE_MANAGED_SIGNING`, since the server's key signs and pays for both.
+pub async fn deploy_contract(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    abi_registry: &AbiRegistry,
+    stats: &TranslationStats,
+    pending: &Arc<PendingTxStore>,
+    params: &[Value],
+) -> Result<Value> {
+    if !config.enable_managed_signing {
+        return Err(TvaError::MethodUnavailable(
+            "tva_deployContract is disabled; deploy with a locally signed transaction, \
+             or set ENABLE_MANAGED_SIGNING=true to deploy from the server's account"
+                .to_string(),
+        )
+        .into());
+    }
+
+    let request = params
+        .first()
+        .filter(|v| v.is_object())
+        .ok_or_else(|| TvaError::InvalidParams("tva_deployContract: deployment object is required".to_string()))?;
+
+    let wasm = request["wasm"]
+        .as_str()
+        .and_then(|s| hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok())
+        .filter(|wasm| !wasm.is_empty())
+        .ok_or_else(|| TvaError::InvalidParams("tva_deployContract: 'wasm' must be non-empty hex".to_string()))?;
+    let abi: Vec<AbiEntry> = match &request["abi"] {
+        Value::Null => Vec::new(),
+        abi => serde_json::from_value(abi.clone())
+            .map_err(|e| TvaError::InvalidParams(format!("tva_deployContract: invalid 'abi': {}", e)))?,
+    };
+    let constructor_args = constructor_scvals(&request["constructorArgs"], &abi)
+        .map_err(|e| TvaError::InvalidParams(format!("tva_deployContract: {}", e)))?;
+
+    let signer = StellarSigner::from_secret(&config.stellar_secret_key)?;
+    let source_account = signer.account_id();
+    let passphrase = client.network_passphrase();
+
+    // Upload, then create, on consecutive sequences after any in-flight submissions
+    let confirmed_sequence = client.get_account_sequence(&source_account).await?;
+    let sequence = state_sequence(pending, &source_account, confirmed_sequence, true) + 1;
+
+    // The salt ties the contract ID to this WASM and sequence
+    let wasm_hash: [u8; 32] = Sha256::digest(&wasm).into();
+    let salt: [u8; 32] = Keccak256::new()
+        .chain_update(wasm_hash)
+        .chain_update(sequence.to_be_bytes())
+        .finalize()
+        .into();
+    let contract_id = contract_id_from_address(passphrase, &signer.public_key(), &salt);
+    let contract_address = mapper.contract_to_evm(&contract_id);
+
+    info!(
+        "tva_deployContract: {} bytes of WASM as {} ({})",
+        wasm.len(),
+        contract_address,
+        encode_strkey(STRKEY_VERSION_CONTRACT, &contract_id)
+    );
+    stats.record_deployment();
+
+    let upload_hash = submit_deployment_step(client, &signer, pending, sequence, |fee| {
+        build_soroban_upload_wasm_tx(&source_account, sequence, &wasm, passphrase, fee)
+    })
+    .await?;
+    let upload_evm_hash = stellar_hash_to_evm_hash(&upload_hash);
+    let upload = client.wait_for_transaction(&upload_hash, UPLOAD_CONFIRMATION_ATTEMPTS).await;
+    if let Ok(response) = &upload {
+        pending.resolve(&upload_evm_hash, response);
+    }
+    upload.map_err(|e| TvaError::Rejected(format!("WASM upload failed: {}", e)))?;
+
+    let create_hash = submit_deployment_step(client, &signer, pending, sequence + 1, |fee| {
+        build_soroban_create_contract_tx(&source_account, sequence + 1, &wasm_hash, &salt, &constructor_args, passphrase, fee)
+    })
+    .await?;
+    let tx_hash = stellar_hash_to_evm_hash(&create_hash);
+    spawn_confirmation_watch(client.clone(), pending.clone(), tx_hash.clone(), CONFIRMATION_POLL_INTERVAL);
+
+    if !abi.is_empty() {
+        abi_registry.register_contract(&contract_address, &abi)?;
+    }
+
+    Ok(json!({
+        "txHash": tx_hash,
+        "contractAddress": contract_address,
+        "contractId": encode_strkey(STRKEY_VERSION_CONTRACT, &contract_id),
+    }))
+}
+
+/// Handler for tva_getContractData
+/// Reads a contract storage entry by its Soroban key. Takes `[contract,
+/// key, durability]`: the contract as an EVM address or C... strkey, the key
+/// as a tagged ScVal (e.g. `{"symbol": "Admin"}`) and the durability
+/// `persistent` (default), `temporary` or `instance`. Returns the value as
+/// rendered JSON with its XDR and ledger bounds, or null if nothing is stored.
+pub async fn get_contract_data(client: &SorobanClient, mapper: &dyn AddressMapper, params: &[Value]) -> Result<Value> {
+    let contract = params
+        .first()
+        .and_then(|v| v.as_str())
+        .and_then(|address| {
+            if address.starts_with('C') {
+                decode_strkey_checked(STRKEY_VERSION_CONTRACT, address).ok()
+            } else {
+                mapper.evm_to_contract(address)
+            }
+        })
+        .ok_or_else(|| {
+            TvaError::InvalidParams("tva_getContractData: contract must be a hex address or C... strkey".to_string())
+        })?;
+    let key = params
+        .get(1)
+        .ok_or_else(|| TvaError::InvalidParams("tva_getContractData: storage key is required".to_string()))
+        .and_then(|key| {
+            scval_from_json(key).map_err(|e| TvaError::InvalidParams(format!("tva_getContractData: key: {}", e)))
+        })?;
+    let durability: StorageDurability = match params.get(2) {
+        None | Some(Value::Null) => StorageDurability::Persistent,
+        Some(durability) => durability
+            .as_str()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|e| TvaError::InvalidParams(format!("tva_getContractData: {}", e)))?,
+    };
+
+    let ledger_key = contract_data_key(&contract, &key, durability);
+    debug!("tva_getContractData: {:?} entry {}", durability, ledger_key);
+
+    let response = client.get_ledger_entries(vec![ledger_key]).await?;
+    let Some(entry) = response.entries.unwrap_or_default().into_iter().next() else {
+        return Ok(Value::Null);
+    };
+    let stored = decode_contract_data(&entry.xdr)?;
+    let value = match durability {
+        StorageDurability::Instance => match instance_storage_value(&stored, &key) {
+            Some(value) => value,
+            None => return Ok(Value::Null),
+        },
+        _ => stored,
+    };
+
+    Ok(json!({
+        "value": value.to_json(),
+        "xdr": base64::engine::general_purpose::STANDARD.encode(value.to_xdr()),
+        "lastModifiedLedgerSeq": entry.last_modified_ledger_seq,
+        "liveUntilLedgerSeq": entry.live_until_ledger_seq,
+    }))
+}
+
+/// Handler for tva_getBalances
+/// Takes `[addresses]` and returns their XLM balances as hex wei, in the
+/// same order, looking them up on Horizon a few at a time. An address whose
+/// lookup fails reports 0 rather than failing the batch.
+pub async fn get_balances(
+    client: &SorobanClient,
+    config: &Config,
+    mapper: &dyn AddressMapper,
+    params: &[Value],
+) -> Result<Value> {
+    let addresses = params
+        .first()
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| TvaError::InvalidParams("tva_getBalances: an array of addresses is required".to_string()))?;
+    if addresses.len() > MAX_BALANCE_BATCH {
+        return Err(TvaError::LimitExceeded(format!(
+            "tva_getBalances: {} addresses requested, maximum is {}",
+            addresses.len(),
+            MAX_BALANCE_BATCH
+        ))
+        .into());
+    }
+    let accounts = addresses
+        .iter()
+        .map(|address| evm_address_to_stellar_account(mapper, address_param("tva_getBalances", Some(address))?))
+        .collect::<Result<Vec<_>>>()?;
+
+    debug!("tva_getBalances: {} addresses", accounts.len());
+
+    let mut balances: Vec<(usize, u64)> = stream::iter(accounts.into_iter().enumerate())
+        .map(|(index, account)| async move {
+            let stroops = client.get_xlm_balance(&account).await.unwrap_or_else(|e| {
+                warn!("tva_getBalances: balance of {} unavailable: {}", account, e);
+                0
+            });
+            (index, stroops)
+        })
+        .buffer_unordered(BALANCE_CONCURRENCY)
+        .collect()
+        .await;
+    balances.sort_unstable_by_key(|(index, _)| *index);
+
+    Ok(Value::Array(
+        balances
+            .into_iter()
+            .map(|(_, stroops)| Value::String(format!("0x{:x}", stroops_to_wei(stroops, config.xlm_wei_factor))))
+            .collect(),
+    ))
+}
+
+/// Constructor arguments as ScVals, typed by the ABI's `constructor` entry.
+fn constructor_scvals(args: &Value, abi: &[AbiEntry]) -> Result<Vec<ScVal>> {
+    let args = match args {
+        Value::Null => &Vec::new(),
+        Value::Array(args) => args,
+        other => return Err(anyhow!("'constructorArgs' must be an array, got {}", other)),
+    };
+    let inputs = abi
+        .iter()
+        .find(|entry| entry.entry_type == "constructor")
+        .map(|entry| entry.inputs.as_slice())
+        .unwrap_or_default();
+    if args.len() != inputs.len() {
+        return Err(anyhow!(
+            "constructor takes {} arguments, got {}",
+            inputs.len(),
+            args.len()
+        ));
+    }
+
+    args.iter()
+        .zip(inputs)
+        .map(|(arg, param)| json_to_scval(arg, param).map_err(|e| anyhow!("constructor argument '{}': {}", param.name, e)))
+        .collect()
+}
+
+/// Simulate the transaction `build` makes for a fee, rebuild it with the
+/// simulated resource fee, sign it and submit it as `sequence`, tracking it in
+/// the pending store. Returns the Stellar transaction hash.
+async fn submit_deployment_step(
+    client: &SorobanClient,
+    signer: &StellarSigner,
+    pending: &PendingTxStore,
+    sequence: u64,
+    build: impl Fn(u32) -> Result<String>,
+) -> Result<String> {
+    let simulation = client.simulate_transaction(&build(100)?).await?;
+    if let Some(error) = &simulation.error {
+        error!("Deployment simulation failed: {}", error);
+        return Err(TvaError::reverted(error.as_str()).into());
+    }
+
+    let resource_fee: u32 = simulation
+        .min_resource_fee
+        .as_ref()
+        .and_then(|f| f.parse::<u32>().ok())
+        .unwrap_or(10000);
+    let fee = resource_fee.saturating_add(1000); // Add buffer
+    let (tx_xdr, _) = signer.sign_envelope(&build(fee)?, client.network_passphrase())?;
+
+    let send_result = client.send_transaction(&tx_xdr).await?;
+    match send_result.status.as_str() {
+        "PENDING" | "SUCCESS" => {
+            let stellar_hash = send_result.hash.unwrap_or_default();
+            pending.insert(
+                &stellar_hash_to_evm_hash(&stellar_hash),
+                PendingTx::submitted(&stellar_hash, &signer.account_id(), sequence, u64::from(fee)),
+            );
+            Ok(stellar_hash)
+        }
+        status => {
+            let error_msg = send_result
+                .error_result_xdr
+                .unwrap_or_else(|| format!("submission status {}", status));
+            error!("Deployment submission failed: {}", error_msg);
+            Err(TvaError::Rejected(error_msg).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::address_map::DefaultAddressMapper;
+    use base64::Engine;
+    use crate::test_utils::spawn_mock_server;
+    use crate::translator::scval::ScVal;
+    use crate::translator::tx::{encode_strkey, STRKEY_VERSION_SEED};
+    use crate::translator::abi::AbiEntry;
+
+    const TO: &str = "0x2222222222222222222222222222222222222222";
+
+    fn config() -> Config {
+        Config::from_lookup(|key| match key {
+            "STELLAR_SECRET_KEY" => Some(encode_strkey(STRKEY_VERSION_SEED, &[5; 32])),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    fn managed_signing_config() -> Config {
+        Config { enable_managed_signing: true, ..config() }
+    }
+
+    /// A chain-protected legacy transaction calling `get(uint32)` with 5.
+    fn raw_call(config: &Config) -> String {
+        let mut data = AbiRegistry::compute_selector("get(uint32)").to_vec();
+        let mut arg = [0u8; 32];
+        arg[31] = 5;
+        data.extend_from_slice(&arg);
+
+        let mut stream = rlp::RlpStream::new_list(9);
+        stream.append(&0u64);
+        stream.append(&1u64);
+        stream.append(&100_000u64);
+        stream.append(&hex::decode(&TO[2..]).unwrap());
+        stream.append(&0u64);
+        stream.append(&data);
+        stream.append(&(config.tva_chain_id * 2 + 35));
+        stream.append(&vec![1u8; 32]);
+        stream.append(&vec![2u8; 32]);
+        format!("0x{}", hex::encode(stream.out()))
+    }
+
+    fn simulation_response(error: Option<&str>) -> String {
+        let result = match error {
+            Some(error) => json!({ "error": error, "latestLedger": 1000 }),
+            None => json!({
+                "results": [{
+                    "xdr": base64::engine::general_purpose::STANDARD.encode(ScVal::U32(9).to_xdr()),
+                }],
+                "minResourceFee": "5000",
+                "latestLedger": 1000,
+            }),
+        };
+        json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string()
+    }
+
+    fn registry() -> AbiRegistry {
+        let registry = AbiRegistry::new();
+        let abi: Vec<AbiEntry> = serde_json::from_value(json!([{
+            "type": "function",
+            "name": "get",
+            "inputs": [{ "name": "key", "type": "uint32" }],
+            "outputs": [{ "name": "", "type": "uint32" }],
+        }]))
+        .unwrap();
+        registry.register_contract(TO, &abi).unwrap();
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_translate_calldata() {
+        let registry = registry();
+        let stats = TranslationStats::default();
+        let calldata = format!("0x{}{:064x}", hex::encode(AbiRegistry::compute_selector("get(uint32)")), 5);
+
+        let result = translate_calldata(&registry, &stats, &[json!(TO), json!(calldata)]).await.unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "selector": format!("0x{}", hex::encode(AbiRegistry::compute_selector("get(uint32)"))),
+                "functionName": "get",
+                "args": ["5"],
+            })
+        );
+
+        // Without an ABI the arguments pass through as raw 32-byte words
+        let other = "0x3333333333333333333333333333333333333333";
+        let result = translate_calldata(&registry, &stats, &[json!(other), json!(calldata)]).await.unwrap();
+        assert_eq!(result["functionName"], Value::Null);
+        assert_eq!(result["args"], json!([format!("0x{:064x}", 5)]));
+
+        assert!(translate_calldata(&registry, &stats, &[json!(TO), json!("0x01")]).await.is_err());
+        assert!(translate_calldata(&registry, &stats, &[json!(TO)]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_function_alias_redirects_selector() {
+        let registry = registry();
+        let stats = TranslationStats::default();
+        let config = Config { enable_function_alias_rpc: true, ..config() };
+        let calldata = format!("0x{}{:064x}", hex::encode(AbiRegistry::compute_selector("get(uint32)")), 5);
+
+        let set = set_function_alias(&config, &registry, &[json!("get(uint32)"), json!("get_value")]).await.unwrap();
+        assert_eq!(set, true);
+        let result = translate_calldata(&registry, &stats, &[json!(TO), json!(calldata)]).await.unwrap();
+        assert_eq!(result["functionName"], "get_value");
+        assert_eq!(result["args"], json!(["5"]));
+
+        // The invocation names the aliased symbol
+        let decoded = decode_calldata(&hex::decode(&calldata[2..]).unwrap(), TO, &registry).unwrap();
+        let xdr = crate::translator::tx::build_soroban_invoke_tx(
+            &encode_strkey(crate::translator::tx::STRKEY_VERSION_ACCOUNT, &[1; 32]),
+            1,
+            &encode_strkey(STRKEY_VERSION_CONTRACT, &[2; 32]),
+            decoded.function_name.as_deref().unwrap(),
+            &decoded.scval_params,
+            "Test SDF Network ; September 2015",
+            100,
+        )
+        .unwrap();
+        use base64::Engine;
+        let envelope = base64::engine::general_purpose::STANDARD.decode(xdr).unwrap();
+        let symbol = |name: &str| [&(name.len() as u32).to_be_bytes()[..], name.as_bytes()].concat();
+        assert!(envelope.windows(13).any(|w| w == symbol("get_value")));
+        assert!(!envelope.windows(7).any(|w| w == symbol("get")));
+
+        // Selectors without an ABI entry are redirected too; null removes the alias
+        let selector = format!("0x{}", hex::encode(AbiRegistry::compute_selector("get(uint32)")));
+        let other = "0x3333333333333333333333333333333333333333";
+        let result = translate_calldata(&registry, &stats, &[json!(other), json!(calldata)]).await.unwrap();
+        assert_eq!(result["functionName"], "get_value");
+        set_function_alias(&config, &registry, &[json!(selector), Value::Null]).await.unwrap();
+        let result = translate_calldata(&registry, &stats, &[json!(TO), json!(calldata)]).await.unwrap();
+        assert_eq!(result["functionName"], "get");
+
+        for params in [vec![json!("0x12")], vec![json!("0x12345678"), json!("bad-name")], vec![json!("0x12345678")]] {
+            let err = set_function_alias(&config, &registry, &params).await.unwrap_err();
+            assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_function_alias_rpc_requires_opt_in() {
+        let registry = registry();
+        let selector = AbiRegistry::compute_selector("get(uint32)");
+
+        let err = set_function_alias(&config(), &registry, &[json!("get(uint32)"), json!("get_value")]).await.unwrap_err();
+        let obj = crate::error::to_error_object(&err);
+        assert_eq!(obj.code(), crate::error::METHOD_NOT_FOUND_CODE);
+        assert!(obj.message().contains("FUNCTION_ALIASES"), "{}", obj.message());
+        assert_eq!(registry.function_alias(&selector), None);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_raw_transaction_reports_translation() {
+        let mapper = DefaultAddressMapper::new();
+        let url = spawn_mock_server(vec![
+            ("GET /accounts/", 200, r#"{"sequence": "41"}"#.to_string()),
+            ("rpc:simulateTransaction", 200, simulation_response(None)),
+        ])
+        .await;
+        let config = config();
+        let client = SorobanClient::new(&url, &config.stellar_network_passphrase).with_horizon_url(&url);
+        let pending = Arc::new(PendingTxStore::new());
+        let stats = TranslationStats::default();
+
+        let params = [json!(raw_call(&config))];
+        let result = simulate_raw_transaction(&client, &config, &mapper, &registry(), &stats, &pending, &params)
+            .await
+            .unwrap();
+
+        assert_eq!(result["success"], true);
+        assert_eq!(result["functionName"], "get");
+        assert_eq!(result["args"], json!(["5"]));
+        assert_eq!(result["minResourceFee"], "5000");
+        assert_eq!(result["returnValue"], format!("0x{:064x}", 9));
+        // Nothing was submitted
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_simulate_raw_transaction_reports_revert() {
+        let mapper = DefaultAddressMapper::new();
+        let url = spawn_mock_server(vec![
+            ("GET /accounts/", 200, r#"{"sequence": "41"}"#.to_string()),
+            ("rpc:simulateTransaction", 200, simulation_response(Some("HostError: trapped"))),
+        ])
+        .await;
+        let config = config();
+        let client = SorobanClient::new(&url, &config.stellar_network_passphrase).with_horizon_url(&url);
+        let pending = Arc::new(PendingTxStore::new());
+        let stats = TranslationStats::default();
+
+        let params = [json!(raw_call(&config))];
+        let result = simulate_raw_transaction(&client, &config, &mapper, &registry(), &stats, &pending, &params)
+            .await
+            .unwrap();
+
+        assert_eq!(result["success"], false);
+        assert_eq!(result["error"], "HostError: trapped");
+        assert_eq!(result["functionName"], "get");
+        assert_eq!(result["returnValue"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_contract_uploads_then_creates() {
+        let mapper = DefaultAddressMapper::new();
+        let stellar_hash = "ab".repeat(32);
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![
+            ("GET /accounts/", 200, r#"{"sequence": "41"}"#.to_string()),
+            ("rpc:simulateTransaction", 200, simulation_response(None)),
+            (
+                "rpc:sendTransaction",
+                200,
+                json!({ "jsonrpc": "2.0", "id": 1, "result": { "status": "PENDING", "hash": stellar_hash } }).to_string(),
+            ),
+            (
+                "rpc:getTransaction",
+                200,
+                json!({ "jsonrpc": "2.0", "id": 1, "result": { "status": "SUCCESS", "ledger": 1001 } }).to_string(),
+            ),
+        ])
+        .await;
+        let config = managed_signing_config();
+        let client = SorobanClient::new(&url, &config.stellar_network_passphrase).with_horizon_url(&url);
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+
+        let wasm = b"\0asm\x01\0\0\0".to_vec();
+        let request = json!({
+            "wasm": format!("0x{}", hex::encode(&wasm)),
+            "constructorArgs": ["1000", "0x5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a5a"],
+            "abi": [
+                {
+                    "type": "constructor",
+                    "inputs": [{ "name": "supply", "type": "uint256" }, { "name": "owner", "type": "address" }],
+                },
+                {
+                    "type": "function",
+                    "name": "get",
+                    "inputs": [{ "name": "key", "type": "uint32" }],
+                    "outputs": [{ "name": "", "type": "uint32" }],
+                },
+            ],
+        });
+        let result = deploy_contract(&client, &config, &mapper, &registry, &stats, &pending, &[request]).await.unwrap();
+
+        assert_eq!(result["txHash"], format!("0x{}", stellar_hash));
+        let contract_address = result["contractAddress"].as_str().unwrap();
+        let contract_id = crate::translator::tx::decode_strkey_checked(
+            STRKEY_VERSION_CONTRACT,
+            result["contractId"].as_str().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(mapper.contract_to_evm(&contract_id), contract_address);
+
+        // The ABI is registered for the new contract
+        let selector = AbiRegistry::compute_selector("get(uint32)");
+        assert_eq!(registry.lookup_function(contract_address, &selector).unwrap().name, "get");
+
+        // An upload at sequence 42, then a creation at 43 with the constructor arguments
+        let envelopes: Vec<Vec<u8>> = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.contains("\"sendTransaction\""))
+            .map(|r| {
+                let body: Value = serde_json::from_str(&r[r.find("\r\n\r\n").unwrap() + 4..]).unwrap();
+                base64::engine::general_purpose::STANDARD
+                    .decode(body["params"]["transaction"].as_str().unwrap())
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(envelopes.len(), 2);
+        let sequence = |envelope: &[u8]| u64::from_be_bytes(envelope[44..52].try_into().unwrap());
+        let host_function = |envelope: &[u8]| u32::from_be_bytes(envelope[72..76].try_into().unwrap());
+        assert_eq!((sequence(&envelopes[0]), host_function(&envelopes[0])), (42, 2));
+        assert_eq!((sequence(&envelopes[1]), host_function(&envelopes[1])), (43, 3));
+        let supply = ScVal::U256([0, 0, 0, 1000]).to_xdr();
+        assert!(envelopes[1].windows(supply.len()).any(|w| w == supply.as_slice()));
+
+        // Both steps were tracked against the server account
+        let source = get_source_account_id(&config).unwrap();
+        assert_eq!(pending.pending_for(&source, 41).sequence(41), 43);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_contract_rejects_bad_requests() {
+        let mapper = DefaultAddressMapper::new();
+        let config = managed_signing_config();
+        let client = SorobanClient::new("http://127.0.0.1:1", &config.stellar_network_passphrase);
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+        let constructor = json!([{ "type": "constructor", "inputs": [{ "name": "supply", "type": "uint256" }] }]);
+
+        for (request, message) in [
+            (json!("0x00"), "deployment object is required"),
+            (json!({ "wasm": "0x" }), "'wasm' must be non-empty hex"),
+            (json!({ "wasm": "0x00", "abi": "nope" }), "invalid 'abi'"),
+            (json!({ "wasm": "0x00", "abi": constructor }), "constructor takes 1 arguments, got 0"),
+            (
+                json!({ "wasm": "0x00", "abi": constructor, "constructorArgs": ["lots"] }),
+                "constructor argument 'supply'",
+            ),
+        ] {
+            let err = deploy_contract(&client, &config, &mapper, &registry, &stats, &pending, &[request]).await.unwrap_err();
+            let obj = crate::error::to_error_object(&err);
+            assert_eq!(obj.code(), crate::error::INVALID_PARAMS_CODE);
+            assert!(obj.message().contains(message), "{}", obj.message());
+        }
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_contract_requires_managed_signing() {
+        let mapper = DefaultAddressMapper::new();
+        let config = config();
+        let client = SorobanClient::new("http://127.0.0.1:1", &config.stellar_network_passphrase);
+        let registry = AbiRegistry::new();
+        let stats = TranslationStats::default();
+        let pending = Arc::new(PendingTxStore::new());
+
+        let request = json!({ "wasm": "0x0061736d01000000" });
+        let err = deploy_contract(&client, &config, &mapper, &registry, &stats, &pending, &[request]).await.unwrap_err();
+        let obj = crate::error::to_error_object(&err);
+        assert_eq!(obj.code(), crate::error::METHOD_NOT_FOUND_CODE);
+        assert!(obj.message().contains("ENABLE_MANAGED_SIGNING"), "{}", obj.message());
+        assert!(pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_contract_data_reads_entry() {
+        let mapper = DefaultAddressMapper::new();
+        let contract = [0x33u8; 32];
+        let key = ScVal::Vec(vec![
+            ScVal::Symbol("Balance".into()),
+            ScVal::Address(crate::translator::scval::StellarAddress::Account([0x44; 32])),
+        ]);
+        // LedgerEntryData: CONTRACT_DATA, ext, contract, key, PERSISTENT, value
+        let mut entry = 6u32.to_be_bytes().to_vec();
+        entry.extend_from_slice(&0u32.to_be_bytes());
+        entry.extend_from_slice(&1u32.to_be_bytes());
+        entry.extend_from_slice(&contract);
+        entry.extend(key.to_xdr());
+        entry.extend_from_slice(&1u32.to_be_bytes());
+        entry.extend(ScVal::I128(250).to_xdr());
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "entries": [{
+                    "key": "",
+                    "xdr": base64::engine::general_purpose::STANDARD.encode(&entry),
+                    "lastModifiedLedgerSeq": 900,
+                    "liveUntilLedgerSeq": 2000,
+                }],
+                "latestLedger": 1000,
+            }
+        });
+        let (url, requests) = crate::test_utils::spawn_recording_mock_server(vec![(
+            "rpc:getLedgerEntries",
+            200,
+            response.to_string(),
+        )])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+
+        let key_json = json!({"vec": [
+            {"symbol": "Balance"},
+            {"address": encode_strkey(crate::translator::tx::STRKEY_VERSION_ACCOUNT, &[0x44; 32])},
+        ]});
+        let params = [
+            Value::String(encode_strkey(STRKEY_VERSION_CONTRACT, &contract)),
+            key_json.clone(),
+            Value::String("persistent".into()),
+        ];
+        let data = get_contract_data(&client, &mapper, &params).await.unwrap();
+        assert_eq!(data["value"], "250");
+        assert_eq!(data["xdr"], base64::engine::general_purpose::STANDARD.encode(ScVal::I128(250).to_xdr()));
+        assert_eq!(data["lastModifiedLedgerSeq"], 900);
+        assert_eq!(data["liveUntilLedgerSeq"], 2000);
+
+        // The request carried the persistent ledger key for the contract and key
+        let expected_key = contract_data_key(&contract, &key, StorageDurability::Persistent);
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.contains(&expected_key), "{}", request);
+
+        // An EVM address resolves to the same contract; durability defaults to persistent
+        let evm_address = mapper.contract_to_evm(&contract);
+        let data = get_contract_data(&client, &mapper, &[Value::String(evm_address), key_json.clone()]).await.unwrap();
+        assert_eq!(data["value"], "250");
+
+        // Nothing stored is null
+        let empty = r#"{"jsonrpc":"2.0","id":1,"result":{"entries":[],"latestLedger":1000}}"#;
+        let url = spawn_mock_server(vec![("rpc:getLedgerEntries", 200, empty.to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015");
+        assert!(get_contract_data(&client, &mapper, &params).await.unwrap().is_null());
+
+        // Malformed parameters are rejected before any request
+        for bad in [
+            vec![json!("0x1234"), key_json.clone()],
+            vec![Value::String(encode_strkey(STRKEY_VERSION_CONTRACT, &contract))],
+            vec![Value::String(encode_strkey(STRKEY_VERSION_CONTRACT, &contract)), json!({"float": 1.5})],
+            vec![Value::String(encode_strkey(STRKEY_VERSION_CONTRACT, &contract)), key_json.clone(), json!("forever")],
+        ] {
+            let err = get_contract_data(&client, &mapper, &bad).await.unwrap_err();
+            assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_balances_in_one_call() {
+        let mapper = DefaultAddressMapper::new();
+        let addresses = [
+            "0x1111111111111111111111111111111111111111",
+            "0x3333333333333333333333333333333333333333",
+            "0x4444444444444444444444444444444444444444",
+        ];
+        let route = |address: &str| -> &'static str {
+            let account = evm_address_to_stellar_account(&mapper, address).unwrap();
+            format!("GET /accounts/{} ", account).leak()
+        };
+        let account = |balance: &str| json!({ "balances": [{ "asset_type": "native", "balance": balance }] }).to_string();
+        let url = spawn_mock_server(vec![
+            (route(addresses[0]), 200, account("1.0000000")),
+            (route(addresses[1]), 200, "not json".to_string()),
+            (route(addresses[2]), 200, account("0.0000002")),
+        ])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url);
+        let config = config();
+
+        // The second lookup fails and reports zero; the others keep their order
+        let balances = get_balances(&client, &config, &mapper, &[json!(addresses)]).await.unwrap();
+        let wei = |stroops: u64| format!("0x{:x}", stroops_to_wei(stroops, config.xlm_wei_factor));
+        assert_eq!(balances, json!([wei(10_000_000), "0x0", wei(2)]));
+
+        let err = get_balances(&client, &config, &mapper, &[json!(["0x12"])]).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+        let too_many = vec![addresses[0]; MAX_BALANCE_BATCH + 1];
+        let err = get_balances(&client, &config, &mapper, &[json!(too_many)]).await.unwrap_err();
+        assert_eq!(crate::error::to_error_object(&err).code(), crate::error::LIMIT_EXCEEDED_CODE);
+    }
+}
@@ -1,8 +1,13 @@
-use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Result};
 use serde_json::Value;
 use sha3::{Digest, Keccak256};
 use tracing::debug;
 
+use crate::error::TvaError;
+use crate::translator::scval::{json_int_word, parse_int_type};
+
 /// Handler for web3_clientVersion
 /// Returns the client version string.
 pub async fn client_version() -> Result<Value> {
@@ -11,20 +16,325 @@ pub async fn client_version() -> Result<Value> {
     Ok(Value::String(version))
 }
 
+/// Hex digits decoded and hashed at a time by web3_sha3.
+const SHA3_CHUNK_HEX_LEN: usize = 8192;
+
 /// Handler for web3_sha3
-/// Returns the Keccak-256 hash of the given data.
+/// Returns the Keccak-256 hash of the given data. The hex is decoded into
+/// the hasher a chunk at a time, so large inputs are not copied whole.
 pub async fn sha3(params: &[Value]) -> Result<Value> {
     let data_hex = params
         .first()
         .and_then(|v| v.as_str())
-        .unwrap_or("0x");
+        .ok_or_else(|| TvaError::InvalidParams("web3_sha3 requires hex data".to_string()))?;
+    let digits = data_hex.strip_prefix("0x").unwrap_or(data_hex);
+    if digits.len() % 2 != 0 {
+        return Err(TvaError::InvalidParams("web3_sha3: hex data has an odd number of digits".to_string()).into());
+    }
 
-    let data_bytes = hex::decode(data_hex.strip_prefix("0x").unwrap_or(data_hex))
-        .unwrap_or_default();
+    let mut hasher = Keccak256::new();
+    let mut buf = [0u8; SHA3_CHUNK_HEX_LEN / 2];
+    for (i, chunk) in digits.as_bytes().chunks(SHA3_CHUNK_HEX_LEN).enumerate() {
+        let out = &mut buf[..chunk.len() / 2];
+        hex::decode_to_slice(chunk, out).map_err(|e| {
+            let message = match e {
+                hex::FromHexError::InvalidHexCharacter { c, index } => format!(
+                    "web3_sha3: invalid hex character '{}' at position {}",
+                    c,
+                    i * SHA3_CHUNK_HEX_LEN + index
+                ),
+                other => format!("web3_sha3: invalid hex data: {}", other),
+            };
+            TvaError::InvalidParams(message)
+        })?;
+        hasher.update(&*out);
+    }
+    let result = format!("0x{}", hex::encode(hasher.finalize()));
 
-    let hash = Keccak256::digest(&data_bytes);
-    let result = format!("0x{}", hex::encode(hash));
+    debug!("web3_sha3: input_len={} -> {}", digits.len() / 2, result);
+    Ok(Value::String(result))
+}
 
-    debug!("web3_sha3: input_len={} -> {}", data_bytes.len(), result);
+/// Handler for tva_hashTypedData
+/// Returns the EIP-712 digest `keccak256(0x19 0x01 ‖ domainSeparator ‖ hashStruct(message))`
+/// of a typed-data object, as signed by eth_signTypedData_v4. The object may
+/// also be passed as a JSON string.
+pub async fn hash_typed_data(params: &[Value]) -> Result<Value> {
+    let typed_data = match params.first() {
+        Some(Value::String(s)) => serde_json::from_str(s)
+            .map_err(|e| TvaError::InvalidParams(format!("Invalid typed data JSON: {}", e)))?,
+        Some(value @ Value::Object(_)) => value.clone(),
+        _ => return Err(TvaError::InvalidParams("Missing typed data object".to_string()).into()),
+    };
+
+    let digest = typed_data_digest(&typed_data).map_err(|e| TvaError::InvalidParams(format!("{:#}", e)))?;
+    let result = format!("0x{}", hex::encode(digest));
+    debug!("tva_hashTypedData -> {}", result);
     Ok(Value::String(result))
 }
+
+/// Fields of each struct type, in declaration order: (name, type).
+type TypedDataTypes = BTreeMap<String, Vec<(String, String)>>;
+
+/// The EIP-712 signing digest of a typed-data object with `types`,
+/// `primaryType`, `domain` and `message`.
+pub fn typed_data_digest(typed_data: &Value) -> Result<[u8; 32]> {
+    let mut types = parse_types(typed_data.get("types").ok_or_else(|| anyhow!("Missing types"))?)?;
+    let primary_type = typed_data
+        .get("primaryType")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("Missing primaryType"))?;
+    let domain = typed_data.get("domain").ok_or_else(|| anyhow!("Missing domain"))?;
+
+    // Without an explicit EIP712Domain type, the domain's fields define it
+    if !types.contains_key("EIP712Domain") {
+        let fields = [
+            ("name", "string"),
+            ("version", "string"),
+            ("chainId", "uint256"),
+            ("verifyingContract", "address"),
+            ("salt", "bytes32"),
+        ]
+        .into_iter()
+        .filter(|(name, _)| domain.get(name).is_some())
+        .map(|(name, ty)| (name.to_string(), ty.to_string()))
+        .collect();
+        types.insert("EIP712Domain".to_string(), fields);
+    }
+
+    let mut preimage = vec![0x19, 0x01];
+    preimage.extend_from_slice(&hash_struct(&types, "EIP712Domain", domain)?);
+    if primary_type != "EIP712Domain" {
+        let message = typed_data.get("message").ok_or_else(|| anyhow!("Missing message"))?;
+        preimage.extend_from_slice(&hash_struct(&types, primary_type, message)?);
+    }
+    Ok(Keccak256::digest(&preimage).into())
+}
+
+fn parse_types(types: &Value) -> Result<TypedDataTypes> {
+    let types = types.as_object().ok_or_else(|| anyhow!("types must be an object"))?;
+    types
+        .iter()
+        .map(|(name, fields)| {
+            let fields = fields
+                .as_array()
+                .ok_or_else(|| anyhow!("Fields of type {} must be an array", name))?
+                .iter()
+                .map(|field| {
+                    let field_name = field.get("name").and_then(Value::as_str);
+                    let field_type = field.get("type").and_then(Value::as_str);
+                    match (field_name, field_type) {
+                        (Some(n), Some(t)) => Ok((n.to_string(), t.to_string())),
+                        _ => Err(anyhow!("Invalid field in type {}: {}", name, field)),
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok((name.clone(), fields))
+        })
+        .collect()
+}
+
+/// `encodeType`: the primary type followed by the struct types it references,
+/// sorted by name, e.g. `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+fn encode_type(types: &TypedDataTypes, primary_type: &str) -> Result<String> {
+    let mut dependencies = BTreeSet::new();
+    collect_dependencies(types, primary_type, &mut dependencies)?;
+    dependencies.remove(primary_type);
+
+    let mut encoded = String::new();
+    for name in std::iter::once(primary_type).chain(dependencies.iter().map(String::as_str)) {
+        let fields: Vec<String> = types[name].iter().map(|(n, t)| format!("{} {}", t, n)).collect();
+        encoded.push_str(&format!("{}({})", name, fields.join(",")));
+    }
+    Ok(encoded)
+}
+
+fn collect_dependencies(types: &TypedDataTypes, type_name: &str, found: &mut BTreeSet<String>) -> Result<()> {
+    if found.contains(type_name) {
+        return Ok(());
+    }
+    let fields = types.get(type_name).ok_or_else(|| anyhow!("Undefined type {}", type_name))?;
+    found.insert(type_name.to_string());
+    for (_, field_type) in fields {
+        let base = field_type.split('[').next().unwrap_or(field_type);
+        if types.contains_key(base) {
+            collect_dependencies(types, base, found)?;
+        }
+    }
+    Ok(())
+}
+
+/// `hashStruct`: keccak256 of the type hash followed by each field's encoding.
+fn hash_struct(types: &TypedDataTypes, type_name: &str, data: &Value) -> Result<[u8; 32]> {
+    let mut encoded = Keccak256::digest(encode_type(types, type_name)?.as_bytes()).to_vec();
+    for (name, field_type) in &types[type_name] {
+        let value = data
+            .get(name)
+            .ok_or_else(|| anyhow!("Missing value for {}.{}", type_name, name))?;
+        encoded.extend_from_slice(&encode_value(types, field_type, value)?);
+    }
+    Ok(Keccak256::digest(&encoded).into())
+}
+
+/// The 32-byte encoding of one field value (`encodeData` of a single member).
+fn encode_value(types: &TypedDataTypes, field_type: &str, value: &Value) -> Result<[u8; 32]> {
+    let invalid = || anyhow!("Invalid {} value: {}", field_type, value);
+
+    // Arrays hash the concatenated encodings of their items
+    if let Some(item_type) = field_type.strip_suffix(']').and_then(|t| t.rsplit_once('[')).map(|(t, _)| t) {
+        let items = value.as_array().ok_or_else(invalid)?;
+        let mut encoded = Vec::with_capacity(items.len() * 32);
+        for item in items {
+            encoded.extend_from_slice(&encode_value(types, item_type, item)?);
+        }
+        return Ok(Keccak256::digest(&encoded).into());
+    }
+    if types.contains_key(field_type) {
+        return hash_struct(types, field_type, value);
+    }
+
+    let hex_bytes = |value: &Value| -> Result<Vec<u8>> {
+        let s = value.as_str().ok_or_else(invalid)?;
+        hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|_| invalid())
+    };
+    let mut word = [0u8; 32];
+    match field_type {
+        "string" => return Ok(Keccak256::digest(value.as_str().ok_or_else(invalid)?.as_bytes()).into()),
+        "bytes" => return Ok(Keccak256::digest(hex_bytes(value)?).into()),
+        "bool" => word[31] = u8::from(value.as_bool().ok_or_else(invalid)?),
+        "address" => {
+            let address = hex_bytes(value)?;
+            if address.len() != 20 {
+                return Err(invalid());
+            }
+            word[12..].copy_from_slice(&address);
+        }
+        _ => {
+            if let Some(width) = field_type.strip_prefix("bytes") {
+                let bytes = hex_bytes(value)?;
+                match width.parse::<usize>() {
+                    Ok(n) if (1..=32).contains(&n) && bytes.len() <= n => word[..bytes.len()].copy_from_slice(&bytes),
+                    _ => return Err(invalid()),
+                }
+            } else if let Some((signed, _)) = parse_int_type(field_type)? {
+                word.copy_from_slice(&json_int_word(value, field_type, signed)?);
+            } else {
+                return Err(anyhow!("Undefined type {}", field_type));
+            }
+        }
+    }
+    Ok(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sha3_rejects_malformed_hex() {
+        let hash = |data: &str| {
+            let params = [Value::String(data.to_string())];
+            async move { sha3(&params).await }
+        };
+        let empty_hash = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+        assert_eq!(hash("0x").await.unwrap(), empty_hash);
+        assert_eq!(
+            hash("0x68656c6c6f20776f726c64").await.unwrap(),
+            "0x47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"
+        );
+
+        for (bad, message) in [
+            ("0xzz", "web3_sha3: invalid hex character 'z' at position 0"),
+            ("0x123", "web3_sha3: hex data has an odd number of digits"),
+        ] {
+            let err = hash(bad).await.unwrap_err();
+            let error = crate::error::to_error_object(&err);
+            assert_eq!(error.code(), crate::error::INVALID_PARAMS_CODE);
+            assert_eq!(error.message(), message);
+        }
+        assert!(sha3(&[]).await.is_err());
+
+        // Inputs spanning several chunks hash as one, and errors report their absolute position
+        let large = "ab".repeat(SHA3_CHUNK_HEX_LEN * 3);
+        let expected = format!("0x{}", hex::encode(Keccak256::digest(hex::decode(&large).unwrap())));
+        assert_eq!(hash(&large).await.unwrap(), expected);
+        let mut typo = large.clone();
+        typo.replace_range(SHA3_CHUNK_HEX_LEN + 5..SHA3_CHUNK_HEX_LEN + 6, "g");
+        let err = hash(&typo).await.unwrap_err();
+        assert!(err.to_string().ends_with(&format!("at position {}", SHA3_CHUNK_HEX_LEN + 5)), "{}", err);
+    }
+
+    /// The `Mail` example from the EIP-712 specification.
+    fn mail_typed_data() -> Value {
+        serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+                "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+                "contents": "Hello, Bob!"
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_hash_typed_data_matches_spec_example() {
+        let types = parse_types(&mail_typed_data()["types"]).unwrap();
+        assert_eq!(
+            encode_type(&types, "Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(hash_struct(&types, "EIP712Domain", &mail_typed_data()["domain"]).unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+
+        let expected = "0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2";
+        assert_eq!(hash_typed_data(&[mail_typed_data()]).await.unwrap(), expected);
+        // eth_signTypedData_v4 passes the object as a JSON string
+        let as_string = Value::String(mail_typed_data().to_string());
+        assert_eq!(hash_typed_data(&[as_string]).await.unwrap(), expected);
+
+        // The domain type is derived from the domain when not declared
+        let mut implicit = mail_typed_data();
+        implicit["types"].as_object_mut().unwrap().remove("EIP712Domain");
+        assert_eq!(hash_typed_data(&[implicit]).await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_hash_typed_data_rejects_malformed_input() {
+        let mut missing_field = mail_typed_data();
+        missing_field["message"].as_object_mut().unwrap().remove("contents");
+        let mut bad_address = mail_typed_data();
+        bad_address["message"]["to"]["wallet"] = Value::String("0x1234".to_string());
+        let mut undefined_type = mail_typed_data();
+        undefined_type["primaryType"] = Value::String("Letter".to_string());
+
+        for params in [vec![], vec![missing_field], vec![bad_address], vec![undefined_type]] {
+            let err = hash_typed_data(&params).await.unwrap_err();
+            assert_eq!(crate::error::to_error_object(&err).code(), crate::error::INVALID_PARAMS_CODE);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use http::{Method, StatusCode};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::server::{HttpBody, HttpRequest, HttpResponse, MethodResponse};
+use jsonrpsee::types::Request;
+use tower::{Layer, Service};
+
+use crate::error::INTERNAL_ERROR_CODE;
+
+/// Path of the Prometheus scrape endpoint.
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Upper bounds (seconds) of the request latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Per-method call statistics.
+#[derive(Debug, Default, Clone)]
+struct MethodStats {
+    calls: u64,
+    client_errors: u64,
+    server_errors: u64,
+    /// Non-cumulative count per bucket; the final slot counts calls above the last bound
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    latency_sum: f64,
+}
+
+/// In-process metrics for RPC methods and upstream Soroban calls,
+/// rendered in the Prometheus text exposition format.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    methods: Mutex<BTreeMap<String, MethodStats>>,
+    upstream_errors: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Metrics {
+    /// Create an empty metrics registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one JSON-RPC call. `error_code` is the JSON-RPC error code if the
+    /// call failed; internal errors count as server errors, everything else
+    /// (invalid params, reverts, unknown methods) as client errors.
+    pub fn record_call(&self, method: &str, elapsed: Duration, error_code: Option<i32>) {
+        let mut methods = self.methods.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = methods.entry(method.to_string()).or_default();
+
+        stats.calls += 1;
+        match error_code {
+            Some(INTERNAL_ERROR_CODE) => stats.server_errors += 1,
+            Some(_) => stats.client_errors += 1,
+            None => {}
+        }
+
+        let secs = elapsed.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        stats.buckets[bucket] += 1;
+        stats.latency_sum += secs;
+    }
+
+    /// Record a failed request to the upstream Soroban RPC.
+    pub fn record_upstream_error(&self, soroban_method: &str) {
+        let mut errors = self.upstream_errors.lock().unwrap_or_else(|e| e.into_inner());
+        *errors.entry(soroban_method.to_string()).or_default() += 1;
+    }
+
+    /// Render all metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let methods = self.methods.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let upstream = self.upstream_errors.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let mut out = String::new();
+
+        out.push_str("# HELP tva_rpc_requests_total JSON-RPC calls by method.\n");
+        out.push_str("# TYPE tva_rpc_requests_total counter\n");
+        for (method, stats) in &methods {
+            let _ = writeln!(out, "tva_rpc_requests_total{{method=\"{}\"}} {}", method, stats.calls);
+        }
+
+        out.push_str("# HELP tva_rpc_errors_total Failed JSON-RPC calls by method and kind (client or server).\n");
+        out.push_str("# TYPE tva_rpc_errors_total counter\n");
+        for (method, stats) in &methods {
+            let _ = writeln!(
+                out,
+                "tva_rpc_errors_total{{method=\"{}\",kind=\"client\"}} {}",
+                method, stats.client_errors
+            );
+            let _ = writeln!(
+                out,
+                "tva_rpc_errors_total{{method=\"{}\",kind=\"server\"}} {}",
+                method, stats.server_errors
+            );
+        }
+
+        out.push_str("# HELP tva_rpc_request_duration_seconds JSON-RPC call latency by method.\n");
+        out.push_str("# TYPE tva_rpc_request_duration_seconds histogram\n");
+        for (method, stats) in &methods {
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(&stats.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "tva_rpc_request_duration_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "tva_rpc_request_duration_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                method, stats.calls
+            );
+            let _ = writeln!(
+                out,
+                "tva_rpc_request_duration_seconds_sum{{method=\"{}\"}} {}",
+                method, stats.latency_sum
+            );
+            let _ = writeln!(
+                out,
+                "tva_rpc_request_duration_seconds_count{{method=\"{}\"}} {}",
+                method, stats.calls
+            );
+        }
+
+        out.push_str("# HELP tva_upstream_errors_total Failed Soroban RPC requests by upstream method.\n");
+        out.push_str("# TYPE tva_upstream_errors_total counter\n");
+        for (method, count) in &upstream {
+            let _ = writeln!(out, "tva_upstream_errors_total{{method=\"{}\"}} {}", method, count);
+        }
+
+        out
+    }
+}
+
+/// JSON-RPC middleware layer that records call count, latency and errors per method.
+#[derive(Clone)]
+pub struct MetricsRpcLayer {
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsRpcLayer {
+    /// Create a layer recording into the given metrics registry.
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> Layer<S> for MetricsRpcLayer {
+    type Service = MetricsRpcService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsRpcService {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// Service produced by `MetricsRpcLayer`.
+#[derive(Clone)]
+pub struct MetricsRpcService<S> {
+    inner: S,
+    metrics: Arc<Metrics>,
+}
+
+impl<'a, S> RpcServiceT<'a> for MetricsRpcService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let method = request.method_name().to_string();
+        let metrics = self.metrics.clone();
+        let started = Instant::now();
+        let fut = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = fut.await;
+            metrics.record_call(&method, started.elapsed(), response.as_error_code());
+            response
+        })
+    }
+}
+
+/// HTTP layer that answers `GET /metrics` and passes everything else through.
+#[derive(Clone)]
+pub struct MetricsLayer {
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsLayer {
+    /// Create a layer serving the given metrics registry.
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// Service produced by `MetricsLayer`.
+#[derive(Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+    metrics: Arc<Metrics>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for MetricsService<S>
+where
+    S: Service<HttpRequest<B>, Response = HttpResponse>,
+    S::Future: Send + 'static,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        if req.method() == Method::GET && req.uri().path() == METRICS_PATH {
+            let response = HttpResponse::builder()
+                .status(StatusCode::OK)
+                .header("content-type", "text/plain; version=0.0.4")
+                .body(HttpBody::from(self.metrics.render()))
+                .expect("Static status and header are valid");
+            return Box::pin(async move { Ok(response) });
+        }
+
+        Box::pin(self.inner.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::server::{RpcModule, RpcServiceBuilder, Server};
+
+    #[test]
+    fn test_render_counts_and_histogram() {
+        let metrics = Metrics::new();
+        metrics.record_call("eth_call", Duration::from_millis(3), None);
+        metrics.record_call("eth_call", Duration::from_millis(300), Some(-32000));
+        metrics.record_call("eth_call", Duration::from_secs(20), Some(INTERNAL_ERROR_CODE));
+        metrics.record_upstream_error("simulateTransaction");
+
+        let text = metrics.render();
+        assert!(text.contains("tva_rpc_requests_total{method=\"eth_call\"} 3\n"));
+        assert!(text.contains("tva_rpc_errors_total{method=\"eth_call\",kind=\"client\"} 1\n"));
+        assert!(text.contains("tva_rpc_errors_total{method=\"eth_call\",kind=\"server\"} 1\n"));
+        assert!(text.contains("tva_rpc_request_duration_seconds_bucket{method=\"eth_call\",le=\"0.005\"} 1\n"));
+        assert!(text.contains("tva_rpc_request_duration_seconds_bucket{method=\"eth_call\",le=\"0.5\"} 2\n"));
+        assert!(text.contains("tva_rpc_request_duration_seconds_bucket{method=\"eth_call\",le=\"10\"} 2\n"));
+        assert!(text.contains("tva_rpc_request_duration_seconds_bucket{method=\"eth_call\",le=\"+Inf\"} 3\n"));
+        assert!(text.contains("tva_rpc_request_duration_seconds_count{method=\"eth_call\"} 3\n"));
+        assert!(text.contains("tva_upstream_errors_total{method=\"simulateTransaction\"} 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_middleware_records_calls_and_serves_metrics() {
+        let metrics = Arc::new(Metrics::new());
+        let mut module = RpcModule::new(());
+        module
+            .register_async_method("ok_method", |_, _, _| async move {
+                Ok::<_, jsonrpsee::types::ErrorObjectOwned>("ok")
+            })
+            .unwrap();
+        module
+            .register_async_method("failing_method", |_, _, _| async move {
+                Err::<(), _>(crate::error::to_error_object(&anyhow::anyhow!("upstream down")))
+            })
+            .unwrap();
+
+        let server = Server::builder()
+            .set_http_middleware(tower::ServiceBuilder::new().layer(MetricsLayer::new(metrics.clone())))
+            .set_rpc_middleware(RpcServiceBuilder::new().layer(MetricsRpcLayer::new(metrics.clone())))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.start(module);
+
+        let http = reqwest::Client::new();
+        for method in ["ok_method", "failing_method"] {
+            http.post(format!("http://{}", addr))
+                .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method }))
+                .send()
+                .await
+                .unwrap();
+        }
+
+        let response = http.get(format!("http://{}{}", addr, METRICS_PATH)).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        let text = response.text().await.unwrap();
+        assert!(text.contains("tva_rpc_requests_total{method=\"ok_method\"} 1\n"));
+        assert!(text.contains("tva_rpc_errors_total{method=\"ok_method\",kind=\"server\"} 0\n"));
+        assert!(text.contains("tva_rpc_errors_total{method=\"failing_method\",kind=\"server\"} 1\n"));
+
+        handle.stop().unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Instant;
+
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::server::MethodResponse;
+use jsonrpsee::types::Request;
+use tower::Layer;
+use tracing::{debug, info, info_span, warn, Instrument};
+
+/// Maximum number of characters of the params logged per request.
+const MAX_PARAMS_LOG_LEN: usize = 256;
+
+/// Generate a random (version 4) UUID to identify a request in the logs.
+pub fn new_request_id() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+    bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Shorten the raw params for logging, cutting on a character boundary.
+fn summarize_params(params: Option<&str>) -> String {
+    let params = params.unwrap_or("[]");
+    match params.char_indices().nth(MAX_PARAMS_LOG_LEN) {
+        Some((cut, _)) => format!("{}... ({} bytes)", &params[..cut], params.len()),
+        None => params.to_string(),
+    }
+}
+
+/// JSON-RPC middleware layer that runs each call inside an `rpc_request` span
+/// carrying a request ID, and logs its params, duration and outcome.
+///
+/// Everything the handler does, including Soroban RPC requests, is logged
+/// within the span, so one request ID correlates the whole path.
+#[derive(Clone, Default)]
+pub struct RequestLogLayer;
+
+impl RequestLogLayer {
+    /// Create a request logging layer.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for RequestLogLayer {
+    type Service = RequestLogService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLogService { inner }
+    }
+}
+
+/// Service produced by `RequestLogLayer`.
+#[derive(Clone)]
+pub struct RequestLogService<S> {
+    inner: S,
+}
+
+impl<'a, S> RpcServiceT<'a> for RequestLogService<S>
+where
+    S: RpcServiceT<'a>,
+    S::Future: 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let request_id = new_request_id();
+        let method = request.method_name().to_string();
+        let span = info_span!("rpc_request", request_id = %request_id, method = %method);
+
+        let params = summarize_params(request.params().as_str());
+        let fut = {
+            let _entered = span.enter();
+            debug!("Request params: {}", params);
+            self.inner.call(request)
+        };
+
+        Box::pin(
+            async move {
+                let started = Instant::now();
+                let response = fut.await;
+                let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+                match response.as_error_code() {
+                    None => info!("Completed in {:.1}ms", elapsed_ms),
+                    Some(code) => warn!("Failed in {:.1}ms with error code {}", elapsed_ms, code),
+                }
+                response
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::server::{RpcModule, RpcServiceBuilder, Server};
+
+    #[test]
+    fn test_request_id_is_uuid_v4() {
+        let id = new_request_id();
+        let parts: Vec<&str> = id.split('-').collect();
+        assert_eq!(parts.iter().map(|p| p.len()).collect::<Vec<_>>(), vec![8, 4, 4, 4, 12]);
+        assert!(parts[2].starts_with('4'));
+        assert!(matches!(parts[3].chars().next(), Some('8' | '9' | 'a' | 'b')));
+        assert_ne!(id, new_request_id());
+    }
+
+    #[test]
+    fn test_summarize_params_truncates() {
+        assert_eq!(summarize_params(None), "[]");
+        assert_eq!(summarize_params(Some("[\"0x1\"]")), "[\"0x1\"]");
+        let long = format!("[\"{}\"]", "é".repeat(300));
+        let summary = summarize_params(Some(&long));
+        assert!(summary.ends_with(&format!("... ({} bytes)", long.len())));
+        assert_eq!(summary.chars().count(), MAX_PARAMS_LOG_LEN + format!("... ({} bytes)", long.len()).len());
+    }
+
+    #[tokio::test]
+    async fn test_handler_runs_inside_request_span() {
+        // Spans are only materialized with a subscriber installed
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry());
+
+        let mut module = RpcModule::new(());
+        module
+            .register_async_method("span_name", |_, _, _| async move {
+                let name = tracing::Span::current().metadata().map(|m| m.name()).unwrap_or("none");
+                Ok::<_, jsonrpsee::types::ErrorObjectOwned>(name.to_string())
+            })
+            .unwrap();
+
+        let server = Server::builder()
+            .set_rpc_middleware(RpcServiceBuilder::new().layer(RequestLogLayer::new()))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.start(module);
+
+        let response: serde_json::Value = reqwest::Client::new()
+            .post(format!("http://{}", addr))
+            .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "span_name" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response["result"], "rpc_request");
+
+        handle.stop().unwrap();
+    }
+}
@@ -1,59 +1,128 @@
-use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use anyhow::{anyhow, Result};
-use jsonrpsee::server::{RpcModule, Server};
+use jsonrpsee::server::{RpcModule, RpcServiceBuilder, Server, ServerBuilder};
+use tower::layer::util::{Identity, Stack};
 use tower::ServiceBuilder;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+use tower_http::decompression::RequestDecompressionLayer;
 use tracing::{info, warn};
 
 use crate::config::Config;
-use crate::methods::{eth, net, web3};
+use crate::emulator::block::BlockHashIndex;
+use crate::emulator::PendingTxStore;
+use crate::error::to_error_object;
+use crate::health::{
+    refresh_upstream_status, spawn_upstream_monitor, HealthLayer, SharedUpstreamStatus,
+    UpstreamStatus, HEALTH_PATH,
+};
+use crate::method_filter::MethodFilterLayer;
+use crate::metrics::{Metrics, MetricsLayer, MetricsRpcLayer, METRICS_PATH};
+use crate::request_log::RequestLogLayer;
+use crate::methods::{eth, net, tva, web3};
+use crate::state_store::StateStore;
 use crate::stellar::SorobanClient;
-use crate::translator::AbiRegistry;
+use crate::translator::{AbiRegistry, AddressMapper, DefaultAddressMapper, TranslationStats};
 
 /// Shared state for the RPC server.
 pub struct RpcState {
     pub config: Config,
     pub soroban_client: SorobanClient,
     pub abi_registry: AbiRegistry,
+    /// EVM <-> Stellar address mapping, passed to all translation code
+    pub address_mapper: Arc<dyn AddressMapper>,
+    /// Block hash to ledger resolution, persisted with `STATE_DB_PATH`
+    pub block_hashes: Arc<BlockHashIndex>,
+    /// Cached upstream health, refreshed in the background
+    pub upstream: SharedUpstreamStatus,
+    /// Transactions submitted through this server that are still in flight
+    pub pending: Arc<PendingTxStore>,
+    /// How calls fared in translation, reported by tva_getTranslationStats
+    pub translation_stats: Arc<TranslationStats>,
+}
+
+impl RpcState {
+    /// Snapshot of the cached upstream status.
+    pub fn upstream_status(&self) -> UpstreamStatus {
+        self.upstream.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
 }
 
 /// Start the JSON-RPC server.
 pub async fn start_server(config: Config) -> Result<()> {
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.tva_rpc_port));
+    let addr = config.socket_addr();
 
     info!("Starting TVA RPC Server on {}", addr);
     info!("Chain ID: {} (0x{:x})", config.tva_chain_id, config.tva_chain_id);
     info!("Stellar RPC: {}", config.stellar_rpc_url);
+    if config.stellar_write_rpc_url != config.stellar_rpc_url {
+        info!("Stellar RPC for submissions: {}", config.stellar_write_rpc_url);
+    }
+
+    // Metrics shared by the RPC middleware, the Soroban client and /metrics
+    let metrics = Arc::new(Metrics::new());
 
     // Initialize Soroban client
     let soroban_client = SorobanClient::new(
         &config.stellar_rpc_url,
         &config.stellar_network_passphrase,
-    );
+    )
+    .with_write_rpc_url(&config.stellar_write_rpc_url)
+    .with_connection_pool(config.upstream_pool_max_idle_per_host, config.upstream_pool_idle_timeout())
+    .with_circuit_breaker(config.upstream_circuit_failure_threshold, config.upstream_circuit_cooldown())
+    .with_horizon_url(&config.stellar_horizon_url)
+    .with_metrics(metrics.clone());
 
     // Check Soroban RPC health
-    match soroban_client.get_health().await {
-        Ok(health) => {
-            info!("Soroban RPC health: status={}", health.status);
-            if let Some(latest) = health.latest_ledger {
-                info!("Latest ledger: {}", latest);
-            }
-        }
-        Err(e) => {
-            warn!("Could not reach Soroban RPC (will retry on requests): {}", e);
+    let initial_status = refresh_upstream_status(&soroban_client, &UpstreamStatus::default()).await;
+    if initial_status.healthy {
+        info!("Soroban RPC health: status=healthy");
+        if let Some(latest) = initial_status.latest_ledger {
+            info!("Latest ledger: {}", latest);
         }
+    } else {
+        warn!("Could not reach Soroban RPC (will retry in the background)");
     }
 
-    // Initialize ABI registry
+    // Keep the cached upstream status fresh for /health, net_peerCount and eth_syncing
+    let upstream = Arc::new(RwLock::new(initial_status));
+    let monitor = spawn_upstream_monitor(
+        soroban_client.clone(),
+        upstream.clone(),
+        config.health_poll_interval(),
+    );
+
+    // Initialize ABI registry, with the configured function aliases
     let abi_registry = AbiRegistry::new();
+    for (selector, symbol) in &config.function_aliases {
+        abi_registry.set_function_alias(*selector, symbol)?;
+    }
+
+    // Mappings persist across restarts only with STATE_DB_PATH
+    let state_store = match &config.state_db_path {
+        Some(path) => Some(Arc::new(StateStore::open(path)?)),
+        None => None,
+    };
+    let mapper = match &state_store {
+        Some(store) => DefaultAddressMapper::with_store(store.clone()),
+        None => DefaultAddressMapper::new(),
+    };
+    let block_hashes = BlockHashIndex::new(&config.stellar_network_passphrase, state_store);
+
+    // Health endpoint reports the same cached status as the RPC methods
+    let health = HealthLayer::new(upstream.clone());
 
     // Create shared state
     let state = Arc::new(RpcState {
         config: config.clone(),
         soroban_client,
         abi_registry,
+        translation_stats: Arc::new(TranslationStats::default()),
+        address_mapper: Arc::new(mapper),
+        block_hashes: Arc::new(block_hashes),
+        upstream,
+        pending: Arc::new(PendingTxStore::new()),
     });
 
     // Build the RPC module
@@ -62,33 +131,85 @@ pub async fn start_server(config: Config) -> Result<()> {
     // Register all RPC methods
     register_methods(&mut module)?;
 
+    // Method lists naming nothing registered are most likely typos
+    for method in config.rpc_method_allowlist.iter().flatten().chain(&config.rpc_method_denylist) {
+        if !module.method_names().any(|name| name == method) {
+            warn!("RPC method list names unknown method '{}'", method);
+        }
+    }
+
     // CORS middleware - allow all origins for development
     let cors = CorsLayer::new()
         .allow_origin(AllowOrigin::any())
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let middleware = ServiceBuilder::new().layer(cors);
+    let middleware = ServiceBuilder::new()
+        .layer(cors)
+        .layer(compression())
+        .layer(health)
+        .layer(MetricsLayer::new(metrics.clone()));
 
-    // Start the server with CORS
-    let server = Server::builder()
+    // Start the server with CORS, compression, the health/metrics endpoints, per-method metrics
+    // and the method allowlist/denylist
+    let server = server_builder(&config)
         .set_http_middleware(middleware)
+        .set_rpc_middleware(rpc_middleware(&config, metrics))
         .build(addr)
         .await
         .map_err(|e| anyhow!("Failed to bind server to {}: {}", addr, e))?;
+    let addr = server.local_addr().unwrap_or(addr);
 
     info!("TVA RPC Server listening on http://{}", addr);
+    info!("Health check available at GET http://{}{}", addr, HEALTH_PATH);
+    info!("Prometheus metrics available at GET http://{}{}", addr, METRICS_PATH);
     info!("Compatible with MetaMask, Hardhat, ethers.js, and other EVM tooling");
 
     let handle = server.start(module);
 
     // Wait for the server to finish (runs until shutdown signal)
     handle.stopped().await;
+    monitor.abort();
 
     info!("TVA RPC Server stopped");
     Ok(())
 }
 
+/// Server builder with the configured limits applied. Requests over
+/// `max_request_bytes` are answered with HTTP 413 and an oversized-request
+/// JSON-RPC error before they are parsed.
+fn server_builder(config: &Config) -> ServerBuilder<Identity, Identity> {
+    Server::builder().max_request_body_size(config.max_request_bytes)
+}
+
+/// Layers of the JSON-RPC middleware, innermost last.
+type RpcMiddleware = Stack<MethodFilterLayer, Stack<MetricsRpcLayer, Stack<RequestLogLayer, Identity>>>;
+
+/// HTTP middleware compressing bodies both ways.
+type Compression = ServiceBuilder<Stack<RequestDecompressionLayer, Stack<CompressionLayer, Identity>>>;
+
+/// gzip and deflate for request and response bodies, as negotiated by
+/// `Content-Encoding` and `Accept-Encoding`. Large eth_getLogs responses and
+/// contract deployments shrink several-fold.
+fn compression() -> Compression {
+    ServiceBuilder::new()
+        .layer(CompressionLayer::new().gzip(true).deflate(true))
+        .layer(RequestDecompressionLayer::new().gzip(true).deflate(true))
+}
+
+/// JSON-RPC middleware wrapping every call: request logging, per-method
+/// metrics and the method allowlist/denylist. Responses built here (refused
+/// methods) reuse the request's id, so string and numeric ids are echoed as sent.
+fn rpc_middleware(
+    config: &Config,
+    metrics: Arc<Metrics>,
+) -> RpcServiceBuilder<RpcMiddleware> {
+    RpcServiceBuilder::new()
+        .layer(RequestLogLayer::new())
+        .layer(MetricsRpcLayer::new(metrics))
+        .layer(MethodFilterLayer::new(config))
+}
+
 /// Register all JSON-RPC methods on the module.
 fn register_methods(module: &mut RpcModule<Arc<RpcState>>) -> Result<()> {
     // --- eth_* methods ---
@@ -97,135 +218,242 @@ fn register_methods(module: &mut RpcModule<Arc<RpcState>>) -> Result<()> {
         let _ = params;
         eth::chain_id(&ctx.config)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_blockNumber", |params, ctx, _| async move {
         let _ = params;
         eth::block_number(&ctx.soroban_client)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getBlockByNumber", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_block_by_number(&ctx.soroban_client, &p)
+        eth::get_block_by_number(&ctx.soroban_client, &ctx.config, &ctx.block_hashes, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getBlockByHash", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_block_by_hash(&ctx.soroban_client, &p)
+        eth::get_block_by_hash(&ctx.soroban_client, &ctx.config, &ctx.block_hashes, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_call", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::call(&ctx.soroban_client, &ctx.config, &ctx.abi_registry, &p)
+        eth::call(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.translation_stats,
+            &ctx.pending,
+            &p,
+        )
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("eth_sendTransaction", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        eth::send_transaction(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.translation_stats,
+            &ctx.pending,
+            &p,
+        )
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_sendRawTransaction", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::send_raw_transaction(&ctx.soroban_client, &ctx.config, &ctx.abi_registry, &p)
+        eth::send_raw_transaction(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.translation_stats,
+            &ctx.pending,
+            &p,
+        )
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getTransactionReceipt", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_transaction_receipt(&ctx.soroban_client, &p)
+        eth::get_transaction_receipt(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.pending,
+            &p,
+        )
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("eth_getBlockReceipts", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        eth::get_block_receipts(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.block_hashes,
+            &ctx.abi_registry,
+            &p,
+        )
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getTransactionByHash", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_transaction_by_hash(&ctx.soroban_client, &p)
+        eth::get_transaction_by_hash(&ctx.soroban_client, &ctx.config, &*ctx.address_mapper, &ctx.abi_registry, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
+    module.register_async_method(
+        "eth_getTransactionByBlockNumberAndIndex",
+        |params, ctx, _| async move {
+            let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+            eth::get_transaction_by_block_number_and_index(
+                &ctx.soroban_client,
+                &ctx.config,
+                &*ctx.address_mapper,
+                &ctx.abi_registry,
+                &p,
+            )
+                .await
+                .map_err(|e| to_error_object(&e))
+        },
+    )?;
+
+    module.register_async_method(
+        "eth_getTransactionByBlockHashAndIndex",
+        |params, ctx, _| async move {
+            let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+            eth::get_transaction_by_block_hash_and_index(
+                &ctx.soroban_client,
+                &ctx.config,
+                &*ctx.address_mapper,
+                &ctx.block_hashes,
+                &ctx.abi_registry,
+                &p,
+            )
+                .await
+                .map_err(|e| to_error_object(&e))
+        },
+    )?;
+
     module.register_async_method("eth_getCode", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_code(&ctx.soroban_client, &p)
+        eth::get_code(&ctx.soroban_client, &*ctx.address_mapper, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getBalance", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_balance(&ctx.soroban_client, &p)
+        eth::get_balance(&ctx.soroban_client, &ctx.config, &*ctx.address_mapper, &ctx.pending, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_gasPrice", |params, ctx, _| async move {
         let _ = params;
-        eth::gas_price(&ctx.soroban_client)
+        eth::gas_price(&ctx.config)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_estimateGas", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::estimate_gas(&ctx.soroban_client, &ctx.config, &ctx.abi_registry, &p)
+        eth::estimate_gas(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.translation_stats,
+            &p,
+        )
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getTransactionCount", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_transaction_count(&ctx.soroban_client, &p)
+        eth::get_transaction_count(&ctx.soroban_client, &*ctx.address_mapper, &ctx.pending, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getLogs", |params, ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
-        eth::get_logs(&ctx.soroban_client, &p)
+        eth::get_logs(&ctx.soroban_client, &ctx.config, &*ctx.address_mapper, &ctx.abi_registry, &p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
-    module.register_async_method("eth_accounts", |_params, _ctx, _| async move {
-        eth::accounts()
+    module.register_async_method("eth_accounts", |_params, ctx, _| async move {
+        eth::accounts(&ctx.config, &*ctx.address_mapper)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_mining", |_params, _ctx, _| async move {
         eth::mining()
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_hashrate", |_params, _ctx, _| async move {
         eth::hashrate()
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
-    module.register_async_method("eth_syncing", |_params, _ctx, _| async move {
-        eth::syncing()
+    for method in eth::MINING_METHODS {
+        module.register_async_method(method, move |_params, _ctx, _| async move {
+            eth::mining_unsupported(method)
+                .await
+                .map_err(|e| to_error_object(&e))
+        })?;
+    }
+
+    module.register_async_method("eth_syncing", |_params, ctx, _| async move {
+        eth::syncing(&ctx.upstream_status())
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_coinbase", |_params, _ctx, _| async move {
         eth::coinbase()
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("eth_getStorageAt", |params, _ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
         eth::get_storage_at(&p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("eth_getProof", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        eth::get_proof(&ctx.soroban_client, &ctx.config, &*ctx.address_mapper, &ctx.pending, &p)
+            .await
+            .map_err(|e| to_error_object(&e))
     })?;
 
     // --- net_* methods ---
@@ -233,19 +461,19 @@ fn register_methods(module: &mut RpcModule<Arc<RpcState>>) -> Result<()> {
     module.register_async_method("net_version", |_params, ctx, _| async move {
         net::version(&ctx.config)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("net_listening", |_params, _ctx, _| async move {
         net::listening()
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
-    module.register_async_method("net_peerCount", |_params, _ctx, _| async move {
-        net::peer_count()
+    module.register_async_method("net_peerCount", |_params, ctx, _| async move {
+        net::peer_count(&ctx.upstream_status())
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     // --- web3_* methods ---
@@ -253,14 +481,87 @@ fn register_methods(module: &mut RpcModule<Arc<RpcState>>) -> Result<()> {
     module.register_async_method("web3_clientVersion", |_params, _ctx, _| async move {
         web3::client_version()
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
     })?;
 
     module.register_async_method("web3_sha3", |params, _ctx, _| async move {
         let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
         web3::sha3(&p)
             .await
-            .map_err(|e| jsonrpsee_error(&e.to_string()))
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    // --- tva_* methods ---
+
+    module.register_async_method("tva_hashTypedData", |params, _ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        web3::hash_typed_data(&p)
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_translateCalldata", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        tva::translate_calldata(&ctx.abi_registry, &ctx.translation_stats, &p)
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_getTranslationStats", |_params, ctx, _| async move {
+        tva::get_translation_stats(&ctx.translation_stats)
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_setFunctionAlias", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        tva::set_function_alias(&ctx.config, &ctx.abi_registry, &p)
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_getContractData", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        tva::get_contract_data(&ctx.soroban_client, &*ctx.address_mapper, &p)
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_getBalances", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        tva::get_balances(&ctx.soroban_client, &ctx.config, &*ctx.address_mapper, &p)
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_simulateRawTransaction", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        tva::simulate_raw_transaction(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.translation_stats,
+            &ctx.pending,
+            &p,
+        )
+            .await
+            .map_err(|e| to_error_object(&e))
+    })?;
+
+    module.register_async_method("tva_deployContract", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        tva::deploy_contract(
+            &ctx.soroban_client,
+            &ctx.config,
+            &*ctx.address_mapper,
+            &ctx.abi_registry,
+            &ctx.translation_stats,
+            &ctx.pending,
+            &p,
+        )
+            .await
+            .map_err(|e| to_error_object(&e))
     })?;
 
     // --- Additional compatibility methods ---
@@ -274,31 +575,234 @@ fn register_methods(module: &mut RpcModule<Arc<RpcState>>) -> Result<()> {
 
     // eth_maxPriorityFeePerGas (EIP-1559)
     module.register_async_method("eth_maxPriorityFeePerGas", |_params, _ctx, _| async move {
-        Ok::<serde_json::Value, jsonrpsee::types::ErrorObjectOwned>(
-            serde_json::Value::String("0x3b9aca00".to_string()), // 1 gwei
-        )
+        eth::max_priority_fee_per_gas()
+            .await
+            .map_err(|e| to_error_object(&e))
     })?;
 
     // eth_feeHistory (EIP-1559)
-    module.register_async_method("eth_feeHistory", |_params, _ctx, _| async move {
-        let response = serde_json::json!({
-            "baseFeePerGas": ["0x3b9aca00"],
-            "gasUsedRatio": [0.5],
-            "oldestBlock": "0x1",
-            "reward": [["0x3b9aca00"]]
-        });
-        Ok::<serde_json::Value, jsonrpsee::types::ErrorObjectOwned>(response)
+    module.register_async_method("eth_feeHistory", |params, ctx, _| async move {
+        let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+        eth::fee_history(&ctx.soroban_client, &ctx.config, &p)
+            .await
+            .map_err(|e| to_error_object(&e))
     })?;
 
+    // Uncle methods: Stellar ledgers have no uncles
+    for method in ["eth_getUncleCountByBlockNumber", "eth_getUncleCountByBlockHash"] {
+        module.register_async_method(method, |_params, _ctx, _| async move {
+            Ok::<serde_json::Value, jsonrpsee::types::ErrorObjectOwned>(serde_json::Value::String("0x0".to_string()))
+        })?;
+    }
+    for method in ["eth_getUncleByBlockNumberAndIndex", "eth_getUncleByBlockHashAndIndex"] {
+        module.register_async_method(method, |_params, _ctx, _| async move {
+            Ok::<serde_json::Value, jsonrpsee::types::ErrorObjectOwned>(serde_json::Value::Null)
+        })?;
+    }
+
     info!("Registered all RPC methods successfully");
     Ok(())
 }
 
-/// Create a jsonrpsee error from a string message.
-fn jsonrpsee_error(message: &str) -> jsonrpsee::types::ErrorObjectOwned {
-    jsonrpsee::types::ErrorObjectOwned::owned(
-        -32603, // Internal error
-        message.to_string(),
-        None::<()>,
-    )
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_constant_compatibility_methods() {
+        let config = Config::from_lookup(|key| match key {
+            "STELLAR_SECRET_KEY" => Some("SECRET".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let soroban_client = SorobanClient::new("http://127.0.0.1:1", &config.stellar_network_passphrase);
+        let block_hashes = BlockHashIndex::new(&config.stellar_network_passphrase, None);
+        let state = Arc::new(RpcState {
+            config,
+            soroban_client,
+            abi_registry: AbiRegistry::new(),
+            translation_stats: Arc::new(TranslationStats::default()),
+            address_mapper: Arc::new(DefaultAddressMapper::new()),
+            block_hashes: Arc::new(block_hashes),
+            upstream: Arc::new(RwLock::new(UpstreamStatus::default())),
+            pending: Arc::new(PendingTxStore::new()),
+        });
+        let mut module = RpcModule::new(state);
+        register_methods(&mut module).unwrap();
+
+        let block_hash = format!("0x{}", "ab".repeat(32));
+        let count: serde_json::Value = module.call("eth_getUncleCountByBlockNumber", ["latest"]).await.unwrap();
+        assert_eq!(count, "0x0");
+        let count: serde_json::Value = module.call("eth_getUncleCountByBlockHash", [&block_hash]).await.unwrap();
+        assert_eq!(count, "0x0");
+        let uncle: serde_json::Value = module
+            .call("eth_getUncleByBlockNumberAndIndex", ["0x10", "0x0"])
+            .await
+            .unwrap();
+        assert_eq!(uncle, serde_json::Value::Null);
+        let uncle: serde_json::Value = module
+            .call("eth_getUncleByBlockHashAndIndex", [&block_hash, "0x0"])
+            .await
+            .unwrap();
+        assert_eq!(uncle, serde_json::Value::Null);
+
+        for method in eth::MINING_METHODS {
+            let err = module.call::<_, serde_json::Value>(method, [0u8; 0]).await.unwrap_err();
+            let jsonrpsee::core::server::MethodsError::JsonRpc(obj) = err else { panic!("{}: {:?}", method, err) };
+            assert_eq!(obj.code(), crate::error::METHOD_NOT_SUPPORTED_CODE);
+            assert_eq!(obj.message(), format!("{}: mining is not supported on TVA; Stellar uses SCP consensus", method));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_request_is_rejected() {
+        let config = Config::from_lookup(|key| match key {
+            "STELLAR_SECRET_KEY" => Some("SECRET".to_string()),
+            "MAX_REQUEST_BYTES" => Some("256".to_string()),
+            _ => None,
+        })
+        .unwrap();
+
+        let mut module = RpcModule::new(());
+        module
+            .register_async_method("echo", |params, _, _| async move {
+                let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+                Ok::<_, jsonrpsee::types::ErrorObjectOwned>(p)
+            })
+            .unwrap();
+        let server = server_builder(&config).build("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.start(module);
+
+        let http = reqwest::Client::new();
+        let request = |data: String| {
+            http.post(format!("http://{}", addr))
+                .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "echo", "params": [data] }))
+                .send()
+        };
+
+        let response = request("0x00".to_string()).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let response = request(format!("0x{}", "00".repeat(256))).await.unwrap();
+        assert_eq!(response.status(), 413);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], jsonrpsee::types::error::OVERSIZED_REQUEST_CODE);
+
+        handle.stop().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gzip_request_and_response_bodies() {
+        use flate2::{read::GzDecoder, write::GzEncoder, Compression as Level};
+        use std::io::{Read, Write};
+
+        let config = Config::from_lookup(|key| match key {
+            "STELLAR_SECRET_KEY" => Some("SECRET".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let mut module = RpcModule::new(());
+        module
+            .register_async_method("echo", |params, _, _| async move {
+                let p: Vec<serde_json::Value> = params.parse().unwrap_or_default();
+                Ok::<_, jsonrpsee::types::ErrorObjectOwned>(p)
+            })
+            .unwrap();
+        let server = server_builder(&config)
+            .set_http_middleware(compression())
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.start(module);
+
+        // Decode bodies by hand to see what went over the wire
+        let http = reqwest::Client::builder().no_gzip().no_deflate().build().unwrap();
+        let data = format!("0x{}", "ab".repeat(4096));
+        let request = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "echo", "params": [data] }).to_string();
+        let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(request.as_bytes()).unwrap();
+        let compressed_request = encoder.finish().unwrap();
+
+        let response = http
+            .post(format!("http://{}", addr))
+            .header("content-type", "application/json")
+            .header("content-encoding", "gzip")
+            .header("accept-encoding", "gzip")
+            .body(compressed_request)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+        let compressed = response.bytes().await.unwrap();
+        assert!(compressed.len() < data.len() / 10);
+
+        let mut body = String::new();
+        GzDecoder::new(&compressed[..]).read_to_string(&mut body).unwrap();
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["result"][0], data);
+
+        // Clients that do not ask for compression get plain JSON
+        let response = http
+            .post(format!("http://{}", addr))
+            .header("content-type", "application/json")
+            .body(request)
+            .send()
+            .await
+            .unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["result"][0], data);
+
+        handle.stop().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_response_id_type_matches_request() {
+        let config = Config::from_lookup(|key| match key {
+            "STELLAR_SECRET_KEY" => Some("SECRET".to_string()),
+            "RPC_METHOD_DENYLIST" => Some("eth_mining".to_string()),
+            _ => None,
+        })
+        .unwrap();
+
+        let mut module = RpcModule::new(());
+        for method in ["eth_chainId", "eth_mining"] {
+            module
+                .register_method(method, |_, _, _| Ok::<_, jsonrpsee::types::ErrorObjectOwned>("0x1"))
+                .unwrap();
+        }
+        let server = server_builder(&config)
+            .set_rpc_middleware(rpc_middleware(&config, Arc::new(Metrics::new())))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let handle = server.start(module);
+
+        let http = reqwest::Client::new();
+        let cases = [
+            ("eth_chainId", serde_json::json!("abc")),
+            ("eth_chainId", serde_json::json!("7")),
+            ("eth_chainId", serde_json::json!(7)),
+            ("eth_mining", serde_json::json!("abc")),
+            ("eth_unknown", serde_json::json!("abc")),
+        ];
+        for (method, id) in cases {
+            let body: serde_json::Value = http
+                .post(format!("http://{}", addr))
+                .json(&serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method }))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(body["id"], id, "{} with id {}", method, id);
+        }
+
+        handle.stop().unwrap();
+    }
 }
@@ -0,0 +1,476 @@
+//! Optional on-disk persistence for the mappings the server builds up while
+//! running: hashed EVM addresses of contracts and accounts, and the block
+//! hashes it has handed out. Without it (the default) they live in memory and
+//! are rebuilt as addresses and blocks are seen again after a restart.
+//!
+//! The store is an append-only file of `namespace<TAB>key<TAB>value` lines,
+//! loaded on open. New entries update the in-memory map at once and are
+//! written by a background thread, so neither the map lock nor the async
+//! handlers that record entries ever wait on the disk. A later line for the
+//! same key wins; a line that is not a well-formed entry of its namespace
+//! (such as one torn by a crash mid-write) is skipped. Every namespace is
+//! capped, dropping its oldest entries first, and the file is rewritten with
+//! just the live entries once it has grown to twice their number.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, RwLock};
+use std::thread::JoinHandle;
+
+use anyhow::{anyhow, Context, Result};
+use tracing::{debug, info, warn};
+
+use crate::translator::address_map::DEFAULT_RECORDED_KEYS_CAPACITY;
+
+/// The maps kept in a store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    /// Hashed EVM address -> contract ID (hex)
+    Contract,
+    /// Hashed EVM address -> account key (hex)
+    Account,
+    /// Block hash -> ledger sequence (decimal)
+    BlockHash,
+}
+
+impl Namespace {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Namespace::Contract => "contract",
+            Namespace::Account => "account",
+            Namespace::BlockHash => "block",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "contract" => Some(Namespace::Contract),
+            "account" => Some(Namespace::Account),
+            "block" => Some(Namespace::BlockHash),
+            _ => None,
+        }
+    }
+
+    /// Entries of this namespace a store keeps: as many addresses as an
+    /// address map holds in memory, and a week of block hashes.
+    fn capacity(&self) -> usize {
+        match self {
+            Namespace::Contract | Namespace::Account => DEFAULT_RECORDED_KEYS_CAPACITY,
+            Namespace::BlockHash => MAX_STORED_BLOCK_HASHES,
+        }
+    }
+
+    /// Whether `key` and `value` are well-formed for this namespace: a
+    /// 20-byte EVM address and a 32-byte key, or a 32-byte block hash and a
+    /// ledger sequence. Keys and hex values are lowercase, as they are written.
+    fn is_valid_entry(&self, key: &str, value: &str) -> bool {
+        let is_hex = |s: &str, len: usize| {
+            s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        };
+        let key_hex = |len: usize| key.strip_prefix("0x").is_some_and(|digits| is_hex(digits, len));
+        match self {
+            Namespace::Contract | Namespace::Account => key_hex(40) && is_hex(value, 64),
+            Namespace::BlockHash => {
+                key_hex(64) && value.bytes().all(|b| b.is_ascii_digit()) && value.parse::<u64>().is_ok()
+            }
+        }
+    }
+}
+
+/// Block hashes a store keeps: a week of ledgers. Older blocks fall out of
+/// Soroban RPC's retention window, so their hashes are dropped first.
+pub const MAX_STORED_BLOCK_HASHES: usize = 7 * 17_280;
+
+/// Lines below which the file is never rewritten.
+const COMPACTION_MIN_LINES: usize = 10_000;
+
+/// A key-value store whose entries are written to one append-only file in
+/// the background.
+#[derive(Debug)]
+pub struct StateStore {
+    path: PathBuf,
+    entries: RwLock<Entries>,
+    /// Queue of the writer thread; taken on drop to stop it
+    writes: Option<mpsc::Sender<FileWrite>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+/// Work for the writer thread, done in the order it was queued.
+#[derive(Debug)]
+enum FileWrite {
+    /// Append one entry line
+    Append(String),
+    /// Replace the file with these live entries
+    Compact(String),
+}
+
+/// The live entries of a store and how many lines its file holds.
+#[derive(Debug, Default)]
+struct Entries {
+    /// (namespace, key) -> (value, age rank)
+    map: HashMap<(Namespace, String), (String, u64)>,
+    /// (age rank, key) of each namespace's entries, oldest first. Block hashes
+    /// rank by ledger sequence, other entries by when they were stored.
+    order: HashMap<Namespace, BTreeSet<(u64, String)>>,
+    stored: u64,
+    lines: usize,
+}
+
+impl Entries {
+    fn insert(&mut self, namespace: Namespace, key: &str, value: &str) {
+        let rank = match namespace {
+            Namespace::BlockHash => value.parse().unwrap_or(0),
+            Namespace::Contract | Namespace::Account => {
+                self.stored += 1;
+                self.stored
+            }
+        };
+        let order = self.order.entry(namespace).or_default();
+        if let Some((_, previous)) = self.map.insert((namespace, key.to_string()), (value.to_string(), rank)) {
+            order.remove(&(previous, key.to_string()));
+        }
+        order.insert((rank, key.to_string()));
+        while order.len() > namespace.capacity() {
+            if let Some((_, oldest)) = order.pop_first() {
+                self.map.remove(&(namespace, oldest));
+            }
+        }
+    }
+
+    fn get(&self, namespace: Namespace, key: &str) -> Option<&str> {
+        self.map.get(&(namespace, key.to_string())).map(|(value, _)| value.as_str())
+    }
+
+    /// Entries of a namespace, oldest first.
+    fn iter(&self, namespace: Namespace) -> impl Iterator<Item = (&str, &str)> {
+        self.order.get(&namespace).into_iter().flatten().filter_map(move |(_, key)| {
+            self.get(namespace, key).map(|value| (key.as_str(), value))
+        })
+    }
+
+    /// Whether the file holds enough dead lines to be worth rewriting.
+    fn compaction_due(&self) -> bool {
+        self.lines >= COMPACTION_MIN_LINES && self.lines > 2 * self.map.len()
+    }
+
+    /// The file contents for the live entries, oldest first so that a reload
+    /// keeps their order.
+    fn contents(&self) -> String {
+        [Namespace::Contract, Namespace::Account, Namespace::BlockHash]
+            .into_iter()
+            .flat_map(|namespace| {
+                self.iter(namespace)
+                    .map(move |(key, value)| format!("{}\t{}\t{}\n", namespace.as_str(), key, value))
+            })
+            .collect()
+    }
+}
+
+impl StateStore {
+    /// Open the store at `path`, creating the file if it does not exist and
+    /// loading every entry already in it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Cannot open state store {}", path.display()))?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .with_context(|| format!("Cannot read state store {}", path.display()))?;
+        // Terminate a torn last line so the next entry starts on its own line
+        if !contents.is_empty() && !contents.ends_with('\n') {
+            file.write_all(b"\n")
+                .with_context(|| format!("Cannot write state store {}", path.display()))?;
+        }
+
+        let mut entries = Entries::default();
+        let mut skipped = 0;
+        for line in contents.lines() {
+            entries.lines += 1;
+            let mut fields = line.splitn(3, '\t');
+            match (fields.next().and_then(Namespace::parse), fields.next(), fields.next()) {
+                (Some(namespace), Some(key), Some(value)) if namespace.is_valid_entry(key, value) => {
+                    entries.insert(namespace, key, value);
+                }
+                _ => skipped += 1,
+            }
+        }
+        if skipped > 0 {
+            warn!("Skipped {} malformed lines in state store {}", skipped, path.display());
+        }
+        info!("Loaded {} entries from state store {}", entries.map.len(), path.display());
+
+        // Drop the skipped, superseded and evicted lines right away
+        if skipped > 0 || entries.compaction_due() {
+            file = compact(&path, &entries.contents())?;
+            debug!("Compacted state store {} from {} to {} lines", path.display(), entries.lines, entries.map.len());
+            entries.lines = entries.map.len();
+        }
+
+        let (writes, queue) = mpsc::channel();
+        let writer_path = path.clone();
+        let writer = std::thread::Builder::new()
+            .name("state-store-writer".to_string())
+            .spawn(move || write_queued(&writer_path, file, queue))
+            .context("Cannot start the state store writer")?;
+
+        Ok(Self {
+            path,
+            entries: RwLock::new(entries),
+            writes: Some(writes),
+            writer: Some(writer),
+        })
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The value stored for `key`, if any.
+    pub fn get(&self, namespace: Namespace, key: &str) -> Option<String> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries.get(namespace, key).map(str::to_string)
+    }
+
+    /// Every entry of a namespace, oldest first.
+    pub fn entries(&self, namespace: Namespace) -> Vec<(String, String)> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .iter(namespace)
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Store `value` for `key` and queue it to be appended to the file.
+    /// Entries that are already stored with the same value are not written
+    /// again; entries that are not well-formed for the namespace are
+    /// rejected. Write failures are logged by the writer thread.
+    pub fn put(&self, namespace: Namespace, key: &str, value: &str) -> Result<()> {
+        if !namespace.is_valid_entry(key, value) {
+            return Err(anyhow!("Invalid {} entry '{}' -> '{}' for state store", namespace.as_str(), key, value));
+        }
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        if entries.get(namespace, key) == Some(value) {
+            return Ok(());
+        }
+        entries.insert(namespace, key, value);
+        entries.lines += 1;
+
+        // Queued under the lock, so the file sees entries in the order the map did
+        let mut queued = vec![FileWrite::Append(format!("{}\t{}\t{}\n", namespace.as_str(), key, value))];
+        if entries.compaction_due() {
+            queued.push(FileWrite::Compact(entries.contents()));
+            entries.lines = entries.map.len();
+        }
+        let writes = self.writes.as_ref().ok_or_else(|| anyhow!("State store {} is closed", self.path.display()))?;
+        for write in queued {
+            writes
+                .send(write)
+                .map_err(|_| anyhow!("State store writer for {} has stopped", self.path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// `put`, logging instead of failing: the in-memory maps stay correct
+    /// without the store, so an error only costs persistence.
+    pub fn record(&self, namespace: Namespace, key: &str, value: &str) {
+        if let Err(e) = self.put(namespace, key, value) {
+            warn!("{:#}", e);
+        }
+    }
+}
+
+impl Drop for StateStore {
+    /// Write out everything queued before the store goes away.
+    fn drop(&mut self) {
+        drop(self.writes.take());
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Body of the writer thread: apply queued writes until the store is dropped,
+/// flushing once per batch of writes that arrived together.
+fn write_queued(path: &Path, file: File, queue: mpsc::Receiver<FileWrite>) {
+    let mut file = BufWriter::new(file);
+    while let Ok(first) = queue.recv() {
+        for write in std::iter::once(first).chain(queue.try_iter()) {
+            match write {
+                FileWrite::Append(line) => {
+                    if let Err(e) = file.write_all(line.as_bytes()) {
+                        warn!("Cannot write state store {}: {}", path.display(), e);
+                    }
+                }
+                // The live entries supersede anything still buffered for the old file
+                FileWrite::Compact(contents) => match compact(path, &contents) {
+                    Ok(compacted) => {
+                        debug!("Compacted state store {} to {} lines", path.display(), contents.lines().count());
+                        file = BufWriter::new(compacted);
+                    }
+                    Err(e) => warn!("{:#}", e),
+                },
+            }
+        }
+        if let Err(e) = file.flush() {
+            warn!("Cannot write state store {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Replace the file at `path` with `contents` and open it for appending. The
+/// new file replaces the old one by rename, so a crash leaves one or the other
+/// intact.
+fn compact(path: &Path, contents: &str) -> Result<File> {
+    let compacted = path.with_extension("compact");
+    std::fs::write(&compacted, contents)
+        .and_then(|_| std::fs::rename(&compacted, path))
+        .and_then(|_| OpenOptions::new().append(true).open(path))
+        .with_context(|| format!("Cannot compact state store {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("tva-state-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn address(byte: u8) -> String {
+        format!("0x{}", hex::encode([byte; 20]))
+    }
+
+    fn block_hash(n: u64) -> String {
+        format!("0x{:064x}", n)
+    }
+
+    #[test]
+    fn test_entries_survive_reopen() {
+        let path = temp_path("reopen");
+        let (key_a, key_b) = (hex::encode([0x11; 32]), hex::encode([0x22; 32]));
+        {
+            let store = StateStore::open(&path).unwrap();
+            store.put(Namespace::Contract, &address(0xaa), &key_a).unwrap();
+            store.put(Namespace::Account, &address(0xaa), &key_b).unwrap();
+            store.put(Namespace::BlockHash, &block_hash(0xbb), "900").unwrap();
+            store.put(Namespace::BlockHash, &block_hash(0xbb), "900").unwrap();
+        }
+
+        let store = StateStore::open(&path).unwrap();
+        assert_eq!(store.get(Namespace::Contract, &address(0xaa)), Some(key_a.clone()));
+        assert_eq!(store.get(Namespace::Account, &address(0xaa)), Some(key_b.clone()));
+        assert_eq!(store.get(Namespace::BlockHash, &block_hash(0xbb)).as_deref(), Some("900"));
+        assert_eq!(store.get(Namespace::BlockHash, &address(0xaa)), None);
+        assert_eq!(store.entries(Namespace::Contract), vec![(address(0xaa), key_a)]);
+
+        // Unchanged entries are written once; a new value for a key is appended and wins
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+        store.put(Namespace::Contract, &address(0xaa), &key_b).unwrap();
+        drop(store);
+        assert_eq!(StateStore::open(&path).unwrap().get(Namespace::Contract, &address(0xaa)), Some(key_b));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_malformed_lines_are_skipped() {
+        let path = temp_path("torn");
+        let key = hex::encode([0x44; 32]);
+        let lines = [
+            format!("contract\t{}\t{}", address(0xaa), key),
+            format!("unknown\t{}\t{}", address(0xbb), key),
+            // Torn inside the value, and a value of the wrong namespace
+            format!("account\t{}\t{}", address(0xcc), &key[..63]),
+            format!("block\t{}\t{}", block_hash(1), key),
+            format!("account\t{}", address(0xdd)),
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let store = StateStore::open(&path).unwrap();
+        assert_eq!(store.get(Namespace::Contract, &address(0xaa)), Some(key.clone()));
+        assert_eq!(store.get(Namespace::Account, &address(0xcc)), None);
+        assert_eq!(store.get(Namespace::BlockHash, &block_hash(1)), None);
+        assert_eq!(store.get(Namespace::Account, &address(0xdd)), None);
+
+        // The skipped lines are dropped from the file, and malformed entries are never written
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert!(store.put(Namespace::Account, &address(0xee), "44").is_err());
+        assert!(store.put(Namespace::BlockHash, &block_hash(2), "0x10").is_err());
+        assert!(store.put(Namespace::Contract, "0xAA", &key).is_err());
+
+        store.put(Namespace::Account, &address(0xdd), &key).unwrap();
+        drop(store);
+        assert_eq!(StateStore::open(&path).unwrap().get(Namespace::Account, &address(0xdd)), Some(key));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_is_compacted() {
+        let path = temp_path("compact");
+        let store = StateStore::open(&path).unwrap();
+        let keys = [hex::encode([1; 32]), hex::encode([2; 32])];
+        for i in 0..COMPACTION_MIN_LINES {
+            store.put(Namespace::Contract, &address(0xaa), &keys[i % 2]).unwrap();
+        }
+        store.put(Namespace::Account, &address(0xbb), &keys[0]).unwrap();
+        assert_eq!(store.get(Namespace::Contract, &address(0xaa)), Some(keys[1].clone()));
+
+        // Superseded lines were dropped once they made up half the file
+        drop(store);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        let store = StateStore::open(&path).unwrap();
+        assert_eq!(store.get(Namespace::Contract, &address(0xaa)), Some(keys[1].clone()));
+        assert_eq!(store.get(Namespace::Account, &address(0xbb)), Some(keys[0].clone()));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_oldest_block_hashes_are_dropped() {
+        let mut entries = Entries::default();
+        for seq in 0..=MAX_STORED_BLOCK_HASHES as u64 {
+            entries.insert(Namespace::BlockHash, &block_hash(seq), &seq.to_string());
+        }
+        entries.insert(Namespace::Contract, &address(0xaa), &hex::encode([1; 32]));
+
+        assert_eq!(entries.order[&Namespace::BlockHash].len(), MAX_STORED_BLOCK_HASHES);
+        assert_eq!(entries.get(Namespace::BlockHash, &block_hash(0)), None);
+        assert_eq!(entries.get(Namespace::BlockHash, &block_hash(1)), Some("1"));
+        assert_eq!(entries.map.len(), MAX_STORED_BLOCK_HASHES + 1);
+    }
+
+    #[test]
+    fn test_oldest_addresses_are_dropped() {
+        let mut entries = Entries::default();
+        let address_of = |i: usize| format!("0x{:040x}", i);
+        let key = hex::encode([1; 32]);
+        for i in 0..DEFAULT_RECORDED_KEYS_CAPACITY {
+            entries.insert(Namespace::Account, &address_of(i), &key);
+        }
+        // Storing the first address again makes it the newest
+        entries.insert(Namespace::Account, &address_of(0), &hex::encode([2; 32]));
+        entries.insert(Namespace::Account, &address_of(DEFAULT_RECORDED_KEYS_CAPACITY), &key);
+        entries.insert(Namespace::Contract, &address_of(1), &key);
+
+        assert_eq!(entries.order[&Namespace::Account].len(), DEFAULT_RECORDED_KEYS_CAPACITY);
+        assert_eq!(entries.get(Namespace::Account, &address_of(1)), None);
+        assert_eq!(entries.get(Namespace::Account, &address_of(2)), Some(key.as_str()));
+        assert_eq!(entries.get(Namespace::Contract, &address_of(1)), Some(key.as_str()));
+
+        // The file is rewritten oldest first, so a reload evicts in the same order
+        let keys: Vec<&str> = entries.iter(Namespace::Account).map(|(key, _)| key).collect();
+        assert_eq!(keys[0], address_of(2));
+        assert_eq!(keys[keys.len() - 2..], [address_of(0), address_of(DEFAULT_RECORDED_KEYS_CAPACITY)]);
+    }
+}
@@ -0,0 +1,147 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::{info, warn};
+
+/// Consecutive upstream failures that open the circuit unless configured otherwise.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open circuit fails calls fast unless configured otherwise.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Circuit breaker around upstream requests.
+///
+/// Closed, requests go through and consecutive failures are counted. After
+/// `failure_threshold` of them the circuit opens and requests fail fast for
+/// `cooldown`. The first request after that is let through as a probe
+/// (half-open): success closes the circuit, failure opens it for another
+/// cooldown. Requests arriving while the probe is in flight still fail fast.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    /// When the circuit (re)opened; None while closed
+    opened_at: Option<Instant>,
+    /// Whether a probe request is being let through
+    half_open: bool,
+}
+
+impl CircuitBreaker {
+    /// Open after `failure_threshold` consecutive failures (0 never opens)
+    /// and fail fast for `cooldown` before probing.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Whether a request may go upstream now. When the circuit is open, the
+    /// error is how long until the next probe.
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.lock();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.cooldown {
+            return Err(self.cooldown - elapsed);
+        }
+        // Let this request probe; others wait out another cooldown meanwhile
+        state.opened_at = Some(Instant::now());
+        state.half_open = true;
+        Ok(())
+    }
+
+    /// Record a request the upstream answered.
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        if state.opened_at.is_some() {
+            info!("Soroban RPC answered again; closing the circuit");
+        }
+        *state = BreakerState::default();
+    }
+
+    /// Record a request the upstream failed to answer.
+    pub fn record_failure(&self) {
+        if self.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        if state.half_open {
+            warn!("Soroban RPC probe failed; failing calls fast for another {:?}", self.cooldown);
+        } else if state.opened_at.is_none() && state.consecutive_failures >= self.failure_threshold {
+            warn!(
+                "Soroban RPC failed {} times in a row; failing calls fast for {:?}",
+                state.consecutive_failures, self.cooldown
+            );
+        } else {
+            return;
+        }
+        state.opened_at = Some(Instant::now());
+        state.half_open = false;
+    }
+
+    /// Whether the circuit is open (or half-open, probing).
+    pub fn is_open(&self) -> bool {
+        self.lock().opened_at.is_some()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_probes_after_cooldown() {
+        let breaker = CircuitBreaker::new(3, Duration::from_millis(50));
+        for _ in 0..2 {
+            breaker.try_acquire().unwrap();
+            breaker.record_failure();
+        }
+        assert!(!breaker.is_open());
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        assert!(breaker.try_acquire().is_err());
+
+        // One probe after the cooldown; a failed probe reopens the circuit
+        std::thread::sleep(Duration::from_millis(60));
+        breaker.try_acquire().unwrap();
+        assert!(breaker.try_acquire().is_err());
+        breaker.record_failure();
+        assert!(breaker.try_acquire().is_err());
+
+        // A successful probe closes it
+        std::thread::sleep(Duration::from_millis(60));
+        breaker.try_acquire().unwrap();
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        breaker.try_acquire().unwrap();
+
+        // A success resets the count
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert!(!breaker.is_open());
+
+        let disabled = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..100 {
+            disabled.record_failure();
+        }
+        assert!(disabled.try_acquire().is_ok());
+    }
+}
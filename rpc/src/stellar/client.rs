@@ -1,49 +1,198 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::{anyhow, Context, Result};
 use reqwest::Client;
-use tracing::{debug, error, info};
+use tracing::{debug, debug_span, error, info, Instrument};
 
+use super::breaker::CircuitBreaker;
 use super::types::*;
+use crate::error::TvaError;
+use crate::metrics::Metrics;
+
+/// Page size for getTransactions (the Soroban RPC maximum).
+const TRANSACTIONS_PAGE_LIMIT: usize = 200;
+
+/// Stroops per XLM; Horizon amounts carry 7 decimal places.
+const STROOPS_PER_XLM: u64 = 10_000_000;
+
+/// Idle connections kept open per upstream host unless configured otherwise.
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 32;
+
+/// How long an idle upstream connection is kept unless configured otherwise.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Keep-alive pings on idle connections (TCP, and HTTP/2 where negotiated),
+/// so load balancers do not silently drop pooled connections.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long an HTTP/2 keep-alive ping may go unanswered before the
+/// connection is closed.
+const HTTP2_KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Soroban RPC methods sent to the write endpoint; everything else reads.
+const WRITE_METHODS: &[&str] = &["sendTransaction"];
+
+/// Fail a Soroban RPC response that carries an error object.
+fn rpc_result(rpc_response: SorobanRpcResponse) -> Result<SorobanRpcResponse> {
+    if let Some(err) = &rpc_response.error {
+        error!(
+            "Soroban RPC error: code={}, message={}",
+            err.code, err.message
+        );
+        return Err(TvaError::Upstream(format!(
+            "Soroban RPC error {}: {}",
+            err.code,
+            err.message
+        ))
+        .into());
+    }
+    Ok(rpc_response)
+}
 
 /// Client wrapper for Soroban RPC API calls.
 #[derive(Clone)]
 pub struct SorobanClient {
     http_client: Client,
     rpc_url: String,
+    /// Endpoint for `WRITE_METHODS`; the read endpoint unless configured
+    write_rpc_url: String,
+    horizon_url: String,
     network_passphrase: String,
+    metrics: Option<Arc<Metrics>>,
+    /// Fails Soroban RPC calls fast while the upstream is down
+    breaker: Option<Arc<CircuitBreaker>>,
 }
 
 impl SorobanClient {
     /// Create a new Soroban RPC client.
+    /// The Horizon URL is derived from the RPC URL (testnet or mainnet).
     pub fn new(rpc_url: &str, network_passphrase: &str) -> Self {
+        // Derive Horizon URL from Soroban RPC URL
+        let horizon_url = if rpc_url.contains("testnet") {
+            "https://horizon-testnet.stellar.org"
+        } else {
+            "https://horizon.stellar.org"
+        };
+
         Self {
-            http_client: Client::new(),
+            http_client: pooled_http_client(DEFAULT_POOL_MAX_IDLE_PER_HOST, DEFAULT_POOL_IDLE_TIMEOUT),
             rpc_url: rpc_url.to_string(),
+            write_rpc_url: rpc_url.to_string(),
+            horizon_url: horizon_url.to_string(),
             network_passphrase: network_passphrase.to_string(),
+            metrics: None,
+            breaker: None,
         }
     }
 
+    /// Use a specific Horizon endpoint instead of the derived one.
+    pub fn with_horizon_url(mut self, horizon_url: &str) -> Self {
+        self.horizon_url = horizon_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Submit transactions to a separate Soroban RPC endpoint, leaving reads
+    /// (simulation, events, ledger entries) on the one passed to `new`.
+    pub fn with_write_rpc_url(mut self, write_rpc_url: &str) -> Self {
+        self.write_rpc_url = write_rpc_url.to_string();
+        self
+    }
+
+    /// Keep up to `max_idle_per_host` idle connections per upstream host, each
+    /// for up to `idle_timeout`, so calls reuse connections instead of paying
+    /// a TCP and TLS handshake each.
+    pub fn with_connection_pool(mut self, max_idle_per_host: usize, idle_timeout: Duration) -> Self {
+        self.http_client = pooled_http_client(max_idle_per_host, idle_timeout);
+        self
+    }
+
+    /// Fail Soroban RPC calls fast for `cooldown` once `failure_threshold`
+    /// calls in a row could not reach it, then probe with the next call.
+    /// A threshold of 0 never opens the circuit.
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.breaker = Some(Arc::new(CircuitBreaker::new(failure_threshold, cooldown)));
+        self
+    }
+
+    /// Whether Soroban RPC calls are currently failing fast.
+    pub fn circuit_open(&self) -> bool {
+        self.breaker.as_ref().is_some_and(|breaker| breaker.is_open())
+    }
+
+    /// Record failed Soroban RPC requests in the given metrics registry.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Get the network passphrase.
     pub fn network_passphrase(&self) -> &str {
         &self.network_passphrase
     }
 
     /// Send a JSON-RPC request to the Soroban RPC endpoint.
+    /// Runs in a `soroban_request` span nested under the caller's request span,
+    /// so upstream traffic is correlated with the originating JSON-RPC call.
+    /// While the circuit breaker is open, fails without a round trip.
     async fn send_request(&self, request: &SorobanRpcRequest) -> Result<SorobanRpcResponse> {
+        let span = debug_span!("soroban_request", upstream_method = %request.method);
+        let started = std::time::Instant::now();
+        let result = match self.breaker.as_deref().map(CircuitBreaker::try_acquire) {
+            Some(Err(retry_in)) => Err(TvaError::Upstream(format!(
+                "Soroban RPC is unavailable: circuit open after repeated failures, retrying in {}s",
+                retry_in.as_secs().max(1)
+            ))
+            .into()),
+            _ => {
+                let result = self.dispatch_request(request).instrument(span.clone()).await;
+                // An error object is an answer; only a missing or garbled reply counts as a failure
+                if let Some(breaker) = &self.breaker {
+                    match &result {
+                        Ok(_) => breaker.record_success(),
+                        Err(_) => breaker.record_failure(),
+                    }
+                }
+                result.and_then(rpc_result)
+            }
+        };
+        span.in_scope(|| match &result {
+            Ok(_) => debug!("Soroban RPC responded in {:?}", started.elapsed()),
+            Err(e) => debug!("Soroban RPC failed after {:?}: {}", started.elapsed(), e),
+        });
+        if result.is_err() {
+            if let Some(metrics) = &self.metrics {
+                metrics.record_upstream_error(&request.method);
+            }
+        }
+        result
+    }
+
+    /// Perform the HTTP round trip for a Soroban RPC request.
+    async fn dispatch_request(&self, request: &SorobanRpcRequest) -> Result<SorobanRpcResponse> {
         debug!("Sending Soroban RPC request: method={}", request.method);
 
+        let url = if WRITE_METHODS.contains(&request.method.as_str()) {
+            &self.write_rpc_url
+        } else {
+            &self.rpc_url
+        };
         let response = self
             .http_client
-            .post(&self.rpc_url)
+            .post(url)
             .json(request)
             .send()
             .await
-            .context("Failed to send request to Soroban RPC")?;
+            .map_err(|e| {
+                let reason = if e.is_timeout() { "timed out" } else { "failed" };
+                TvaError::Upstream(format!("Request to Soroban RPC {}: {}", reason, e))
+            })?;
 
         let status = response.status();
         if !status.is_success() {
             let body = response.text().await.unwrap_or_default();
             error!("Soroban RPC returned HTTP {}: {}", status, body);
-            return Err(anyhow!("Soroban RPC HTTP error: {} - {}", status, body));
+            return Err(TvaError::Upstream(format!("Soroban RPC HTTP error: {} - {}", status, body)).into());
         }
 
         let rpc_response: SorobanRpcResponse = response
@@ -51,18 +200,6 @@ impl SorobanClient {
             .await
             .context("Failed to parse Soroban RPC response")?;
 
-        if let Some(err) = &rpc_response.error {
-            error!(
-                "Soroban RPC error: code={}, message={}",
-                err.code, err.message
-            );
-            return Err(anyhow!(
-                "Soroban RPC error {}: {}",
-                err.code,
-                err.message
-            ));
-        }
-
         Ok(rpc_response)
     }
 
@@ -97,6 +234,44 @@ impl SorobanClient {
         serde_json::from_value(result).context("Failed to parse getTransaction response")
     }
 
+    /// Get all transactions applied in a ledger, in application order.
+    pub async fn get_ledger_transactions(&self, sequence: u64) -> Result<Vec<TransactionInfo>> {
+        let mut transactions = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            // startLedger and cursor are mutually exclusive
+            let params = match &cursor {
+                Some(cursor) => serde_json::json!({
+                    "pagination": { "cursor": cursor, "limit": TRANSACTIONS_PAGE_LIMIT }
+                }),
+                None => serde_json::json!({
+                    "startLedger": sequence,
+                    "pagination": { "limit": TRANSACTIONS_PAGE_LIMIT }
+                }),
+            };
+            let request = SorobanRpcRequest::new("getTransactions", Some(params));
+            let response = self.send_request(&request).await?;
+            let result = response
+                .result
+                .ok_or_else(|| anyhow!("No result in getTransactions response"))?;
+            let page: GetTransactionsResponse = serde_json::from_value(result)
+                .context("Failed to parse getTransactions response")?;
+
+            let page_len = page.transactions.len();
+            let passed_ledger = page.transactions.iter().any(|tx| tx.ledger > sequence);
+            transactions.extend(page.transactions.into_iter().filter(|tx| tx.ledger == sequence));
+
+            if passed_ledger || page_len < TRANSACTIONS_PAGE_LIMIT || page.cursor.is_none() {
+                break;
+            }
+            cursor = page.cursor;
+        }
+
+        transactions.sort_by_key(|tx| tx.application_order);
+        Ok(transactions)
+    }
+
     /// Simulate a transaction (for eth_call and eth_estimateGas).
     pub async fn simulate_transaction(
         &self,
@@ -153,45 +328,39 @@ impl SorobanClient {
 
     /// Get the account sequence number for a Stellar address.
     /// Uses Horizon API since Soroban RPC does not expose this directly.
+    /// An account that does not exist reads as sequence 0; any other Horizon
+    /// failure is an upstream error.
     pub async fn get_account_sequence(&self, account_id: &str) -> Result<u64> {
-        // Derive Horizon URL from Soroban RPC URL
-        let horizon_url = if self.rpc_url.contains("testnet") {
-            "https://horizon-testnet.stellar.org"
-        } else {
-            "https://horizon.stellar.org"
-        };
-
-        let url = format!("{}/accounts/{}", horizon_url, account_id);
+        let url = format!("{}/accounts/{}", self.horizon_url, account_id);
         let response = self
             .http_client
             .get(&url)
             .send()
             .await
-            .context("Failed to query Horizon for account")?;
+            .map_err(|e| TvaError::Upstream(format!("Request to Horizon for account failed: {}", e)))?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
             return Ok(0); // Account not found, return 0
         }
+        if !status.is_success() {
+            error!("Horizon returned HTTP {} for account {}", status, account_id);
+            return Err(TvaError::Upstream(format!("Horizon HTTP error: {} for account {}", status, account_id)).into());
+        }
 
-        let body: serde_json::Value = response.json().await?;
-        let sequence = body["sequence"]
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| TvaError::Upstream(format!("Invalid Horizon account response: {}", e)))?;
+        body["sequence"]
             .as_str()
-            .unwrap_or("0")
-            .parse::<u64>()
-            .unwrap_or(0);
-
-        Ok(sequence)
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| TvaError::Upstream(format!("Horizon account {} has no valid sequence", account_id)).into())
     }
 
     /// Get the XLM balance for a Stellar address in stroops.
     pub async fn get_xlm_balance(&self, account_id: &str) -> Result<u64> {
-        let horizon_url = if self.rpc_url.contains("testnet") {
-            "https://horizon-testnet.stellar.org"
-        } else {
-            "https://horizon.stellar.org"
-        };
-
-        let url = format!("{}/accounts/{}", horizon_url, account_id);
+        let url = format!("{}/accounts/{}", self.horizon_url, account_id);
         let response = self
             .http_client
             .get(&url)
@@ -210,16 +379,7 @@ impl SorobanClient {
             for balance in balances {
                 if balance["asset_type"].as_str() == Some("native") {
                     let balance_str = balance["balance"].as_str().unwrap_or("0");
-                    // Convert from XLM (7 decimal) to stroops
-                    let parts: Vec<&str> = balance_str.split('.').collect();
-                    let whole: u64 = parts[0].parse().unwrap_or(0);
-                    let frac: u64 = if parts.len() > 1 {
-                        let frac_str = format!("{:0<7}", parts[1]);
-                        frac_str[..7].parse().unwrap_or(0)
-                    } else {
-                        0
-                    };
-                    return Ok(whole * 10_000_000 + frac);
+                    return parse_xlm_amount(balance_str);
                 }
             }
         }
@@ -229,13 +389,7 @@ impl SorobanClient {
 
     /// Get the current base fee from the network.
     pub async fn get_base_fee(&self) -> Result<u64> {
-        let horizon_url = if self.rpc_url.contains("testnet") {
-            "https://horizon-testnet.stellar.org"
-        } else {
-            "https://horizon.stellar.org"
-        };
-
-        let url = format!("{}/fee_stats", horizon_url);
+        let url = format!("{}/fee_stats", self.horizon_url);
         let response = self
             .http_client
             .get(&url)
@@ -257,6 +411,57 @@ impl SorobanClient {
         Ok(fee)
     }
 
+    /// Get the latest ledger closed by the network, from Horizon's stellar-core view.
+    pub async fn get_network_head(&self) -> Result<u64> {
+        let response = self
+            .http_client
+            .get(&self.horizon_url)
+            .send()
+            .await
+            .context("Failed to query Horizon root")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Horizon root returned HTTP {}", response.status()));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        body["core_latest_ledger"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("Horizon root has no core_latest_ledger"))
+    }
+
+    /// Get a ledger's hash and close time from Horizon.
+    /// Returns None if Horizon does not have the ledger (not yet ingested or pruned).
+    pub async fn get_ledger_header(&self, sequence: u64) -> Result<Option<LedgerHeader>> {
+        let url = format!("{}/ledgers/{}", self.horizon_url, sequence);
+        let response = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to query Horizon for ledger")?;
+
+        if !response.status().is_success() {
+            debug!("Horizon has no ledger {}: HTTP {}", sequence, response.status());
+            return Ok(None);
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let hash = body["hash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Horizon ledger {} has no hash", sequence))?;
+        let closed_at = body["closed_at"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Horizon ledger {} has no closed_at", sequence))?;
+        let close_time = chrono::DateTime::parse_from_rfc3339(closed_at)
+            .with_context(|| format!("Invalid closed_at '{}' for ledger {}", closed_at, sequence))?;
+
+        Ok(Some(LedgerHeader {
+            hash: hash.to_string(),
+            close_time: close_time.timestamp() as u64,
+        }))
+    }
+
     /// Wait for a transaction to be confirmed, polling getTransaction.
     pub async fn wait_for_transaction(
         &self,
@@ -300,3 +505,206 @@ impl SorobanClient {
         ))
     }
 }
+
+/// Convert a Horizon decimal XLM amount (e.g. "123.4567890") to stroops.
+/// Digits beyond the 7th decimal place are below one stroop and truncated,
+/// so a balance is never overstated.
+fn parse_xlm_amount(amount: &str) -> Result<u64> {
+    let invalid = || anyhow!("Invalid XLM amount from Horizon: '{}'", amount);
+    let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
+
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    if !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let whole: u64 = whole.parse().map_err(|_| invalid())?;
+    // All-ASCII digits, so any byte index is a char boundary
+    let frac = &frac[..frac.len().min(7)];
+    let frac: u64 = if frac.is_empty() {
+        0
+    } else {
+        format!("{:0<7}", frac).parse().map_err(|_| invalid())?
+    };
+
+    whole
+        .checked_mul(STROOPS_PER_XLM)
+        .and_then(|stroops| stroops.checked_add(frac))
+        .ok_or_else(invalid)
+}
+
+/// HTTP client shared by all upstream calls, with the given pool limits and
+/// keep-alive on idle connections. Responses may come back gzip or deflate
+/// compressed; large getEvents and getTransactions pages shrink several-fold.
+fn pooled_http_client(max_idle_per_host: usize, idle_timeout: Duration) -> Client {
+    Client::builder()
+        .gzip(true)
+        .deflate(true)
+        .pool_max_idle_per_host(max_idle_per_host)
+        .pool_idle_timeout(idle_timeout)
+        .tcp_keepalive(KEEP_ALIVE_INTERVAL)
+        .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+        .http2_keep_alive_timeout(HTTP2_KEEP_ALIVE_TIMEOUT)
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .expect("HTTP client configuration is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{latest_ledger_response, spawn_mock_server, spawn_recording_mock_server};
+
+    const ACCOUNT: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    async fn horizon_client(routes: Vec<(&'static str, u16, String)>) -> SorobanClient {
+        let url = spawn_mock_server(routes).await;
+        SorobanClient::new(&url, "Test SDF Network ; September 2015").with_horizon_url(&url)
+    }
+
+    fn account_body(balance: &str) -> String {
+        serde_json::json!({
+            "id": ACCOUNT,
+            "sequence": "4294967302",
+            "balances": [
+                { "asset_type": "credit_alphanum4", "asset_code": "USDC", "balance": "9.0000000" },
+                { "asset_type": "native", "balance": balance }
+            ]
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_account_queries_use_configured_horizon() {
+        let client = horizon_client(vec![
+            ("GET /accounts/", 200, account_body("123.4567890")),
+            ("GET /fee_stats", 200, r#"{"last_ledger_base_fee":"250"}"#.to_string()),
+        ])
+        .await;
+
+        assert_eq!(client.get_xlm_balance(ACCOUNT).await.unwrap(), 1_234_567_890);
+        assert_eq!(client.get_account_sequence(ACCOUNT).await.unwrap(), 4_294_967_302);
+        assert_eq!(client.get_base_fee().await.unwrap(), 250);
+    }
+
+    #[tokio::test]
+    async fn test_missing_account_reads_as_empty() {
+        let client = horizon_client(vec![("GET /accounts/", 404, "{}".to_string())]).await;
+        assert_eq!(client.get_xlm_balance(ACCOUNT).await.unwrap(), 0);
+        assert_eq!(client.get_account_sequence(ACCOUNT).await.unwrap(), 0);
+        // fee_stats is not routed either: default base fee
+        assert_eq!(client.get_base_fee().await.unwrap(), 100);
+
+        // Other failures are not read as a fresh account
+        let failing = horizon_client(vec![("GET /accounts/", 500, "{}".to_string())]).await;
+        assert!(failing.get_account_sequence(ACCOUNT).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_xlm_amount_fraction_lengths() {
+        assert_eq!(parse_xlm_amount("10").unwrap(), 100_000_000);
+        assert_eq!(parse_xlm_amount("10.5").unwrap(), 105_000_000);
+        assert_eq!(parse_xlm_amount("10.1234567").unwrap(), 101_234_567);
+        // The 8th decimal is below one stroop and truncated
+        assert_eq!(parse_xlm_amount("10.12345678").unwrap(), 101_234_567);
+        assert_eq!(parse_xlm_amount("10.12345679999").unwrap(), 101_234_567);
+        assert_eq!(parse_xlm_amount("10.").unwrap(), 100_000_000);
+        assert!(parse_xlm_amount("10.1234567x").is_err());
+    }
+
+    #[test]
+    fn test_parse_xlm_amount() {
+        assert_eq!(parse_xlm_amount("123.4567890").unwrap(), 1_234_567_890);
+        assert_eq!(parse_xlm_amount("0.0000001").unwrap(), 1);
+        assert_eq!(parse_xlm_amount("10").unwrap(), 100_000_000);
+        assert_eq!(parse_xlm_amount("10.").unwrap(), 100_000_000);
+        // Short fractions are right-padded, not read as whole stroops
+        assert_eq!(parse_xlm_amount("1.5").unwrap(), 15_000_000);
+        assert_eq!(parse_xlm_amount("1.05").unwrap(), 10_500_000);
+
+        assert!(parse_xlm_amount("").is_err());
+        assert!(parse_xlm_amount(".5").is_err());
+        assert!(parse_xlm_amount("-1.0").is_err());
+        assert!(parse_xlm_amount("1.2.3").is_err());
+        assert!(parse_xlm_amount("18446744073709551615.0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_writes_go_to_write_endpoint() {
+        let simulation = r#"{"jsonrpc": "2.0", "id": 1, "result": {"latestLedger": 7}}"#.to_string();
+        let submission = r#"{"jsonrpc": "2.0", "id": 1, "result": {"status": "PENDING", "hash": "ab"}}"#.to_string();
+        let (read_url, reads) = spawn_recording_mock_server(vec![("rpc:simulateTransaction", 200, simulation)]).await;
+        let (write_url, writes) = spawn_recording_mock_server(vec![("rpc:sendTransaction", 200, submission)]).await;
+        let client = SorobanClient::new(&read_url, "Test SDF Network ; September 2015").with_write_rpc_url(&write_url);
+
+        client.simulate_transaction("AAAA").await.unwrap();
+        let sent = client.send_transaction("AAAA").await.unwrap();
+        assert_eq!(sent.hash.as_deref(), Some("ab"));
+
+        let methods = |requests: &Arc<std::sync::Mutex<Vec<String>>>| -> Vec<bool> {
+            let requests = requests.lock().unwrap();
+            requests.iter().map(|r| r.contains("sendTransaction")).collect()
+        };
+        assert_eq!(methods(&reads), vec![false]);
+        assert_eq!(methods(&writes), vec![true]);
+
+        // Without a write endpoint, submissions use the read one
+        let client = SorobanClient::new(&read_url, "Test SDF Network ; September 2015");
+        assert!(client.send_transaction("AAAA").await.is_err());
+        assert_eq!(methods(&reads), vec![false, true]);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_small_pool() {
+        let url = spawn_mock_server(vec![("rpc:getLatestLedger", 200, latest_ledger_response(42))]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_connection_pool(2, Duration::from_secs(5));
+
+        // Far more calls in flight than idle connections kept
+        let calls = (0..64).map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.get_latest_ledger().await })
+        });
+        for call in calls.collect::<Vec<_>>() {
+            assert_eq!(call.await.unwrap().unwrap().sequence, 42);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_when_upstream_down() {
+        let (url, requests) =
+            spawn_recording_mock_server(vec![("rpc:getLatestLedger", 503, "upstream down".to_string())]).await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_circuit_breaker(3, Duration::from_secs(60));
+
+        for _ in 0..3 {
+            assert!(client.get_latest_ledger().await.unwrap_err().to_string().contains("HTTP error"));
+        }
+        assert!(client.circuit_open());
+
+        // Past the threshold, calls fail without reaching the upstream
+        let started = std::time::Instant::now();
+        for _ in 0..10 {
+            let err = client.get_latest_ledger().await.unwrap_err();
+            assert!(err.to_string().contains("circuit open"), "{}", err);
+        }
+        assert!(started.elapsed() < Duration::from_millis(100));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        // Error objects are answers, not failures
+        let url = spawn_mock_server(vec![(
+            "rpc:getLatestLedger",
+            200,
+            r#"{"jsonrpc": "2.0", "id": 1, "error": {"code": -32600, "message": "bad"}}"#.to_string(),
+        )])
+        .await;
+        let client = SorobanClient::new(&url, "Test SDF Network ; September 2015")
+            .with_circuit_breaker(3, Duration::from_secs(60));
+        for _ in 0..5 {
+            assert!(client.get_latest_ledger().await.is_err());
+        }
+        assert!(!client.circuit_open());
+    }
+}
@@ -1,4 +1,7 @@
+pub mod breaker;
 pub mod client;
+pub mod signer;
 pub mod types;
 
 pub use client::SorobanClient;
+pub use signer::StellarSigner;
@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+
+use crate::translator::address_map::AddressMapper;
+use crate::translator::tx::{
+    decode_strkey_checked, encode_strkey, STRKEY_VERSION_ACCOUNT, STRKEY_VERSION_SEED,
+};
+
+/// XDR discriminant of ENVELOPE_TYPE_TX, also used as the signature payload tag.
+const ENVELOPE_TYPE_TX: u32 = 2;
+
+/// Signs Stellar transactions with the server's Ed25519 key.
+pub struct StellarSigner {
+    key: SigningKey,
+}
+
+impl StellarSigner {
+    /// Load the signer from an S... secret seed.
+    pub fn from_secret(secret_seed: &str) -> Result<Self> {
+        let seed = decode_strkey_checked(STRKEY_VERSION_SEED, secret_seed)
+            .map_err(|e| anyhow!("Invalid Stellar secret key: {}", e))?;
+        Ok(Self { key: SigningKey::from_bytes(&seed) })
+    }
+
+    /// Raw Ed25519 public key.
+    pub fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+
+    /// The signer's G... account ID.
+    pub fn account_id(&self) -> String {
+        encode_strkey(STRKEY_VERSION_ACCOUNT, &self.public_key())
+    }
+
+    /// The EVM address the signer's account maps to, as in receipts.
+    pub fn evm_address(&self, mapper: &dyn AddressMapper) -> String {
+        mapper.account_to_evm(&self.public_key())
+    }
+
+    /// Sign a base64 `TransactionEnvelope` (ENVELOPE_TYPE_TX, no signatures yet)
+    /// for `network_passphrase`. Returns the signed envelope and the hex
+    /// transaction hash.
+    pub fn sign_envelope(&self, envelope_xdr: &str, network_passphrase: &str) -> Result<(String, String)> {
+        let mut envelope = base64::engine::general_purpose::STANDARD
+            .decode(envelope_xdr)
+            .map_err(|e| anyhow!("Invalid envelope base64: {}", e))?;
+        if envelope.len() < 8 || envelope[..4] != ENVELOPE_TYPE_TX.to_be_bytes() {
+            return Err(anyhow!("Only ENVELOPE_TYPE_TX envelopes can be signed"));
+        }
+        if envelope[envelope.len() - 4..] != 0u32.to_be_bytes() {
+            return Err(anyhow!("Envelope is already signed"));
+        }
+
+        // Strip the empty signature array; what remains after the type is the Transaction
+        envelope.truncate(envelope.len() - 4);
+        let tx_hash = transaction_hash(&envelope[4..], network_passphrase);
+        let signature = self.key.sign(&tx_hash);
+
+        // DecoratedSignature: hint (last 4 bytes of the public key), then the signature
+        let public_key = self.public_key();
+        envelope.extend_from_slice(&1u32.to_be_bytes());
+        envelope.extend_from_slice(&public_key[28..]);
+        envelope.extend_from_slice(&64u32.to_be_bytes());
+        envelope.extend_from_slice(&signature.to_bytes());
+
+        Ok((
+            base64::engine::general_purpose::STANDARD.encode(&envelope),
+            hex::encode(tx_hash),
+        ))
+    }
+}
+
+/// Hash a `Transaction` as Stellar signs it:
+/// sha256(sha256(passphrase) || ENVELOPE_TYPE_TX || transaction).
+pub fn transaction_hash(transaction_xdr: &[u8], network_passphrase: &str) -> [u8; 32] {
+    let network_id = Sha256::digest(network_passphrase.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(network_id);
+    hasher.update(ENVELOPE_TYPE_TX.to_be_bytes());
+    hasher.update(transaction_xdr);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+    #[test]
+    fn test_sign_envelope_appends_verifiable_signature() {
+        let signer = StellarSigner::from_secret(&encode_strkey(STRKEY_VERSION_SEED, &[9; 32])).unwrap();
+        assert!(signer.account_id().starts_with('G'));
+        let mapper = crate::translator::DefaultAddressMapper::new();
+        assert_eq!(signer.evm_address(&mapper), mapper.account_to_evm(&signer.public_key()));
+
+        let mut unsigned = ENVELOPE_TYPE_TX.to_be_bytes().to_vec();
+        unsigned.extend_from_slice(&[0xab; 40]); // stand-in Transaction body
+        unsigned.extend_from_slice(&0u32.to_be_bytes());
+        let unsigned_b64 = base64::engine::general_purpose::STANDARD.encode(&unsigned);
+
+        let (signed_b64, hash) = signer.sign_envelope(&unsigned_b64, PASSPHRASE).unwrap();
+        let signed = base64::engine::general_purpose::STANDARD.decode(&signed_b64).unwrap();
+        let expected_hash = transaction_hash(&[0xab; 40], PASSPHRASE);
+        assert_eq!(hash, hex::encode(expected_hash));
+
+        // Body unchanged, one signature with the key hint
+        assert_eq!(signed[..44], unsigned[..44]);
+        assert_eq!(signed[44..48], 1u32.to_be_bytes());
+        assert_eq!(signed[48..52], signer.public_key()[28..]);
+        assert_eq!(signed[52..56], 64u32.to_be_bytes());
+        let signature = Signature::from_slice(&signed[56..120]).unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&signer.public_key()).unwrap();
+        assert!(verifying_key.verify(&expected_hash, &signature).is_ok());
+
+        // A signature for another network does not verify
+        let other = transaction_hash(&[0xab; 40], "Public Global Stellar Network ; September 2015");
+        assert!(verifying_key.verify(&other, &signature).is_err());
+
+        assert!(signer.sign_envelope(&signed_b64, PASSPHRASE).is_err());
+    }
+
+    #[test]
+    fn test_from_secret_rejects_invalid_seed() {
+        assert!(StellarSigner::from_secret("SABC").is_err());
+        let account = encode_strkey(STRKEY_VERSION_ACCOUNT, &[9; 32]);
+        assert!(StellarSigner::from_secret(&account).is_err());
+    }
+}
@@ -47,6 +47,15 @@ pub struct LatestLedgerResponse {
     pub sequence: u64,
 }
 
+/// Header fields of a closed ledger, as reported by Horizon
+#[derive(Debug, Clone)]
+pub struct LedgerHeader {
+    /// Hex-encoded Stellar ledger hash
+    pub hash: String,
+    /// Close time in unix seconds
+    pub close_time: u64,
+}
+
 /// Response from getHealth
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -61,7 +70,7 @@ pub struct HealthResponse {
 }
 
 /// Response from getTransaction
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetTransactionResponse {
     pub status: String,
@@ -87,6 +96,56 @@ pub struct GetTransactionResponse {
     pub result_meta_xdr: Option<String>,
 }
 
+/// Response from getTransactions
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionsResponse {
+    #[serde(default)]
+    pub transactions: Vec<TransactionInfo>,
+    #[serde(default)]
+    pub latest_ledger: Option<u64>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// A single transaction in a getTransactions page
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInfo {
+    pub status: String,
+    pub tx_hash: String,
+    pub ledger: u64,
+    #[serde(default)]
+    pub application_order: u32,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    #[serde(default)]
+    pub envelope_xdr: Option<String>,
+    #[serde(default)]
+    pub result_xdr: Option<String>,
+    #[serde(default)]
+    pub result_meta_xdr: Option<String>,
+}
+
+impl TransactionInfo {
+    /// View this entry as a getTransaction response for translation.
+    pub fn to_transaction_response(&self) -> GetTransactionResponse {
+        GetTransactionResponse {
+            status: self.status.clone(),
+            latest_ledger: None,
+            latest_ledger_close_time: None,
+            oldest_ledger: None,
+            oldest_ledger_close_time: None,
+            ledger: Some(self.ledger),
+            created_at: self.created_at.map(|t| t.to_string()),
+            application_order: Some(self.application_order),
+            envelope_xdr: self.envelope_xdr.clone(),
+            result_xdr: self.result_xdr.clone(),
+            result_meta_xdr: self.result_meta_xdr.clone(),
+        }
+    }
+}
+
 /// Response from simulateTransaction
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -171,6 +230,9 @@ pub struct GetEventsResponse {
     pub events: Option<Vec<SorobanEvent>>,
     #[serde(default)]
     pub latest_ledger: Option<u64>,
+    /// Cursor to continue after the last returned event
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -195,7 +257,9 @@ pub struct SorobanEvent {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetEventsParams {
-    pub start_ledger: u64,
+    /// Must be omitted when paging with a cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_ledger: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_ledger: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -204,7 +268,7 @@ pub struct GetEventsParams {
     pub pagination: Option<EventPagination>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EventFilter {
     #[serde(rename = "type")]
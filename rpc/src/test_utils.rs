@@ -0,0 +1,89 @@
+//! Test helpers shared across modules.
+
+use std::sync::{Arc, Mutex};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serve canned responses for Soroban RPC (POST) and Horizon (GET) requests.
+/// `routes` maps a request-line prefix (e.g. "GET /ledgers/5"), or `rpc:<method>` for
+/// a JSON-RPC method, to a status and body. The first matching route wins.
+pub async fn spawn_mock_server(routes: Vec<(&'static str, u16, String)>) -> String {
+    spawn_recording_mock_server(routes).await.0
+}
+
+/// Like `spawn_mock_server`, also returning every request received (request
+/// line, headers and body) in arrival order.
+pub async fn spawn_recording_mock_server(
+    routes: Vec<(&'static str, u16, String)>,
+) -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(_) => return,
+            };
+
+            // Read the headers and any body so the client sees a clean close
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| {
+                            l.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+
+            let text = String::from_utf8_lossy(&request);
+            recorded.lock().unwrap_or_else(|e| e.into_inner()).push(text.to_string());
+            let (status, body) = routes
+                .iter()
+                .find(|(key, _, _)| match key.strip_prefix("rpc:") {
+                    Some(method) => text.contains(&format!("\"method\":\"{}\"", method)),
+                    None => text.starts_with(key),
+                })
+                .map(|(_, status, body)| (*status, body.clone()))
+                .unwrap_or((404, "{}".to_string()));
+            let response = format!(
+                "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+
+    (format!("http://{}", addr), requests)
+}
+
+/// A Soroban RPC getLatestLedger response for the given sequence.
+pub fn latest_ledger_response(sequence: u64) -> String {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": { "id": "abc", "protocolVersion": 21, "sequence": sequence }
+    })
+    .to_string()
+}
@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::sync::Arc;
 use tracing::debug;
 
 /// ABI function parameter definition.
@@ -15,6 +16,9 @@ pub struct AbiParam {
     pub indexed: bool,
     #[serde(default)]
     pub components: Option<Vec<AbiParam>>,
+    /// Solidity's `internalType`, e.g. `address payable` or `contract IERC20`
+    #[serde(default, rename = "internalType", skip_serializing_if = "Option::is_none")]
+    pub internal_type: Option<String>,
 }
 
 /// ABI function/event entry.
@@ -42,10 +46,57 @@ pub struct FunctionInfo {
     pub state_mutability: String,
 }
 
+/// Stores an event's signature hash (topic[0]) and its inputs.
+#[derive(Debug, Clone)]
+pub struct EventInfo {
+    pub name: String,
+    pub signature: String,
+    pub topic: [u8; 32],
+    pub inputs: Vec<AbiParam>,
+}
+
+/// A contract's functions, indexed at registration so per-call lookups are
+/// hash lookups rather than scans.
+#[derive(Debug, Default)]
+struct ContractFunctions {
+    /// Selector -> function (the first declared, should two collide)
+    by_selector: HashMap<[u8; 4], FunctionInfo>,
+    /// Name -> selectors of its overloads, in declaration order
+    by_name: HashMap<String, Vec<[u8; 4]>>,
+    /// Selectors in declaration order
+    selectors: Vec<[u8; 4]>,
+}
+
+impl ContractFunctions {
+    fn insert(&mut self, info: FunctionInfo) {
+        if self.by_selector.contains_key(&info.selector) {
+            return;
+        }
+        self.by_name.entry(info.name.clone()).or_default().push(info.selector);
+        self.selectors.push(info.selector);
+        self.by_selector.insert(info.selector, info);
+    }
+}
+
+/// Everything registered for one contract.
+#[derive(Debug, Default)]
+struct ContractAbi {
+    functions: ContractFunctions,
+    events: Vec<EventInfo>,
+    /// Function invoked for empty calldata: `receive` if the contract has
+    /// one, otherwise `fallback`
+    empty_calldata: Option<FunctionInfo>,
+}
+
 /// ABI Registry: maps contract addresses to their ABI entries and function selectors.
+///
+/// The map is an immutable snapshot swapped atomically on registration, so
+/// lookups never wait for a registration in progress (or for each other).
 pub struct AbiRegistry {
-    /// Map of contract address (hex, lowercase, no 0x) -> list of function infos
-    contracts: RwLock<HashMap<String, Vec<FunctionInfo>>>,
+    /// Map of contract address (hex, lowercase, no 0x) -> its registered ABI
+    contracts: ArcSwap<HashMap<String, Arc<ContractAbi>>>,
+    /// Selector -> Soroban function invoked in place of the ABI name
+    function_aliases: ArcSwap<HashMap<[u8; 4], String>>,
 }
 
 impl Default for AbiRegistry {
@@ -58,7 +109,8 @@ impl AbiRegistry {
     /// Create a new empty ABI registry.
     pub fn new() -> Self {
         Self {
-            contracts: RwLock::new(HashMap::new()),
+            contracts: ArcSwap::from_pointee(HashMap::new()),
+            function_aliases: ArcSwap::from_pointee(HashMap::new()),
         }
     }
 
@@ -106,9 +158,49 @@ impl AbiRegistry {
     /// Register a contract's ABI entries.
     pub fn register_contract(&self, address: &str, abi: &[AbiEntry]) -> Result<()> {
         let addr = normalize_address(address);
-        let mut functions = Vec::new();
+        let mut functions = ContractFunctions::default();
+        let mut events = Vec::new();
+        let mut receive = None;
+        let mut fallback = None;
 
         for entry in abi {
+            if entry.entry_type == "receive" || entry.entry_type == "fallback" {
+                // No name or selector; the Soroban function carries the entry type's name
+                let info = FunctionInfo {
+                    name: entry.entry_type.clone(),
+                    selector: [0u8; 4],
+                    inputs: Vec::new(),
+                    outputs: Vec::new(),
+                    state_mutability: entry
+                        .state_mutability
+                        .clone()
+                        .unwrap_or_else(|| "nonpayable".to_string()),
+                };
+                debug!("Registered {} function for contract {}", entry.entry_type, addr);
+                if entry.entry_type == "receive" {
+                    receive = Some(info);
+                } else {
+                    fallback = Some(info);
+                }
+                continue;
+            }
+
+            if entry.entry_type == "event" {
+                if let Some(name) = &entry.name {
+                    let signature = Self::build_signature(name, &entry.inputs);
+                    let mut topic = [0u8; 32];
+                    topic.copy_from_slice(&Keccak256::digest(signature.as_bytes()));
+                    debug!("Registered event: {} for contract {}", signature, addr);
+                    events.push(EventInfo {
+                        name: name.clone(),
+                        signature,
+                        topic,
+                        inputs: entry.inputs.clone(),
+                    });
+                }
+                continue;
+            }
+
             if entry.entry_type == "function" {
                 if let Some(name) = &entry.name {
                     let signature = Self::build_signature(name, &entry.inputs);
@@ -119,7 +211,7 @@ impl AbiRegistry {
                         hex::encode(selector),
                         addr
                     );
-                    functions.push(FunctionInfo {
+                    functions.insert(FunctionInfo {
                         name: name.clone(),
                         selector,
                         inputs: entry.inputs.clone(),
@@ -133,45 +225,129 @@ impl AbiRegistry {
             }
         }
 
-        let mut contracts = self.contracts.write().map_err(|e| anyhow!("Lock poisoned: {}", e))?;
-        contracts.insert(addr, functions);
+        let contract = Arc::new(ContractAbi {
+            functions,
+            events,
+            empty_calldata: receive.or(fallback),
+        });
+        // Copy the map with this contract swapped in; retried if another
+        // registration lands first, so neither is lost
+        self.contracts.rcu(|contracts| {
+            let mut contracts = HashMap::clone(contracts);
+            contracts.insert(addr.clone(), Arc::clone(&contract));
+            contracts
+        });
         Ok(())
     }
 
+    /// Look up an event by name for a given contract.
+    /// Soroban contracts conventionally use lowercase event symbols (e.g. "transfer"),
+    /// so an exact match is preferred but a case-insensitive one is accepted.
+    pub fn lookup_event_by_name(&self, address: &str, name: &str) -> Option<EventInfo> {
+        let contract = self.contract(address)?;
+        let events = &contract.events;
+
+        events
+            .iter()
+            .find(|e| e.name == name)
+            .or_else(|| events.iter().find(|e| e.name.eq_ignore_ascii_case(name)))
+            .cloned()
+    }
+
+    /// Look up an event by its signature hash (topic[0]) for a given contract.
+    pub fn lookup_event_by_topic(&self, address: &str, topic: &[u8; 32]) -> Option<EventInfo> {
+        self.contract(address)?.events.iter().find(|e| &e.topic == topic).cloned()
+    }
+
     /// Look up a function by its 4-byte selector for a given contract.
     pub fn lookup_function(&self, address: &str, selector: &[u8; 4]) -> Option<FunctionInfo> {
-        let addr = normalize_address(address);
-        let contracts = self.contracts.read().ok()?;
-        let functions = contracts.get(&addr)?;
+        self.contract(address)?.functions.by_selector.get(selector).cloned()
+    }
 
-        functions.iter().find(|f| &f.selector == selector).cloned()
+    /// Look up the function a call with empty calldata runs: the contract's
+    /// `receive` function, or its `fallback` if it has no `receive`.
+    pub fn lookup_empty_calldata_function(&self, address: &str) -> Option<FunctionInfo> {
+        self.contract(address)?.empty_calldata.clone()
     }
 
     /// Look up a function by name for a given contract.
     pub fn lookup_function_by_name(&self, address: &str, name: &str) -> Option<FunctionInfo> {
-        let addr = normalize_address(address);
-        let contracts = self.contracts.read().ok()?;
-        let functions = contracts.get(&addr)?;
+        let contract = self.contract(address)?;
+        let functions = &contract.functions;
 
-        functions.iter().find(|f| f.name == name).cloned()
+        let selector = functions.by_name.get(name)?.first()?;
+        functions.by_selector.get(selector).cloned()
     }
 
     /// Check if a contract is registered.
     pub fn has_contract(&self, address: &str) -> bool {
-        let addr = normalize_address(address);
-        let contracts = self.contracts.read().unwrap_or_else(|e| e.into_inner());
-        contracts.contains_key(&addr)
+        self.contract(address).is_some()
     }
 
     /// Get all registered function selectors for a contract.
     pub fn get_selectors(&self, address: &str) -> Vec<[u8; 4]> {
-        let addr = normalize_address(address);
-        let contracts = self.contracts.read().unwrap_or_else(|e| e.into_inner());
-        contracts
-            .get(&addr)
-            .map(|funcs| funcs.iter().map(|f| f.selector).collect())
+        self.contract(address)
+            .map(|contract| contract.functions.selectors.clone())
             .unwrap_or_default()
     }
+
+    /// Invoke the Soroban function `symbol` for calls with `selector`, on
+    /// every contract, instead of the function named in the ABI. For
+    /// contracts whose exported names differ from the Solidity ones.
+    pub fn set_function_alias(&self, selector: [u8; 4], symbol: &str) -> Result<()> {
+        validate_symbol(symbol)?;
+        debug!("Aliased selector 0x{} to {}", hex::encode(selector), symbol);
+        self.function_aliases.rcu(|aliases| {
+            let mut aliases = HashMap::clone(aliases);
+            aliases.insert(selector, symbol.to_string());
+            aliases
+        });
+        Ok(())
+    }
+
+    /// Drop the alias of `selector`, if any. Returns whether there was one.
+    pub fn remove_function_alias(&self, selector: &[u8; 4]) -> bool {
+        let previous = self.function_aliases.rcu(|aliases| {
+            let mut aliases = HashMap::clone(aliases);
+            aliases.remove(selector);
+            aliases
+        });
+        previous.contains_key(selector)
+    }
+
+    /// The Soroban function aliased to `selector`, if any.
+    pub fn function_alias(&self, selector: &[u8; 4]) -> Option<String> {
+        self.function_aliases.load().get(selector).cloned()
+    }
+
+    /// The registered ABI of a contract, from the current snapshot.
+    fn contract(&self, address: &str) -> Option<Arc<ContractAbi>> {
+        self.contracts.load().get(&normalize_address(address)).cloned()
+    }
+}
+
+/// Check that `symbol` can name a Soroban function: 1 to 32 characters from
+/// `[a-zA-Z0-9_]`.
+pub fn validate_symbol(symbol: &str) -> Result<()> {
+    if symbol.is_empty() || symbol.len() > 32 || !symbol.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
+        return Err(anyhow!(
+            "Invalid Soroban function name '{}': expected 1-32 characters from [a-zA-Z0-9_]",
+            symbol
+        ));
+    }
+    Ok(())
+}
+
+/// Parse a function selector given as 4 hex bytes (`0xa9059cbb`) or as a
+/// signature (`transfer(address,uint256)`).
+pub fn parse_selector(selector: &str) -> Result<[u8; 4]> {
+    if selector.contains('(') {
+        return Ok(AbiRegistry::compute_selector(selector));
+    }
+    let mut bytes = [0u8; 4];
+    hex::decode_to_slice(selector.strip_prefix("0x").unwrap_or(selector), &mut bytes)
+        .map_err(|_| anyhow!("Invalid function selector '{}': expected 4 hex bytes or a signature", selector))?;
+    Ok(bytes)
 }
 
 /// Normalize an address to lowercase without 0x prefix.
@@ -182,8 +358,15 @@ fn normalize_address(address: &str) -> String {
         .to_lowercase()
 }
 
+/// Most bytes `decode_abi_params` returns for one call. Dynamic parameters
+/// may all point at the same data, so the total can exceed the input size.
+pub const MAX_DECODED_ABI_BYTES: usize = 1024 * 1024;
+
 /// Decode ABI-encoded parameters given their types.
 /// Returns a vector of decoded values as raw byte chunks.
+///
+/// Offsets and lengths come from untrusted calldata: each is checked against
+/// the data actually present before anything is sliced or copied.
 pub fn decode_abi_params(data: &[u8], param_types: &[AbiParam]) -> Result<Vec<Vec<u8>>> {
     if data.is_empty() && param_types.is_empty() {
         return Ok(Vec::new());
@@ -191,76 +374,94 @@ pub fn decode_abi_params(data: &[u8], param_types: &[AbiParam]) -> Result<Vec<Ve
 
     let mut decoded = Vec::new();
     let mut offset = 0;
+    let mut total = 0usize;
 
     for param in param_types {
-        if is_dynamic_type(&param.param_type) {
+        if offset + 32 > data.len() {
+            return Err(anyhow!(
+                "ABI data too short for {} param '{}'",
+                if is_dynamic_type(&param.param_type) { "dynamic" } else { "static" },
+                param.name
+            ));
+        }
+        let value = if is_dynamic_type(&param.param_type) {
             // Dynamic types: read the offset pointer, then the data
-            if offset + 32 > data.len() {
-                return Err(anyhow!("ABI data too short for dynamic offset"));
-            }
             let data_offset = read_u256_as_usize(&data[offset..offset + 32])?;
-            let dynamic_data = decode_dynamic_param(data, data_offset, &param.param_type)?;
-            decoded.push(dynamic_data);
-            offset += 32;
+            decode_dynamic_param(data, data_offset, &param.param_type)?
         } else {
             // Static types: read 32 bytes
-            if offset + 32 > data.len() {
-                return Err(anyhow!("ABI data too short for static param"));
-            }
-            decoded.push(data[offset..offset + 32].to_vec());
-            offset += 32;
+            &data[offset..offset + 32]
+        };
+        offset += 32;
+
+        total += value.len();
+        if total > MAX_DECODED_ABI_BYTES {
+            return Err(anyhow!("Decoded ABI parameters exceed {} bytes", MAX_DECODED_ABI_BYTES));
         }
+        decoded.push(value.to_vec());
     }
 
     Ok(decoded)
 }
 
 /// Check if a type is dynamic (bytes, string, dynamic arrays).
-fn is_dynamic_type(param_type: &str) -> bool {
+pub fn is_dynamic_type(param_type: &str) -> bool {
     param_type == "bytes"
         || param_type == "string"
         || param_type.ends_with("[]")
         || (param_type == "tuple") // Simplified; real impl would check components
 }
 
-/// Read a 256-bit big-endian integer as usize.
+/// Read a 256-bit big-endian offset or length as usize. Values that do not
+/// fit cannot be within the data, so they are rejected rather than truncated.
 fn read_u256_as_usize(data: &[u8]) -> Result<usize> {
     if data.len() < 32 {
         return Err(anyhow!("Not enough data for u256"));
     }
-    // Only look at last 8 bytes (usize is at most 64-bit)
     let mut bytes = [0u8; 8];
     bytes.copy_from_slice(&data[24..32]);
-    Ok(u64::from_be_bytes(bytes) as usize)
+    let value = u64::from_be_bytes(bytes);
+    if data[..24].iter().any(|&b| b != 0) {
+        return Err(anyhow!("ABI offset or length 0x{} is out of range", hex::encode(&data[..32])));
+    }
+    usize::try_from(value).map_err(|_| anyhow!("ABI offset or length {} is out of range", value))
 }
 
-/// Decode a dynamic ABI parameter.
-fn decode_dynamic_param(data: &[u8], offset: usize, param_type: &str) -> Result<Vec<u8>> {
-    if offset + 32 > data.len() {
-        return Err(anyhow!("Dynamic param offset out of bounds"));
-    }
+/// Decode a dynamic ABI parameter at `offset`: the contents of bytes and
+/// strings, or an array's length word and elements.
+fn decode_dynamic_param<'a>(data: &'a [u8], offset: usize, param_type: &str) -> Result<&'a [u8]> {
+    let start = offset
+        .checked_add(32)
+        .filter(|&start| start <= data.len())
+        .ok_or_else(|| anyhow!("Dynamic param offset {} out of bounds ({} bytes of data)", offset, data.len()))?;
+    let remaining = data.len() - start;
 
     if param_type == "bytes" || param_type == "string" {
-        let length = read_u256_as_usize(&data[offset..offset + 32])?;
-        let start = offset + 32;
-        let end = start + length;
-        if end > data.len() {
-            return Err(anyhow!("Dynamic param data out of bounds"));
+        let length = read_u256_as_usize(&data[offset..start])?;
+        if length > remaining {
+            return Err(anyhow!(
+                "{} length {} exceeds the {} bytes of data after it",
+                param_type,
+                length,
+                remaining
+            ));
         }
-        Ok(data[start..end].to_vec())
+        Ok(&data[start..start + length])
     } else if param_type.ends_with("[]") {
         // Dynamic array: length + elements
-        let length = read_u256_as_usize(&data[offset..offset + 32])?;
-        let start = offset + 32;
-        let end = start + length * 32;
-        if end > data.len() {
-            return Err(anyhow!("Dynamic array data out of bounds"));
+        let length = read_u256_as_usize(&data[offset..start])?;
+        if length > remaining / 32 {
+            return Err(anyhow!(
+                "{} length {} exceeds the {} words of data after it",
+                param_type,
+                length,
+                remaining / 32
+            ));
         }
-        Ok(data[offset..end].to_vec())
+        Ok(&data[offset..start + length * 32])
     } else {
         // Fallback: return 32 bytes from offset
-        let end = (offset + 32).min(data.len());
-        Ok(data[offset..end].to_vec())
+        Ok(&data[offset..start])
     }
 }
 
@@ -332,12 +533,14 @@ mod tests {
                 param_type: "address".to_string(),
                 indexed: false,
                 components: None,
+                internal_type: None,
             },
             AbiParam {
                 name: "amount".to_string(),
                 param_type: "uint256".to_string(),
                 indexed: false,
                 components: None,
+                internal_type: None,
             },
         ];
         let sig = AbiRegistry::build_signature("transfer", &inputs);
@@ -356,12 +559,14 @@ mod tests {
                     param_type: "address".to_string(),
                     indexed: false,
                     components: None,
+                    internal_type: None,
                 },
                 AbiParam {
                     name: "amount".to_string(),
                     param_type: "uint256".to_string(),
                     indexed: false,
                     components: None,
+                    internal_type: None,
                 },
             ],
             outputs: vec![AbiParam {
@@ -369,6 +574,7 @@ mod tests {
                 param_type: "bool".to_string(),
                 indexed: false,
                 components: None,
+                internal_type: None,
             }],
             state_mutability: Some("nonpayable".to_string()),
         }];
@@ -385,4 +591,188 @@ mod tests {
         assert_eq!(func.name, "transfer");
         assert_eq!(func.inputs.len(), 2);
     }
+
+    #[test]
+    fn test_overloads_resolve_by_selector() {
+        let param = |param_type: &str| AbiParam {
+            name: String::new(),
+            param_type: param_type.to_string(),
+            indexed: false,
+            components: None,
+            internal_type: None,
+        };
+        let function = |name: &str, inputs: Vec<AbiParam>| AbiEntry {
+            entry_type: "function".to_string(),
+            name: Some(name.to_string()),
+            inputs,
+            outputs: vec![],
+            state_mutability: None,
+        };
+        let address = "0x1234567890abcdef1234567890abcdef12345678";
+        let registry = AbiRegistry::new();
+        registry
+            .register_contract(
+                address,
+                &[
+                    function("mint", vec![param("address")]),
+                    function("mint", vec![param("address"), param("uint256")]),
+                    function("burn", vec![param("uint256")]),
+                ],
+            )
+            .unwrap();
+
+        let one = AbiRegistry::compute_selector("mint(address)");
+        let two = AbiRegistry::compute_selector("mint(address,uint256)");
+        let burn = AbiRegistry::compute_selector("burn(uint256)");
+        assert_eq!(registry.lookup_function(address, &one).unwrap().inputs.len(), 1);
+        assert_eq!(registry.lookup_function(address, &two).unwrap().inputs.len(), 2);
+        assert!(registry.lookup_function(address, &[0; 4]).is_none());
+
+        // By name, the first declared overload
+        assert_eq!(registry.lookup_function_by_name(address, "mint").unwrap().selector, one);
+        assert_eq!(registry.get_selectors(address), vec![one, two, burn]);
+    }
+
+    #[test]
+    fn test_lookups_run_during_concurrent_registration() {
+        let transfer = AbiEntry {
+            entry_type: "function".to_string(),
+            name: Some("transfer".to_string()),
+            inputs: vec![],
+            outputs: vec![],
+            state_mutability: None,
+        };
+        let selector = AbiRegistry::compute_selector("transfer()");
+        let registry = Arc::new(AbiRegistry::new());
+        let base = "0x1234567890abcdef1234567890abcdef12345678";
+        registry.register_contract(base, std::slice::from_ref(&transfer)).unwrap();
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let registry = Arc::clone(&registry);
+                std::thread::spawn(move || {
+                    for _ in 0..2000 {
+                        assert_eq!(registry.lookup_function(base, &selector).unwrap().name, "transfer");
+                        assert!(registry.has_contract(base));
+                    }
+                })
+            })
+            .collect();
+        let writers: Vec<_> = (0..2u8)
+            .map(|writer| {
+                let registry = Arc::clone(&registry);
+                let transfer = transfer.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100u8 {
+                        let address = format!("0x{:02x}{:02x}{}", writer, i, "00".repeat(18));
+                        registry.register_contract(&address, std::slice::from_ref(&transfer)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in readers.into_iter().chain(writers) {
+            handle.join().unwrap();
+        }
+
+        // No registration was lost to a concurrent one
+        for writer in 0..2u8 {
+            for i in 0..100u8 {
+                let address = format!("0x{:02x}{:02x}{}", writer, i, "00".repeat(18));
+                assert!(registry.lookup_function(&address, &selector).is_some(), "{}", address);
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_calldata_prefers_receive_over_fallback() {
+        let entry = |entry_type: &str| AbiEntry {
+            entry_type: entry_type.to_string(),
+            name: None,
+            inputs: vec![],
+            outputs: vec![],
+            state_mutability: Some("payable".to_string()),
+        };
+        let registry = AbiRegistry::new();
+        let address = "0x1234567890abcdef1234567890abcdef12345678";
+
+        registry.register_contract(address, &[entry("fallback")]).unwrap();
+        assert_eq!(registry.lookup_empty_calldata_function(address).unwrap().name, "fallback");
+        assert!(registry.get_selectors(address).is_empty());
+
+        registry
+            .register_contract(address, &[entry("fallback"), entry("receive")])
+            .unwrap();
+        assert_eq!(registry.lookup_empty_calldata_function(address).unwrap().name, "receive");
+
+        registry.register_contract(address, &[]).unwrap();
+        assert!(registry.lookup_empty_calldata_function(address).is_none());
+    }
+
+    fn param(param_type: &str) -> AbiParam {
+        AbiParam {
+            name: "p".to_string(),
+            param_type: param_type.to_string(),
+            indexed: false,
+            components: None,
+            internal_type: None,
+        }
+    }
+
+    fn word(value: u64) -> Vec<u8> {
+        let mut word = vec![0u8; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    #[test]
+    fn test_dynamic_params_decode_within_bounds() {
+        let mut hi = b"hi".to_vec();
+        hi.resize(32, 0);
+        let data = [word(64), word(128), word(2), hi, word(1), word(7)].concat();
+        let decoded = decode_abi_params(&data, &[param("string"), param("uint256[]")]).unwrap();
+        assert_eq!(decoded, vec![b"hi".to_vec(), data[128..192].to_vec()]);
+    }
+
+    #[test]
+    fn test_claimed_lengths_beyond_data_are_rejected() {
+        // A string claiming 1000 bytes with 32 present
+        let data = [word(32), word(1000), vec![0x61; 32]].concat();
+        let err = decode_abi_params(&data, &[param("string")]).unwrap_err();
+        assert_eq!(err.to_string(), "string length 1000 exceeds the 32 bytes of data after it");
+
+        // An array claiming u64::MAX elements: length * 32 would overflow
+        let data = [word(32), word(u64::MAX), word(1)].concat();
+        let err = decode_abi_params(&data, &[param("uint256[]")]).unwrap_err();
+        assert!(err.to_string().contains("exceeds the 1 words"), "{}", err);
+
+        // Lengths and offsets above 64 bits are rejected, not truncated
+        let mut huge = word(1);
+        huge[0] = 0x80;
+        let data = [word(32), huge.clone()].concat();
+        assert!(decode_abi_params(&data, &[param("bytes")]).unwrap_err().to_string().contains("out of range"));
+        assert!(decode_abi_params(&huge, &[param("bytes")]).unwrap_err().to_string().contains("out of range"));
+
+        // An offset at the end of usize cannot overflow into the data
+        let err = decode_abi_params(&word(u64::MAX), &[param("bytes")]).unwrap_err();
+        assert!(err.to_string().contains("out of bounds"), "{}", err);
+
+        // Missing head words
+        assert!(decode_abi_params(&word(1), &[param("uint256"), param("uint256")]).is_err());
+    }
+
+    #[test]
+    fn test_decoded_size_is_capped() {
+        // Every param points at the same 64 KiB of bytes
+        let length = 64 * 1024;
+        let params: Vec<AbiParam> = (0..MAX_DECODED_ABI_BYTES / length + 1).map(|_| param("bytes")).collect();
+        let head_size = params.len() as u64 * 32;
+        let mut data: Vec<u8> = params.iter().flat_map(|_| word(head_size)).collect();
+        data.extend(word(length as u64));
+        data.extend(vec![0u8; length]);
+
+        let err = decode_abi_params(&data, &params).unwrap_err();
+        assert_eq!(err.to_string(), format!("Decoded ABI parameters exceed {} bytes", MAX_DECODED_ABI_BYTES));
+        // Exactly at the cap is fine
+        assert_eq!(decode_abi_params(&data, &params[1..]).unwrap().len(), params.len() - 1);
+    }
 }
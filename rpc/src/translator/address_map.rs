@@ -0,0 +1,381 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+
+use sha3::{Digest, Keccak256};
+use tracing::warn;
+
+use crate::state_store::{Namespace, StateStore};
+
+use super::tx::{
+    encode_strkey, evm_address_to_stellar_contract, STRKEY_VERSION_ACCOUNT, STRKEY_VERSION_CONTRACT,
+};
+
+/// Hashed addresses an [`AddressMap`] keeps in memory unless configured
+/// otherwise, a few tens of MB per map.
+pub const DEFAULT_RECORDED_KEYS_CAPACITY: usize = 250_000;
+
+/// Bidirectional map between Stellar keys (contract IDs or account keys) and
+/// the EVM addresses they are shown as in logs, receipts and transactions.
+///
+/// A key derived from an EVM address (the address right-aligned after 12
+/// zero bytes) maps to that address in both directions without being stored.
+/// Any other key is shown as the last 20 bytes of the keccak256 of its strkey;
+/// that cannot be inverted, so the key is recorded the first time its address
+/// is handed out and looked up on the way back.
+///
+/// Every native contract or account in served data adds a record, so the
+/// records are capped: past the capacity the oldest is dropped. A dropped
+/// address resolves again from the state store if one is configured, and
+/// otherwise once its key is handed out again.
+#[derive(Debug)]
+pub struct AddressMap {
+    /// Strkey version the keys are hashed under (C... or G...)
+    strkey_version: u8,
+    keys: RwLock<RecordedKeys>,
+    /// Where recorded keys are persisted, if anywhere
+    store: Option<(Arc<StateStore>, Namespace)>,
+}
+
+/// Hashed EVM address (lowercase, 0x-prefixed) -> key, oldest dropped first.
+#[derive(Debug)]
+struct RecordedKeys {
+    keys: HashMap<String, [u8; 32]>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl RecordedKeys {
+    fn new(capacity: usize) -> Self {
+        Self {
+            keys: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Record `key` for `evm_address`. Returns false if it was already recorded.
+    fn insert(&mut self, evm_address: String, key: [u8; 32]) -> bool {
+        if self.keys.contains_key(&evm_address) || self.capacity == 0 {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+        self.order.push_back(evm_address.clone());
+        self.keys.insert(evm_address, key);
+        true
+    }
+}
+
+impl AddressMap {
+    /// An empty map of contract IDs.
+    pub fn contracts() -> Self {
+        Self::with_strkey_version(STRKEY_VERSION_CONTRACT)
+    }
+
+    /// An empty map of account keys.
+    pub fn accounts() -> Self {
+        Self::with_strkey_version(STRKEY_VERSION_ACCOUNT)
+    }
+
+    fn with_strkey_version(strkey_version: u8) -> Self {
+        Self {
+            strkey_version,
+            keys: RwLock::new(RecordedKeys::new(DEFAULT_RECORDED_KEYS_CAPACITY)),
+            store: None,
+        }
+    }
+
+    /// Keep at most `capacity` hashed addresses in memory.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        let keys = self.keys.get_mut().unwrap_or_else(|e| e.into_inner());
+        while keys.order.len() > capacity {
+            if let Some(oldest) = keys.order.pop_front() {
+                keys.keys.remove(&oldest);
+            }
+        }
+        keys.capacity = capacity;
+        self
+    }
+
+    /// Persist recorded keys in `store` under `namespace`, starting from the
+    /// keys already stored there.
+    pub fn persisted_in(mut self, store: Arc<StateStore>, namespace: Namespace) -> Self {
+        let keys = self.keys.get_mut().unwrap_or_else(|e| e.into_inner());
+        for (evm_address, key_hex) in store.entries(namespace) {
+            match decode_key(&key_hex) {
+                Some(key) => {
+                    keys.insert(evm_address, key);
+                }
+                None => warn!("Ignoring stored key '{}' for {}", key_hex, evm_address),
+            }
+        }
+        self.store = Some((store, namespace));
+        self
+    }
+
+    /// The EVM address of a key, remembering the key so the address maps
+    /// back to it.
+    pub fn evm_address_of(&self, key: &[u8; 32]) -> String {
+        if key[..12].iter().all(|&b| b == 0) {
+            return format!("0x{}", hex::encode(&key[12..]));
+        }
+
+        let strkey = encode_strkey(self.strkey_version, key);
+        let hash = Keccak256::digest(strkey.as_bytes());
+        let evm_address = format!("0x{}", hex::encode(&hash[12..32]));
+
+        // Persist after releasing the lock, so translations never wait on the store
+        let inserted = self
+            .keys
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(evm_address.clone(), *key);
+        if inserted {
+            if let Some((store, namespace)) = &self.store {
+                store.record(*namespace, &evm_address, &hex::encode(key));
+            }
+        }
+        evm_address
+    }
+
+    /// The key behind an EVM address: a recorded key if the address was
+    /// handed out for one, otherwise the key derived from the address.
+    /// Returns None if `evm_address` is not a 20-byte hex address.
+    pub fn key_of(&self, evm_address: &str) -> Option<[u8; 32]> {
+        let digits = evm_address.strip_prefix("0x").unwrap_or(evm_address);
+        let mut address = [0u8; 20];
+        hex::decode_to_slice(digits, &mut address).ok()?;
+
+        let lookup = format!("0x{}", digits.to_lowercase());
+        let recorded = self.keys.read().unwrap_or_else(|e| e.into_inner()).keys.get(&lookup).copied();
+        let stored = || {
+            let (store, namespace) = self.store.as_ref()?;
+            decode_key(&store.get(*namespace, &lookup)?)
+        };
+        Some(
+            recorded
+                .or_else(stored)
+                .unwrap_or_else(|| evm_address_to_stellar_contract(&address)),
+        )
+    }
+
+    /// Number of recorded (hashed) keys held in memory.
+    pub fn len(&self) -> usize {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).keys.len()
+    }
+
+    /// Whether no keys are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Translation between EVM addresses and Stellar contract IDs and account
+/// keys. Each direction must invert the other: an address handed out for a
+/// key maps back to that key, and a key resolved from an address maps back to
+/// that address, so requests, receipts and logs agree on every address.
+pub trait AddressMapper: Send + Sync + std::fmt::Debug {
+    /// The contract ID behind an EVM address, or None if it is not a 20-byte hex address.
+    fn evm_to_contract(&self, evm_address: &str) -> Option<[u8; 32]>;
+
+    /// The EVM address a contract ID is shown as.
+    fn contract_to_evm(&self, contract: &[u8; 32]) -> String;
+
+    /// The account key behind an EVM address, or None if it is not a 20-byte hex address.
+    fn evm_to_account(&self, evm_address: &str) -> Option<[u8; 32]>;
+
+    /// The EVM address an account key is shown as.
+    fn account_to_evm(&self, key: &[u8; 32]) -> String;
+}
+
+/// The default mapping: keys derived from an EVM address map to it directly,
+/// any other key to a hashed address recorded in an [`AddressMap`].
+#[derive(Debug)]
+pub struct DefaultAddressMapper {
+    contracts: AddressMap,
+    accounts: AddressMap,
+}
+
+impl DefaultAddressMapper {
+    pub fn new() -> Self {
+        Self {
+            contracts: AddressMap::contracts(),
+            accounts: AddressMap::accounts(),
+        }
+    }
+}
+
+impl DefaultAddressMapper {
+    /// The default mapping with its recorded addresses persisted in `store`.
+    pub fn with_store(store: Arc<StateStore>) -> Self {
+        Self {
+            contracts: AddressMap::contracts().persisted_in(store.clone(), Namespace::Contract),
+            accounts: AddressMap::accounts().persisted_in(store, Namespace::Account),
+        }
+    }
+}
+
+impl Default for DefaultAddressMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AddressMapper for DefaultAddressMapper {
+    fn evm_to_contract(&self, evm_address: &str) -> Option<[u8; 32]> {
+        self.contracts.key_of(evm_address)
+    }
+
+    fn contract_to_evm(&self, contract: &[u8; 32]) -> String {
+        self.contracts.evm_address_of(contract)
+    }
+
+    fn evm_to_account(&self, evm_address: &str) -> Option<[u8; 32]> {
+        self.accounts.key_of(evm_address)
+    }
+
+    fn account_to_evm(&self, key: &[u8; 32]) -> String {
+        self.accounts.evm_address_of(key)
+    }
+}
+
+fn decode_key(key_hex: &str) -> Option<[u8; 32]> {
+    let mut key = [0u8; 32];
+    hex::decode_to_slice(key_hex, &mut key).ok()?;
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashed_contract_roundtrips_once_seen() {
+        let map = AddressMap::contracts();
+        let contract = [0x7fu8; 32];
+
+        // Before the address is handed out, it reads as a derived contract ID
+        let evm_address = {
+            let strkey = encode_strkey(STRKEY_VERSION_CONTRACT, &contract);
+            format!("0x{}", hex::encode(&Keccak256::digest(strkey.as_bytes())[12..32]))
+        };
+        assert_ne!(map.key_of(&evm_address), Some(contract));
+
+        assert_eq!(map.evm_address_of(&contract), evm_address);
+        assert_eq!(map.key_of(&evm_address), Some(contract));
+        assert_eq!(map.key_of(&evm_address.to_uppercase().replace("0X", "0x")), Some(contract));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_derived_contract_maps_without_recording() {
+        let map = AddressMap::contracts();
+        let address = [0x22u8; 20];
+        let contract = evm_address_to_stellar_contract(&address);
+
+        assert_eq!(map.evm_address_of(&contract), format!("0x{}", "22".repeat(20)));
+        assert_eq!(map.key_of(&format!("0x{}", "22".repeat(20))), Some(contract));
+        assert!(map.is_empty());
+
+        assert_eq!(map.key_of("0x1234"), None);
+    }
+
+    #[test]
+    fn test_accounts_hash_their_g_strkey() {
+        let accounts = AddressMap::accounts();
+        let contracts = AddressMap::contracts();
+        let key = [0x7fu8; 32];
+
+        let strkey = encode_strkey(STRKEY_VERSION_ACCOUNT, &key);
+        let expected = format!("0x{}", hex::encode(&Keccak256::digest(strkey.as_bytes())[12..32]));
+        assert_eq!(accounts.evm_address_of(&key), expected);
+        assert_ne!(contracts.evm_address_of(&key), expected);
+        assert_eq!(accounts.key_of(&expected), Some(key));
+    }
+
+    #[test]
+    fn test_default_mapper_directions_invert() {
+        let mapper = DefaultAddressMapper::new();
+        let native = [0x7fu8; 32];
+        let derived = evm_address_to_stellar_contract(&[0x22; 20]);
+
+        // Stellar -> EVM -> Stellar
+        for key in [native, derived] {
+            assert_eq!(mapper.evm_to_contract(&mapper.contract_to_evm(&key)), Some(key));
+            assert_eq!(mapper.evm_to_account(&mapper.account_to_evm(&key)), Some(key));
+        }
+
+        // EVM -> Stellar -> EVM, for addresses handed out and fresh ones
+        let hashed_contract = mapper.contract_to_evm(&native);
+        let hashed_account = mapper.account_to_evm(&native);
+        for address in [hashed_contract.clone(), hashed_account.clone(), format!("0x{}", "33".repeat(20))] {
+            assert_eq!(mapper.contract_to_evm(&mapper.evm_to_contract(&address).unwrap()), address);
+            assert_eq!(mapper.account_to_evm(&mapper.evm_to_account(&address).unwrap()), address);
+        }
+
+        // Contracts and accounts keep separate records
+        assert_ne!(hashed_contract, hashed_account);
+        assert_eq!(mapper.evm_to_contract("0x1234"), None);
+        assert_eq!(mapper.evm_to_account("not an address"), None);
+    }
+
+    #[test]
+    fn test_recorded_addresses_persist() {
+        let path = std::env::temp_dir().join(format!("tva-state-mapper-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let contract = [0x7fu8; 32];
+        let account = [0x6eu8; 32];
+
+        let (contract_address, account_address) = {
+            let mapper = DefaultAddressMapper::with_store(Arc::new(StateStore::open(&path).unwrap()));
+            // Derived keys need no record
+            mapper.contract_to_evm(&evm_address_to_stellar_contract(&[0x22; 20]));
+            (mapper.contract_to_evm(&contract), mapper.account_to_evm(&account))
+        };
+        let store = Arc::new(StateStore::open(&path).unwrap());
+        assert_eq!(store.entries(Namespace::Contract).len(), 1);
+
+        // A fresh mapper resolves the hashed addresses without seeing the keys again
+        let mapper = DefaultAddressMapper::with_store(store);
+        assert_eq!(mapper.evm_to_contract(&contract_address), Some(contract));
+        assert_eq!(mapper.evm_to_account(&account_address), Some(account));
+        assert_ne!(DefaultAddressMapper::new().evm_to_contract(&contract_address), Some(contract));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_recorded_keys_are_capped() {
+        let map = AddressMap::contracts().with_capacity(2);
+        let addresses: Vec<String> = (1..=3u8).map(|b| map.evm_address_of(&[b; 32])).collect();
+        assert_eq!(map.len(), 2);
+
+        // The oldest record is dropped; the others still resolve
+        assert_ne!(map.key_of(&addresses[0]), Some([1; 32]));
+        assert_eq!(map.key_of(&addresses[1]), Some([2; 32]));
+        assert_eq!(map.key_of(&addresses[2]), Some([3; 32]));
+
+        // Handing the address out again records it again
+        map.evm_address_of(&[1; 32]);
+        assert_eq!(map.key_of(&addresses[0]), Some([1; 32]));
+        assert_ne!(map.key_of(&addresses[1]), Some([2; 32]));
+    }
+
+    #[test]
+    fn test_dropped_records_resolve_from_the_store() {
+        let path = std::env::temp_dir().join(format!("tva-state-capped-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = Arc::new(StateStore::open(&path).unwrap());
+        let map = AddressMap::contracts().persisted_in(store, Namespace::Contract).with_capacity(1);
+
+        let first = map.evm_address_of(&[1; 32]);
+        map.evm_address_of(&[2; 32]);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.key_of(&first), Some([1; 32]));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
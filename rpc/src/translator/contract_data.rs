@@ -0,0 +1,229 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+
+use super::scval::{parse_scval_prefix, ScVal};
+
+/// LedgerEntryType of contract data entries.
+const LEDGER_ENTRY_CONTRACT_DATA: u32 = 6;
+/// ScVal discriminant of a contract instance value.
+const SC_VAL_CONTRACT_INSTANCE: u32 = 19;
+/// ScVal discriminant of the key a contract instance is stored under.
+const SC_VAL_LEDGER_KEY_CONTRACT_INSTANCE: u32 = 20;
+/// ContractExecutable discriminant of a WASM contract (followed by its hash).
+const CONTRACT_EXECUTABLE_WASM: u32 = 0;
+
+/// Where a contract keeps a storage entry. Temporary and persistent entries
+/// are ledger entries of their own; instance storage lives inside the
+/// contract instance entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageDurability {
+    Temporary,
+    Persistent,
+    Instance,
+}
+
+impl StorageDurability {
+    /// The `ContractDataDurability` of the ledger entry holding the value.
+    fn xdr_durability(&self) -> u32 {
+        match self {
+            StorageDurability::Temporary => 0,
+            StorageDurability::Persistent | StorageDurability::Instance => 1,
+        }
+    }
+}
+
+impl FromStr for StorageDurability {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "temporary" => Ok(StorageDurability::Temporary),
+            "persistent" => Ok(StorageDurability::Persistent),
+            "instance" => Ok(StorageDurability::Instance),
+            _ => Err(anyhow!("durability must be persistent, temporary or instance, got '{}'", s)),
+        }
+    }
+}
+
+/// Base64 `LedgerKey` of the contract data entry holding `key` for
+/// `contract`. For instance storage this is the contract instance entry,
+/// whatever the key.
+pub fn contract_data_key(contract: &[u8; 32], key: &ScVal, durability: StorageDurability) -> String {
+    let mut xdr = LEDGER_ENTRY_CONTRACT_DATA.to_be_bytes().to_vec();
+    xdr.extend_from_slice(&1u32.to_be_bytes()); // SC_ADDRESS_TYPE_CONTRACT
+    xdr.extend_from_slice(contract);
+    match durability {
+        StorageDurability::Instance => xdr.extend_from_slice(&SC_VAL_LEDGER_KEY_CONTRACT_INSTANCE.to_be_bytes()),
+        _ => xdr.extend(key.to_xdr()),
+    }
+    xdr.extend_from_slice(&durability.xdr_durability().to_be_bytes());
+    base64::engine::general_purpose::STANDARD.encode(xdr)
+}
+
+/// The value of a contract data entry, from its base64 `LedgerEntryData`.
+/// A contract instance entry yields its instance storage as a map.
+pub fn decode_contract_data(entry_xdr: &str) -> Result<ScVal> {
+    let data = decode_entry(entry_xdr)?;
+    let mut offset = value_offset(&data)?;
+
+    let value = data.get(offset..).unwrap_or_default();
+    if value.get(..4) != Some(&SC_VAL_CONTRACT_INSTANCE.to_be_bytes()) {
+        return Ok(parse_scval_prefix(value)?.0);
+    }
+
+    // ContractInstance: executable, then optional instance storage map
+    offset += 4;
+    if read_u32(&data, &mut offset)? == CONTRACT_EXECUTABLE_WASM {
+        offset += 32;
+    }
+    let mut storage = Vec::new();
+    if read_u32(&data, &mut offset)? != 0 {
+        let count = read_u32(&data, &mut offset)?;
+        for _ in 0..count {
+            let (key, used) = parse_scval_prefix(data.get(offset..).unwrap_or_default())?;
+            offset += used;
+            let (val, used) = parse_scval_prefix(data.get(offset..).unwrap_or_default())?;
+            offset += used;
+            storage.push((key, val));
+        }
+    }
+    Ok(ScVal::Map(storage))
+}
+
+/// The WASM hash a contract instance entry runs, from its base64
+/// `LedgerEntryData`. None for built-in executables (Stellar Asset Contracts).
+pub fn instance_wasm_hash(entry_xdr: &str) -> Result<Option<[u8; 32]>> {
+    let data = decode_entry(entry_xdr)?;
+    let mut offset = value_offset(&data)?;
+
+    if read_u32(&data, &mut offset)? != SC_VAL_CONTRACT_INSTANCE {
+        return Err(anyhow!("Ledger entry is not a contract instance"));
+    }
+    if read_u32(&data, &mut offset)? != CONTRACT_EXECUTABLE_WASM {
+        return Ok(None);
+    }
+    let hash = data
+        .get(offset..offset + 32)
+        .ok_or_else(|| anyhow!("Ledger entry truncated at byte {}", offset))?;
+    Ok(Some(hash.try_into().expect("32 bytes")))
+}
+
+fn decode_entry(entry_xdr: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(entry_xdr)
+        .map_err(|e| anyhow!("Invalid ledger entry base64: {}", e))
+}
+
+/// Offset of the value in a contract data entry, past its contract, key and
+/// durability.
+fn value_offset(data: &[u8]) -> Result<usize> {
+    let mut offset = 0;
+
+    let entry_type = read_u32(data, &mut offset)?;
+    if entry_type != LEDGER_ENTRY_CONTRACT_DATA {
+        return Err(anyhow!("Ledger entry type {} is not contract data", entry_type));
+    }
+    read_u32(data, &mut offset)?; // ext
+    offset += match read_u32(data, &mut offset)? {
+        0 => 36, // SC_ADDRESS_TYPE_ACCOUNT: key type + ed25519 key
+        1 => 32, // SC_ADDRESS_TYPE_CONTRACT: contract hash
+        other => return Err(anyhow!("Unsupported contract address type {}", other)),
+    };
+
+    // The key: the instance key has no body, anything else is a plain ScVal
+    let tail = data.get(offset..).unwrap_or_default();
+    if tail.get(..4) == Some(&SC_VAL_LEDGER_KEY_CONTRACT_INSTANCE.to_be_bytes()) {
+        offset += 4;
+    } else {
+        offset += parse_scval_prefix(tail)?.1;
+    }
+    read_u32(data, &mut offset)?; // durability
+    Ok(offset)
+}
+
+/// Read a big-endian u32 at `offset`, advancing past it.
+fn read_u32(data: &[u8], offset: &mut usize) -> Result<u32> {
+    let bytes = data
+        .get(*offset..*offset + 4)
+        .ok_or_else(|| anyhow!("Ledger entry truncated at byte {}", offset))?;
+    *offset += 4;
+    Ok(u32::from_be_bytes(bytes.try_into().expect("4 bytes")))
+}
+
+/// The value stored under `key` in a decoded instance storage map.
+pub fn instance_storage_value(storage: &ScVal, key: &ScVal) -> Option<ScVal> {
+    let key_xdr = key.to_xdr();
+    match storage {
+        ScVal::Map(entries) => entries
+            .iter()
+            .find(|(entry_key, _)| entry_key.to_xdr() == key_xdr)
+            .map(|(_, val)| val.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Base64 `LedgerEntryData` for a contract data entry, from its ledger key.
+    fn entry_xdr(ledger_key: &str, value: &[u8]) -> String {
+        let mut xdr = base64::engine::general_purpose::STANDARD.decode(ledger_key).unwrap();
+        // LedgerKey is type + (contract, key, durability); the entry adds ext and the value
+        xdr.splice(4..4, 0u32.to_be_bytes());
+        xdr.extend_from_slice(value);
+        base64::engine::general_purpose::STANDARD.encode(xdr)
+    }
+
+    #[test]
+    fn test_persistent_entry_roundtrip() {
+        let contract = [0x11u8; 32];
+        let key = ScVal::Vec(vec![ScVal::Symbol("Balance".into()), ScVal::U32(7)]);
+        let ledger_key = contract_data_key(&contract, &key, StorageDurability::Persistent);
+
+        let raw = base64::engine::general_purpose::STANDARD.decode(&ledger_key).unwrap();
+        assert_eq!(&raw[..8], &[0, 0, 0, 6, 0, 0, 0, 1]);
+        assert_eq!(&raw[8..40], &contract);
+        assert_eq!(&raw[raw.len() - 4..], &1u32.to_be_bytes());
+        let temporary = contract_data_key(&contract, &key, StorageDurability::Temporary);
+        assert_ne!(temporary, ledger_key);
+
+        let value = decode_contract_data(&entry_xdr(&ledger_key, &ScVal::I128(-5).to_xdr())).unwrap();
+        assert_eq!(value.to_json(), serde_json::json!("-5"));
+
+        assert!(decode_contract_data("AAAAAA==").is_err());
+        assert!("forever".parse::<StorageDurability>().is_err());
+        assert_eq!("Instance".parse::<StorageDurability>().unwrap(), StorageDurability::Instance);
+    }
+
+    #[test]
+    fn test_instance_storage_lookup() {
+        let contract = [0x22u8; 32];
+        let ledger_key = contract_data_key(&contract, &ScVal::Void, StorageDurability::Instance);
+
+        let mut instance = SC_VAL_CONTRACT_INSTANCE.to_be_bytes().to_vec();
+        instance.extend_from_slice(&CONTRACT_EXECUTABLE_WASM.to_be_bytes());
+        instance.extend_from_slice(&[0xaa; 32]);
+        instance.extend_from_slice(&1u32.to_be_bytes());
+        instance.extend_from_slice(&2u32.to_be_bytes());
+        instance.extend(ScVal::Symbol("Admin".into()).to_xdr());
+        instance.extend(ScVal::U64(9).to_xdr());
+        instance.extend(ScVal::Symbol("Name".into()).to_xdr());
+        instance.extend(ScVal::Str("Token".into()).to_xdr());
+
+        let storage = decode_contract_data(&entry_xdr(&ledger_key, &instance)).unwrap();
+        let name = instance_storage_value(&storage, &ScVal::Symbol("Name".into())).unwrap();
+        assert_eq!(name.to_json(), serde_json::json!("Token"));
+        assert!(instance_storage_value(&storage, &ScVal::Symbol("Missing".into())).is_none());
+        assert_eq!(instance_wasm_hash(&entry_xdr(&ledger_key, &instance)).unwrap(), Some([0xaa; 32]));
+
+        // A Stellar Asset Contract runs a built-in executable, with no WASM hash
+        let mut asset = SC_VAL_CONTRACT_INSTANCE.to_be_bytes().to_vec();
+        asset.extend_from_slice(&1u32.to_be_bytes());
+        asset.extend_from_slice(&0u32.to_be_bytes());
+        assert_eq!(instance_wasm_hash(&entry_xdr(&ledger_key, &asset)).unwrap(), None);
+        assert!(instance_wasm_hash(&entry_xdr(&ledger_key, &ScVal::U32(1).to_xdr())).is_err());
+    }
+}
@@ -0,0 +1,486 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+
+use super::address_map::AddressMapper;
+use super::scval::{parse_scval_prefix, ScVal};
+use super::tx::{encode_strkey, STRKEY_VERSION_ACCOUNT};
+
+/// TransactionEnvelope discriminants (EnvelopeType).
+const ENVELOPE_TYPE_TX_V0: u32 = 0;
+const ENVELOPE_TYPE_TX: u32 = 2;
+const ENVELOPE_TYPE_TX_FEE_BUMP: u32 = 5;
+
+/// MuxedAccount discriminants (CryptoKeyType).
+const KEY_TYPE_ED25519: u32 = 0;
+const KEY_TYPE_MUXED_ED25519: u32 = 0x100;
+
+/// Operation type of InvokeHostFunctionOp.
+const OP_INVOKE_HOST_FUNCTION: u32 = 24;
+/// HostFunction discriminant for a contract call.
+const HOST_FUNCTION_TYPE_INVOKE_CONTRACT: u32 = 0;
+/// ScAddress discriminants.
+const SC_ADDRESS_TYPE_ACCOUNT: u32 = 0;
+const SC_ADDRESS_TYPE_CONTRACT: u32 = 1;
+
+/// LedgerEntryType discriminants of a LedgerKey.
+const LEDGER_KEY_ACCOUNT: u32 = 0;
+const LEDGER_KEY_TRUSTLINE: u32 = 1;
+const LEDGER_KEY_OFFER: u32 = 2;
+const LEDGER_KEY_DATA: u32 = 3;
+const LEDGER_KEY_CLAIMABLE_BALANCE: u32 = 4;
+const LEDGER_KEY_LIQUIDITY_POOL: u32 = 5;
+const LEDGER_KEY_CONTRACT_DATA: u32 = 6;
+const LEDGER_KEY_CONTRACT_CODE: u32 = 7;
+const LEDGER_KEY_CONFIG_SETTING: u32 = 8;
+const LEDGER_KEY_TTL: u32 = 9;
+/// ScVal discriminants only used as contract data keys.
+const SCV_LEDGER_KEY_CONTRACT_INSTANCE: u32 = 20;
+const SCV_LEDGER_KEY_NONCE: u32 = 21;
+
+/// The parts of a Stellar transaction envelope that map to EVM transaction fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvelopeSummary {
+    /// Ed25519 key of the transaction source account
+    pub source_account: [u8; 32],
+    /// Sequence number of the (inner) transaction
+    pub sequence: i64,
+    /// Maximum fee the transaction bid, in stroops: the outer fee of a fee bump
+    pub max_fee: u64,
+    /// Contract invoked by the first operation, if it is an InvokeHostFunction call
+    pub invoked_contract: Option<[u8; 32]>,
+    /// Function the first operation invokes on `invoked_contract`
+    pub invoked_function: Option<String>,
+    /// Arguments of that invocation
+    pub invoke_args: Vec<ScVal>,
+}
+
+impl EnvelopeSummary {
+    /// The source account as a G... strkey.
+    pub fn source_account_id(&self) -> String {
+        encode_strkey(STRKEY_VERSION_ACCOUNT, &self.source_account)
+    }
+
+    /// EVM `from` address for the source account.
+    pub fn from_address(&self, mapper: &dyn AddressMapper) -> String {
+        mapper.account_to_evm(&self.source_account)
+    }
+
+    /// EVM `to` address for the invoked contract, if any.
+    pub fn to_address(&self, mapper: &dyn AddressMapper) -> Option<String> {
+        self.invoked_contract.as_ref().map(|contract| mapper.contract_to_evm(contract))
+    }
+}
+
+/// Decode the source account and invoked contract from a base64
+/// `TransactionEnvelope`. Fee-bump envelopes are unwrapped to the inner
+/// transaction, whose source is the logical sender.
+pub fn decode_envelope_summary(envelope_xdr: &str) -> Result<EnvelopeSummary> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(envelope_xdr)
+        .map_err(|e| anyhow!("Invalid envelope base64: {}", e))?;
+    let mut reader = XdrReader::new(&bytes);
+
+    let mut envelope_type = reader.read_u32()?;
+    let mut fee_bump_fee = None;
+    if envelope_type == ENVELOPE_TYPE_TX_FEE_BUMP {
+        // FeeBumpTransaction: feeSource, fee, then the inner envelope
+        read_muxed_account(&mut reader)?;
+        fee_bump_fee = Some(reader.read_i64()?);
+        envelope_type = reader.read_u32()?;
+        if envelope_type != ENVELOPE_TYPE_TX {
+            return Err(anyhow!("Unsupported fee bump inner envelope type {}", envelope_type));
+        }
+    }
+
+    let source_account = match envelope_type {
+        ENVELOPE_TYPE_TX => read_muxed_account(&mut reader)?,
+        // TransactionV0 stores the bare Ed25519 key
+        ENVELOPE_TYPE_TX_V0 => reader.read_bytes32()?,
+        other => return Err(anyhow!("Unsupported envelope type {}", other)),
+    };
+
+    let fee = reader.read_u32()?;
+    let sequence = reader.read_i64()?;
+    if envelope_type == ENVELOPE_TYPE_TX_V0 {
+        // Optional TimeBounds
+        if reader.read_u32()? != 0 {
+            reader.skip(16)?;
+        }
+    } else {
+        skip_preconditions(&mut reader)?;
+    }
+    skip_memo(&mut reader)?;
+
+    let op_count = reader.read_u32()?;
+    let invocation = if op_count > 0 {
+        read_invocation(&mut reader)?
+    } else {
+        None
+    };
+    let (invoked_contract, invoked_function, invoke_args) = match invocation {
+        Some((contract, function, args)) => (Some(contract), Some(function), args),
+        None => (None, None, Vec::new()),
+    };
+
+    Ok(EnvelopeSummary {
+        source_account,
+        sequence,
+        max_fee: fee_bump_fee.map_or(u64::from(fee), |fee| u64::try_from(fee).unwrap_or(0)),
+        invoked_contract,
+        invoked_function,
+        invoke_args,
+    })
+}
+
+/// The ledger keys (base64 `LedgerKey`s) in the footprint of a base64
+/// `SorobanTransactionData`, read-only keys first.
+pub fn decode_footprint_keys(transaction_data_xdr: &str) -> Result<Vec<String>> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(transaction_data_xdr)
+        .map_err(|e| anyhow!("Invalid transaction data base64: {}", e))?;
+    let mut reader = XdrReader::new(&bytes);
+
+    match reader.read_u32()? {
+        0 => {}
+        // SorobanResourcesExtV0: indices of archived entries
+        1 => {
+            let count = reader.read_u32()? as usize;
+            reader.skip(count * 4)?;
+        }
+        other => return Err(anyhow!("Unsupported transaction data extension {}", other)),
+    }
+
+    let mut keys = Vec::new();
+    // Footprint: readOnly, then readWrite
+    for _ in 0..2 {
+        let count = reader.read_u32()?;
+        for _ in 0..count {
+            let start = reader.pos;
+            skip_ledger_key(&mut reader)?;
+            keys.push(base64::engine::general_purpose::STANDARD.encode(&bytes[start..reader.pos]));
+        }
+    }
+    Ok(keys)
+}
+
+/// Skip a LedgerKey.
+fn skip_ledger_key(reader: &mut XdrReader) -> Result<()> {
+    match reader.read_u32()? {
+        LEDGER_KEY_ACCOUNT => skip_account_id(reader),
+        LEDGER_KEY_TRUSTLINE => {
+            skip_account_id(reader)?;
+            match reader.read_u32()? {
+                // Native
+                0 => Ok(()),
+                // AlphaNum4 and AlphaNum12: code, then issuer
+                1 => reader.skip(4).and_then(|_| skip_account_id(reader)),
+                2 => reader.skip(12).and_then(|_| skip_account_id(reader)),
+                // Pool share: pool ID
+                3 => reader.skip(32),
+                other => Err(anyhow!("Unsupported trustline asset type {}", other)),
+            }
+        }
+        LEDGER_KEY_OFFER => skip_account_id(reader).and_then(|_| reader.skip(8)),
+        LEDGER_KEY_DATA => skip_account_id(reader).and_then(|_| reader.skip_var_opaque()),
+        // ClaimableBalanceID: type, then hash
+        LEDGER_KEY_CLAIMABLE_BALANCE => reader.skip(36),
+        LEDGER_KEY_CONTRACT_DATA => {
+            match reader.read_u32()? {
+                SC_ADDRESS_TYPE_ACCOUNT => skip_account_id(reader)?,
+                SC_ADDRESS_TYPE_CONTRACT => reader.skip(32)?,
+                other => return Err(anyhow!("Unsupported contract data address type {}", other)),
+            }
+            skip_contract_data_key(reader)?;
+            // Durability
+            reader.skip(4)
+        }
+        // Hash of the pool, WASM or TTL'd key
+        LEDGER_KEY_LIQUIDITY_POOL | LEDGER_KEY_CONTRACT_CODE | LEDGER_KEY_TTL => reader.skip(32),
+        LEDGER_KEY_CONFIG_SETTING => reader.skip(4),
+        other => Err(anyhow!("Unsupported ledger key type {}", other)),
+    }
+}
+
+/// Skip the ScVal key of a contract data entry, including the ledger-key
+/// kinds that only appear there.
+fn skip_contract_data_key(reader: &mut XdrReader) -> Result<()> {
+    match reader.peek_u32()? {
+        SCV_LEDGER_KEY_CONTRACT_INSTANCE => reader.skip(4),
+        SCV_LEDGER_KEY_NONCE => reader.skip(12),
+        _ => reader.read_scval().map(|_| ()),
+    }
+}
+
+/// Skip an AccountID (an Ed25519 PublicKey).
+fn skip_account_id(reader: &mut XdrReader) -> Result<()> {
+    match reader.read_u32()? {
+        KEY_TYPE_ED25519 => reader.skip(32),
+        other => Err(anyhow!("Unsupported account key type {}", other)),
+    }
+}
+
+/// Read a MuxedAccount and return its Ed25519 key.
+fn read_muxed_account(reader: &mut XdrReader) -> Result<[u8; 32]> {
+    match reader.read_u32()? {
+        KEY_TYPE_ED25519 => reader.read_bytes32(),
+        KEY_TYPE_MUXED_ED25519 => {
+            reader.skip(8)?; // mux id
+            reader.read_bytes32()
+        }
+        other => Err(anyhow!("Unsupported muxed account type {}", other)),
+    }
+}
+
+/// Skip a Preconditions union.
+fn skip_preconditions(reader: &mut XdrReader) -> Result<()> {
+    match reader.read_u32()? {
+        0 => Ok(()),                 // PRECOND_NONE
+        1 => reader.skip(16),        // PRECOND_TIME: TimeBounds
+        2 => {
+            // PRECOND_V2: timeBounds*, ledgerBounds*, minSeqNum*, minSeqAge, minSeqLedgerGap, extraSigners<2>
+            for optional_len in [16, 8, 8] {
+                if reader.read_u32()? != 0 {
+                    reader.skip(optional_len)?;
+                }
+            }
+            reader.skip(8 + 4)?;
+            let signers = reader.read_u32()?;
+            for _ in 0..signers {
+                // SignerKey: ed25519, pre-auth tx, hash(x), or ed25519 signed payload
+                let signer_type = reader.read_u32()?;
+                reader.skip(32)?;
+                if signer_type == 3 {
+                    reader.skip_var_opaque()?;
+                }
+            }
+            Ok(())
+        }
+        other => Err(anyhow!("Unsupported preconditions type {}", other)),
+    }
+}
+
+/// Skip a Memo union.
+fn skip_memo(reader: &mut XdrReader) -> Result<()> {
+    match reader.read_u32()? {
+        0 => Ok(()),                  // MEMO_NONE
+        1 => reader.skip_var_opaque(), // MEMO_TEXT
+        2 => reader.skip(8),          // MEMO_ID
+        3 | 4 => reader.skip(32),     // MEMO_HASH, MEMO_RETURN
+        other => Err(anyhow!("Unsupported memo type {}", other)),
+    }
+}
+
+/// Contract, function name and arguments of an InvokeContractArgs.
+type Invocation = ([u8; 32], String, Vec<ScVal>);
+
+/// Read the first operation and return the call it makes, if it is an
+/// InvokeHostFunction contract call.
+fn read_invocation(reader: &mut XdrReader) -> Result<Option<Invocation>> {
+    // Optional operation source account
+    if reader.read_u32()? != 0 {
+        read_muxed_account(reader)?;
+    }
+    if reader.read_u32()? != OP_INVOKE_HOST_FUNCTION {
+        return Ok(None);
+    }
+    if reader.read_u32()? != HOST_FUNCTION_TYPE_INVOKE_CONTRACT {
+        // Contract creation or WASM upload
+        return Ok(None);
+    }
+
+    let contract = match reader.read_u32()? {
+        SC_ADDRESS_TYPE_CONTRACT => reader.read_bytes32()?,
+        SC_ADDRESS_TYPE_ACCOUNT => return Ok(None),
+        other => return Err(anyhow!("Unsupported contract address type {}", other)),
+    };
+    let function = reader.read_string()?;
+    let arg_count = reader.read_u32()?;
+    let args = (0..arg_count).map(|_| reader.read_scval()).collect::<Result<_>>()?;
+    Ok(Some((contract, function, args)))
+}
+
+/// Sequential reader over XDR bytes.
+struct XdrReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> XdrReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos + len;
+        if end > self.data.len() {
+            return Err(anyhow!("Envelope XDR truncated at byte {}", self.pos));
+        }
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn peek_u32(&self) -> Result<u32> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + 4)
+            .ok_or_else(|| anyhow!("Envelope XDR truncated at byte {}", self.pos))?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_i64(&mut self) -> Result<i64> {
+        let bytes = self.take(8)?;
+        Ok(i64::from_be_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    fn read_bytes32(&mut self) -> Result<[u8; 32]> {
+        let mut out = [0u8; 32];
+        out.copy_from_slice(self.take(32)?);
+        Ok(out)
+    }
+
+    /// Skip a length-prefixed, 4-byte padded opaque or string.
+    fn skip_var_opaque(&mut self) -> Result<()> {
+        let len = self.read_u32()? as usize;
+        self.skip(len + (4 - len % 4) % 4)
+    }
+
+    /// Read a length-prefixed, 4-byte padded string.
+    fn read_string(&mut self) -> Result<String> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.take(len)?;
+        self.skip((4 - len % 4) % 4)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| anyhow!("Invalid XDR string: {}", e))
+    }
+
+    fn read_scval(&mut self) -> Result<ScVal> {
+        let (value, used) = parse_scval_prefix(&self.data[self.pos..])?;
+        self.skip(used)?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator::address_map::DefaultAddressMapper;
+    use crate::translator::scval::ScVal;
+    use crate::translator::tx::build_soroban_invoke_tx;
+
+    #[test]
+    fn test_decode_invoke_envelope() {
+        let source_key = [7u8; 32];
+        let source = encode_strkey(STRKEY_VERSION_ACCOUNT, &source_key);
+        let contract_id = format!("{}{}", "0".repeat(24), "ab".repeat(20));
+
+        let envelope = build_soroban_invoke_tx(
+            &source,
+            42,
+            &contract_id,
+            "transfer",
+            &[ScVal::U32(5)],
+            "Test SDF Network ; September 2015",
+            100,
+        )
+        .unwrap();
+
+        let summary = decode_envelope_summary(&envelope).unwrap();
+        assert_eq!(summary.source_account, source_key);
+        assert_eq!(summary.source_account_id(), source);
+        let mapper = DefaultAddressMapper::new();
+        assert_eq!(summary.to_address(&mapper).unwrap(), format!("0x{}", "ab".repeat(20)));
+        assert_eq!(summary.from_address(&mapper), mapper.account_to_evm(&source_key));
+        assert_eq!(summary.sequence, 42);
+        assert_eq!(summary.max_fee, 100);
+        assert_eq!(summary.invoked_function.as_deref(), Some("transfer"));
+        assert_eq!(summary.invoke_args, vec![ScVal::U32(5)]);
+    }
+
+    #[test]
+    fn test_decode_fee_bump_with_memo_and_time_bounds() {
+        let mut xdr = Vec::new();
+        xdr.extend(ENVELOPE_TYPE_TX_FEE_BUMP.to_be_bytes());
+        xdr.extend(KEY_TYPE_ED25519.to_be_bytes());
+        xdr.extend([9u8; 32]); // fee source
+        xdr.extend(1000i64.to_be_bytes());
+        xdr.extend(ENVELOPE_TYPE_TX.to_be_bytes());
+        xdr.extend(KEY_TYPE_MUXED_ED25519.to_be_bytes());
+        xdr.extend(77u64.to_be_bytes());
+        xdr.extend([3u8; 32]); // inner source
+        xdr.extend(100u32.to_be_bytes());
+        xdr.extend(5i64.to_be_bytes());
+        xdr.extend(1u32.to_be_bytes()); // PRECOND_TIME
+        xdr.extend([0u8; 16]);
+        xdr.extend(1u32.to_be_bytes()); // MEMO_TEXT "hello"
+        xdr.extend(5u32.to_be_bytes());
+        xdr.extend(b"hello\0\0\0");
+        xdr.extend(1u32.to_be_bytes()); // one operation
+        xdr.extend(0u32.to_be_bytes()); // no op source
+        xdr.extend(OP_INVOKE_HOST_FUNCTION.to_be_bytes());
+        xdr.extend(HOST_FUNCTION_TYPE_INVOKE_CONTRACT.to_be_bytes());
+        xdr.extend(SC_ADDRESS_TYPE_CONTRACT.to_be_bytes());
+        xdr.extend([0x11u8; 32]);
+        xdr.extend(4u32.to_be_bytes()); // function "mint", no arguments
+        xdr.extend(b"mint");
+        xdr.extend(0u32.to_be_bytes());
+        let envelope = base64::engine::general_purpose::STANDARD.encode(&xdr);
+
+        let summary = decode_envelope_summary(&envelope).unwrap();
+        assert_eq!(summary.source_account, [3u8; 32]);
+        assert_eq!(summary.invoked_contract, Some([0x11u8; 32]));
+        assert_eq!(summary.invoked_function.as_deref(), Some("mint"));
+        assert_eq!((summary.sequence, summary.max_fee), (5, 1000));
+        assert!(summary.to_address(&DefaultAddressMapper::new()).unwrap().starts_with("0x"));
+        assert!(decode_envelope_summary(&base64::engine::general_purpose::STANDARD.encode(&xdr[..40])).is_err());
+    }
+
+    #[test]
+    fn test_decode_footprint_keys() {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        let mut instance = Vec::new();
+        instance.extend(LEDGER_KEY_CONTRACT_DATA.to_be_bytes());
+        instance.extend(SC_ADDRESS_TYPE_CONTRACT.to_be_bytes());
+        instance.extend([0x11u8; 32]);
+        instance.extend(SCV_LEDGER_KEY_CONTRACT_INSTANCE.to_be_bytes());
+        instance.extend(1u32.to_be_bytes()); // persistent
+        let mut code = LEDGER_KEY_CONTRACT_CODE.to_be_bytes().to_vec();
+        code.extend([0x22u8; 32]);
+        let mut balance = Vec::new();
+        balance.extend(LEDGER_KEY_CONTRACT_DATA.to_be_bytes());
+        balance.extend(SC_ADDRESS_TYPE_CONTRACT.to_be_bytes());
+        balance.extend([0x11u8; 32]);
+        balance.extend(ScVal::Vec(vec![ScVal::Symbol("Balance".into()), ScVal::U32(7)]).to_xdr());
+        balance.extend(1u32.to_be_bytes());
+        let mut trustline = LEDGER_KEY_TRUSTLINE.to_be_bytes().to_vec();
+        trustline.extend(KEY_TYPE_ED25519.to_be_bytes());
+        trustline.extend([0x33u8; 32]);
+        trustline.extend(1u32.to_be_bytes()); // AlphaNum4
+        trustline.extend(b"USDC");
+        trustline.extend(KEY_TYPE_ED25519.to_be_bytes());
+        trustline.extend([0x44u8; 32]);
+
+        // Extension v1 with one archived entry index
+        let mut xdr = vec![0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0];
+        xdr.extend(2u32.to_be_bytes());
+        xdr.extend(&instance);
+        xdr.extend(&code);
+        xdr.extend(2u32.to_be_bytes());
+        xdr.extend(&balance);
+        xdr.extend(&trustline);
+        // Resources and resource fee
+        xdr.extend([0u8; 20]);
+
+        let keys = decode_footprint_keys(&encode(&xdr)).unwrap();
+        assert_eq!(keys, [&instance, &code, &balance, &trustline].map(|key| encode(key)));
+        assert!(decode_footprint_keys(&encode(&xdr[..60])).is_err());
+    }
+}
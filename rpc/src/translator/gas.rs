@@ -0,0 +1,145 @@
+use super::tx::{gas_covering_fee, stroops_to_wei, wei_to_stroops, DEFAULT_WEI_PER_STROOP};
+
+/// Converts Soroban costs and fees to EVM gas at one fixed gas price, and
+/// stroops to wei at the configured XLM peg.
+///
+/// `eth_gasPrice` reports `wei_per_gas`, receipts report the fee charged as
+/// `fee / wei_per_gas` gas at that price, and estimates never fall below the
+/// gas that pays the simulated fee at that price. A wallet's
+/// `estimateGas * gasPrice` therefore covers the fee, and a receipt's
+/// `gasUsed * effectiveGasPrice` is exactly the fee charged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasModel {
+    /// Price of one gas unit in wei. Must divide `wei_per_stroop` so fees
+    /// convert to a whole number of gas units.
+    pub wei_per_gas: u128,
+    /// Wei-equivalent of one stroop, for balances, values and fees
+    pub wei_per_stroop: u128,
+    /// Soroban CPU instructions counted as one gas unit in estimates
+    pub cpu_insns_per_gas: u64,
+    /// Soroban memory bytes counted as one gas unit in estimates
+    pub mem_bytes_per_gas: u64,
+}
+
+impl Default for GasModel {
+    /// 1 XLM shown as 1 ETH, 1 gwei per gas (100 gas per stroop), 1000
+    /// instructions or 100 bytes per gas.
+    fn default() -> Self {
+        Self {
+            wei_per_gas: 1_000_000_000,
+            wei_per_stroop: DEFAULT_WEI_PER_STROOP,
+            cpu_insns_per_gas: 1000,
+            mem_bytes_per_gas: 100,
+        }
+    }
+}
+
+impl GasModel {
+    /// Gas price in wei, as reported by eth_gasPrice and in receipts.
+    pub fn gas_price(&self) -> u128 {
+        self.wei_per_gas
+    }
+
+    /// Wei-equivalent of `stroops` at the model's XLM peg.
+    pub fn stroops_to_wei(&self, stroops: u64) -> u128 {
+        stroops_to_wei(stroops, self.wei_per_stroop)
+    }
+
+    /// Stroops in `wei` at the model's XLM peg, rounded down.
+    pub fn wei_to_stroops(&self, wei: u128) -> u64 {
+        wei_to_stroops(wei, self.wei_per_stroop)
+    }
+
+    /// Gas that pays `fee_stroops` at the model's gas price, rounded up.
+    pub fn fee_to_gas(&self, fee_stroops: u64) -> u64 {
+        gas_covering_fee(0, self.stroops_to_wei(fee_stroops), self.wei_per_gas)
+    }
+
+    /// Gas for the CPU and memory a simulation reported.
+    pub fn resource_gas(&self, cpu_insns: u64, mem_bytes: u64) -> u64 {
+        (cpu_insns / self.cpu_insns_per_gas).saturating_add(mem_bytes / self.mem_bytes_per_gas)
+    }
+
+    /// Gas estimate for a transaction: intrinsic gas plus the simulated
+    /// resources, raised if needed so it pays `fee_stroops` at the model's price.
+    pub fn estimate(&self, intrinsic_gas: u64, cpu_insns: u64, mem_bytes: u64, fee_stroops: u64) -> u64 {
+        intrinsic_gas
+            .saturating_add(self.resource_gas(cpu_insns, mem_bytes))
+            .max(self.fee_to_gas(fee_stroops))
+    }
+
+    /// Problems with the coefficients, for configuration validation.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.wei_per_stroop == 0 {
+            problems.push("XLM_WEI_FACTOR must be nonzero".to_string());
+        } else if self.wei_per_gas == 0 || !self.wei_per_stroop.is_multiple_of(self.wei_per_gas) {
+            problems.push(format!(
+                "GAS_PRICE_WEI must be a nonzero divisor of one stroop ({} wei), got {}",
+                self.wei_per_stroop, self.wei_per_gas
+            ));
+        }
+        if self.cpu_insns_per_gas == 0 {
+            problems.push("GAS_CPU_INSNS_PER_GAS must be nonzero".to_string());
+        }
+        if self.mem_bytes_per_gas == 0 {
+            problems.push("GAS_MEM_BYTES_PER_GAS must be nonzero".to_string());
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_for_known_cost() {
+        let model = GasModel::default();
+
+        // 5M instructions and 200 KB of memory: 5000 + 2000 gas on top of 21000
+        assert_eq!(model.resource_gas(5_000_000, 200_000), 7_000);
+        assert_eq!(model.estimate(21_000, 5_000_000, 200_000, 100), 28_000);
+
+        // A 1000-stroop fee needs 100,000 gas at 1 gwei, more than the resources
+        assert_eq!(model.estimate(21_000, 5_000_000, 200_000, 1_000), 100_000);
+        assert_eq!(
+            u128::from(model.estimate(21_000, 5_000_000, 200_000, 1_000)) * model.gas_price(),
+            model.stroops_to_wei(1_000)
+        );
+    }
+
+    #[test]
+    fn test_fee_to_gas_matches_price() {
+        let model = GasModel::default();
+        assert_eq!(model.gas_price(), 1_000_000_000);
+        assert_eq!(model.fee_to_gas(58_213), 5_821_300);
+        assert_eq!(u128::from(model.fee_to_gas(58_213)) * model.gas_price(), model.stroops_to_wei(58_213));
+
+        let one_per_stroop = GasModel { wei_per_gas: model.wei_per_stroop, ..model };
+        assert_eq!(one_per_stroop.fee_to_gas(58_213), 58_213);
+    }
+
+    #[test]
+    fn test_problems() {
+        assert!(GasModel::default().problems().is_empty());
+        let bad = GasModel { wei_per_gas: 3, cpu_insns_per_gas: 0, mem_bytes_per_gas: 0, ..GasModel::default() };
+        assert_eq!(bad.problems().len(), 3);
+
+        // The gas price must divide a custom peg as well
+        let peg = GasModel { wei_per_stroop: 10_000_000_000, ..GasModel::default() };
+        assert!(peg.problems().is_empty());
+        assert_eq!(GasModel { wei_per_gas: 3_000_000_000, ..peg }.problems().len(), 1);
+        assert_eq!(GasModel { wei_per_stroop: 0, ..peg }.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_custom_peg_keeps_fee_exact() {
+        // 1 stroop = 1 wei: XLM shown with its own 7 decimals, 1 wei per gas
+        let model = GasModel { wei_per_gas: 1, wei_per_stroop: 1, ..GasModel::default() };
+        assert!(model.problems().is_empty());
+        assert_eq!(model.fee_to_gas(58_213), 58_213);
+        assert_eq!(model.wei_to_stroops(model.stroops_to_wei(58_213)), 58_213);
+        assert_eq!(u128::from(model.fee_to_gas(58_213)) * model.gas_price(), model.stroops_to_wei(58_213));
+    }
+}
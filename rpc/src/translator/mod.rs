@@ -1,12 +1,21 @@
 pub mod abi;
+pub mod address_map;
+pub mod contract_data;
+pub mod envelope;
+pub mod gas;
 pub mod receipt;
 pub mod scval;
+pub mod stats;
 pub mod tx;
 
 pub use abi::AbiRegistry;
+pub use address_map::{AddressMap, AddressMapper, DefaultAddressMapper};
+pub use gas::GasModel;
 pub use receipt::{EvmLog, EvmTransaction, EvmTransactionReceipt};
+pub use stats::TranslationStats;
 pub use tx::{
-    decode_calldata, decode_raw_transaction, build_soroban_invoke_tx,
-    evm_address_to_stellar_contract, stroops_to_wei, wei_to_stroops,
-    stellar_fee_to_gas_price, DecodedCalldata, DecodedEvmTransaction, TranslatedTransaction,
+    decode_calldata, decode_raw_transaction, decode_raw_transaction_hex, invokes_contract,
+    build_soroban_invoke_tx, evm_address_to_stellar_contract, parse_call_object, stroops_to_wei, wei_to_stroops,
+    stellar_fee_to_gas_price, CallObject, DecodedCalldata, DecodedEvmTransaction,
+    TranslatedTransaction,
 };
@@ -1,8 +1,62 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use sha3::{Digest, Keccak256};
+use tracing::{debug, warn};
 
 use crate::stellar::types::GetTransactionResponse;
+use crate::translator::address_map::AddressMapper;
+use crate::translator::envelope::EnvelopeSummary;
+use crate::translator::gas::GasModel;
+
+/// TransactionResultCode values that mean the transaction applied successfully.
+const TX_SUCCESS: i32 = 0;
+const TX_FEE_BUMP_INNER_SUCCESS: i32 = 1;
+
+/// Fee and outcome decoded from a Stellar `TransactionResult` XDR.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransactionOutcome {
+    /// Fee charged, in stroops
+    pub fee_charged: u64,
+    /// Whether the transaction (or the inner transaction of a fee bump) succeeded
+    pub success: bool,
+}
+
+/// Decode the fee charged and result code from a base64 `TransactionResult`:
+/// `int64 feeCharged` followed by the `TransactionResultCode` discriminant.
+pub fn parse_transaction_result(result_xdr: &str) -> Result<TransactionOutcome> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(result_xdr)
+        .map_err(|e| anyhow!("Invalid TransactionResult base64: {}", e))?;
+    if bytes.len() < 12 {
+        return Err(anyhow!("TransactionResult too short: {} bytes", bytes.len()));
+    }
+
+    let fee_charged = i64::from_be_bytes(bytes[0..8].try_into().expect("8 bytes"));
+    let code = i32::from_be_bytes(bytes[8..12].try_into().expect("4 bytes"));
+    Ok(TransactionOutcome {
+        fee_charged: u64::try_from(fee_charged).unwrap_or(0),
+        success: code == TX_SUCCESS || code == TX_FEE_BUMP_INNER_SUCCESS,
+    })
+}
+
+/// Outcome of a getTransaction response, from its result XDR when present
+/// and otherwise from the reported status.
+fn transaction_outcome(tx_response: &GetTransactionResponse) -> TransactionOutcome {
+    let parsed = tx_response.result_xdr.as_deref().map(parse_transaction_result);
+    match parsed {
+        Some(Ok(outcome)) => outcome,
+        other => {
+            if let Some(Err(e)) = other {
+                warn!("Could not decode transaction result: {}", e);
+            }
+            TransactionOutcome {
+                fee_charged: 0,
+                success: tx_response.status == "SUCCESS",
+            }
+        }
+    }
+}
 
 /// EVM-formatted transaction receipt.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +93,42 @@ pub struct EvmTransactionReceipt {
     pub tx_type: String,
 }
 
+impl EvmTransactionReceipt {
+    /// Attach the logs the transaction emitted, and the bloom filter over them.
+    pub fn set_logs(&mut self, logs: Vec<EvmLog>) {
+        self.logs_bloom = logs_bloom(&logs);
+        self.logs = logs;
+    }
+}
+
+/// The 2048-bit bloom filter over a set of logs: for the address and each
+/// topic, three bits taken from the low 11 bits of the first three byte pairs
+/// of its keccak256 hash.
+pub fn logs_bloom(logs: &[EvmLog]) -> String {
+    let mut bloom = [0u8; 256];
+    let mut add = |value: &str| {
+        let bytes = match hex::decode(value.trim_start_matches("0x")) {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                warn!("Not adding non-hex value '{}' to logs bloom", value);
+                return;
+            }
+        };
+        let hash = Keccak256::digest(&bytes);
+        for i in [0, 2, 4] {
+            let bit = (usize::from(hash[i]) << 8 | usize::from(hash[i + 1])) & 2047;
+            bloom[255 - bit / 8] |= 1 << (bit % 8);
+        }
+    };
+    for log in logs {
+        add(&log.address);
+        for topic in &log.topics {
+            add(topic);
+        }
+    }
+    format!("0x{}", hex::encode(bloom))
+}
+
 /// EVM-formatted log entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -107,12 +197,10 @@ pub fn build_receipt_from_stellar(
     from_address: &str,
     to_address: Option<&str>,
     contract_address: Option<&str>,
+    gas_model: &GasModel,
 ) -> Result<EvmTransactionReceipt> {
-    let status = match tx_response.status.as_str() {
-        "SUCCESS" => "0x1".to_string(),
-        "FAILED" => "0x0".to_string(),
-        _ => "0x0".to_string(),
-    };
+    let outcome = transaction_outcome(tx_response);
+    let status = if outcome.success { "0x1" } else { "0x0" };
 
     let block_number = tx_response
         .ledger
@@ -123,9 +211,11 @@ pub fn build_receipt_from_stellar(
     let ledger_num = tx_response.ledger.unwrap_or(0);
     let block_hash = format!("0x{:064x}", ledger_num);
 
-    // Estimate gas used from Soroban resource consumption
-    // Default to a reasonable gas amount
-    let gas_used = "0x5208"; // 21000 (standard transfer gas)
+    // Gas is the fee charged at the model's gas price, so `gasUsed *
+    // effectiveGasPrice` is exactly the fee; a single-transaction view of the
+    // ledger makes cumulative gas equal to this transaction's
+    let gas_used = format!("0x{:x}", gas_model.fee_to_gas(outcome.fee_charged));
+    let effective_gas_price = format!("0x{:x}", gas_model.gas_price());
 
     let receipt = EvmTransactionReceipt {
         transaction_hash: ensure_0x_prefix(tx_hash_hex),
@@ -134,13 +224,13 @@ pub fn build_receipt_from_stellar(
         block_number,
         from: ensure_0x_prefix(from_address),
         to: to_address.map(ensure_0x_prefix),
-        cumulative_gas_used: gas_used.to_string(),
-        gas_used: gas_used.to_string(),
-        effective_gas_price: "0x3b9aca00".to_string(), // 1 gwei
+        cumulative_gas_used: gas_used.clone(),
+        gas_used,
+        effective_gas_price,
         contract_address: contract_address.map(ensure_0x_prefix),
-        logs: Vec::new(), // TODO: parse events from result_meta_xdr
+        logs: Vec::new(), // see `set_logs`
         logs_bloom: format!("0x{}", "0".repeat(512)),
-        status,
+        status: status.to_string(),
         tx_type: "0x0".to_string(),
     };
 
@@ -149,11 +239,20 @@ pub fn build_receipt_from_stellar(
 }
 
 /// Build an EVM transaction object from a Stellar transaction.
+///
+/// `envelope` is the decoded transaction envelope and `input` the calldata
+/// its invocation re-encodes to. The nonce is the sequence number less one
+/// (the mapping raw transactions are submitted under), `gas` is the fee bid
+/// at the model's gas price -- the most the transaction could have been
+/// charged, resource fee included -- and the transaction index is its
+/// position in the ledger's application order.
 pub fn build_transaction_from_stellar(
     tx_response: &GetTransactionResponse,
     tx_hash_hex: &str,
-    from_address: &str,
-    to_address: Option<&str>,
+    envelope: Option<&EnvelopeSummary>,
+    input: &str,
+    gas_model: &GasModel,
+    mapper: &dyn AddressMapper,
 ) -> Result<EvmTransaction> {
     let block_number = tx_response
         .ledger
@@ -163,18 +262,32 @@ pub fn build_transaction_from_stellar(
     let ledger_num = tx_response.ledger.unwrap_or(0);
     let block_hash = format!("0x{:064x}", ledger_num);
 
+    // application_order is 1-based
+    let transaction_index = match tx_response.application_order {
+        Some(order @ 1..) => order - 1,
+        _ => 0,
+    };
+
+    let zero = format!("0x{}", "0".repeat(40));
+    let (from, to) = match envelope {
+        Some(summary) => (summary.from_address(mapper), summary.to_address(mapper)),
+        None => (zero.clone(), Some(zero)),
+    };
+    let nonce = envelope.map_or(0, |summary| summary.sequence.saturating_sub(1).max(0));
+    let gas = envelope.map_or(0, |summary| gas_model.fee_to_gas(summary.max_fee));
+
     Ok(EvmTransaction {
         hash: ensure_0x_prefix(tx_hash_hex),
-        nonce: "0x0".to_string(),
+        nonce: format!("0x{:x}", nonce),
         block_hash,
         block_number,
-        transaction_index: "0x0".to_string(),
-        from: ensure_0x_prefix(from_address),
-        to: to_address.map(ensure_0x_prefix),
+        transaction_index: format!("0x{:x}", transaction_index),
+        from,
+        to,
         value: "0x0".to_string(),
-        gas_price: "0x3b9aca00".to_string(),
-        gas: "0x5208".to_string(),
-        input: "0x".to_string(),
+        gas_price: format!("0x{:x}", gas_model.gas_price()),
+        gas: format!("0x{:x}", gas),
+        input: ensure_0x_prefix(input),
         v: "0x1b".to_string(),
         r: format!("0x{}", "0".repeat(64)),
         s: format!("0x{}", "0".repeat(64)),
@@ -210,3 +323,109 @@ fn ensure_0x_prefix(s: &str) -> String {
         format!("0x{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Base64 `TransactionResult` with the given fee and result code, no operation results.
+    fn result_xdr(fee_charged: i64, code: i32) -> String {
+        let mut bytes = fee_charged.to_be_bytes().to_vec();
+        bytes.extend(code.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes()); // empty results / ext
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    fn tx_response(status: &str, result_xdr: Option<String>) -> GetTransactionResponse {
+        serde_json::from_value(serde_json::json!({
+            "status": status,
+            "ledger": 1000,
+            "resultXdr": result_xdr,
+        }))
+        .unwrap()
+    }
+
+    /// One gas unit per stroop, so gas reads as the fee in stroops.
+    fn stroop_gas() -> GasModel {
+        let model = GasModel::default();
+        GasModel { wei_per_gas: model.wei_per_stroop, ..model }
+    }
+
+    #[test]
+    fn test_receipt_status_and_gas_from_result_xdr() {
+        let success = tx_response("SUCCESS", Some(result_xdr(58_213, TX_SUCCESS)));
+        let receipt = build_receipt_from_stellar(&success, "ab", "0x01", None, None, &stroop_gas()).unwrap();
+        assert_eq!(receipt.status, "0x1");
+        assert_eq!(receipt.gas_used, format!("0x{:x}", 58_213));
+        assert_eq!(receipt.cumulative_gas_used, receipt.gas_used);
+        let gas = u128::from_str_radix(&receipt.gas_used[2..], 16).unwrap();
+        let price = u128::from_str_radix(&receipt.effective_gas_price[2..], 16).unwrap();
+        assert_eq!(gas * price, GasModel::default().stroops_to_wei(58_213));
+
+        // txFAILED (-1) charges the fee but reports failure
+        let failed = tx_response("FAILED", Some(result_xdr(100, -1)));
+        let receipt = build_receipt_from_stellar(&failed, "ab", "0x01", None, None, &stroop_gas()).unwrap();
+        assert_eq!(receipt.status, "0x0");
+        assert_eq!(receipt.gas_used, "0x64");
+
+        // At the default 1 gwei per gas, the product is still the fee charged
+        let receipt = build_receipt_from_stellar(&success, "ab", "0x01", None, None, &GasModel::default()).unwrap();
+        assert_eq!(receipt.gas_used, format!("0x{:x}", 5_821_300));
+        let gas = u128::from_str_radix(&receipt.gas_used[2..], 16).unwrap();
+        let price = u128::from_str_radix(&receipt.effective_gas_price[2..], 16).unwrap();
+        assert_eq!(gas * price, GasModel::default().stroops_to_wei(58_213));
+    }
+
+    #[test]
+    fn test_receipt_status_falls_back_to_response_status() {
+        let receipt =
+            build_receipt_from_stellar(&tx_response("SUCCESS", None), "ab", "0x01", None, None, &stroop_gas())
+                .unwrap();
+        assert_eq!(receipt.status, "0x1");
+        assert_eq!(receipt.gas_used, "0x0");
+
+        let fee_bump = parse_transaction_result(&result_xdr(200, TX_FEE_BUMP_INNER_SUCCESS)).unwrap();
+        assert!(fee_bump.success);
+        assert!(parse_transaction_result("AAAA").is_err());
+    }
+
+    #[test]
+    fn test_logs_bloom_sets_three_bits_per_value() {
+        let log = |address: &str, topics: Vec<String>| EvmLog {
+            address: address.to_string(),
+            topics,
+            data: "0x".to_string(),
+            block_number: "0x1".to_string(),
+            transaction_hash: format!("0x{}", "0".repeat(64)),
+            transaction_index: "0x0".to_string(),
+            block_hash: format!("0x{}", "0".repeat(64)),
+            log_index: "0x0".to_string(),
+            removed: false,
+        };
+        let bits = |bloom: &str| -> u32 {
+            hex::decode(&bloom[2..]).unwrap().iter().map(|b| b.count_ones()).sum()
+        };
+
+        let empty = logs_bloom(&[]);
+        assert_eq!(empty, format!("0x{}", "0".repeat(512)));
+
+        let address = format!("0x{}", "11".repeat(20));
+        let topic = format!("0x{}", "22".repeat(32));
+        let address_only = logs_bloom(&[log(&address, vec![])]);
+        assert_eq!(address_only.len(), 2 + 512);
+        assert!((1..=3).contains(&bits(&address_only)));
+
+        // Each value's bits are present in the bloom of a log containing it
+        let both = logs_bloom(&[log(&address, vec![topic])]);
+        assert!(bits(&both) > bits(&address_only) && bits(&both) <= 6);
+        let both_bytes = hex::decode(&both[2..]).unwrap();
+        for (a, b) in hex::decode(&address_only[2..]).unwrap().iter().zip(&both_bytes) {
+            assert_eq!(a & b, *a);
+        }
+
+        let mut receipt = build_pending_receipt("ab");
+        receipt.set_logs(vec![log(&address, vec![])]);
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(receipt.logs_bloom, address_only);
+    }
+}
@@ -1,12 +1,16 @@
 use anyhow::{anyhow, Result};
-use tracing::debug;
+use tracing::{debug, trace};
 
-use super::abi::AbiParam;
+use super::abi::{encode_abi_values, is_dynamic_type, AbiParam};
+use super::address_map::AddressMapper;
+use super::tx::{
+    decode_strkey_checked, evm_address_to_stellar_contract, STRKEY_VERSION_ACCOUNT, STRKEY_VERSION_CONTRACT,
+};
 
 /// Represents a Soroban ScVal type for transaction construction.
 /// Since we are building XDR manually without the full stellar-sdk crate,
 /// we represent ScVal as its XDR-encoded bytes.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ScVal {
     /// Boolean value
     Bool(bool),
@@ -43,7 +47,7 @@ pub enum ScVal {
 }
 
 /// A Stellar address can be either an account (G...) or a contract (C...).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum StellarAddress {
     Account([u8; 32]),
     Contract([u8; 32]),
@@ -157,6 +161,7 @@ impl ScVal {
                 match addr {
                     StellarAddress::Account(key) => {
                         buf.extend_from_slice(&0u32.to_be_bytes()); // SC_ADDRESS_TYPE_ACCOUNT
+                        buf.extend_from_slice(&0u32.to_be_bytes()); // PUBLIC_KEY_TYPE_ED25519
                         buf.extend_from_slice(key);
                     }
                     StellarAddress::Contract(hash) => {
@@ -187,10 +192,97 @@ impl ScVal {
         }
         buf
     }
+
+    /// Render this ScVal as JSON for display. Integers are decimal strings
+    /// (256-bit ones included), bytes and addresses are 0x-prefixed hex of the
+    /// raw bytes or key, and maps are objects keyed by the rendered key (symbols
+    /// and strings as-is, anything else as its JSON text).
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+
+        match self {
+            ScVal::Bool(v) => Value::Bool(*v),
+            ScVal::Void => Value::Null,
+            ScVal::U32(v) => Value::String(v.to_string()),
+            ScVal::I32(v) => Value::String(v.to_string()),
+            ScVal::U64(v) => Value::String(v.to_string()),
+            ScVal::I64(v) => Value::String(v.to_string()),
+            ScVal::U128(v) => Value::String(v.to_string()),
+            ScVal::I128(v) => Value::String(v.to_string()),
+            ScVal::U256(limbs) => Value::String(limbs_to_decimal(*limbs)),
+            ScVal::I256(limbs) => {
+                if limbs[0] >> 63 == 0 {
+                    Value::String(limbs_to_decimal(*limbs))
+                } else {
+                    Value::String(format!("-{}", limbs_to_decimal(negate_limbs(*limbs))))
+                }
+            }
+            ScVal::Bytes(data) => Value::String(format!("0x{}", hex::encode(data))),
+            ScVal::Str(v) | ScVal::Symbol(v) => Value::String(v.clone()),
+            ScVal::Address(StellarAddress::Account(key))
+            | ScVal::Address(StellarAddress::Contract(key)) => {
+                Value::String(format!("0x{}", hex::encode(key)))
+            }
+            ScVal::Vec(items) => Value::Array(items.iter().map(ScVal::to_json).collect()),
+            ScVal::Map(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, val)| {
+                        let key = match key.to_json() {
+                            Value::String(key) => key,
+                            other => other.to_string(),
+                        };
+                        (key, val.to_json())
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Decimal digits of a 256-bit unsigned integer held as big-endian limbs.
+fn limbs_to_decimal(mut limbs: [u64; 4]) -> String {
+    const CHUNK: u128 = 10_000_000_000_000_000_000; // 10^19, the largest power of ten in a u64
+    let mut chunks = Vec::new();
+    while limbs.iter().any(|&l| l != 0) {
+        let mut rem: u128 = 0;
+        for limb in limbs.iter_mut() {
+            let cur = (rem << 64) | *limb as u128;
+            *limb = (cur / CHUNK) as u64;
+            rem = cur % CHUNK;
+        }
+        chunks.push(rem as u64);
+    }
+    match chunks.split_last() {
+        None => "0".to_string(),
+        Some((most, rest)) => rest
+            .iter()
+            .rev()
+            .fold(most.to_string(), |acc, chunk| format!("{}{:019}", acc, chunk)),
+    }
+}
+
+/// Two's complement negation of a 256-bit integer held as big-endian limbs.
+fn negate_limbs(limbs: [u64; 4]) -> [u64; 4] {
+    let mut out = [0u64; 4];
+    let mut carry = true;
+    for i in (0..4).rev() {
+        let (sum, overflow) = (!limbs[i]).overflowing_add(carry as u64);
+        out[i] = sum;
+        carry = overflow;
+    }
+    out
 }
 
 /// Convert ABI-encoded parameter bytes to a ScVal based on the ABI type.
 pub fn abi_param_to_scval(data: &[u8], param: &AbiParam) -> Result<ScVal> {
+    trace!(param = %param.name, param_type = %param.param_type, raw = %hex::encode(data), "ABI param bytes");
+    let scval = convert_abi_param(data, param)?;
+    trace!(param = %param.name, scval = ?scval, "ABI param converted");
+    Ok(scval)
+}
+
+fn convert_abi_param(data: &[u8], param: &AbiParam) -> Result<ScVal> {
     let param_type = param.param_type.as_str();
     debug!(
         "Converting ABI param '{}' of type '{}' ({} bytes)",
@@ -199,6 +291,10 @@ pub fn abi_param_to_scval(data: &[u8], param: &AbiParam) -> Result<ScVal> {
         data.len()
     );
 
+    if let Some((signed, bits)) = parse_int_type(param_type)? {
+        return int_word_to_scval(data, param_type, signed, bits);
+    }
+
     match param_type {
         "bool" => {
             if data.len() < 32 {
@@ -212,82 +308,12 @@ pub fn abi_param_to_scval(data: &[u8], param: &AbiParam) -> Result<ScVal> {
             if data.len() < 32 {
                 return Err(anyhow!("Address data too short"));
             }
-            let mut addr_bytes = [0u8; 32];
-            // Pad the 20-byte EVM address to 32 bytes for Stellar
-            addr_bytes[12..32].copy_from_slice(&data[12..32]);
-            Ok(ScVal::Address(StellarAddress::Contract(addr_bytes)))
-        }
-        "uint8" | "uint16" | "uint32" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Uint data too short"));
-            }
-            let mut bytes = [0u8; 4];
-            bytes.copy_from_slice(&data[28..32]);
-            Ok(ScVal::U32(u32::from_be_bytes(bytes)))
-        }
-        "int8" | "int16" | "int32" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Int data too short"));
-            }
-            let mut bytes = [0u8; 4];
-            bytes.copy_from_slice(&data[28..32]);
-            Ok(ScVal::I32(i32::from_be_bytes(bytes)))
-        }
-        "uint64" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Uint64 data too short"));
-            }
-            let mut bytes = [0u8; 8];
-            bytes.copy_from_slice(&data[24..32]);
-            Ok(ScVal::U64(u64::from_be_bytes(bytes)))
-        }
-        "int64" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Int64 data too short"));
-            }
-            let mut bytes = [0u8; 8];
-            bytes.copy_from_slice(&data[24..32]);
-            Ok(ScVal::I64(i64::from_be_bytes(bytes)))
-        }
-        "uint128" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Uint128 data too short"));
-            }
-            let mut bytes = [0u8; 16];
-            bytes.copy_from_slice(&data[16..32]);
-            Ok(ScVal::U128(u128::from_be_bytes(bytes)))
-        }
-        "int128" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Int128 data too short"));
-            }
-            let mut bytes = [0u8; 16];
-            bytes.copy_from_slice(&data[16..32]);
-            Ok(ScVal::I128(i128::from_be_bytes(bytes)))
-        }
-        "uint256" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Uint256 data too short"));
-            }
-            let mut limbs = [0u64; 4];
-            for i in 0..4 {
-                let mut bytes = [0u8; 8];
-                bytes.copy_from_slice(&data[i * 8..(i + 1) * 8]);
-                limbs[i] = u64::from_be_bytes(bytes);
-            }
-            Ok(ScVal::U256(limbs))
-        }
-        "int256" => {
-            if data.len() < 32 {
-                return Err(anyhow!("Int256 data too short"));
-            }
-            let mut limbs = [0u64; 4];
-            for i in 0..4 {
-                let mut bytes = [0u8; 8];
-                bytes.copy_from_slice(&data[i * 8..(i + 1) * 8]);
-                limbs[i] = u64::from_be_bytes(bytes);
+            if data[..12].iter().any(|&b| b != 0) {
+                return Err(anyhow!("Address word has nonzero upper bytes"));
             }
-            Ok(ScVal::I256(limbs))
+            let mut evm_address = [0u8; 20];
+            evm_address.copy_from_slice(&data[12..32]);
+            Ok(ScVal::Address(evm_to_stellar_address(&evm_address, param)))
         }
         "bytes" => {
             Ok(ScVal::Bytes(data.to_vec()))
@@ -298,21 +324,21 @@ pub fn abi_param_to_scval(data: &[u8], param: &AbiParam) -> Result<ScVal> {
             Ok(ScVal::Str(s))
         }
         t if t.starts_with("bytes") && t.len() > 5 => {
-            // bytesN (fixed-size bytes)
-            let n: usize = t[5..].parse().unwrap_or(32);
-            let end = n.min(data.len());
-            Ok(ScVal::Bytes(data[..end].to_vec()))
+            // bytesN (fixed-size bytes), left-aligned in the word
+            let n: usize = t[5..]
+                .parse()
+                .ok()
+                .filter(|n| (1..=32).contains(n))
+                .ok_or_else(|| anyhow!("Invalid fixed bytes type: {}", t))?;
+            if data.len() < n {
+                return Err(anyhow!("{} data too short", t));
+            }
+            Ok(ScVal::Bytes(data[..n].to_vec()))
         }
         _ => {
             // Default: treat as U256
             if data.len() >= 32 {
-                let mut limbs = [0u64; 4];
-                for i in 0..4 {
-                    let mut bytes = [0u8; 8];
-                    bytes.copy_from_slice(&data[i * 8..(i + 1) * 8]);
-                    limbs[i] = u64::from_be_bytes(bytes);
-                }
-                Ok(ScVal::U256(limbs))
+                Ok(ScVal::U256(read_limbs(&data[..32])))
             } else {
                 Ok(ScVal::Bytes(data.to_vec()))
             }
@@ -320,8 +346,255 @@ pub fn abi_param_to_scval(data: &[u8], param: &AbiParam) -> Result<ScVal> {
     }
 }
 
+/// Convert a JSON argument to a ScVal by its ABI type, as the same value
+/// ABI-encoded in calldata would be. Integers are JSON numbers or decimal or
+/// 0x-hex strings; addresses and bytes are 0x-hex strings. Arrays and tuples
+/// are not supported.
+pub fn json_to_scval(value: &serde_json::Value, param: &AbiParam) -> Result<ScVal> {
+    let param_type = param.param_type.as_str();
+    let hex_arg = || -> Result<Vec<u8>> {
+        let s = value
+            .as_str()
+            .ok_or_else(|| anyhow!("{} argument must be a hex string, got {}", param_type, value))?;
+        hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|e| anyhow!("Invalid {} argument '{}': {}", param_type, s, e))
+    };
+
+    let data = if let Some((signed, _)) = parse_int_type(param_type)? {
+        json_int_word(value, param_type, signed)?
+    } else {
+        match param_type {
+            "bool" => {
+                let b = value
+                    .as_bool()
+                    .ok_or_else(|| anyhow!("bool argument must be true or false, got {}", value))?;
+                let mut word = vec![0u8; 32];
+                word[31] = b as u8;
+                word
+            }
+            "address" => {
+                let address = hex_arg()?;
+                if address.len() != 20 {
+                    return Err(anyhow!("address argument must be 20 bytes, got {}", address.len()));
+                }
+                let mut word = vec![0u8; 12];
+                word.extend(address);
+                word
+            }
+            "string" => value
+                .as_str()
+                .ok_or_else(|| anyhow!("string argument must be a string, got {}", value))?
+                .as_bytes()
+                .to_vec(),
+            "bytes" => hex_arg()?,
+            t if t.starts_with("bytes") => {
+                let mut bytes = hex_arg()?;
+                if t[5..].parse::<usize>().ok() != Some(bytes.len()) {
+                    return Err(anyhow!("{} argument must be {} bytes, got {}", t, &t[5..], bytes.len()));
+                }
+                bytes.resize(32, 0);
+                bytes
+            }
+            other => return Err(anyhow!("Unsupported argument type: {}", other)),
+        }
+    };
+    abi_param_to_scval(&data, param)
+}
+
+/// A JSON integer argument as a 32-byte two's complement ABI word.
+pub(crate) fn json_int_word(value: &serde_json::Value, param_type: &str, signed: bool) -> Result<Vec<u8>> {
+    use num_bigint::{BigInt, Sign};
+
+    let invalid = || anyhow!("Invalid {} argument: {}", param_type, value);
+    let n = match value {
+        serde_json::Value::Number(n) => n.to_string().parse::<BigInt>().map_err(|_| invalid())?,
+        serde_json::Value::String(s) => match s.strip_prefix("0x") {
+            Some(digits) => BigInt::parse_bytes(digits.as_bytes(), 16).ok_or_else(invalid)?,
+            None => s.parse::<BigInt>().map_err(|_| invalid())?,
+        },
+        _ => return Err(invalid()),
+    };
+    if !signed && n.sign() == Sign::Minus {
+        return Err(anyhow!("Value out of range for {}", param_type));
+    }
+
+    let bytes = n.to_signed_bytes_be();
+    // An unsigned 256-bit value may need a 33rd (zero) sign byte
+    let bytes = if !signed && bytes.len() == 33 && bytes[0] == 0 { &bytes[1..] } else { &bytes[..] };
+    if bytes.len() > 32 {
+        return Err(anyhow!("Value out of range for {}", param_type));
+    }
+    let fill = if n.sign() == Sign::Minus { 0xff } else { 0x00 };
+    let mut word = vec![fill; 32 - bytes.len()];
+    word.extend_from_slice(bytes);
+    Ok(word)
+}
+
+/// Parse a ScVal from its tagged JSON form, as the Stellar CLI writes it: an
+/// object with one key naming the type, e.g. `{"symbol": "Balance"}`,
+/// `{"u32": 7}` or `{"vec": [{"symbol": "Allowance"}, {"address": "G..."}]}`.
+/// Integers are JSON numbers or decimal or 0x-hex strings, bytes are hex,
+/// addresses are G... or C... strkeys and maps are lists of `{key, val}`
+/// objects. Void is `"void"`.
+pub fn scval_from_json(value: &serde_json::Value) -> Result<ScVal> {
+    if value.as_str() == Some("void") {
+        return Ok(ScVal::Void);
+    }
+    let (tag, inner) = match value.as_object() {
+        Some(object) if object.len() == 1 => object.iter().next().expect("one entry"),
+        _ => return Err(anyhow!("ScVal must be an object with one type key, got {}", value)),
+    };
+    let text = || {
+        inner
+            .as_str()
+            .ok_or_else(|| anyhow!("{} value must be a string, got {}", tag, inner))
+    };
+    let integer = |signed: bool, bits: usize| {
+        let word = json_int_word(inner, tag, signed)?;
+        int_word_to_scval(&word, tag, signed, bits)
+    };
+
+    match tag.as_str() {
+        "bool" => inner
+            .as_bool()
+            .map(ScVal::Bool)
+            .ok_or_else(|| anyhow!("bool value must be true or false, got {}", inner)),
+        "void" => Ok(ScVal::Void),
+        "u32" => integer(false, 32),
+        "i32" => integer(true, 32),
+        "u64" => integer(false, 64),
+        "i64" => integer(true, 64),
+        "u128" => integer(false, 128),
+        "i128" => integer(true, 128),
+        "u256" => integer(false, 256),
+        "i256" => integer(true, 256),
+        "bytes" => {
+            let s = text()?;
+            hex::decode(s.strip_prefix("0x").unwrap_or(s))
+                .map(ScVal::Bytes)
+                .map_err(|e| anyhow!("Invalid bytes value '{}': {}", s, e))
+        }
+        "string" => Ok(ScVal::Str(text()?.to_string())),
+        "symbol" => Ok(ScVal::Symbol(text()?.to_string())),
+        "address" => {
+            let s = text()?;
+            match s.chars().next() {
+                Some('G') => decode_strkey_checked(STRKEY_VERSION_ACCOUNT, s)
+                    .map(|key| ScVal::Address(StellarAddress::Account(key))),
+                Some('C') => decode_strkey_checked(STRKEY_VERSION_CONTRACT, s)
+                    .map(|key| ScVal::Address(StellarAddress::Contract(key))),
+                _ => Err(anyhow!("address value must be a G... or C... strkey, got '{}'", s)),
+            }
+        }
+        "vec" => inner
+            .as_array()
+            .ok_or_else(|| anyhow!("vec value must be a list, got {}", inner))?
+            .iter()
+            .map(scval_from_json)
+            .collect::<Result<Vec<_>>>()
+            .map(ScVal::Vec),
+        "map" => inner
+            .as_array()
+            .ok_or_else(|| anyhow!("map value must be a list of {{key, val}} objects, got {}", inner))?
+            .iter()
+            .map(|entry| match (entry.get("key"), entry.get("val")) {
+                (Some(key), Some(val)) => Ok((scval_from_json(key)?, scval_from_json(val)?)),
+                _ => Err(anyhow!("map entry must have key and val, got {}", entry)),
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(ScVal::Map),
+        other => Err(anyhow!("Unsupported ScVal type '{}'", other)),
+    }
+}
+
+/// Map an EVM address to a Soroban address.
+///
+/// Convention: the 32-byte Stellar key (ed25519 public key or contract hash)
+/// is the 20 address bytes right-aligned after 12 zero bytes, the same layout
+/// as `evm_address_to_stellar_contract`. The address is an account when the
+/// ABI declares it `address payable`, and a contract otherwise (including
+/// `contract X` internal types).
+pub fn evm_to_stellar_address(evm_address: &[u8; 20], param: &AbiParam) -> StellarAddress {
+    let key = evm_address_to_stellar_contract(evm_address);
+    match param.internal_type.as_deref() {
+        Some("address payable") => StellarAddress::Account(key),
+        _ => StellarAddress::Contract(key),
+    }
+}
+
+/// Map a Soroban address back to an EVM address. Keys in the layout produced
+/// by `evm_to_stellar_address` round-trip exactly; any other account or
+/// contract maps to the hash of its strkey, as in receipts and event logs.
+pub fn stellar_to_evm_address(mapper: &dyn AddressMapper, addr: &StellarAddress) -> [u8; 20] {
+    let hex_address = match addr {
+        StellarAddress::Account(key) => mapper.account_to_evm(key),
+        StellarAddress::Contract(hash) => mapper.contract_to_evm(hash),
+    };
+    let mut evm_address = [0u8; 20];
+    hex::decode_to_slice(&hex_address[2..], &mut evm_address)
+        .expect("EVM address mapping yields 20 hex-encoded bytes");
+    evm_address
+}
+
+/// Parse a Solidity integer type into (signed, bit width). Returns None for
+/// non-integer types; `uint`/`int` are aliases for the 256-bit types.
+pub(crate) fn parse_int_type(param_type: &str) -> Result<Option<(bool, usize)>> {
+    let (signed, width) = if let Some(w) = param_type.strip_prefix("uint") {
+        (false, w)
+    } else if let Some(w) = param_type.strip_prefix("int") {
+        (true, w)
+    } else {
+        return Ok(None);
+    };
+
+    if width.is_empty() {
+        return Ok(Some((signed, 256)));
+    }
+    match width.parse::<usize>() {
+        Ok(bits) if bits % 8 == 0 && (8..=256).contains(&bits) => Ok(Some((signed, bits))),
+        _ => Err(anyhow!("Invalid integer type: {}", param_type)),
+    }
+}
+
+/// Convert an ABI integer word to the smallest ScVal integer type holding
+/// `bits` bits. The bytes above the declared width must be zero (unsigned)
+/// or the sign extension of the value (signed).
+fn int_word_to_scval(data: &[u8], param_type: &str, signed: bool, bits: usize) -> Result<ScVal> {
+    if data.len() < 32 {
+        return Err(anyhow!("{} data too short", param_type));
+    }
+    let word = &data[..32];
+
+    let value_start = 32 - bits / 8;
+    let fill = if signed && word[value_start] & 0x80 != 0 { 0xff } else { 0x00 };
+    if word[..value_start].iter().any(|&b| b != fill) {
+        return Err(anyhow!("Value out of range for {}", param_type));
+    }
+
+    let tail = |n: usize| &word[32 - n..];
+    let scval = match (bits, signed) {
+        (0..=32, false) => ScVal::U32(u32::from_be_bytes(tail(4).try_into()?)),
+        (0..=32, true) => ScVal::I32(i32::from_be_bytes(tail(4).try_into()?)),
+        (33..=64, false) => ScVal::U64(u64::from_be_bytes(tail(8).try_into()?)),
+        (33..=64, true) => ScVal::I64(i64::from_be_bytes(tail(8).try_into()?)),
+        (65..=128, false) => ScVal::U128(u128::from_be_bytes(tail(16).try_into()?)),
+        (65..=128, true) => ScVal::I128(i128::from_be_bytes(tail(16).try_into()?)),
+        _ => {
+            let limbs = read_limbs(word);
+            if signed {
+                ScVal::I256(limbs)
+            } else {
+                ScVal::U256(limbs)
+            }
+        }
+    };
+    Ok(scval)
+}
+
 /// Convert a ScVal back to ABI-encoded bytes based on the expected ABI type.
-pub fn scval_to_abi_bytes(scval: &ScVal, param: &AbiParam) -> Result<Vec<u8>> {
+///
+/// Static types produce a single 32-byte word. Dynamic types (`string`, `bytes`)
+/// produce the bare payload; use `encode_abi_values` to add head/tail framing.
+pub fn scval_to_abi_bytes(mapper: &dyn AddressMapper, scval: &ScVal, param: &AbiParam) -> Result<Vec<u8>> {
     let mut result = vec![0u8; 32]; // Most ABI values are 32 bytes
 
     match scval {
@@ -332,29 +605,19 @@ pub fn scval_to_abi_bytes(scval: &ScVal, param: &AbiParam) -> Result<Vec<u8>> {
             result[28..32].copy_from_slice(&v.to_be_bytes());
         }
         ScVal::I32(v) => {
-            // Sign-extend for negative values
-            if *v < 0 {
-                result = vec![0xffu8; 32];
-            }
-            result[28..32].copy_from_slice(&v.to_be_bytes());
+            result = sign_extend_to_word(&v.to_be_bytes());
         }
         ScVal::U64(v) => {
             result[24..32].copy_from_slice(&v.to_be_bytes());
         }
         ScVal::I64(v) => {
-            if *v < 0 {
-                result = vec![0xffu8; 32];
-            }
-            result[24..32].copy_from_slice(&v.to_be_bytes());
+            result = sign_extend_to_word(&v.to_be_bytes());
         }
         ScVal::U128(v) => {
             result[16..32].copy_from_slice(&v.to_be_bytes());
         }
         ScVal::I128(v) => {
-            if *v < 0 {
-                result = vec![0xffu8; 32];
-            }
-            result[16..32].copy_from_slice(&v.to_be_bytes());
+            result = sign_extend_to_word(&v.to_be_bytes());
         }
         ScVal::U256(limbs) => {
             for (i, limb) in limbs.iter().enumerate() {
@@ -362,17 +625,14 @@ pub fn scval_to_abi_bytes(scval: &ScVal, param: &AbiParam) -> Result<Vec<u8>> {
             }
         }
         ScVal::I256(limbs) => {
+            // Limbs already hold the full 256-bit two's-complement representation
+            // (hi_hi, hi_lo, lo_hi, lo_lo), so the word is copied verbatim.
             for (i, limb) in limbs.iter().enumerate() {
                 result[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_be_bytes());
             }
         }
         ScVal::Address(addr) => {
-            match addr {
-                StellarAddress::Account(key) | StellarAddress::Contract(key) => {
-                    // Place 20 bytes of address at offset 12
-                    result[12..32].copy_from_slice(&key[12..32]);
-                }
-            }
+            result[12..32].copy_from_slice(&stellar_to_evm_address(mapper, addr));
         }
         ScVal::Bytes(data) => {
             if param.param_type == "bytes" {
@@ -397,8 +657,22 @@ pub fn scval_to_abi_bytes(scval: &ScVal, param: &AbiParam) -> Result<Vec<u8>> {
     Ok(result)
 }
 
+/// Sign-extend a big-endian two's-complement integer to a 32-byte ABI word.
+/// The upper bytes are filled with 0xff when the most significant bit is set.
+fn sign_extend_to_word(be_bytes: &[u8]) -> Vec<u8> {
+    let negative = be_bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let fill = if negative { 0xffu8 } else { 0x00u8 };
+    let mut word = vec![fill; 32];
+    word[32 - be_bytes.len()..].copy_from_slice(be_bytes);
+    word
+}
+
 /// Convert a raw XDR ScVal result (from simulateTransaction) to ABI-encoded return bytes.
-pub fn decode_scval_xdr_to_abi(xdr_base64: &str, output_types: &[AbiParam]) -> Result<Vec<u8>> {
+pub fn decode_scval_xdr_to_abi(
+    mapper: &dyn AddressMapper,
+    xdr_base64: &str,
+    output_types: &[AbiParam],
+) -> Result<Vec<u8>> {
     let xdr_bytes = base64::Engine::decode(
         &base64::engine::general_purpose::STANDARD,
         xdr_base64,
@@ -408,37 +682,120 @@ pub fn decode_scval_xdr_to_abi(xdr_base64: &str, output_types: &[AbiParam]) -> R
     // Parse the ScVal from XDR
     let scval = parse_scval_from_xdr(&xdr_bytes)?;
 
-    // If there is one output type, encode directly
-    if output_types.len() == 1 {
-        return scval_to_abi_bytes(&scval, &output_types[0]);
+    if output_types.is_empty() {
+        return Ok(Vec::new());
     }
 
-    // Multiple outputs: expect a Vec/Tuple ScVal
-    if let ScVal::Vec(items) = &scval {
-        let mut result = Vec::new();
-        let default_param = AbiParam {
-            name: String::new(),
-            param_type: "uint256".to_string(),
-            indexed: false,
-            components: None,
-        };
-        for (i, item) in items.iter().enumerate() {
-            let param = output_types.get(i).unwrap_or(&default_param);
-            result.extend(scval_to_abi_bytes(item, param)?);
+    // If there is one output type, encode it with head/tail framing so that
+    // dynamic returns (string, bytes) get their offset and length words
+    if output_types.len() == 1 {
+        let param = &output_types[0];
+
+        // A single struct/tuple output arrives as a Map (or Vec) of its fields
+        if let (Some(components), ScVal::Vec(_) | ScVal::Map(_)) =
+            (tuple_components(param), &scval)
+        {
+            let encoded = encode_scval_outputs(mapper, &scval, components)?;
+            if components.iter().any(|c| is_dynamic_type(&c.param_type)) {
+                // Dynamic tuples are referenced through an offset word
+                let mut framed = vec![0u8; 32];
+                framed[31] = 0x20;
+                framed.extend(encoded);
+                return Ok(framed);
+            }
+            return Ok(encoded);
         }
-        return Ok(result);
+
+        let value = scval_to_abi_bytes(mapper, &scval, param)?;
+        return Ok(encode_abi_values(&[value], output_types));
     }
 
-    // Single value, single output
-    if output_types.is_empty() {
-        return Ok(Vec::new());
+    // Multiple outputs: expect a Vec (tuple) or Map (struct) ScVal
+    encode_scval_outputs(mapper, &scval, output_types)
+}
+
+/// Return the components of a `tuple` ABI param, if it has any.
+fn tuple_components(param: &AbiParam) -> Option<&[AbiParam]> {
+    if param.param_type != "tuple" {
+        return None;
+    }
+    param.components.as_deref().filter(|c| !c.is_empty())
+}
+
+/// Encode a Vec/Map ScVal holding several return values as an ABI tuple.
+fn encode_scval_outputs(mapper: &dyn AddressMapper, scval: &ScVal, output_types: &[AbiParam]) -> Result<Vec<u8>> {
+    let items = scval_output_items(scval, output_types)?;
+    let mut values = Vec::with_capacity(items.len());
+    for (item, param) in items.iter().zip(output_types) {
+        values.push(scval_to_abi_bytes(mapper, item, param)?);
+    }
+    Ok(encode_abi_values(&values, output_types))
+}
+
+/// Pick the ScVal for each declared output from a multi-value return.
+///
+/// Vec items are matched positionally. Map entries (Soroban structs) are matched
+/// by field name when every output is named, otherwise in key order.
+fn scval_output_items<'a>(scval: &'a ScVal, output_types: &[AbiParam]) -> Result<Vec<&'a ScVal>> {
+    let items: Vec<&ScVal> = match scval {
+        ScVal::Vec(items) => items.iter().collect(),
+        ScVal::Map(entries) if output_types.iter().all(|p| !p.name.is_empty()) => {
+            let mut matched = Vec::with_capacity(output_types.len());
+            for param in output_types {
+                let value = entries
+                    .iter()
+                    .find(|(key, _)| scval_key_name(key) == Some(param.name.as_str()))
+                    .map(|(_, value)| value)
+                    .ok_or_else(|| {
+                        anyhow!("Contract return value has no field named '{}'", param.name)
+                    })?;
+                matched.push(value);
+            }
+            if entries.len() != output_types.len() {
+                debug!(
+                    "Ignoring {} extra map fields in contract return value",
+                    entries.len() - output_types.len()
+                );
+            }
+            matched
+        }
+        ScVal::Map(entries) => entries.iter().map(|(_, value)| value).collect(),
+        other => {
+            return Err(anyhow!(
+                "Expected {} return values as a Vec or Map, got {:?}",
+                output_types.len(),
+                other
+            ));
+        }
+    };
+
+    if items.len() != output_types.len() {
+        return Err(anyhow!(
+            "Contract returned {} values but the ABI declares {} outputs",
+            items.len(),
+            output_types.len()
+        ));
     }
 
-    scval_to_abi_bytes(&scval, &output_types[0])
+    Ok(items)
+}
+
+/// Get the field name of a Map key (Symbol or String).
+fn scval_key_name(key: &ScVal) -> Option<&str> {
+    match key {
+        ScVal::Symbol(s) | ScVal::Str(s) => Some(s.as_str()),
+        _ => None,
+    }
 }
 
 /// Parse a ScVal from raw XDR bytes.
 pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
+    parse_scval_prefix(data).map(|(scval, _)| scval)
+}
+
+/// Parse a ScVal from the start of `data`, returning the value and the number
+/// of bytes it occupied (including XDR padding). Used for nested Vec/Map items.
+pub(crate) fn parse_scval_prefix(data: &[u8]) -> Result<(ScVal, usize)> {
     if data.len() < 4 {
         return Err(anyhow!("XDR too short for ScVal discriminant"));
     }
@@ -455,11 +812,11 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             }
             let mut vb = [0u8; 4];
             vb.copy_from_slice(&data[4..8]);
-            Ok(ScVal::Bool(u32::from_be_bytes(vb) != 0))
+            Ok((ScVal::Bool(u32::from_be_bytes(vb) != 0), 8))
         }
         1 => {
             // Void
-            Ok(ScVal::Void)
+            Ok((ScVal::Void, 4))
         }
         3 => {
             // U32
@@ -468,7 +825,7 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             }
             let mut vb = [0u8; 4];
             vb.copy_from_slice(&data[4..8]);
-            Ok(ScVal::U32(u32::from_be_bytes(vb)))
+            Ok((ScVal::U32(u32::from_be_bytes(vb)), 8))
         }
         4 => {
             // I32
@@ -477,7 +834,7 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             }
             let mut vb = [0u8; 4];
             vb.copy_from_slice(&data[4..8]);
-            Ok(ScVal::I32(i32::from_be_bytes(vb)))
+            Ok((ScVal::I32(i32::from_be_bytes(vb)), 8))
         }
         5 => {
             // U64
@@ -486,7 +843,7 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             }
             let mut vb = [0u8; 8];
             vb.copy_from_slice(&data[4..12]);
-            Ok(ScVal::U64(u64::from_be_bytes(vb)))
+            Ok((ScVal::U64(u64::from_be_bytes(vb)), 12))
         }
         6 => {
             // I64
@@ -495,7 +852,7 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             }
             let mut vb = [0u8; 8];
             vb.copy_from_slice(&data[4..12]);
-            Ok(ScVal::I64(i64::from_be_bytes(vb)))
+            Ok((ScVal::I64(i64::from_be_bytes(vb)), 12))
         }
         9 => {
             // U128: hi(u64) + lo(u64)
@@ -508,7 +865,7 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             lo_bytes.copy_from_slice(&data[12..20]);
             let hi = u64::from_be_bytes(hi_bytes) as u128;
             let lo = u64::from_be_bytes(lo_bytes) as u128;
-            Ok(ScVal::U128((hi << 64) | lo))
+            Ok((ScVal::U128((hi << 64) | lo), 20))
         }
         10 => {
             // I128
@@ -521,78 +878,252 @@ pub fn parse_scval_from_xdr(data: &[u8]) -> Result<ScVal> {
             lo_bytes.copy_from_slice(&data[12..20]);
             let hi = i64::from_be_bytes(hi_bytes) as i128;
             let lo = u64::from_be_bytes(lo_bytes) as i128;
-            Ok(ScVal::I128((hi << 64) | lo))
+            Ok((ScVal::I128((hi << 64) | lo), 20))
         }
         11 => {
             // U256: 4x u64
             if data.len() < 36 {
                 return Err(anyhow!("XDR too short for U256"));
             }
-            let mut limbs = [0u64; 4];
-            for i in 0..4 {
-                let mut lb = [0u8; 8];
-                lb.copy_from_slice(&data[4 + i * 8..12 + i * 8]);
-                limbs[i] = u64::from_be_bytes(lb);
+            Ok((ScVal::U256(read_limbs(&data[4..36])), 36))
+        }
+        12 => {
+            // I256: 4x 64-bit limbs (hi_hi is signed, the rest unsigned)
+            if data.len() < 36 {
+                return Err(anyhow!("XDR too short for I256"));
             }
-            Ok(ScVal::U256(limbs))
+            Ok((ScVal::I256(read_limbs(&data[4..36])), 36))
         }
         13 => {
             // Bytes
-            if data.len() < 8 {
-                return Err(anyhow!("XDR too short for Bytes length"));
-            }
-            let mut lb = [0u8; 4];
-            lb.copy_from_slice(&data[4..8]);
-            let len = u32::from_be_bytes(lb) as usize;
-            let end = 8 + len;
-            if data.len() < end {
-                return Err(anyhow!("XDR too short for Bytes data"));
-            }
-            Ok(ScVal::Bytes(data[8..end].to_vec()))
+            let (payload, consumed) = read_xdr_opaque(data, "Bytes")?;
+            Ok((ScVal::Bytes(payload.to_vec()), consumed))
         }
         14 => {
             // String
-            if data.len() < 8 {
-                return Err(anyhow!("XDR too short for String length"));
-            }
-            let mut lb = [0u8; 4];
-            lb.copy_from_slice(&data[4..8]);
-            let len = u32::from_be_bytes(lb) as usize;
-            let end = 8 + len;
-            if data.len() < end {
-                return Err(anyhow!("XDR too short for String data"));
-            }
-            let s = String::from_utf8(data[8..end].to_vec())
-                .unwrap_or_else(|_| hex::encode(&data[8..end]));
-            Ok(ScVal::Str(s))
+            let (payload, consumed) = read_xdr_opaque(data, "String")?;
+            let s = String::from_utf8(payload.to_vec())
+                .unwrap_or_else(|_| hex::encode(payload));
+            Ok((ScVal::Str(s), consumed))
         }
         15 => {
             // Symbol
+            let (payload, consumed) = read_xdr_opaque(data, "Symbol")?;
+            let s = String::from_utf8(payload.to_vec())
+                .unwrap_or_else(|_| hex::encode(payload));
+            Ok((ScVal::Symbol(s), consumed))
+        }
+        16 => {
+            // Vec: optional flag + count + items
+            let (count, mut offset) = read_xdr_optional_count(data, "Vec")?;
+            let mut items = Vec::with_capacity(count.min(data.len() / 4));
+            for _ in 0..count {
+                let (item, used) = parse_scval_prefix(&data[offset..])?;
+                items.push(item);
+                offset += used;
+            }
+            Ok((ScVal::Vec(items), offset))
+        }
+        17 => {
+            // Map: optional flag + count + (key, value) pairs
+            let (count, mut offset) = read_xdr_optional_count(data, "Map")?;
+            let mut entries = Vec::with_capacity(count.min(data.len() / 8));
+            for _ in 0..count {
+                let (key, key_used) = parse_scval_prefix(&data[offset..])?;
+                offset += key_used;
+                let (val, val_used) = parse_scval_prefix(&data[offset..])?;
+                offset += val_used;
+                entries.push((key, val));
+            }
+            Ok((ScVal::Map(entries), offset))
+        }
+        18 => {
+            // Address: SCAddress type + payload
             if data.len() < 8 {
-                return Err(anyhow!("XDR too short for Symbol length"));
+                return Err(anyhow!("XDR too short for Address type"));
             }
-            let mut lb = [0u8; 4];
-            lb.copy_from_slice(&data[4..8]);
-            let len = u32::from_be_bytes(lb) as usize;
-            let end = 8 + len;
-            if data.len() < end {
-                return Err(anyhow!("XDR too short for Symbol data"));
+            let mut tb = [0u8; 4];
+            tb.copy_from_slice(&data[4..8]);
+            match u32::from_be_bytes(tb) {
+                0 => {
+                    // SC_ADDRESS_TYPE_ACCOUNT: PublicKey type + ed25519 key
+                    if data.len() < 44 {
+                        return Err(anyhow!("XDR too short for account Address"));
+                    }
+                    let mut key = [0u8; 32];
+                    key.copy_from_slice(&data[12..44]);
+                    Ok((ScVal::Address(StellarAddress::Account(key)), 44))
+                }
+                1 => {
+                    // SC_ADDRESS_TYPE_CONTRACT: 32-byte contract hash
+                    if data.len() < 40 {
+                        return Err(anyhow!("XDR too short for contract Address"));
+                    }
+                    let mut hash = [0u8; 32];
+                    hash.copy_from_slice(&data[8..40]);
+                    Ok((ScVal::Address(StellarAddress::Contract(hash)), 40))
+                }
+                other => Err(anyhow!("Unsupported SCAddress type: {}", other)),
             }
-            let s = String::from_utf8(data[8..end].to_vec())
-                .unwrap_or_else(|_| hex::encode(&data[8..end]));
-            Ok(ScVal::Symbol(s))
         }
         _ => {
             // Unknown type: return as raw bytes
             debug!("Unknown ScVal discriminant: {}, returning as bytes", disc);
-            Ok(ScVal::Bytes(data.to_vec()))
+            Ok((ScVal::Bytes(data.to_vec()), data.len()))
         }
     }
 }
 
+/// Read four big-endian u64 limbs (hi_hi, hi_lo, lo_hi, lo_lo) from a 32-byte slice.
+fn read_limbs(data: &[u8]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let mut lb = [0u8; 8];
+        lb.copy_from_slice(&data[i * 8..(i + 1) * 8]);
+        *limb = u64::from_be_bytes(lb);
+    }
+    limbs
+}
+
+/// Read a variable-length XDR opaque (length + data + padding) that follows a
+/// 4-byte discriminant. Returns the payload and the total bytes consumed.
+fn read_xdr_opaque<'a>(data: &'a [u8], kind: &str) -> Result<(&'a [u8], usize)> {
+    if data.len() < 8 {
+        return Err(anyhow!("XDR too short for {} length", kind));
+    }
+    let mut lb = [0u8; 4];
+    lb.copy_from_slice(&data[4..8]);
+    let len = u32::from_be_bytes(lb) as usize;
+    let end = 8 + len;
+    if data.len() < end {
+        return Err(anyhow!("XDR too short for {} data", kind));
+    }
+    let padding = (4 - (len % 4)) % 4;
+    Ok((&data[8..end], (end + padding).min(data.len())))
+}
+
+/// Read the optional-present flag and element count of an XDR Vec/Map.
+/// Returns the count and the offset of the first element.
+fn read_xdr_optional_count(data: &[u8], kind: &str) -> Result<(usize, usize)> {
+    if data.len() < 8 {
+        return Err(anyhow!("XDR too short for {} presence flag", kind));
+    }
+    let mut fb = [0u8; 4];
+    fb.copy_from_slice(&data[4..8]);
+    if u32::from_be_bytes(fb) == 0 {
+        // Absent (None) collection
+        return Ok((0, 8));
+    }
+    if data.len() < 12 {
+        return Err(anyhow!("XDR too short for {} length", kind));
+    }
+    let mut lb = [0u8; 4];
+    lb.copy_from_slice(&data[8..12]);
+    Ok((u32::from_be_bytes(lb) as usize, 12))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::translator::address_map::DefaultAddressMapper;
+
+    fn mapper() -> DefaultAddressMapper {
+        DefaultAddressMapper::new()
+    }
+
+    #[test]
+    fn test_scval_from_tagged_json() {
+        use serde_json::json;
+
+        let account = crate::translator::tx::encode_strkey(STRKEY_VERSION_ACCOUNT, &[0x44; 32]);
+        let parsed = scval_from_json(&json!({"vec": [
+            {"symbol": "Allowance"},
+            {"address": account},
+            {"u32": 7},
+            {"i128": "-5"},
+            {"u256": "0x100"},
+            {"bytes": "0xdead"},
+            {"map": [{"key": {"string": "a"}, "val": {"bool": true}}]},
+            "void",
+        ]}))
+        .unwrap();
+        let expected = ScVal::Vec(vec![
+            ScVal::Symbol("Allowance".into()),
+            ScVal::Address(StellarAddress::Account([0x44; 32])),
+            ScVal::U32(7),
+            ScVal::I128(-5),
+            ScVal::U256([0, 0, 0, 0x100]),
+            ScVal::Bytes(vec![0xde, 0xad]),
+            ScVal::Map(vec![(ScVal::Str("a".into()), ScVal::Bool(true))]),
+            ScVal::Void,
+        ]);
+        assert_eq!(parsed.to_xdr(), expected.to_xdr());
+
+        for bad in [
+            json!({"u32": -1}),
+            json!({"u32": 4294967296u64}),
+            json!({"symbol": 5}),
+            json!({"address": "0x1234"}),
+            json!({"float": 1.5}),
+            json!({"u32": 1, "i32": 1}),
+            json!({"map": [{"key": {"u32": 1}}]}),
+        ] {
+            assert!(scval_from_json(&bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_to_json_u256() {
+        use serde_json::json;
+
+        // 2^256 - 1 and 2^64 exercise carries across every limb
+        assert_eq!(
+            ScVal::U256([u64::MAX; 4]).to_json(),
+            json!("115792089237316195423570985008687907853269984665640564039457584007913129639935")
+        );
+        assert_eq!(ScVal::U256([0, 0, 1, 0]).to_json(), json!("18446744073709551616"));
+        assert_eq!(ScVal::U256([0, 0, 0, 0]).to_json(), json!("0"));
+        assert_eq!(ScVal::U256([0, 0, 0, 10_000_000_000_000_000_000]).to_json(), json!("10000000000000000000"));
+        assert_eq!(ScVal::I256([u64::MAX; 4]).to_json(), json!("-1"));
+        assert_eq!(
+            ScVal::I256([1 << 63, 0, 0, 0]).to_json(),
+            json!("-57896044618658097711785492504343953926634992332820282019728792003956564819968")
+        );
+        assert_eq!(ScVal::I128(-7).to_json(), json!("-7"));
+        assert_eq!(ScVal::U32(5).to_json(), json!("5"));
+    }
+
+    #[test]
+    fn test_to_json_address() {
+        use serde_json::json;
+
+        assert_eq!(
+            ScVal::Address(StellarAddress::Contract([0xab; 32])).to_json(),
+            json!(format!("0x{}", "ab".repeat(32)))
+        );
+        assert_eq!(
+            ScVal::Address(StellarAddress::Account([0x01; 32])).to_json(),
+            json!(format!("0x{}", "01".repeat(32)))
+        );
+        assert_eq!(ScVal::Bytes(vec![0xde, 0xad]).to_json(), json!("0xdead"));
+    }
+
+    #[test]
+    fn test_to_json_nested_vec_and_map() {
+        use serde_json::json;
+
+        let scval = ScVal::Vec(vec![
+            ScVal::Map(vec![
+                (ScVal::Symbol("owner".to_string()), ScVal::Bool(true)),
+                (ScVal::U32(1), ScVal::Vec(vec![ScVal::Str("a".to_string()), ScVal::Void])),
+            ]),
+            ScVal::I64(-2),
+        ]);
+        assert_eq!(
+            scval.to_json(),
+            json!([{ "owner": true, "1": ["a", null] }, "-2"])
+        );
+    }
 
     #[test]
     fn test_u32_roundtrip() {
@@ -606,6 +1137,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_account_address_xdr_roundtrip() {
+        // SCAddress account: SC_ADDRESS_TYPE_ACCOUNT, then a PublicKey
+        // (PUBLIC_KEY_TYPE_ED25519 and the key)
+        let account = ScVal::Address(StellarAddress::Account([0x42; 32]));
+        let xdr = account.to_xdr();
+        assert_eq!(xdr.len(), 44);
+        assert_eq!(xdr[..12], [0, 0, 0, 18, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(xdr[12..], [0x42; 32]);
+        assert_eq!(parse_scval_from_xdr(&xdr).unwrap().to_xdr(), xdr);
+
+        // The item after it is read from the right offset
+        let contract = ScVal::Address(StellarAddress::Contract([0x43; 32]));
+        let vec = ScVal::Vec(vec![account, contract, ScVal::U32(7)]).to_xdr();
+        assert_eq!(parse_scval_from_xdr(&vec).unwrap().to_xdr(), vec);
+    }
+
     #[test]
     fn test_bool_roundtrip() {
         let scval = ScVal::Bool(true);
@@ -618,6 +1166,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_vec_of_unaligned_bytes_roundtrip() {
+        let scval = ScVal::Vec(vec![ScVal::Bytes(vec![0xaa]), ScVal::Bytes(vec![1, 2, 3, 4, 5])]);
+        let xdr = scval.to_xdr();
+
+        // Vec disc, present, count; then each Bytes as disc, len, data padded to 4
+        let expected = hex::decode(concat!(
+            "00000010", "00000001", "00000002",
+            "0000000d", "00000001", "aa000000",
+            "0000000d", "00000005", "0102030405000000",
+        ))
+        .unwrap();
+        assert_eq!(xdr, expected);
+
+        match parse_scval_from_xdr(&xdr).unwrap() {
+            ScVal::Vec(items) => match items.as_slice() {
+                [ScVal::Bytes(a), ScVal::Bytes(b)] => {
+                    assert_eq!(a, &[0xaa]);
+                    assert_eq!(b, &[1, 2, 3, 4, 5]);
+                }
+                other => panic!("Expected two Bytes, got {:?}", other),
+            },
+            other => panic!("Expected Vec, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_map_of_unaligned_str_and_bytes_roundtrip() {
+        let scval = ScVal::Map(vec![
+            (ScVal::Str("k".to_string()), ScVal::Bytes(vec![9; 5])),
+            (ScVal::Symbol("abcde".to_string()), ScVal::U32(7)),
+        ]);
+        let xdr = scval.to_xdr();
+        assert_eq!(xdr.len() % 4, 0);
+
+        match parse_scval_from_xdr(&xdr).unwrap() {
+            ScVal::Map(entries) => match entries.as_slice() {
+                [(ScVal::Str(k1), ScVal::Bytes(v1)), (ScVal::Symbol(k2), ScVal::U32(v2))] => {
+                    assert_eq!(k1, "k");
+                    assert_eq!(v1, &[9; 5]);
+                    assert_eq!(k2, "abcde");
+                    assert_eq!(*v2, 7);
+                }
+                other => panic!("Unexpected entries {:?}", other),
+            },
+            other => panic!("Expected Map, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_abi_to_scval_uint256() {
         let mut data = [0u8; 32];
@@ -627,6 +1224,7 @@ mod tests {
             param_type: "uint256".to_string(),
             indexed: false,
             components: None,
+            internal_type: None,
         };
         let scval = abi_param_to_scval(&data, &param).unwrap();
         if let ScVal::U256(limbs) = scval {
@@ -636,4 +1234,327 @@ mod tests {
             panic!("Expected U256");
         }
     }
+
+    #[test]
+    fn test_uint256_limb_order_roundtrips_through_xdr() {
+        use base64::Engine;
+
+        // Limb 0 (hi_hi) and limb 3 (lo_lo) set, so a swapped order would show
+        let mut word = [0u8; 32];
+        word[7] = 0x01;
+        word[31] = 0x01;
+
+        for param_type in ["uint256", "int256"] {
+            let param = int_param(param_type);
+            let scval = abi_param_to_scval(&word, &param).unwrap();
+            let limbs = match &scval {
+                ScVal::U256(limbs) | ScVal::I256(limbs) => *limbs,
+                other => panic!("Expected a 256-bit ScVal, got {:?}", other),
+            };
+            assert_eq!(limbs, [1, 0, 0, 1]);
+
+            // XDR is the discriminant followed by hi_hi, hi_lo, lo_hi, lo_lo
+            let xdr = scval.to_xdr();
+            assert_eq!(&xdr[4..], &word[..]);
+
+            let abi = decode_scval_xdr_to_abi(
+                &mapper(),
+                &base64::engine::general_purpose::STANDARD.encode(&xdr),
+                std::slice::from_ref(&param),
+            )
+            .unwrap();
+            assert_eq!(abi, word.to_vec());
+        }
+    }
+
+    fn int_param(param_type: &str) -> AbiParam {
+        AbiParam {
+            name: "value".to_string(),
+            param_type: param_type.to_string(),
+            indexed: false,
+            components: None,
+            internal_type: None,
+        }
+    }
+
+    #[test]
+    fn test_int256_minus_one_roundtrip() {
+        let data = [0xffu8; 32];
+        let param = int_param("int256");
+        let scval = abi_param_to_scval(&data, &param).unwrap();
+        let encoded = scval_to_abi_bytes(&mapper(), &scval, &param).unwrap();
+        assert_eq!(encoded, data.to_vec());
+
+        let decoded = parse_scval_from_xdr(&scval.to_xdr()).unwrap();
+        assert_eq!(scval_to_abi_bytes(&mapper(), &decoded, &param).unwrap(), data.to_vec());
+    }
+
+    #[test]
+    fn test_int128_minus_one_roundtrip() {
+        let data = [0xffu8; 32];
+        let param = int_param("int128");
+        let scval = abi_param_to_scval(&data, &param).unwrap();
+        assert!(matches!(scval, ScVal::I128(-1)));
+        assert_eq!(scval_to_abi_bytes(&mapper(), &scval, &param).unwrap(), data.to_vec());
+    }
+
+    #[test]
+    fn test_large_negative_int128_roundtrip() {
+        let value: i128 = i128::MIN + 12345;
+        let mut data = [0xffu8; 32];
+        data[16..32].copy_from_slice(&value.to_be_bytes());
+        let param = int_param("int128");
+
+        let scval = abi_param_to_scval(&data, &param).unwrap();
+        let decoded = parse_scval_from_xdr(&scval.to_xdr()).unwrap();
+        if let ScVal::I128(v) = decoded {
+            assert_eq!(v, value);
+        } else {
+            panic!("Expected I128");
+        }
+        assert_eq!(scval_to_abi_bytes(&mapper(), &scval, &param).unwrap(), data.to_vec());
+    }
+
+    #[test]
+    fn test_decode_string_return_is_abi_framed() {
+        let xdr = ScVal::Str("TVA Token".to_string()).to_xdr();
+        let xdr_base64 =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &xdr);
+        let outputs = vec![AbiParam {
+            name: String::new(),
+            param_type: "string".to_string(),
+            indexed: false,
+            components: None,
+            internal_type: None,
+        }];
+
+        let encoded = decode_scval_xdr_to_abi(&mapper(), &xdr_base64, &outputs).unwrap();
+        assert_eq!(encoded.len(), 96);
+        assert_eq!(encoded[31], 0x20); // offset
+        assert_eq!(encoded[63], 9); // length
+        assert_eq!(&encoded[64..73], b"TVA Token");
+        assert!(encoded[73..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_decode_static_return_is_single_word() {
+        let xdr = ScVal::U32(7).to_xdr();
+        let xdr_base64 =
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &xdr);
+        let outputs = vec![int_param("uint32")];
+
+        let encoded = decode_scval_xdr_to_abi(&mapper(), &xdr_base64, &outputs).unwrap();
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(encoded[31], 7);
+    }
+
+    fn named_param(name: &str, param_type: &str) -> AbiParam {
+        AbiParam {
+            name: name.to_string(),
+            param_type: param_type.to_string(),
+            indexed: false,
+            components: None,
+            internal_type: None,
+        }
+    }
+
+    fn to_base64(scval: &ScVal) -> String {
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, scval.to_xdr())
+    }
+
+    #[test]
+    fn test_decode_two_tuple_from_vec() {
+        let scval = ScVal::Vec(vec![ScVal::U32(5), ScVal::Bool(true)]);
+        let outputs = vec![named_param("count", "uint32"), named_param("ok", "bool")];
+
+        let encoded = decode_scval_xdr_to_abi(&mapper(), &to_base64(&scval), &outputs).unwrap();
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(encoded[31], 5);
+        assert_eq!(encoded[63], 1);
+    }
+
+    #[test]
+    fn test_decode_two_tuple_from_map_by_name() {
+        // Soroban sorts struct fields by key, so "count" precedes "ok" here even
+        // though the ABI lists "ok" first.
+        let scval = ScVal::Map(vec![
+            (ScVal::Symbol("count".to_string()), ScVal::U32(5)),
+            (ScVal::Symbol("ok".to_string()), ScVal::Bool(true)),
+        ]);
+        let outputs = vec![named_param("ok", "bool"), named_param("count", "uint32")];
+
+        let encoded = decode_scval_xdr_to_abi(&mapper(), &to_base64(&scval), &outputs).unwrap();
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(encoded[31], 1);
+        assert_eq!(encoded[63], 5);
+    }
+
+    #[test]
+    fn test_decode_single_tuple_output_from_map() {
+        let scval = ScVal::Map(vec![
+            (ScVal::Symbol("a".to_string()), ScVal::U32(1)),
+            (ScVal::Symbol("b".to_string()), ScVal::U32(2)),
+        ]);
+        let outputs = vec![AbiParam {
+            name: "pair".to_string(),
+            param_type: "tuple".to_string(),
+            indexed: false,
+            components: Some(vec![named_param("a", "uint32"), named_param("b", "uint32")]),
+            internal_type: None,
+        }];
+
+        let encoded = decode_scval_xdr_to_abi(&mapper(), &to_base64(&scval), &outputs).unwrap();
+        assert_eq!(encoded.len(), 64);
+        assert_eq!(encoded[31], 1);
+        assert_eq!(encoded[63], 2);
+    }
+
+    #[test]
+    fn test_decode_output_count_mismatch_errors() {
+        let scval = ScVal::Vec(vec![ScVal::U32(5)]);
+        let outputs = vec![named_param("a", "uint32"), named_param("b", "uint32")];
+
+        let err = decode_scval_xdr_to_abi(&mapper(), &to_base64(&scval), &outputs).unwrap_err();
+        assert!(err.to_string().contains("returned 1 values"));
+    }
+
+    #[test]
+    fn test_negative_small_ints_sign_extend() {
+        let encoded = scval_to_abi_bytes(&mapper(), &ScVal::I32(-2), &int_param("int32")).unwrap();
+        let mut expected = [0xffu8; 32];
+        expected[31] = 0xfe;
+        assert_eq!(encoded, expected.to_vec());
+
+        let encoded = scval_to_abi_bytes(&mapper(), &ScVal::I64(7), &int_param("int64")).unwrap();
+        let mut expected = [0u8; 32];
+        expected[31] = 7;
+        assert_eq!(encoded, expected.to_vec());
+    }
+
+    #[test]
+    fn test_odd_width_uints_use_smallest_fitting_type() {
+        let mut data = [0u8; 32];
+        data[29..32].copy_from_slice(&[0xff, 0xff, 0xff]);
+        let scval = abi_param_to_scval(&data, &int_param("uint24")).unwrap();
+        assert!(matches!(scval, ScVal::U32(0xff_ffff)));
+
+        let mut data = [0u8; 32];
+        data[27..32].copy_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x02]);
+        let scval = abi_param_to_scval(&data, &int_param("uint40")).unwrap();
+        assert!(matches!(scval, ScVal::U64(0x01_0000_0002)));
+
+        // A set bit above the declared width is malformed calldata
+        data[26] = 1;
+        assert!(abi_param_to_scval(&data, &int_param("uint40")).is_err());
+    }
+
+    #[test]
+    fn test_odd_width_ints_check_sign_extension() {
+        let mut data = [0xffu8; 32];
+        data[31] = 0xfe;
+        let scval = abi_param_to_scval(&data, &int_param("int24")).unwrap();
+        assert!(matches!(scval, ScVal::I32(-2)));
+        let scval = abi_param_to_scval(&data, &int_param("int72")).unwrap();
+        assert!(matches!(scval, ScVal::I128(-2)));
+
+        // A negative int24 must be sign-extended through the whole word
+        let mut data = [0u8; 32];
+        data[29..32].copy_from_slice(&[0x80, 0x00, 0x00]);
+        assert!(abi_param_to_scval(&data, &int_param("int24")).is_err());
+
+        let scval = abi_param_to_scval(&[0u8; 32], &int_param("uint")).unwrap();
+        assert!(matches!(scval, ScVal::U256(_)));
+        assert!(abi_param_to_scval(&[0u8; 32], &int_param("uint7")).is_err());
+        assert!(abi_param_to_scval(&[0u8; 32], &int_param("int264")).is_err());
+    }
+
+    #[test]
+    fn test_fixed_bytes_sizes() {
+        let data = [0xabu8; 32];
+        let scval = abi_param_to_scval(&data, &int_param("bytes3")).unwrap();
+        assert!(matches!(scval, ScVal::Bytes(ref b) if b.len() == 3));
+        assert!(abi_param_to_scval(&data, &int_param("bytes33")).is_err());
+        assert!(abi_param_to_scval(&data, &int_param("bytes0")).is_err());
+    }
+
+    fn address_param(internal_type: Option<&str>) -> AbiParam {
+        AbiParam {
+            internal_type: internal_type.map(str::to_string),
+            ..int_param("address")
+        }
+    }
+
+    #[test]
+    fn test_address_roundtrip_account_and_contract() {
+        let mut word = [0u8; 32];
+        word[12..32].copy_from_slice(&[0x5a; 20]);
+
+        for (internal_type, is_account) in [
+            (None, false),
+            (Some("contract IERC20"), false),
+            (Some("address payable"), true),
+        ] {
+            let param = address_param(internal_type);
+            let scval = abi_param_to_scval(&word, &param).unwrap();
+            match &scval {
+                ScVal::Address(StellarAddress::Account(key)) => {
+                    assert!(is_account);
+                    assert_eq!(key[12..], [0x5a; 20]);
+                }
+                ScVal::Address(StellarAddress::Contract(hash)) => {
+                    assert!(!is_account);
+                    assert_eq!(hash[12..], [0x5a; 20]);
+                }
+                other => panic!("Expected Address, got {:?}", other),
+            }
+
+            // Through XDR and back to the same ABI word
+            let decoded = parse_scval_from_xdr(&scval.to_xdr()).unwrap();
+            assert_eq!(scval_to_abi_bytes(&mapper(), &decoded, &param).unwrap(), word.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_native_stellar_address_maps_to_hashed_evm_address() {
+        // A real account key does not fit in 20 bytes; it maps the same way
+        // transaction senders do instead of being truncated
+        let key = [0x42u8; 32];
+        let mapper = mapper();
+        let address = ScVal::Address(StellarAddress::Account(key));
+        let encoded = scval_to_abi_bytes(&mapper, &address, &address_param(None)).unwrap();
+        assert_eq!(encoded[..12], [0u8; 12]);
+        assert_eq!(format!("0x{}", hex::encode(&encoded[12..])), mapper.account_to_evm(&key));
+        assert_ne!(encoded[12..], key[12..]);
+
+        // Dirty upper bytes are not a valid address word
+        let word = [0x01u8; 32];
+        assert!(abi_param_to_scval(&word, &address_param(None)).is_err());
+    }
+
+    #[test]
+    fn test_json_arguments_to_scval() {
+        let to_json = |value: serde_json::Value, ty: &str| json_to_scval(&value, &int_param(ty)).map(|v| v.to_json());
+
+        assert_eq!(to_json(serde_json::json!(42), "uint32").unwrap(), "42");
+        assert_eq!(to_json(serde_json::json!("-5"), "int64").unwrap(), "-5");
+        assert_eq!(to_json(serde_json::json!("0xff"), "uint8").unwrap(), "255");
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(to_json(serde_json::json!(max), "uint256").unwrap(), max);
+        assert_eq!(to_json(serde_json::json!(true), "bool").unwrap(), true);
+        assert_eq!(to_json(serde_json::json!("hello"), "string").unwrap(), "hello");
+        assert_eq!(to_json(serde_json::json!("0x0102"), "bytes").unwrap(), "0x0102");
+        assert_eq!(to_json(serde_json::json!("0x0102"), "bytes2").unwrap(), "0x0102");
+
+        let address = format!("0x{}", "5a".repeat(20));
+        let scval = json_to_scval(&serde_json::json!(address), &address_param(None)).unwrap();
+        assert!(matches!(scval, ScVal::Address(StellarAddress::Contract(key)) if key[12..] == [0x5a; 20]));
+
+        // Out of range, mistyped and unsupported arguments
+        assert!(to_json(serde_json::json!(256), "uint8").is_err());
+        assert!(to_json(serde_json::json!(-1), "uint256").is_err());
+        assert!(to_json(serde_json::json!("ten"), "uint256").is_err());
+        assert!(to_json(serde_json::json!("0x01"), "bytes2").is_err());
+        assert!(to_json(serde_json::json!("0x1234"), "address").is_err());
+        assert!(to_json(serde_json::json!([1]), "uint256[]").is_err());
+    }
 }
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{json, Value};
+
+/// Counters of how calls fared in translation, reported by
+/// `tva_getTranslationStats`. One instance lives in the server state and is
+/// handed to every handler that decodes or simulates a call.
+#[derive(Debug, Default)]
+pub struct TranslationStats {
+    /// Calldata whose selector resolved to a registered ABI function
+    abi_resolved: AtomicU64,
+    /// Calldata passed on as raw bytes for want of a registered function
+    raw_fallback: AtomicU64,
+    /// Contract calls simulated
+    simulations: AtomicU64,
+    /// Simulations that failed (eth_call and eth_estimateGas report a revert)
+    reverted: AtomicU64,
+    /// Contract deployments attempted
+    deployments: AtomicU64,
+}
+
+impl TranslationStats {
+    /// Record a calldata decode: `resolved` if the ABI registry named the function.
+    pub fn record_decoded(&self, resolved: bool) {
+        let counter = if resolved { &self.abi_resolved } else { &self.raw_fallback };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a simulated contract call and whether it failed.
+    pub fn record_simulation(&self, reverted: bool) {
+        self.simulations.fetch_add(1, Ordering::Relaxed);
+        if reverted {
+            self.reverted.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Record an attempted contract deployment.
+    pub fn record_deployment(&self) {
+        self.deployments.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The counters as a JSON object.
+    pub fn summary(&self) -> Value {
+        let get = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        json!({
+            "calldataResolvedByAbi": get(&self.abi_resolved),
+            "calldataRawFallback": get(&self.raw_fallback),
+            "simulations": get(&self.simulations),
+            "simulationsReverted": get(&self.reverted),
+            "deploymentsAttempted": get(&self.deployments),
+        })
+    }
+}
@@ -1,10 +1,11 @@
 use anyhow::{anyhow, Context, Result};
 use rlp::Rlp;
 use sha3::{Digest, Keccak256};
-use tracing::{debug, info, warn};
+use tracing::{debug, info, trace, warn};
 
 use super::abi::{AbiRegistry, decode_abi_params};
 use super::scval::{abi_param_to_scval, ScVal};
+use crate::error::TvaError;
 
 /// Decoded EVM transaction fields.
 #[derive(Debug, Clone)]
@@ -29,17 +30,35 @@ pub struct DecodedEvmTransaction {
     pub r: Vec<u8>,
     /// S value of signature
     pub s: Vec<u8>,
+    /// EIP-2930 access list (typed transactions only). Soroban has no use for
+    /// it, but it is part of the signed payload.
+    pub access_list: Vec<AccessListItem>,
+    /// Hash the sender signed, from which the sender is recovered
+    pub signing_hash: [u8; 32],
     /// Raw transaction hash
     pub tx_hash: [u8; 32],
 }
 
+/// One entry of an EIP-2930 access list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessListItem {
+    /// Account address
+    pub address: [u8; 20],
+    /// Storage slots of the account
+    pub storage_keys: Vec<[u8; 32]>,
+}
+
 /// Decoded calldata from an EVM transaction.
 #[derive(Debug, Clone)]
 pub struct DecodedCalldata {
     /// 4-byte function selector
     pub selector: [u8; 4],
-    /// Function name (if resolved from ABI registry)
+    /// Soroban function to invoke: the selector's alias if one is set,
+    /// otherwise the name resolved from the ABI registry
     pub function_name: Option<String>,
+    /// Whether the selector resolved to a function in the ABI registry
+    /// (otherwise the parameters are passed on as raw bytes)
+    pub abi_resolved: bool,
     /// Raw parameter bytes (after selector)
     pub params_data: Vec<u8>,
     /// Decoded parameter values as ScVals (if ABI is available)
@@ -59,9 +78,31 @@ pub struct TranslatedTransaction {
     pub is_deployment: bool,
 }
 
+/// Largest raw transaction accepted, in bytes (geth's limit).
+pub const MAX_RAW_TX_BYTES: usize = 128 * 1024;
+
+/// Decode a raw transaction's hex string, rejecting anything over
+/// `MAX_RAW_TX_BYTES` before the bytes are allocated.
+pub fn decode_raw_transaction_hex(raw_tx_hex: &str) -> Result<Vec<u8>> {
+    let digits = raw_tx_hex.strip_prefix("0x").unwrap_or(raw_tx_hex);
+    let size = digits.len().div_ceil(2);
+    if size > MAX_RAW_TX_BYTES {
+        return Err(oversized_transaction(size));
+    }
+    hex::decode(digits).map_err(|e| anyhow!("Invalid raw transaction hex: {}", e))
+}
+
+fn oversized_transaction(size: usize) -> anyhow::Error {
+    anyhow!("oversized data: transaction size {}, limit {}", size, MAX_RAW_TX_BYTES)
+}
+
 /// RLP-decode a raw EVM transaction.
 /// Supports both legacy and EIP-155 transaction formats.
 pub fn decode_raw_transaction(raw_tx: &[u8]) -> Result<DecodedEvmTransaction> {
+    if raw_tx.len() > MAX_RAW_TX_BYTES {
+        return Err(oversized_transaction(raw_tx.len()));
+    }
+
     // Check for EIP-2718 typed transactions
     let (tx_data, is_typed) = if !raw_tx.is_empty() && raw_tx[0] < 0x7f {
         // Type prefix: skip it for now (handle Type 2 EIP-1559 in future)
@@ -112,7 +153,7 @@ fn decode_legacy_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTrans
     };
 
     let value_bytes: Vec<u8> = rlp.val_at(4).unwrap_or_default();
-    let value = bytes_to_u128(&value_bytes);
+    let value = bytes_to_u128(&value_bytes)?;
 
     let data: Vec<u8> = rlp.val_at(5).unwrap_or_default();
 
@@ -127,9 +168,15 @@ fn decode_legacy_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTrans
         None
     };
 
-    let tx_hash = Keccak256::digest(raw_tx);
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&tx_hash);
+    // EIP-155 signs [nonce, gasPrice, gasLimit, to, value, data, chainId, 0, 0];
+    // pre-EIP-155 signs the first six fields only
+    let mut payload = raw_fields(rlp, 6, 3 * chain_id.is_some() as usize)?;
+    if let Some(id) = chain_id {
+        payload.append(&id);
+        payload.append(&0u8);
+        payload.append(&0u8);
+    }
+    let signing_hash = keccak(&payload.out());
 
     Ok(DecodedEvmTransaction {
         nonce,
@@ -142,7 +189,9 @@ fn decode_legacy_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTrans
         v,
         r,
         s,
-        tx_hash: hash,
+        access_list: Vec::new(),
+        signing_hash,
+        tx_hash: keccak(raw_tx),
     })
 }
 
@@ -164,18 +213,27 @@ fn decode_eip1559_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTran
     };
 
     let value_bytes: Vec<u8> = rlp.val_at(6).unwrap_or_default();
-    let value = bytes_to_u128(&value_bytes);
+    let value = bytes_to_u128(&value_bytes)?;
 
     let data: Vec<u8> = rlp.val_at(7).unwrap_or_default();
-    // access_list at index 8 is ignored for now
+
+    // Unusable on Soroban, but validated: it is covered by the signature
+    let access_list = decode_access_list(&rlp.at(8).map_err(|e| anyhow!("Missing access list: {}", e))?)?;
+    if !access_list.is_empty() {
+        debug!(
+            "EIP-1559 transaction carries an access list of {} entries (not applied on Soroban)",
+            access_list.len()
+        );
+    }
 
     let v: u64 = rlp.val_at(9).unwrap_or(0);
     let r: Vec<u8> = rlp.val_at(10).unwrap_or_default();
     let s: Vec<u8> = rlp.val_at(11).unwrap_or_default();
 
-    let tx_hash = Keccak256::digest(raw_tx);
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&tx_hash);
+    // The sender signs 0x02 || rlp([chain_id, ..., data, access_list])
+    let mut signed = vec![raw_tx[0]];
+    signed.extend_from_slice(&raw_fields(rlp, 9, 0)?.out());
+    let signing_hash = keccak(&signed);
 
     Ok(DecodedEvmTransaction {
         nonce,
@@ -188,7 +246,9 @@ fn decode_eip1559_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTran
         v,
         r,
         s,
-        tx_hash: hash,
+        access_list,
+        signing_hash,
+        tx_hash: keccak(raw_tx),
     })
 }
 
@@ -207,13 +267,11 @@ fn decode_unsigned_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTra
     };
 
     let value_bytes: Vec<u8> = rlp.val_at(4).unwrap_or_default();
-    let value = bytes_to_u128(&value_bytes);
+    let value = bytes_to_u128(&value_bytes)?;
 
     let data: Vec<u8> = rlp.val_at(5).unwrap_or_default();
 
-    let tx_hash = Keccak256::digest(raw_tx);
-    let mut hash = [0u8; 32];
-    hash.copy_from_slice(&tx_hash);
+    let hash = keccak(raw_tx);
 
     Ok(DecodedEvmTransaction {
         nonce,
@@ -226,16 +284,104 @@ fn decode_unsigned_transaction(rlp: &Rlp, raw_tx: &[u8]) -> Result<DecodedEvmTra
         v: 0,
         r: Vec::new(),
         s: Vec::new(),
+        access_list: Vec::new(),
+        signing_hash: hash,
         tx_hash: hash,
     })
 }
 
+/// Decode an EIP-2930 access list: [[address, [storage_key, ...]], ...].
+fn decode_access_list(rlp: &Rlp) -> Result<Vec<AccessListItem>> {
+    if !rlp.is_list() {
+        return Err(anyhow!("Access list must be an RLP list"));
+    }
+
+    rlp.iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            if !entry.is_list() || entry.item_count().ok() != Some(2) {
+                return Err(anyhow!("Access list entry {} must be [address, storageKeys]", i));
+            }
+            let address: Vec<u8> = entry
+                .val_at(0)
+                .map_err(|e| anyhow!("Invalid access list address {}: {}", i, e))?;
+            let address: [u8; 20] = address
+                .try_into()
+                .map_err(|_| anyhow!("Access list address {} must be 20 bytes", i))?;
+
+            let keys = entry.at(1).map_err(|e| anyhow!("Invalid access list entry {}: {}", i, e))?;
+            if !keys.is_list() {
+                return Err(anyhow!("Storage keys of access list entry {} must be a list", i));
+            }
+            let storage_keys = keys
+                .iter()
+                .map(|key| {
+                    let key: Vec<u8> = key
+                        .as_val()
+                        .map_err(|e| anyhow!("Invalid storage key in access list entry {}: {}", i, e))?;
+                    key.try_into()
+                        .map_err(|_| anyhow!("Storage keys in access list entry {} must be 32 bytes", i))
+                })
+                .collect::<Result<_>>()?;
+
+            Ok(AccessListItem { address, storage_keys })
+        })
+        .collect()
+}
+
+/// Start an RLP list of the first `count` fields of `rlp`, copied verbatim,
+/// with room for `extra` more.
+fn raw_fields(rlp: &Rlp, count: usize, extra: usize) -> Result<rlp::RlpStream> {
+    let mut stream = rlp::RlpStream::new_list(count + extra);
+    for i in 0..count {
+        let field = rlp.at(i).map_err(|e| anyhow!("Missing transaction field {}: {}", i, e))?;
+        stream.append_raw(field.as_raw(), 1);
+    }
+    Ok(stream)
+}
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Whether `calldata` sent to `contract_address` invokes a contract function:
+/// it has a selector, or it is empty and the contract has a receive or
+/// fallback function. Anything else is a plain value transfer.
+pub fn invokes_contract(calldata: &[u8], contract_address: &str, abi_registry: &AbiRegistry) -> bool {
+    calldata.len() >= 4
+        || (calldata.is_empty()
+            && abi_registry
+                .lookup_empty_calldata_function(contract_address)
+                .is_some())
+}
+
 /// Decode calldata into function selector and parameters.
+/// Empty calldata resolves to the contract's receive or fallback function,
+/// with a zero selector and no arguments.
 pub fn decode_calldata(
     calldata: &[u8],
     contract_address: &str,
     abi_registry: &AbiRegistry,
 ) -> Result<DecodedCalldata> {
+    if calldata.is_empty() {
+        let info = abi_registry
+            .lookup_empty_calldata_function(contract_address)
+            .ok_or_else(|| {
+                TvaError::AbiNotFound(format!(
+                    "Empty calldata and no receive or fallback function registered for {}",
+                    contract_address
+                ))
+            })?;
+        info!("Resolved {} function for contract {}", info.name, contract_address);
+        return Ok(DecodedCalldata {
+            selector: info.selector,
+            function_name: Some(info.name),
+            abi_resolved: true,
+            params_data: Vec::new(),
+            scval_params: Vec::new(),
+        });
+    }
+
     if calldata.len() < 4 {
         return Err(anyhow!(
             "Calldata too short for function selector (need at least 4 bytes, got {})",
@@ -252,11 +398,12 @@ pub fn decode_calldata(
         hex::encode(selector),
         params_data.len()
     );
+    trace!(selector = %hex::encode(selector), params = %hex::encode(&params_data), "Calldata");
 
     // Look up function in ABI registry
     let function_info = abi_registry.lookup_function(contract_address, &selector);
 
-    let (function_name, scval_params) = if let Some(info) = function_info {
+    let (function_name, scval_params, abi_resolved) = if let Some(info) = function_info {
         info!(
             "Resolved function: {} for contract {}",
             info.name, contract_address
@@ -264,6 +411,12 @@ pub fn decode_calldata(
 
         // Decode ABI params
         let decoded_params = decode_abi_params(&params_data, &info.inputs)?;
+        trace!(
+            function = %info.name,
+            signature = ?info.inputs.iter().map(|p| p.param_type.as_str()).collect::<Vec<_>>(),
+            "Resolved selector 0x{}",
+            hex::encode(selector)
+        );
 
         // Convert to ScVal
         let mut scvals = Vec::new();
@@ -274,7 +427,8 @@ pub fn decode_calldata(
             }
         }
 
-        (Some(info.name), scvals)
+        let symbol = abi_registry.function_alias(&selector).unwrap_or(info.name);
+        (Some(symbol), scvals, true)
     } else {
         warn!(
             "Function selector 0x{} not found in ABI registry for {}",
@@ -287,12 +441,13 @@ pub fn decode_calldata(
         } else {
             vec![]
         };
-        (None, scvals)
+        (abi_registry.function_alias(&selector), scvals, false)
     };
 
     Ok(DecodedCalldata {
         selector,
         function_name,
+        abi_resolved,
         params_data,
         scval_params,
     })
@@ -309,23 +464,112 @@ pub fn build_soroban_invoke_tx(
     network_passphrase: &str,
     fee: u32,
 ) -> Result<String> {
-    // Build the InvokeContractArgs XDR
-    let invoke_args_xdr = build_invoke_contract_args(contract_id, function_name, args)?;
+    // HostFunction: HOST_FUNCTION_TYPE_INVOKE_CONTRACT = 0, then InvokeContractArgs
+    let mut host_function = HOST_FUNCTION_TYPE_INVOKE_CONTRACT.to_be_bytes().to_vec();
+    host_function.extend(build_invoke_contract_args(contract_id, function_name, args)?);
+
+    trace!(contract = %contract_id, function = %function_name, args = ?args, "Invoke arguments");
 
     // Build the transaction XDR
     let tx_xdr = build_transaction_envelope(
         source_account,
         sequence_number,
-        &invoke_args_xdr,
+        &host_function,
         network_passphrase,
         fee,
     )?;
 
-    // Base64 encode
+    // Base64 encode. The envelope is unsigned; signing happens later and is never traced
+    use base64::Engine;
+    let envelope = base64::engine::general_purpose::STANDARD.encode(&tx_xdr);
+    trace!(source = %source_account, sequence = sequence_number, fee, xdr = %envelope, "Unsigned invoke envelope");
+    Ok(envelope)
+}
+
+/// HostFunction discriminants (HostFunctionType).
+const HOST_FUNCTION_TYPE_INVOKE_CONTRACT: u32 = 0;
+const HOST_FUNCTION_TYPE_UPLOAD_CONTRACT_WASM: u32 = 2;
+const HOST_FUNCTION_TYPE_CREATE_CONTRACT_V2: u32 = 3;
+/// EnvelopeType of the HashIDPreimage a contract ID is hashed from.
+const ENVELOPE_TYPE_CONTRACT_ID: u32 = 8;
+
+/// Build a Soroban transaction XDR uploading contract WASM. The WASM's
+/// SHA-256 is the hash `build_soroban_create_contract_tx` deploys it by.
+pub fn build_soroban_upload_wasm_tx(
+    source_account: &str,
+    sequence_number: u64,
+    wasm: &[u8],
+    network_passphrase: &str,
+    fee: u32,
+) -> Result<String> {
+    // HostFunction: HOST_FUNCTION_TYPE_UPLOAD_CONTRACT_WASM = 2, then opaque wasm<>
+    let mut host_function = HOST_FUNCTION_TYPE_UPLOAD_CONTRACT_WASM.to_be_bytes().to_vec();
+    host_function.extend_from_slice(&(wasm.len() as u32).to_be_bytes());
+    host_function.extend_from_slice(wasm);
+    host_function.extend(vec![0u8; (4 - wasm.len() % 4) % 4]);
+
+    let tx_xdr = build_transaction_envelope(source_account, sequence_number, &host_function, network_passphrase, fee)?;
+
+    use base64::Engine;
+    Ok(base64::engine::general_purpose::STANDARD.encode(&tx_xdr))
+}
+
+/// Build a Soroban transaction XDR creating a contract from uploaded WASM,
+/// with the source account as deployer and its constructor called with
+/// `constructor_args`. The new contract's ID is `contract_id_from_address`
+/// of the source account and `salt`.
+pub fn build_soroban_create_contract_tx(
+    source_account: &str,
+    sequence_number: u64,
+    wasm_hash: &[u8; 32],
+    salt: &[u8; 32],
+    constructor_args: &[ScVal],
+    network_passphrase: &str,
+    fee: u32,
+) -> Result<String> {
+    let deployer = decode_stellar_address(source_account)?;
+
+    // HostFunction: HOST_FUNCTION_TYPE_CREATE_CONTRACT_V2 = 3, then CreateContractArgsV2
+    let mut host_function = HOST_FUNCTION_TYPE_CREATE_CONTRACT_V2.to_be_bytes().to_vec();
+    host_function.extend(contract_id_preimage_from_address(&deployer, salt));
+    // ContractExecutable: CONTRACT_EXECUTABLE_WASM = 0, then the WASM hash
+    host_function.extend_from_slice(&0u32.to_be_bytes());
+    host_function.extend_from_slice(wasm_hash);
+    host_function.extend_from_slice(&(constructor_args.len() as u32).to_be_bytes());
+    for arg in constructor_args {
+        host_function.extend(arg.to_xdr());
+    }
+
+    let tx_xdr = build_transaction_envelope(source_account, sequence_number, &host_function, network_passphrase, fee)?;
+
     use base64::Engine;
     Ok(base64::engine::general_purpose::STANDARD.encode(&tx_xdr))
 }
 
+/// ID of the contract `deployer` creates with `salt` on the network with
+/// `network_passphrase`: SHA-256 of the XDR HashIDPreimage
+/// (ENVELOPE_TYPE_CONTRACT_ID, network ID, ContractIDPreimage).
+pub fn contract_id_from_address(network_passphrase: &str, deployer: &[u8; 32], salt: &[u8; 32]) -> [u8; 32] {
+    use sha2::Sha256;
+
+    let mut preimage = ENVELOPE_TYPE_CONTRACT_ID.to_be_bytes().to_vec();
+    preimage.extend_from_slice(&Sha256::digest(network_passphrase.as_bytes()));
+    preimage.extend(contract_id_preimage_from_address(deployer, salt));
+    Sha256::digest(&preimage).into()
+}
+
+/// ContractIDPreimage XDR for CONTRACT_ID_PREIMAGE_FROM_ADDRESS with an
+/// account address.
+fn contract_id_preimage_from_address(deployer: &[u8; 32], salt: &[u8; 32]) -> Vec<u8> {
+    let mut xdr = Vec::with_capacity(76);
+    xdr.extend_from_slice(&0u32.to_be_bytes()); // CONTRACT_ID_PREIMAGE_FROM_ADDRESS
+    xdr.extend_from_slice(&0u32.to_be_bytes()); // SC_ADDRESS_TYPE_ACCOUNT
+    xdr.extend_from_slice(&0u32.to_be_bytes()); // PUBLIC_KEY_TYPE_ED25519
+    xdr.extend_from_slice(deployer);
+    xdr.extend_from_slice(salt);
+    xdr
+}
+
 /// Build InvokeContractArgs XDR.
 fn build_invoke_contract_args(
     contract_id: &str,
@@ -360,7 +604,7 @@ fn build_invoke_contract_args(
 fn build_transaction_envelope(
     source_account: &str,
     sequence_number: u64,
-    invoke_args: &[u8],
+    host_function: &[u8],
     _network_passphrase: &str,
     fee: u32,
 ) -> Result<Vec<u8>> {
@@ -395,11 +639,8 @@ fn build_transaction_envelope(
     // Operation type: INVOKE_HOST_FUNCTION = 24
     xdr.extend_from_slice(&24u32.to_be_bytes());
 
-    // Host function type: HOST_FUNCTION_TYPE_INVOKE_CONTRACT = 0
-    xdr.extend_from_slice(&0u32.to_be_bytes());
-
-    // InvokeContractArgs
-    xdr.extend(invoke_args);
+    // HostFunction (discriminant and body)
+    xdr.extend(host_function);
 
     // Auth entries (empty for simulation)
     xdr.extend_from_slice(&0u32.to_be_bytes());
@@ -460,6 +701,82 @@ fn decode_strkey(address: &str) -> Result<[u8; 32]> {
     Ok(result)
 }
 
+/// Strkey version byte of an Ed25519 account ID (G...).
+pub const STRKEY_VERSION_ACCOUNT: u8 = 6 << 3;
+/// Strkey version byte of a contract ID (C...).
+pub const STRKEY_VERSION_CONTRACT: u8 = 2 << 3;
+/// Strkey version byte of an Ed25519 secret seed (S...).
+pub const STRKEY_VERSION_SEED: u8 = 18 << 3;
+
+/// Decode a strkey of the given version, verifying its length, version byte
+/// and checksum.
+pub fn decode_strkey_checked(version: u8, strkey: &str) -> Result<[u8; 32]> {
+    if strkey.len() != 56 {
+        return Err(anyhow!("Strkey must be 56 characters, got {}", strkey.len()));
+    }
+    let decoded = base32_decode(strkey)?;
+    if decoded.len() != 35 {
+        return Err(anyhow!("Strkey decodes to {} bytes, expected 35", decoded.len()));
+    }
+    if decoded[0] != version {
+        return Err(anyhow!("Unexpected strkey version byte 0x{:02x}", decoded[0]));
+    }
+    let checksum = u16::from_le_bytes([decoded[33], decoded[34]]);
+    if crc16_xmodem(&decoded[..33]) != checksum {
+        return Err(anyhow!("Strkey checksum mismatch"));
+    }
+    let mut payload = [0u8; 32];
+    payload.copy_from_slice(&decoded[1..33]);
+    Ok(payload)
+}
+
+/// Encode 32 raw bytes as a Stellar strkey: version byte, payload and
+/// CRC16-XModem checksum (little-endian), base32 without padding.
+pub fn encode_strkey(version: u8, payload: &[u8; 32]) -> String {
+    let mut data = Vec::with_capacity(35);
+    data.push(version);
+    data.extend_from_slice(payload);
+    let checksum = crc16_xmodem(&data);
+    data.extend_from_slice(&checksum.to_le_bytes());
+    base32_encode(&data)
+}
+
+/// CRC16-XModem, the strkey checksum.
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Simple base32 encoding (RFC 4648, no padding).
+fn base32_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    let mut result = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits_in_buffer = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits_in_buffer += 8;
+        while bits_in_buffer >= 5 {
+            bits_in_buffer -= 5;
+            result.push(ALPHABET[((buffer >> bits_in_buffer) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits_in_buffer) - 1;
+    }
+    if bits_in_buffer > 0 {
+        result.push(ALPHABET[((buffer << (5 - bits_in_buffer)) & 0x1f) as usize] as char);
+    }
+
+    result
+}
+
 /// Decode a Stellar address (G...) to 32 bytes.
 fn decode_stellar_address(address: &str) -> Result<[u8; 32]> {
     if address.starts_with('G') && address.len() == 56 {
@@ -506,12 +823,20 @@ fn base32_decode(input: &str) -> Result<Vec<u8>> {
 }
 
 /// Convert a byte slice to u128 (big-endian).
-fn bytes_to_u128(bytes: &[u8]) -> u128 {
-    let mut result: u128 = 0;
-    for &b in bytes {
-        result = (result << 8) | (b as u128);
+///
+/// EVM values are uint256; anything with more than 16 significant bytes does
+/// not fit and is rejected rather than wrapped.
+fn bytes_to_u128(bytes: &[u8]) -> Result<u128> {
+    let significant = &bytes[bytes.iter().take_while(|&&b| b == 0).count()..];
+    if significant.len() > 16 {
+        return Err(anyhow!(
+            "Value 0x{} exceeds the maximum supported value (2^128 - 1)",
+            hex::encode(significant)
+        ));
     }
-    result
+    Ok(significant
+        .iter()
+        .fold(0u128, |acc, &b| (acc << 8) | b as u128))
 }
 
 /// Convert an EVM address (20 bytes) to a Stellar-compatible contract address string.
@@ -523,24 +848,203 @@ pub fn evm_address_to_stellar_contract(evm_address: &[u8; 20]) -> [u8; 32] {
     stellar_addr
 }
 
-/// Convert stroops to a wei-equivalent value.
+/// Default wei-equivalent of one stroop.
 /// 1 XLM = 10^7 stroops, 1 ETH = 10^18 wei
 /// We map: 1 XLM = 1 "ETH" for display, so 1 stroop = 10^11 wei-equivalent
-pub fn stroops_to_wei(stroops: u64) -> u128 {
-    (stroops as u128) * 100_000_000_000 // 10^11
+pub const DEFAULT_WEI_PER_STROOP: u128 = 100_000_000_000;
+
+/// Convert stroops to a wei-equivalent value at `wei_per_stroop` (the
+/// configured `XLM_WEI_FACTOR`).
+pub fn stroops_to_wei(stroops: u64, wei_per_stroop: u128) -> u128 {
+    (stroops as u128).saturating_mul(wei_per_stroop)
 }
 
-/// Convert wei-equivalent to stroops.
-pub fn wei_to_stroops(wei: u128) -> u64 {
-    (wei / 100_000_000_000) as u64
+/// Convert wei-equivalent to stroops at `wei_per_stroop`, rounding down.
+pub fn wei_to_stroops(wei: u128, wei_per_stroop: u128) -> u64 {
+    u64::try_from(wei / wei_per_stroop.max(1)).unwrap_or(u64::MAX)
 }
 
 /// Convert a Stellar fee (in stroops) to an EVM gas price.
 /// Gas price = fee / gas_limit, represented in wei.
-pub fn stellar_fee_to_gas_price(fee_stroops: u64) -> u128 {
+pub fn stellar_fee_to_gas_price(fee_stroops: u64, wei_per_stroop: u128) -> u128 {
     // Represent as a reasonable gas price in gwei range
     // 100 stroops ~= 1 gwei equivalent
-    stroops_to_wei(fee_stroops)
+    stroops_to_wei(fee_stroops, wei_per_stroop)
+}
+
+/// Fee fields from an eth_call / eth_estimateGas call object, in wei.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallFees {
+    /// Legacy gas price
+    pub gas_price: Option<u128>,
+    /// EIP-1559 fee cap
+    pub max_fee_per_gas: Option<u128>,
+    /// EIP-1559 priority fee (tip)
+    pub max_priority_fee_per_gas: Option<u128>,
+}
+
+impl CallFees {
+    /// Parse `gasPrice`, `maxFeePerGas` and `maxPriorityFeePerGas` from a call object,
+    /// rejecting the combinations geth rejects.
+    pub fn from_call_object(call_obj: &serde_json::Value) -> Result<Self> {
+        let fees = CallFees {
+            gas_price: parse_quantity_field(call_obj, "gasPrice")?,
+            max_fee_per_gas: parse_quantity_field(call_obj, "maxFeePerGas")?,
+            max_priority_fee_per_gas: parse_quantity_field(call_obj, "maxPriorityFeePerGas")?,
+        };
+
+        if fees.gas_price.is_some()
+            && (fees.max_fee_per_gas.is_some() || fees.max_priority_fee_per_gas.is_some())
+        {
+            return Err(anyhow!(
+                "both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified"
+            ));
+        }
+        if let (Some(max_fee), Some(tip)) = (fees.max_fee_per_gas, fees.max_priority_fee_per_gas) {
+            if tip > max_fee {
+                return Err(anyhow!(
+                    "maxPriorityFeePerGas ({}) is greater than maxFeePerGas ({})",
+                    tip,
+                    max_fee
+                ));
+            }
+        }
+
+        Ok(fees)
+    }
+
+    /// Whether the call object specified any fee field.
+    pub fn is_empty(&self) -> bool {
+        self == &CallFees::default()
+    }
+
+    /// Price per gas the caller would pay at the given base fee (both in wei).
+    /// Returns None if no fee fields were specified.
+    pub fn effective_gas_price(&self, base_fee_wei: u128) -> Option<u128> {
+        if let Some(price) = self.gas_price {
+            return Some(price);
+        }
+        let tip = self.max_priority_fee_per_gas.unwrap_or(0);
+        match self.max_fee_per_gas {
+            Some(max_fee) => Some(max_fee.min(base_fee_wei.saturating_add(tip))),
+            None if self.max_priority_fee_per_gas.is_some() => {
+                Some(base_fee_wei.saturating_add(tip))
+            }
+            None => None,
+        }
+    }
+}
+
+/// A parsed eth_call / eth_estimateGas call object.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallObject {
+    /// Sender address, if given
+    pub from: Option<String>,
+    /// Target address; None for contract creation
+    pub to: Option<String>,
+    /// Gas limit, if given
+    pub gas: Option<u64>,
+    /// Value in wei
+    pub value: u128,
+    /// Calldata, from `input` or the deprecated `data` field
+    pub data: Vec<u8>,
+    /// Fee fields
+    pub fees: CallFees,
+}
+
+/// Parse a call object. Calldata is read from `input`, falling back to the
+/// deprecated `data`; like geth, both may only be given if they are equal.
+pub fn parse_call_object(call_obj: &serde_json::Value) -> Result<CallObject> {
+    if !call_obj.is_object() {
+        return Err(anyhow!("Call object must be a JSON object, got {}", call_obj));
+    }
+
+    let input = parse_hex_field(call_obj, "input")?;
+    let data = parse_hex_field(call_obj, "data")?;
+    let data = match (input, data) {
+        (Some(input), Some(data)) if input != data => {
+            return Err(anyhow!(
+                "both \"data\" and \"input\" are set and not equal. Please use \"input\" to pass transaction call data"
+            ));
+        }
+        (Some(input), _) => input,
+        (None, Some(data)) => data,
+        (None, None) => Vec::new(),
+    };
+
+    let gas = parse_quantity_field(call_obj, "gas")?
+        .map(|gas| u64::try_from(gas).map_err(|_| anyhow!("Invalid gas: {} exceeds 64 bits", gas)))
+        .transpose()?;
+
+    Ok(CallObject {
+        from: parse_address_field(call_obj, "from")?,
+        to: parse_address_field(call_obj, "to")?,
+        gas,
+        value: parse_quantity_field(call_obj, "value")?.unwrap_or(0),
+        data,
+        fees: CallFees::from_call_object(call_obj)?,
+    })
+}
+
+fn parse_address_field(call_obj: &serde_json::Value, field: &str) -> Result<Option<String>> {
+    match &call_obj[field] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) => Ok(Some(s.clone())),
+        other => Err(anyhow!("Invalid {}: {}", field, other)),
+    }
+}
+
+fn parse_hex_field(call_obj: &serde_json::Value, field: &str) -> Result<Option<Vec<u8>>> {
+    match &call_obj[field] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) => hex::decode(s.strip_prefix("0x").unwrap_or(s))
+            .map(Some)
+            .map_err(|e| anyhow!("Invalid calldata hex in {}: {}", field, e)),
+        other => Err(anyhow!("Invalid {}: {}", field, other)),
+    }
+}
+
+/// Read an optional quantity field of a call object: a hex quantity, or a
+/// JSON number as some clients send.
+fn parse_quantity_field(call_obj: &serde_json::Value, field: &str) -> Result<Option<u128>> {
+    match &call_obj[field] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(s) => parse_quantity(s)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Invalid {} '{}': expected a 0x-prefixed hex quantity", field, s)),
+        serde_json::Value::Number(n) => n
+            .as_u64()
+            .map(|v| Some(v as u128))
+            .ok_or_else(|| anyhow!("Invalid {}: {}", field, n)),
+        other => Err(anyhow!("Invalid {}: {}", field, other)),
+    }
+}
+
+/// Parse a JSON-RPC hex quantity: "0x" followed by at least one hex digit.
+pub fn parse_quantity(value: &str) -> Option<u128> {
+    value
+        .strip_prefix("0x")
+        .filter(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|digits| u128::from_str_radix(digits, 16).ok())
+}
+
+/// Intrinsic EVM gas for a transaction: 21000 base (plus 32000 for contract
+/// creation), 16 per non-zero and 4 per zero calldata byte.
+pub fn intrinsic_gas(data: &[u8], is_creation: bool) -> u64 {
+    let base: u64 = if is_creation { 53_000 } else { 21_000 };
+    let data_gas: u64 = data.iter().map(|&b| if b == 0 { 4 } else { 16 }).sum();
+    base + data_gas
+}
+
+/// Raise a gas estimate so that `gas * gas_price_wei` covers a Stellar fee,
+/// given in wei. Wallets budget `gas * maxFeePerGas`, so an estimate below
+/// this would leave the Soroban fee unpaid.
+pub fn gas_covering_fee(estimate: u64, fee_wei: u128, gas_price_wei: u128) -> u64 {
+    if gas_price_wei == 0 {
+        return estimate;
+    }
+    let needed = fee_wei.div_ceil(gas_price_wei);
+    estimate.max(u64::try_from(needed).unwrap_or(u64::MAX))
 }
 
 #[cfg(test)]
@@ -549,21 +1053,444 @@ mod tests {
 
     #[test]
     fn test_bytes_to_u128() {
-        assert_eq!(bytes_to_u128(&[0x01]), 1);
-        assert_eq!(bytes_to_u128(&[0x01, 0x00]), 256);
-        assert_eq!(bytes_to_u128(&[]), 0);
+        assert_eq!(bytes_to_u128(&[0x01]).unwrap(), 1);
+        assert_eq!(bytes_to_u128(&[0x01, 0x00]).unwrap(), 256);
+        assert_eq!(bytes_to_u128(&[]).unwrap(), 0);
+        assert_eq!(bytes_to_u128(&[0xff; 16]).unwrap(), u128::MAX);
+
+        // Leading zeros do not count towards the width
+        let mut padded = [0u8; 20];
+        padded[19] = 0x2a;
+        assert_eq!(bytes_to_u128(&padded).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_value_wider_than_u128_is_rejected() {
+        let value = [0x01u8; 20];
+        assert!(bytes_to_u128(&value).is_err());
+
+        let mut stream = rlp::RlpStream::new_list(6);
+        stream.append(&0u64);
+        stream.append(&1u64);
+        stream.append(&21_000u64);
+        stream.append(&vec![0x11u8; 20]);
+        stream.append(&value.to_vec());
+        stream.append(&Vec::<u8>::new());
+        let err = decode_raw_transaction(&stream.out()).unwrap_err();
+        assert!(err.to_string().contains("exceeds the maximum supported value"));
+    }
+
+    #[test]
+    fn test_oversized_raw_transaction_is_rejected() {
+        let at_limit = format!("0x{}", "00".repeat(MAX_RAW_TX_BYTES));
+        assert_eq!(decode_raw_transaction_hex(&at_limit).unwrap().len(), MAX_RAW_TX_BYTES);
+
+        let over_limit = format!("0x{}", "00".repeat(MAX_RAW_TX_BYTES + 1));
+        let err = decode_raw_transaction_hex(&over_limit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("oversized data: transaction size {}, limit {}", MAX_RAW_TX_BYTES + 1, MAX_RAW_TX_BYTES)
+        );
+        assert!(decode_raw_transaction(&vec![0xc0; MAX_RAW_TX_BYTES + 1]).is_err());
+        assert!(decode_raw_transaction_hex("0xzz").is_err());
     }
 
     #[test]
     fn test_stroops_to_wei() {
-        assert_eq!(stroops_to_wei(10_000_000), 1_000_000_000_000_000_000); // 1 XLM = 1 ETH equivalent
-        assert_eq!(stroops_to_wei(1), 100_000_000_000); // 1 stroop
+        assert_eq!(stroops_to_wei(10_000_000, DEFAULT_WEI_PER_STROOP), 1_000_000_000_000_000_000); // 1 XLM = 1 ETH equivalent
+        assert_eq!(stroops_to_wei(1, DEFAULT_WEI_PER_STROOP), 100_000_000_000); // 1 stroop
     }
 
     #[test]
     fn test_wei_to_stroops() {
-        assert_eq!(wei_to_stroops(1_000_000_000_000_000_000), 10_000_000); // 1 ETH = 1 XLM
-        assert_eq!(wei_to_stroops(100_000_000_000), 1); // Minimum
+        assert_eq!(wei_to_stroops(1_000_000_000_000_000_000, DEFAULT_WEI_PER_STROOP), 10_000_000); // 1 ETH = 1 XLM
+        assert_eq!(wei_to_stroops(100_000_000_000, DEFAULT_WEI_PER_STROOP), 1); // Minimum
+    }
+
+    #[test]
+    fn test_custom_wei_per_stroop_roundtrips() {
+        // XLM shown with its own 7 decimals: 1 stroop = 1 wei
+        assert_eq!(stroops_to_wei(12_345, 1), 12_345);
+        assert_eq!(wei_to_stroops(12_345, 1), 12_345);
+
+        // A 1 XLM = 0.1 ETH peg
+        let factor = 10_000_000_000;
+        assert_eq!(stroops_to_wei(10_000_000, factor), 100_000_000_000_000_000);
+        for stroops in [0, 1, 58_213, 10_000_000, u64::MAX] {
+            assert_eq!(wei_to_stroops(stroops_to_wei(stroops, factor), factor), stroops);
+        }
+        // Wei below one stroop rounds down
+        assert_eq!(wei_to_stroops(factor - 1, factor), 0);
+    }
+
+    #[test]
+    fn test_call_fees_eip1559() {
+        let call_obj = serde_json::json!({
+            "to": "0x1234567890123456789012345678901234567890",
+            "maxFeePerGas": "0x2540be400",        // 10 gwei
+            "maxPriorityFeePerGas": "0x3b9aca00", // 1 gwei
+        });
+        let fees = CallFees::from_call_object(&call_obj).unwrap();
+        assert_eq!(fees.gas_price, None);
+        assert_eq!(fees.max_fee_per_gas, Some(10_000_000_000));
+        assert_eq!(fees.max_priority_fee_per_gas, Some(1_000_000_000));
+
+        // Base fee + tip below the cap
+        assert_eq!(fees.effective_gas_price(2_000_000_000), Some(3_000_000_000));
+        // Capped at maxFeePerGas
+        assert_eq!(fees.effective_gas_price(stroops_to_wei(100, DEFAULT_WEI_PER_STROOP)), Some(10_000_000_000));
+
+        // 100 stroops = 10^13 wei at 10 gwei per gas needs 1000 gas
+        assert_eq!(gas_covering_fee(21_000, stroops_to_wei(100, DEFAULT_WEI_PER_STROOP), 10_000_000_000), 21_000);
+        // A 1 XLM resource fee needs 10^8 gas at the same price
+        assert_eq!(gas_covering_fee(21_000, stroops_to_wei(10_000_000, DEFAULT_WEI_PER_STROOP), 10_000_000_000), 100_000_000);
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("0x0"), Some(0));
+        assert_eq!(parse_quantity("0x2540be400"), Some(10_000_000_000));
+        assert_eq!(parse_quantity("0xFF"), Some(255));
+        for bad in ["", "0x", "10", "0x+1", "0xzz", "0x1000000000000000000000000000000000"] {
+            assert_eq!(parse_quantity(bad), None, "{}", bad);
+        }
+
+        // Call object fields take quantities or JSON numbers, nothing else
+        assert_eq!(parse_call_object(&serde_json::json!({ "gas": 21000 })).unwrap().gas, Some(21_000));
+        for bad in [serde_json::json!({ "value": "10" }), serde_json::json!({ "maxFeePerGas": "0x" })] {
+            assert!(parse_call_object(&bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_call_fees_rejects_invalid_combinations() {
+        let both = serde_json::json!({ "gasPrice": "0x1", "maxFeePerGas": "0x2" });
+        assert!(CallFees::from_call_object(&both).is_err());
+
+        let tip_above_cap = serde_json::json!({ "maxFeePerGas": "0x1", "maxPriorityFeePerGas": "0x2" });
+        assert!(CallFees::from_call_object(&tip_above_cap).is_err());
+
+        let none = CallFees::from_call_object(&serde_json::json!({})).unwrap();
+        assert!(none.is_empty());
+        assert_eq!(none.effective_gas_price(1), None);
+    }
+
+    fn eip1559_raw_tx(access_list: impl Fn(&mut rlp::RlpStream)) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(12);
+        stream.append(&1414676736u64);
+        stream.append(&3u64);
+        stream.append(&1u64);
+        stream.append(&100u64);
+        stream.append(&50_000u64);
+        stream.append(&vec![0x22u8; 20]);
+        stream.append(&0u64);
+        stream.append(&vec![0xa9u8, 0x05, 0x9c, 0xbb]);
+        access_list(&mut stream);
+        stream.append(&1u64);
+        stream.append(&vec![1u8; 32]);
+        stream.append(&vec![2u8; 32]);
+        let mut raw = vec![0x02];
+        raw.extend_from_slice(&stream.out());
+        raw
+    }
+
+    #[test]
+    fn test_eip1559_access_list_is_decoded_and_signed() {
+        let raw = eip1559_raw_tx(|s| {
+            s.begin_list(1);
+            s.begin_list(2);
+            s.append(&vec![0x33u8; 20]);
+            s.begin_list(2);
+            s.append(&vec![0x44u8; 32]);
+            s.append(&vec![0x55u8; 32]);
+        });
+        let tx = decode_raw_transaction(&raw).unwrap();
+        assert_eq!(tx.chain_id, Some(1414676736));
+        assert_eq!(tx.data, vec![0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(
+            tx.access_list,
+            vec![AccessListItem { address: [0x33; 20], storage_keys: vec![[0x44; 32], [0x55; 32]] }]
+        );
+
+        // The signing hash covers the access list: 0x02 || rlp(first nine fields)
+        let rlp = Rlp::new(&raw[1..]);
+        let mut unsigned = rlp::RlpStream::new_list(9);
+        for i in 0..9 {
+            unsigned.append_raw(rlp.at(i).unwrap().as_raw(), 1);
+        }
+        let mut payload = vec![0x02];
+        payload.extend_from_slice(&unsigned.out());
+        assert_eq!(tx.signing_hash, keccak(&payload));
+
+        let empty = decode_raw_transaction(&eip1559_raw_tx(|s| {
+            s.begin_list(0);
+        }))
+        .unwrap();
+        assert!(empty.access_list.is_empty());
+        assert_ne!(empty.signing_hash, tx.signing_hash);
+    }
+
+    #[test]
+    fn test_eip1559_rejects_malformed_access_list() {
+        let not_a_list = eip1559_raw_tx(|s| {
+            s.append(&vec![0x33u8; 20]);
+        });
+        assert!(decode_raw_transaction(&not_a_list).is_err());
+
+        let short_key = eip1559_raw_tx(|s| {
+            s.begin_list(1);
+            s.begin_list(2);
+            s.append(&vec![0x33u8; 20]);
+            s.begin_list(1);
+            s.append(&vec![0x44u8; 31]);
+        });
+        assert!(decode_raw_transaction(&short_key).is_err());
+    }
+
+    #[test]
+    fn test_legacy_signing_hash_matches_eip155_example() {
+        // The worked example from EIP-155
+        let raw = hex::decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+        let tx = decode_raw_transaction(&raw).unwrap();
+        assert_eq!(tx.chain_id, Some(1));
+        assert_eq!(
+            hex::encode(tx.signing_hash),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+    }
+
+    /// Raw transactions signed by the well-known development key
+    /// 0xac09...ff80 (address 0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266).
+    /// Signatures are RFC 6979 deterministic, so ethers.js produces the same bytes.
+    const SIGNED_EIP155_CALL: &str = "f8ad07843b9aca00830186a094222222222222222222222222222222222222222280b844a9059cbb0000000000000000000000003333333333333333333333333333333333333333000000000000000000000000000000000000000000000000000000000000006484a8a48223a04e5c62912fb26ad81bfd4bda092e4c4ddba964ff401a67c646635262e9451667a02dd59842c11c6623dd56e18a962749caca5d422a0171c7efebb99709f4cc95c7";
+    const SIGNED_PRE_EIP155_TRANSFER: &str = "f86b80843b9aca00825208943535353535353535353535353535353535353535880de0b6b3a7640000801ba07bb77b36708b7633062191c82af199add6cf3c15f53daced15715691d9d74033a00bb030b5930f6068b830c7346378172941dcdcf91c559e2f6e0526bbd553e07e";
+    const SIGNED_EIP1559_CALL: &str = "02f8b3845452410003843b9aca00847735940082ea6094222222222222222222222222222222222222222280b844a9059cbb00000000000000000000000033333333333333333333333333333333333333330000000000000000000000000000000000000000000000000000000000000064c080a01535be6f5e0a82cd1988ef73a99879ecef5c80335b1279fb26570117b4e21a7ca00afaa9265b938edfa0a5bf141ab01e2438d3a76bfb227676b2e313f0ef713efb";
+    const SIGNED_EIP1559_CREATE: &str = "02f86c845452410004843b9aca0084773594008307a1208080916080604052348015600f57600080fd5b50c001a035da85030d4ccaaf04e9a459f36c891dded0e9553c06f2dda24f6e0de86a80dfa024030a6b48d7ab843769b9affd697701786a216e00479070f8c68183b9787ad8";
+
+    /// transfer(0x3333...3333, 100)
+    fn transfer_calldata() -> Vec<u8> {
+        let mut data = hex::decode("a9059cbb").unwrap();
+        data.extend([0u8; 12]);
+        data.extend([0x33u8; 20]);
+        data.extend([0u8; 31]);
+        data.push(100);
+        data
+    }
+
+    #[test]
+    fn test_decode_signed_eip155_transaction() {
+        let tx = decode_raw_transaction(&decode_raw_transaction_hex(SIGNED_EIP155_CALL).unwrap()).unwrap();
+        assert_eq!(tx.nonce, 7);
+        assert_eq!(tx.gas_price, 1_000_000_000);
+        assert_eq!(tx.gas_limit, 100_000);
+        assert_eq!(tx.to, Some([0x22; 20]));
+        assert_eq!(tx.value, 0);
+        assert_eq!(tx.data, transfer_calldata());
+        assert_eq!(tx.chain_id, Some(1414676736));
+        assert_eq!(tx.v, 1414676736 * 2 + 35);
+        assert_eq!(hex::encode(tx.signing_hash), "f1c5a73d063f59ad3a726660f65d7226841f7d0a44d5f898535d64829ec6293b");
+        assert_eq!(hex::encode(tx.tx_hash), "8a8463f1dcc1a3d1658a056eb0870e29e35bac60af63228bd352dccbc88b3897");
+    }
+
+    #[test]
+    fn test_decode_signed_pre_eip155_transaction() {
+        let tx = decode_raw_transaction(&hex::decode(SIGNED_PRE_EIP155_TRANSFER).unwrap()).unwrap();
+        assert_eq!(tx.nonce, 0);
+        assert_eq!(tx.gas_limit, 21_000);
+        assert_eq!(tx.to, Some([0x35; 20]));
+        assert_eq!(tx.value, 1_000_000_000_000_000_000);
+        assert!(tx.data.is_empty());
+        assert_eq!(tx.chain_id, None);
+        assert_eq!(tx.v, 27);
+        assert_eq!(hex::encode(tx.signing_hash), "a3bc80bd323cc3443a5df7976bf4f8c6aa370122aeeee95d3e6d55d681764d84");
+        assert_eq!(hex::encode(tx.tx_hash), "c18be351ca27fe29af97c6520a1cdf686272a80fb789b4a59ca5c167d7ea63f7");
+    }
+
+    #[test]
+    fn test_decode_signed_eip1559_transaction() {
+        let tx = decode_raw_transaction(&hex::decode(SIGNED_EIP1559_CALL).unwrap()).unwrap();
+        assert_eq!(tx.nonce, 3);
+        // The max fee stands in for the gas price
+        assert_eq!(tx.gas_price, 2_000_000_000);
+        assert_eq!(tx.gas_limit, 60_000);
+        assert_eq!(tx.to, Some([0x22; 20]));
+        assert_eq!(tx.value, 0);
+        assert_eq!(tx.data, transfer_calldata());
+        assert_eq!(tx.chain_id, Some(1414676736));
+        assert_eq!(tx.v, 0);
+        assert!(tx.access_list.is_empty());
+        assert_eq!(hex::encode(tx.signing_hash), "5472fb10df04afd13e13e371ff4b485e971bb8c2b13cfcba5e9f2572a1f38523");
+        assert_eq!(hex::encode(tx.tx_hash), "9ce5f9c044f1ff94761d891b463876eb35c72b8c6d06ccb9ee9880f4d4ff0198");
+    }
+
+    #[test]
+    fn test_decode_signed_eip1559_contract_creation() {
+        let tx = decode_raw_transaction(&hex::decode(SIGNED_EIP1559_CREATE).unwrap()).unwrap();
+        assert_eq!(tx.nonce, 4);
+        assert_eq!(tx.gas_limit, 500_000);
+        assert_eq!(tx.to, None);
+        assert_eq!(tx.value, 0);
+        assert_eq!(tx.data, hex::decode("6080604052348015600f57600080fd5b50").unwrap());
+        assert_eq!(tx.chain_id, Some(1414676736));
+        assert_eq!(tx.v, 1);
+        assert_eq!(hex::encode(tx.signing_hash), "64cb2c211d9d2d39176c0d8240c0526edd62f0e8b24518a4af4197006398b16f");
+        assert_eq!(hex::encode(tx.tx_hash), "21ad89868b00b7e1a659128c156cbf75e93a700bb7dc8e6d555164368e2cf90e");
+    }
+
+    #[test]
+    fn test_decode_unsigned_transaction() {
+        let mut stream = rlp::RlpStream::new_list(6);
+        stream.append(&2u64);
+        stream.append(&1_000_000_000u64);
+        stream.append(&21_000u64);
+        stream.append_empty_data();
+        stream.append(&vec![0x01u8]);
+        stream.append(&vec![0x60u8, 0x80]);
+        let raw = stream.out().to_vec();
+
+        let tx = decode_raw_transaction(&raw).unwrap();
+        assert_eq!(tx.nonce, 2);
+        assert_eq!(tx.to, None);
+        assert_eq!(tx.value, 1);
+        assert_eq!(tx.data, vec![0x60, 0x80]);
+        assert_eq!(tx.chain_id, None);
+        assert!(tx.r.is_empty() && tx.s.is_empty());
+        assert_eq!(tx.tx_hash, keccak(&raw));
+        assert_eq!(tx.signing_hash, tx.tx_hash);
+    }
+
+    #[test]
+    fn test_parse_call_object_reads_input_or_data() {
+        let call = parse_call_object(&serde_json::json!({
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "gas": "0x5208",
+            "value": "0x10",
+            "input": "0xa9059cbb",
+        }))
+        .unwrap();
+        assert_eq!(call.from.as_deref(), Some("0x1111111111111111111111111111111111111111"));
+        assert_eq!(call.to.as_deref(), Some("0x2222222222222222222222222222222222222222"));
+        assert_eq!(call.gas, Some(21_000));
+        assert_eq!(call.value, 16);
+        assert_eq!(call.data, vec![0xa9, 0x05, 0x9c, 0xbb]);
+
+        let legacy = parse_call_object(&serde_json::json!({ "data": "0xa9059cbb" })).unwrap();
+        assert_eq!(legacy.data, call.data);
+        assert_eq!(legacy.to, None);
+
+        let both = serde_json::json!({ "data": "0xa9059cbb", "input": "0xa9059cbb" });
+        assert_eq!(parse_call_object(&both).unwrap().data, call.data);
+
+        let conflicting = serde_json::json!({ "data": "0x01", "input": "0x02" });
+        assert!(parse_call_object(&conflicting).is_err());
+        assert!(parse_call_object(&serde_json::json!({ "input": "0xzz" })).is_err());
+        assert!(parse_call_object(&serde_json::json!("0x01")).is_err());
+    }
+
+    #[test]
+    fn test_empty_calldata_routes_to_fallback() {
+        let registry = AbiRegistry::new();
+        let contract = "0x2222222222222222222222222222222222222222";
+        assert!(!invokes_contract(&[], contract, &registry));
+        assert!(decode_calldata(&[], contract, &registry).is_err());
+
+        let fallback: Vec<crate::translator::abi::AbiEntry> =
+            serde_json::from_value(serde_json::json!([{ "type": "fallback" }]))
+        .unwrap();
+        registry.register_contract(contract, &fallback).unwrap();
+
+        assert!(invokes_contract(&[], contract, &registry));
+        let decoded = decode_calldata(&[], contract, &registry).unwrap();
+        assert_eq!(decoded.function_name.as_deref(), Some("fallback"));
+        assert!(decoded.scval_params.is_empty());
+
+        // Short calldata is still neither a call nor a transfer the contract can handle
+        assert!(!invokes_contract(&[0x01], contract, &registry));
+        assert!(decode_calldata(&[0x01], contract, &registry).is_err());
+    }
+
+    #[test]
+    fn test_translation_is_traced() {
+        #[derive(Clone, Default)]
+        struct Captured(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl std::io::Write for Captured {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let registry = AbiRegistry::new();
+        let contract = "0x2222222222222222222222222222222222222222";
+        let abi: Vec<crate::translator::abi::AbiEntry> = serde_json::from_value(serde_json::json!([{
+            "type": "function",
+            "name": "setValue",
+            "inputs": [{ "name": "value", "type": "uint32" }],
+            "outputs": []
+        }]))
+        .unwrap();
+        registry.register_contract(contract, &abi).unwrap();
+
+        let mut calldata = Keccak256::digest(b"setValue(uint32)")[..4].to_vec();
+        calldata.extend_from_slice(&[0u8; 31]);
+        calldata.push(7);
+        let decoded = decode_calldata(&calldata, contract, &registry).unwrap();
+
+        let source = encode_strkey(STRKEY_VERSION_ACCOUNT, &[9; 32]);
+        let contract_id = encode_strkey(STRKEY_VERSION_CONTRACT, &[4; 32]);
+        let xdr = build_soroban_invoke_tx(
+            &source,
+            5,
+            &contract_id,
+            "setValue",
+            &decoded.scval_params,
+            "Test SDF Network ; September 2015",
+            100,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains(&format!("selector={}", hex::encode(&calldata[..4]))));
+        assert!(output.contains("function=setValue"));
+        assert!(output.contains(&format!("raw={}", hex::encode(&calldata[4..]))));
+        assert!(output.contains("scval=U32(7)"));
+        assert!(output.contains(&format!("xdr={}", xdr)));
+    }
+
+    #[test]
+    fn test_intrinsic_gas() {
+        assert_eq!(intrinsic_gas(&[], false), 21_000);
+        assert_eq!(intrinsic_gas(&[0, 1, 2], false), 21_000 + 4 + 16 + 16);
+        assert_eq!(intrinsic_gas(&[], true), 53_000);
+    }
+
+    #[test]
+    fn test_strkey_roundtrip() {
+        // Well-known testnet account and its raw key
+        let account = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+        let key = decode_strkey(account).unwrap();
+        assert_eq!(encode_strkey(STRKEY_VERSION_ACCOUNT, &key), account);
+
+        let contract = encode_strkey(STRKEY_VERSION_CONTRACT, &[0x11; 32]);
+        assert!(contract.starts_with('C'));
+        assert_eq!(contract.len(), 56);
+        assert_eq!(decode_contract_id(&contract).unwrap(), [0x11; 32]);
     }
 
     #[test]
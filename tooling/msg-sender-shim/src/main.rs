@@ -9,6 +9,10 @@
 // Usage:
 //   msg-sender-shim <input.sol> [-o output.sol]
 //   msg-sender-shim --dir <contracts/> [--out-dir <contracts/.processed/>]
+//   msg-sender-shim --check --dir <contracts/>
+//   msg-sender-shim <input.sol> -o output.sol --source-map output.map.json
+//   msg-sender-shim --stdin < input.sol > output.sol
+//   msg-sender-shim --dir <contracts/> --report-json report.json
 //
 // The tool is designed to be used as a preprocessor step before compiling
 // Solidity contracts with Solang for the Soroban target.
@@ -25,12 +29,16 @@
 //       v
 //   Solang compiles to WASM
 
+mod source_map;
 mod transform;
 
 use clap::Parser;
+use serde::Serialize;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use transform::{MsgSenderTransformer, TransformConfig};
+use source_map::{align_lines, build_source_map, source_map_to_json, LineOp};
+use transform::{msg_sender_lines, MsgSenderTransformer, TransformConfig, TransformResult};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -39,10 +47,14 @@ use transform::{MsgSenderTransformer, TransformConfig};
     version
 )]
 struct Cli {
-    /// Input Solidity file to transform
+    /// Input Solidity file to transform ("-" reads standard input)
     #[arg(value_name = "INPUT")]
     input: Option<PathBuf>,
 
+    /// Read the source from standard input instead of a file
+    #[arg(long, conflicts_with_all = ["input", "dir"])]
+    stdin: bool,
+
     /// Output file path (defaults to stdout if not specified)
     #[arg(short, long, value_name = "OUTPUT")]
     output: Option<PathBuf>,
@@ -59,6 +71,10 @@ struct Cli {
     #[arg(long, default_value = "_caller")]
     caller_name: String,
 
+    /// Name for the amount parameter replacing msg.value (default: _value)
+    #[arg(long, default_value = "_value")]
+    value_name: String,
+
     /// Keep redundant require statements (don't remove msg.sender == X checks)
     #[arg(long)]
     keep_requires: bool,
@@ -74,6 +90,40 @@ struct Cli {
     /// Dry run: show what would be changed without writing files
     #[arg(long)]
     dry_run: bool,
+
+    /// Check mode: exit non-zero if any output file is out of date or
+    /// msg.sender remains in executable code. Nothing is written.
+    #[arg(long)]
+    check: bool,
+
+    /// Write a JSON source map relating each output line to the original line
+    /// (single-file mode only)
+    #[arg(long, value_name = "FILE", conflicts_with = "dir")]
+    source_map: Option<PathBuf>,
+
+    /// Write a JSON report of every processed file (counts, patterns detected
+    /// per function and warnings)
+    #[arg(long, value_name = "FILE")]
+    report_json: Option<PathBuf>,
+}
+
+/// Running totals for batch processing.
+#[derive(Default)]
+struct BatchStats {
+    total_files: usize,
+    total_transformed: usize,
+    /// Files whose processed output is missing or out of date (check mode)
+    stale: Vec<PathBuf>,
+    /// Per-file results for --report-json
+    reports: Vec<FileReport>,
+}
+
+/// Transformation report of one file in the --report-json document.
+#[derive(Serialize)]
+struct FileReport {
+    file: String,
+    #[serde(flatten)]
+    result: TransformResult,
 }
 
 fn main() {
@@ -81,6 +131,7 @@ fn main() {
 
     let config = TransformConfig {
         caller_param_name: cli.caller_name.clone(),
+        value_param_name: cli.value_name.clone(),
         remove_redundant_requires: !cli.keep_requires,
         transform_modifiers: !cli.skip_modifiers,
     };
@@ -90,30 +141,55 @@ fn main() {
     if let Some(dir) = &cli.dir {
         // Batch mode: process all .sol files in directory
         process_directory(&transformer, dir, &cli);
+    } else if cli.stdin || cli.input.as_deref() == Some(Path::new("-")) {
+        // Pipe mode: source on stdin, output on stdout unless -o is given
+        process_single_file(&transformer, None, &cli);
     } else if let Some(input) = &cli.input {
         // Single file mode
-        process_single_file(&transformer, input, &cli);
+        process_single_file(&transformer, Some(input), &cli);
     } else {
-        eprintln!("Error: Either provide an input file or use --dir for batch processing.");
+        eprintln!("Error: Either provide an input file, --stdin, or --dir for batch processing.");
         eprintln!("Usage: msg-sender-shim <INPUT.sol> [-o OUTPUT.sol]");
+        eprintln!("       msg-sender-shim --stdin < INPUT.sol");
         eprintln!("       msg-sender-shim --dir <contracts/> [--out-dir <output/>]");
         std::process::exit(1);
     }
 }
 
-fn process_single_file(transformer: &MsgSenderTransformer, input: &Path, cli: &Cli) {
-    let source = match fs::read_to_string(input) {
+/// Transform one source, read from `input` or from stdin when it is None.
+fn process_single_file(transformer: &MsgSenderTransformer, input: Option<&Path>, cli: &Cli) {
+    let input_name = input.map_or_else(|| "<stdin>".to_string(), |path| path.display().to_string());
+    let source = match input {
+        Some(path) => fs::read_to_string(path),
+        None => io::read_to_string(io::stdin()),
+    };
+    let source = match source {
         Ok(s) => s,
         Err(e) => {
-            eprintln!("Error reading {}: {}", input.display(), e);
+            eprintln!("Error reading {}: {}", input_name, e);
             std::process::exit(1);
         }
     };
 
     let result = transformer.transform(&source);
+    write_report(cli, &[FileReport { file: input_name.clone(), result: result.clone() }]);
+
+    if cli.check {
+        // Without -o the input itself is expected to already be processed
+        let Some(target) = cli.output.as_deref().or(input) else {
+            eprintln!("Error: --check on standard input needs -o to compare against");
+            std::process::exit(1);
+        };
+        if !check_output(target, &result.output) {
+            eprintln!("1 file would change:");
+            eprintln!("  {}", target.display());
+            std::process::exit(1);
+        }
+        return;
+    }
 
     if cli.verbose {
-        eprintln!("--- Transformation Report for {} ---", input.display());
+        eprintln!("--- Transformation Report for {} ---", input_name);
         eprintln!("  Functions transformed: {}", result.functions_transformed);
         eprintln!("  Modifiers transformed: {}", result.modifiers_transformed);
         for (func_name, patterns) in &result.patterns_detected {
@@ -130,6 +206,18 @@ fn process_single_file(transformer: &MsgSenderTransformer, input: &Path, cli: &C
         return;
     }
 
+    if let Some(map_path) = &cli.source_map {
+        let map = build_source_map(&source, &result.output);
+        let json = source_map_to_json(&map, &input_name);
+        fs::write(map_path, json).unwrap_or_else(|e| {
+            eprintln!("Error writing source map to {}: {}", map_path.display(), e);
+            std::process::exit(1);
+        });
+        if cli.verbose {
+            eprintln!("Source map written to: {}", map_path.display());
+        }
+    }
+
     if let Some(output_path) = &cli.output {
         if let Some(parent) = output_path.parent() {
             if !parent.exists() {
@@ -160,22 +248,37 @@ fn process_directory(transformer: &MsgSenderTransformer, dir: &Path, cli: &Cli)
 
     let out_dir = cli.out_dir.clone().unwrap_or_else(|| dir.join(".processed"));
 
-    if !cli.dry_run {
+    if !cli.dry_run && !cli.check {
         fs::create_dir_all(&out_dir).unwrap_or_else(|e| {
             eprintln!("Error creating output directory {}: {}", out_dir.display(), e);
             std::process::exit(1);
         });
     }
 
-    let mut total_files = 0;
-    let mut total_transformed = 0;
+    let mut stats = BatchStats::default();
 
-    process_dir_recursive(transformer, dir, &out_dir, dir, cli, &mut total_files, &mut total_transformed);
+    process_dir_recursive(transformer, dir, &out_dir, dir, cli, &mut stats);
+    stats.reports.sort_by(|a, b| a.file.cmp(&b.file));
+    write_report(cli, &stats.reports);
+
+    if cli.check {
+        if !stats.stale.is_empty() {
+            eprintln!("{} file(s) would change:", stats.stale.len());
+            for path in &stats.stale {
+                eprintln!("  {}", path.display());
+            }
+            std::process::exit(1);
+        }
+        if cli.verbose {
+            eprintln!("Checked {} files, all up to date", stats.total_files);
+        }
+        return;
+    }
 
-    if cli.verbose || total_transformed > 0 {
+    if cli.verbose || stats.total_transformed > 0 {
         eprintln!(
             "Processed {} files, {} had msg.sender transformations applied",
-            total_files, total_transformed
+            stats.total_files, stats.total_transformed
         );
     }
 }
@@ -186,8 +289,7 @@ fn process_dir_recursive(
     out_base: &Path,
     src_base: &Path,
     cli: &Cli,
-    total_files: &mut usize,
-    total_transformed: &mut usize,
+    stats: &mut BatchStats,
 ) {
     let entries = match fs::read_dir(current) {
         Ok(e) => e,
@@ -210,9 +312,9 @@ fn process_dir_recursive(
             if path.file_name().is_some_and(|n| n == ".processed") {
                 continue;
             }
-            process_dir_recursive(transformer, &path, out_base, src_base, cli, total_files, total_transformed);
+            process_dir_recursive(transformer, &path, out_base, src_base, cli, stats);
         } else if path.extension().is_some_and(|ext| ext == "sol") {
-            *total_files += 1;
+            stats.total_files += 1;
 
             let source = match fs::read_to_string(&path) {
                 Ok(s) => s,
@@ -225,7 +327,7 @@ fn process_dir_recursive(
             let result = transformer.transform(&source);
 
             if result.functions_transformed > 0 || result.modifiers_transformed > 0 {
-                *total_transformed += 1;
+                stats.total_transformed += 1;
 
                 if cli.verbose {
                     eprintln!(
@@ -237,11 +339,15 @@ fn process_dir_recursive(
                 }
             }
 
-            if !cli.dry_run {
-                // Compute relative path and create output path
-                let relative = path.strip_prefix(src_base).unwrap_or(&path);
-                let out_path = out_base.join(relative);
+            // Compute relative path and create output path
+            let relative = path.strip_prefix(src_base).unwrap_or(&path);
+            let out_path = out_base.join(relative);
 
+            if cli.check {
+                if !check_output(&out_path, &result.output) {
+                    stats.stale.push(out_path);
+                }
+            } else if !cli.dry_run {
                 if let Some(parent) = out_path.parent() {
                     fs::create_dir_all(parent).unwrap_or_else(|e| {
                         eprintln!("Error creating directory {}: {}", parent.display(), e);
@@ -255,6 +361,97 @@ fn process_dir_recursive(
                 println!("--- {} ---", path.display());
                 println!("{}", result.output);
             }
+
+            stats.reports.push(FileReport { file: path.display().to_string(), result });
         }
     }
 }
+
+/// Write the --report-json document, if requested, as
+/// `{"files": [{"file", "functionsTransformed", "modifiersTransformed",
+/// "patternsDetected", "warnings"}]}`.
+fn write_report(cli: &Cli, reports: &[FileReport]) {
+    let Some(report_path) = &cli.report_json else {
+        return;
+    };
+
+    #[derive(Serialize)]
+    struct Report<'a> {
+        files: &'a [FileReport],
+    }
+
+    let json = serde_json::to_string_pretty(&Report { files: reports }).expect("report serializes");
+    fs::write(report_path, json + "\n").unwrap_or_else(|e| {
+        eprintln!("Error writing report to {}: {}", report_path.display(), e);
+        std::process::exit(1);
+    });
+    if cli.verbose {
+        eprintln!("Report written to: {}", report_path.display());
+    }
+}
+
+/// Compare the transformed output against the file at `target`, printing a
+/// diff-style report and any surviving msg.sender lines. Returns true if the
+/// file is up to date.
+fn check_output(target: &Path, expected: &str) -> bool {
+    let mut up_to_date = true;
+
+    match fs::read_to_string(target) {
+        Ok(existing) if existing == expected => {}
+        Ok(existing) => {
+            println!("--- {}", target.display());
+            println!("+++ {} (transformed)", target.display());
+            print!("{}", line_diff(&existing, expected));
+            up_to_date = false;
+        }
+        Err(_) => {
+            println!("{}: missing processed output", target.display());
+            up_to_date = false;
+        }
+    }
+
+    for line in msg_sender_lines(expected) {
+        println!("{}:{}: msg.sender remains in executable code", target.display(), line);
+        up_to_date = false;
+    }
+
+    up_to_date
+}
+
+/// Produce a minimal line diff of `old` against `new`, marking removed lines
+/// with '-' and added lines with '+'. Unchanged lines are omitted.
+fn line_diff(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    let mut diff = String::new();
+    // Next unconsumed line in each file, for hunk headers
+    let (mut i, mut j) = (0, 0);
+    let mut in_hunk = false;
+    for op in align_lines(old, new) {
+        if let LineOp::Equal { original, processed } = op {
+            i = original + 1;
+            j = processed + 1;
+            in_hunk = false;
+            continue;
+        }
+
+        if !in_hunk {
+            diff.push_str(&format!("@@ -{} +{} @@\n", i + 1, j + 1));
+            in_hunk = true;
+        }
+        match op {
+            LineOp::Added { processed } => {
+                diff.push_str(&format!("+{}\n", b[processed]));
+                j = processed + 1;
+            }
+            LineOp::Removed { original } => {
+                diff.push_str(&format!("-{}\n", a[original]));
+                i = original + 1;
+            }
+            LineOp::Equal { .. } => unreachable!(),
+        }
+    }
+
+    diff
+}
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: Apache-2.0
+//
+// TVA Protocol - Source line mapping for the msg.sender shim
+//
+// The transformer rewrites function signatures, injects requireAuth() calls and
+// removes redundant requires, so line numbers in the processed file drift from
+// the developer's original. This module aligns the two files line by line so
+// that Solang diagnostics against the processed output can be translated back.
+//
+// Unchanged lines map to their original position. Lines introduced or rewritten
+// by the shim map to the first original line they replace or precede.
+
+use serde::Serialize;
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+/// One step of a line alignment between the original and processed source.
+/// Indices are zero-based.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineOp {
+    /// The line is identical in both files
+    Equal { original: usize, processed: usize },
+    /// The original line was dropped or rewritten
+    Removed { original: usize },
+    /// The processed line was introduced or rewritten by the shim
+    Added { processed: usize },
+}
+
+/// A single entry of the source map. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMapEntry {
+    pub processed_line: usize,
+    pub original_line: usize,
+}
+
+/// Align the lines of `original` and `processed` with a Myers diff, which
+/// runs in linear memory. When a line is rewritten, additions are emitted
+/// before removals.
+pub fn align_lines(original: &str, processed: &str) -> Vec<LineOp> {
+    let a: Vec<&str> = original.lines().collect();
+    let b: Vec<&str> = processed.lines().collect();
+
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    for op in capture_diff_slices(Algorithm::Myers, &a, &b) {
+        match op {
+            DiffOp::Equal { old_index, new_index, len } => {
+                ops.extend((0..len).map(|k| LineOp::Equal {
+                    original: old_index + k,
+                    processed: new_index + k,
+                }));
+            }
+            DiffOp::Delete { old_index, old_len, .. } => {
+                ops.extend(
+                    (old_index..old_index + old_len).map(|original| LineOp::Removed { original }),
+                );
+            }
+            DiffOp::Insert { new_index, new_len, .. } => {
+                ops.extend(
+                    (new_index..new_index + new_len).map(|processed| LineOp::Added { processed }),
+                );
+            }
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => {
+                ops.extend(
+                    (new_index..new_index + new_len).map(|processed| LineOp::Added { processed }),
+                );
+                ops.extend(
+                    (old_index..old_index + old_len).map(|original| LineOp::Removed { original }),
+                );
+            }
+        }
+    }
+
+    ops
+}
+
+/// Build a source map with exactly one entry per line of `processed`.
+pub fn build_source_map(original: &str, processed: &str) -> Vec<SourceMapEntry> {
+    let original_count = original.lines().count();
+    let mut entries = Vec::new();
+    // Next original line not yet consumed by the alignment
+    let mut next_original = 0;
+
+    for op in align_lines(original, processed) {
+        match op {
+            LineOp::Equal { original, processed } => {
+                entries.push(SourceMapEntry {
+                    processed_line: processed + 1,
+                    original_line: original + 1,
+                });
+                next_original = original + 1;
+            }
+            LineOp::Removed { original } => next_original = original + 1,
+            LineOp::Added { processed } => {
+                // Lines appended after the end of the original map to its last line
+                let original_line = next_original.min(original_count.saturating_sub(1)) + 1;
+                entries.push(SourceMapEntry {
+                    processed_line: processed + 1,
+                    original_line,
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SourceMapItem<'a> {
+    processed_line: usize,
+    original_file: &'a str,
+    original_line: usize,
+}
+
+/// Serialize a source map as a JSON array of
+/// `{"processedLine", "originalFile", "originalLine"}` objects.
+pub fn source_map_to_json(entries: &[SourceMapEntry], original_file: &str) -> String {
+    let items: Vec<SourceMapItem> = entries
+        .iter()
+        .map(|e| SourceMapItem {
+            processed_line: e.processed_line,
+            original_file,
+            original_line: e.original_line,
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&items).expect("source map serializes") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::{MsgSenderTransformer, TransformConfig};
+
+    #[test]
+    fn test_source_map_covers_every_output_line() {
+        let src = r#"pragma solidity ^0.8.0;
+contract Owned {
+    address public owner;
+    function doStuff() public {
+        require(msg.sender == owner, "not owner");
+        owner = msg.sender;
+    }
+    function get() public view returns (address) {
+        return owner;
+    }
+}
+"#;
+        let result = MsgSenderTransformer::new(TransformConfig::default()).transform(src);
+        let map = build_source_map(src, &result.output);
+
+        assert_eq!(map.len(), result.output.lines().count());
+        for (i, entry) in map.iter().enumerate() {
+            assert_eq!(entry.processed_line, i + 1);
+        }
+
+        // Untouched lines keep their original position
+        let out_lines: Vec<&str> = result.output.lines().collect();
+        let get_line = out_lines
+            .iter()
+            .position(|l| l.contains("function get()"))
+            .unwrap();
+        assert_eq!(map[get_line].original_line, 8);
+
+        // The injected requireAuth maps into the transformed function
+        let auth_line = out_lines
+            .iter()
+            .position(|l| l.contains("owner.requireAuth()"))
+            .unwrap();
+        assert!((4..=6).contains(&map[auth_line].original_line));
+    }
+
+    #[test]
+    fn test_source_map_identity() {
+        let src = "a\nb\nc\n";
+        let map = build_source_map(src, src);
+        let lines: Vec<(usize, usize)> = map
+            .iter()
+            .map(|e| (e.processed_line, e.original_line))
+            .collect();
+        assert_eq!(lines, vec![(1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn test_align_lines_rewritten_line_adds_before_removing() {
+        let ops = align_lines("a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(
+            ops,
+            vec![
+                LineOp::Equal { original: 0, processed: 0 },
+                LineOp::Added { processed: 1 },
+                LineOp::Removed { original: 1 },
+                LineOp::Equal { original: 2, processed: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_source_map_large_input() {
+        let original: String = (0..20_000).map(|i| format!("line {}\n", i)).collect();
+        let processed = original
+            .replace("line 10\n", "line 10\ninjected\n")
+            .replace("line 15000\n", "");
+        let map = build_source_map(&original, &processed);
+
+        assert_eq!(map.len(), processed.lines().count());
+        assert_eq!(map[11].original_line, 12);
+        assert_eq!(map[12].original_line, 12);
+        assert_eq!(map[15_000].original_line, 15_000);
+        assert_eq!(map[15_001].original_line, 15_002);
+        assert_eq!(map.last().unwrap().original_line, 20_000);
+    }
+
+    #[test]
+    fn test_source_map_json_escapes_file_name() {
+        let map = vec![SourceMapEntry { processed_line: 1, original_line: 1 }];
+        let json = source_map_to_json(&map, "dir\\My \"Token\".sol");
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!([
+                {"processedLine": 1, "originalFile": "dir\\My \"Token\".sol", "originalLine": 1}
+            ])
+        );
+    }
+}
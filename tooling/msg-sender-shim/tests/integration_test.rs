@@ -3,7 +3,8 @@
 // Integration tests for the msg-sender-shim preprocessor.
 // These tests validate end-to-end transformation of realistic Solidity contracts.
 
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 use std::path::Path;
 use tempfile::TempDir;
 use std::fs;
@@ -376,6 +377,150 @@ contract NoSender {
     assert_eq!(result.trim(), src.trim());
 }
 
+#[test]
+fn test_check_mode_single_file() {
+    ensure_built();
+
+    let src = r#"pragma solidity ^0.8.0;
+contract Owned {
+    address public owner;
+    function doStuff() public {
+        require(msg.sender == owner, "not owner");
+    }
+}
+"#;
+
+    let tmp = TempDir::new().unwrap();
+    let input_path = tmp.path().join("Owned.sol");
+    let output_path = tmp.path().join("Owned.processed.sol");
+    fs::write(&input_path, src).unwrap();
+
+    // Processed output does not exist yet
+    let output = Command::new(binary_path())
+        .args([input_path.to_str().unwrap(), "-o", output_path.to_str().unwrap(), "--check"])
+        .output()
+        .expect("Failed to execute");
+    assert!(!output.status.success(), "Check should fail before processing");
+    assert!(!output_path.exists(), "Check mode must not write files");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Owned.processed.sol"));
+
+    // Raw input still contains msg.sender
+    let output = Command::new(binary_path())
+        .args([input_path.to_str().unwrap(), "--check"])
+        .output()
+        .expect("Failed to execute");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("-        require(msg.sender == owner, \"not owner\");"));
+    assert!(stdout.contains("+        owner.requireAuth();"));
+
+    // After running the preprocessor the check passes
+    let status = Command::new(binary_path())
+        .args([input_path.to_str().unwrap(), "-o", output_path.to_str().unwrap()])
+        .status()
+        .expect("Failed to execute");
+    assert!(status.success());
+    let output = Command::new(binary_path())
+        .args([input_path.to_str().unwrap(), "-o", output_path.to_str().unwrap(), "--check"])
+        .output()
+        .expect("Failed to execute");
+    assert!(output.status.success(), "Check failed: {:?}", output);
+}
+
+#[test]
+fn test_check_mode_directory() {
+    ensure_built();
+
+    let tmp = TempDir::new().unwrap();
+    let contracts = tmp.path().join("contracts");
+    fs::create_dir_all(&contracts).unwrap();
+    fs::write(
+        contracts.join("Token.sol"),
+        r#"pragma solidity ^0.8.0;
+contract Token {
+    mapping(address => uint256) balances;
+    function mint(uint256 amount) public {
+        balances[msg.sender] += amount;
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        contracts.join("Plain.sol"),
+        "pragma solidity ^0.8.0;\ncontract Plain {}\n",
+    )
+    .unwrap();
+
+    let status = Command::new(binary_path())
+        .args(["--dir", contracts.to_str().unwrap()])
+        .status()
+        .expect("Failed to execute");
+    assert!(status.success());
+
+    let check = || {
+        Command::new(binary_path())
+            .args(["--dir", contracts.to_str().unwrap(), "--check"])
+            .output()
+            .expect("Failed to execute")
+    };
+    assert!(check().status.success(), "Fresh output should pass the check");
+
+    // Edit a source file without re-running the preprocessor
+    fs::write(
+        contracts.join("Plain.sol"),
+        "pragma solidity ^0.8.0;\ncontract Plain { uint256 x; }\n",
+    )
+    .unwrap();
+    let output = check();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 file(s) would change"));
+    assert!(stderr.contains("Plain.sol"));
+    assert!(!stderr.contains("Token.sol"));
+}
+
+#[test]
+fn test_source_map_output() {
+    ensure_built();
+
+    let src = r#"pragma solidity ^0.8.0;
+contract Token {
+    mapping(address => uint256) balances;
+    function mint(uint256 amount) public {
+        balances[msg.sender] += amount;
+    }
+}
+"#;
+
+    let tmp = TempDir::new().unwrap();
+    let input_path = tmp.path().join("Token.sol");
+    let output_path = tmp.path().join("Token.processed.sol");
+    let map_path = tmp.path().join("Token.map.json");
+    fs::write(&input_path, src).unwrap();
+
+    let output = Command::new(binary_path())
+        .args([
+            input_path.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+            "--source-map",
+            map_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let processed = fs::read_to_string(&output_path).unwrap();
+    let map = fs::read_to_string(&map_path).unwrap();
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&map).unwrap();
+    assert_eq!(entries.len(), processed.lines().count());
+    assert_eq!(entries[0]["processedLine"], 1);
+    assert_eq!(entries[0]["originalLine"], 1);
+    assert!(entries[0]["originalFile"].as_str().unwrap().ends_with("Token.sol"));
+}
+
 #[test]
 fn test_verbose_output() {
     ensure_built();
@@ -405,6 +550,114 @@ contract Foo {
     assert!(stderr.contains("Functions transformed: 1"));
 }
 
+#[test]
+fn test_stdin_mode() {
+    ensure_built();
+
+    let src = r#"pragma solidity ^0.8.0;
+contract Foo {
+    mapping(address => uint256) balances;
+    function get() public view returns (uint256) {
+        return balances[msg.sender];
+    }
+}
+"#;
+
+    let run = |args: &[&str]| {
+        let mut child = Command::new(binary_path())
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute");
+        child.stdin.take().unwrap().write_all(src.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    // The report goes to stderr, leaving only the transformed source on stdout
+    let output = run(&["--stdin", "--verbose"]);
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("pragma solidity ^0.8.0;"));
+    assert!(stdout.contains("balances[_caller]"));
+    assert_no_msg_sender_in_code(&stdout);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Transformation Report for <stdin>"));
+
+    // "-" as the input reads stdin too
+    let output = run(&["-"]);
+    assert!(output.status.success(), "Command failed: {:?}", output);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), stdout);
+}
+
+#[test]
+fn test_report_json() {
+    ensure_built();
+
+    let src = r#"pragma solidity ^0.8.0;
+contract Token {
+    address owner;
+    mapping(address => uint256) balances;
+
+    modifier onlyOwner() {
+        require(msg.sender == owner, "not owner");
+        _;
+    }
+
+    function transfer(address to, uint256 amount) public {
+        balances[msg.sender] -= amount;
+        balances[to] += amount;
+    }
+
+    function myBalance() public view returns (uint256) {
+        return balances[msg.sender];
+    }
+
+    function mint(address to, uint256 amount) public onlyOwner {
+        balances[to] += amount;
+    }
+}
+"#;
+
+    let tmp = TempDir::new().unwrap();
+    let input_path = tmp.path().join("Token.sol");
+    let report_path = tmp.path().join("report.json");
+    fs::write(&input_path, src).unwrap();
+
+    let output = Command::new(binary_path())
+        .args([
+            input_path.to_str().unwrap(),
+            "-o",
+            tmp.path().join("Token.processed.sol").to_str().unwrap(),
+            "--report-json",
+            report_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute");
+    assert!(output.status.success(), "Command failed: {:?}", output);
+
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+    let files = report["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    let file = &files[0];
+    assert_eq!(file["file"], input_path.to_str().unwrap());
+    assert_eq!(file["functionsTransformed"], 2);
+    assert_eq!(file["modifiersTransformed"], 1);
+    assert_eq!(
+        file["patternsDetected"][0],
+        serde_json::json!({
+            "function": "transfer",
+            "patterns": [{ "kind": "mappingAccess", "mappingName": "balances" }],
+        })
+    );
+    assert_eq!(file["patternsDetected"][1]["function"], "myBalance");
+    let warnings = file["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].as_str().unwrap().contains("'myBalance' is declared view"));
+    assert!(file.get("output").is_none());
+}
+
 #[test]
 fn test_actual_test_contracts() {
     ensure_built();
@@ -441,7 +694,7 @@ fn test_actual_test_contracts() {
     // Verify all output files have no msg.sender in code
     for entry in fs::read_dir(&out_dir).unwrap() {
         let entry = entry.unwrap();
-        if entry.path().extension().map_or(false, |ext| ext == "sol") {
+        if entry.path().extension().is_some_and(|ext| ext == "sol") {
             let content = fs::read_to_string(entry.path()).unwrap();
             assert_no_msg_sender_in_code(&content);
         }